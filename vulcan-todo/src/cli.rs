@@ -1,4 +1,6 @@
 use crate::models::{Priority, Status};
+use crate::report::ReportKind;
+use crate::store::layered::StoreTarget;
 use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
@@ -21,10 +23,20 @@ pub struct Cli {
     #[arg(long)]
     pub json: bool,
 
-    /// Path to task store (default: ~/.config/vulcan-todo/tasks.json)
+    /// Path to task store, used as-is (default: ~/.config/vulcan-todo/tasks.json,
+    /// merged with the nearest .vulcan-todo/ store)
     #[arg(long, short = 'p')]
     pub path: Option<PathBuf>,
 
+    /// Don't run hooks from the hooks directory for this invocation
+    #[arg(long)]
+    pub no_hooks: bool,
+
+    /// Store for new tasks and sprints (default: the repository's
+    /// .vulcan-todo/ store if there is one, else global)
+    #[arg(long, value_enum)]
+    pub store: Option<StoreArg>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -53,6 +65,10 @@ pub enum Commands {
         /// Limit results
         #[arg(long, short = 'n', default_value = "50")]
         limit: usize,
+
+        /// Include tasks that are waiting (snoozed until a later date)
+        #[arg(long)]
+        include_waiting: bool,
     },
 
     /// Show a single task
@@ -91,6 +107,14 @@ pub enum Commands {
         /// Assign to sprint (sprint ID)
         #[arg(long, short = 's')]
         sprint: Option<String>,
+
+        /// Hide until later: 3d, 4h, 2w, tomorrow, or YYYY-MM-DD
+        #[arg(long, short = 'w')]
+        wait: Option<String>,
+
+        /// Effort estimate in points (for sprint planning)
+        #[arg(long, short = 'e')]
+        estimate: Option<u32>,
     },
 
     /// Edit a task
@@ -118,6 +142,10 @@ pub enum Commands {
         /// Set project (use empty string to remove)
         #[arg(long, short = 'P')]
         project: Option<String>,
+
+        /// Set effort estimate in points (0 to clear)
+        #[arg(long, short = 'e')]
+        estimate: Option<u32>,
     },
 
     /// Complete a task
@@ -134,6 +162,21 @@ pub enum Commands {
         id: String,
     },
 
+    /// Snooze a task (hide it from default listings until later)
+    #[command(name = "snooze")]
+    Snooze {
+        /// Task ID
+        id: String,
+
+        /// How long to wait: 3d, 4h, 2w, tomorrow, or YYYY-MM-DD
+        #[arg(default_value = "1d")]
+        until: String,
+
+        /// Clear the wait date so the task shows up again
+        #[arg(long)]
+        clear: bool,
+    },
+
     /// Delete a task
     #[command(name = "delete")]
     Delete {
@@ -163,7 +206,7 @@ pub enum Commands {
         position: i32,
     },
 
-    /// Search tasks
+    /// Fuzzy search tasks, best matches first
     #[command(name = "search")]
     Search {
         /// Search query
@@ -207,6 +250,126 @@ pub enum Commands {
         #[command(subcommand)]
         command: SprintCommands,
     },
+
+    /// Session-scoped task management (tasks created from MCP sessions)
+    #[command(name = "session")]
+    Session {
+        #[command(subcommand)]
+        command: SessionCommands,
+    },
+
+    /// Import TODO/FIXME/HACK comments from a source tree as tasks
+    #[command(name = "scan")]
+    Scan {
+        /// Directory to scan (default: current directory)
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Project for imported tasks (default: repository name)
+        #[arg(long, short = 'P')]
+        project: Option<String>,
+
+        /// Show what would change without writing
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Create a .vulcan-todo/ task store for the current directory (repository)
+    #[command(name = "init")]
+    Init,
+
+    /// Generate a standup or sprint review report (Markdown, or JSON with --json)
+    #[command(name = "report")]
+    Report {
+        /// Report kind
+        #[arg(value_enum)]
+        kind: ReportKindArg,
+
+        /// Only tasks in this project
+        #[arg(long, short = 'P')]
+        project: Option<String>,
+
+        /// Only tasks in this sprint (reviews default to the project's active sprint)
+        #[arg(long, short = 's')]
+        sprint: Option<String>,
+
+        /// Start of the range: YYYY-MM-DD or a duration ago (1d, 2w)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// End of the range (default: now)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Markdown template (default: ~/.config/vulcan-todo/templates/<kind>.md)
+        #[arg(long)]
+        template: Option<PathBuf>,
+
+        /// Write the report to a file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+
+    /// vulcan-vault integration (context notes)
+    #[cfg(feature = "vault")]
+    #[command(name = "vault")]
+    Vault {
+        #[command(subcommand)]
+        command: VaultCommands,
+    },
+}
+
+#[cfg(feature = "vault")]
+#[derive(Subcommand, Debug)]
+pub enum VaultCommands {
+    /// Find context note links pointing at notes missing from the vault
+    #[command(name = "check")]
+    Check {
+        /// Remove the dangling links from their tasks
+        #[arg(long)]
+        prune: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SessionCommands {
+    /// List sessions with task counts
+    #[command(name = "list")]
+    List,
+
+    /// Show tasks in a session
+    #[command(name = "show")]
+    Show {
+        /// Session ID
+        id: String,
+    },
+
+    /// Promote a session's unfinished tasks to the global scope
+    #[command(name = "promote")]
+    Promote {
+        /// Session ID
+        id: String,
+
+        /// Also assign the promoted tasks to a project
+        #[arg(long, short = 'P')]
+        project: Option<String>,
+
+        /// Promote completed tasks too
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Expire inactive sessions: archive their tasks
+    #[command(name = "expire")]
+    Expire {
+        /// Inactivity threshold: 12h, 7d, 2w
+        #[arg(long, default_value = "7d")]
+        after: String,
+
+        /// Show what would be expired without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -244,6 +407,10 @@ pub enum SprintCommands {
         /// End date (YYYY-MM-DD)
         #[arg(long)]
         end: Option<String>,
+
+        /// Point budget for sprint planning
+        #[arg(long, short = 'c')]
+        capacity: Option<u32>,
     },
 
     /// Show sprint details
@@ -260,11 +427,45 @@ pub enum SprintCommands {
         id: String,
     },
 
-    /// Complete a sprint
+    /// Complete a sprint, carrying unfinished tasks over to the next planning sprint
     #[command(name = "complete")]
     Complete {
         /// Sprint ID
         id: String,
+
+        /// Sprint to carry unfinished tasks into (default: next planning sprint, else backlog)
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Leave unfinished tasks in the completed sprint
+        #[arg(long, conflicts_with = "to")]
+        no_rollover: bool,
+    },
+
+    /// Move a sprint's unfinished tasks to the next planning sprint (or backlog)
+    #[command(name = "rollover")]
+    Rollover {
+        /// Sprint ID
+        id: String,
+
+        /// Target sprint ID (default: next planning sprint, else backlog)
+        #[arg(long)]
+        to: Option<String>,
+    },
+
+    /// Propose backlog tasks to fill a sprint up to its point budget
+    #[command(name = "plan")]
+    Plan {
+        /// Sprint ID
+        id: String,
+
+        /// Point budget (default: sprint capacity)
+        #[arg(long, short = 'b')]
+        budget: Option<u32>,
+
+        /// Add the proposed tasks to the sprint
+        #[arg(long)]
+        apply: bool,
     },
 
     /// Delete a sprint (moves tasks to backlog)
@@ -352,6 +553,40 @@ pub enum SprintStatusArg {
     All,
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum StoreArg {
+    /// ~/.config/vulcan-todo/tasks.json
+    Global,
+    /// The nearest .vulcan-todo/ store
+    Local,
+}
+
+impl From<StoreArg> for StoreTarget {
+    fn from(val: StoreArg) -> Self {
+        match val {
+            StoreArg::Global => StoreTarget::Global,
+            StoreArg::Local => StoreTarget::Local,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ReportKindArg {
+    /// Daily standup (default: last 24 hours)
+    Standup,
+    /// Sprint review (default: the sprint, else the last 7 days)
+    Review,
+}
+
+impl From<ReportKindArg> for ReportKind {
+    fn from(val: ReportKindArg) -> Self {
+        match val {
+            ReportKindArg::Standup => ReportKind::Standup,
+            ReportKindArg::Review => ReportKind::Review,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -409,6 +644,89 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_snooze() {
+        let cli = Cli::parse_from(["vulcan-todo", "snooze", "abc", "3d"]);
+        match cli.command {
+            Some(Commands::Snooze { id, until, clear }) => {
+                assert_eq!(id, "abc");
+                assert_eq!(until, "3d");
+                assert!(!clear);
+            }
+            _ => panic!("Expected Snooze command"),
+        }
+
+        let cli = Cli::parse_from(["vulcan-todo", "list", "--include-waiting"]);
+        match cli.command {
+            Some(Commands::List {
+                include_waiting, ..
+            }) => assert!(include_waiting),
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_session() {
+        let cli = Cli::parse_from([
+            "vulcan-todo",
+            "session",
+            "promote",
+            "abc",
+            "-P",
+            "vulcan-os",
+        ]);
+        match cli.command {
+            Some(Commands::Session {
+                command: SessionCommands::Promote { id, project, all },
+            }) => {
+                assert_eq!(id, "abc");
+                assert_eq!(project, Some("vulcan-os".to_string()));
+                assert!(!all);
+            }
+            _ => panic!("Expected Session Promote command"),
+        }
+
+        let cli = Cli::parse_from(["vulcan-todo", "session", "expire", "--dry-run"]);
+        match cli.command {
+            Some(Commands::Session {
+                command: SessionCommands::Expire { after, dry_run },
+            }) => {
+                assert_eq!(after, "7d");
+                assert!(dry_run);
+            }
+            _ => panic!("Expected Session Expire command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_sprint_plan() {
+        let cli = Cli::parse_from(["vulcan-todo", "sprint", "plan", "abc", "-b", "8", "--apply"]);
+        match cli.command {
+            Some(Commands::Sprint {
+                command: SprintCommands::Plan { id, budget, apply },
+            }) => {
+                assert_eq!(id, "abc");
+                assert_eq!(budget, Some(8));
+                assert!(apply);
+            }
+            _ => panic!("Expected Sprint Plan command"),
+        }
+
+        let cli = Cli::parse_from(["vulcan-todo", "sprint", "complete", "abc", "--no-rollover"]);
+        match cli.command {
+            Some(Commands::Sprint {
+                command:
+                    SprintCommands::Complete {
+                        to, no_rollover, ..
+                    },
+            }) => {
+                assert_eq!(to, None);
+                assert!(no_rollover);
+            }
+            _ => panic!("Expected Sprint Complete command"),
+        }
+    }
+
     #[test]
     fn test_cli_mcp_flag() {
         let cli = Cli::parse_from(["vulcan-todo", "--mcp"]);
@@ -7,21 +7,32 @@ use tokio;
 mod cli;
 mod mcp;
 mod models;
+mod report;
+mod scan;
 mod store;
 mod ui;
+#[cfg(feature = "vault")]
+mod vault;
 
 pub use models::{Sprint, Task};
 
 /// Store type alias
 type StoreType = store::JsonStore;
 
-fn get_store(path: Option<PathBuf>) -> Result<Arc<dyn store::Store>> {
-    let store = if let Some(p) = path {
-        StoreType::with_path(p)?
-    } else {
-        StoreType::new()?
-    };
-    Ok(Arc::new(store))
+/// Open the store: an explicit path is used as-is, otherwise the global
+/// store layered under the nearest `.vulcan-todo/` store
+fn get_store(
+    path: Option<PathBuf>,
+    workspace: store::layered::WorkspaceOptions,
+) -> Result<Arc<dyn store::Store>> {
+    if let Some(p) = path {
+        let mut store = StoreType::with_path(p)?;
+        if !workspace.no_hooks {
+            store = store.with_hooks(store::hooks::Hooks::discover());
+        }
+        return Ok(Arc::new(store));
+    }
+    store::layered::open_workspace(&std::env::current_dir()?, workspace)
 }
 
 #[tokio::main]
@@ -40,15 +51,25 @@ async fn main() -> Result<()> {
     });
 
     // Get store
-    let store = get_store(path)?;
+    let workspace = store::layered::WorkspaceOptions {
+        target: cli.store.map(Into::into),
+        no_hooks: cli.no_hooks,
+    };
+    // MCP clients can switch workspaces unless the store path is fixed
+    let mcp_workspace = path.is_none().then_some(workspace);
+    let store = get_store(path, workspace)?;
 
     // Handle mode
     if cli.mcp {
         // Run MCP server mode
-        mcp::run_mcp_server(store).await
+        mcp::run_mcp_server(store, mcp_workspace).await
     } else if cli.command.is_some() {
         // Handle CLI commands
-        handle_command(cli.command.unwrap(), &store, cli.json)
+        let result = handle_command(cli.command.unwrap(), &store, cli.json);
+        for line in store.take_hook_output() {
+            eprintln!("{}", line);
+        }
+        result
     } else {
         // Default: run TUI (if feature enabled)
         #[cfg(feature = "tui")]
@@ -73,10 +94,12 @@ fn handle_command(command: cli::Commands, store: &Arc<dyn store::Store>, json: b
             project,
             search,
             limit,
+            include_waiting,
         } => {
             let all_tasks = store.get_all()?;
             let mut tasks: Vec<Task> = all_tasks
                 .into_iter()
+                .filter(|t| include_waiting || !t.is_waiting())
                 .filter(|t| {
                     let status_match = match status {
                         Some(cli::StatusArg::Pending) => t.is_pending(),
@@ -118,7 +141,23 @@ fn handle_command(command: cli::Commands, store: &Arc<dyn store::Store>, json: b
             for (i, task) in tasks.iter().enumerate() {
                 let status = if task.is_done() { "[✓]" } else { "[ ]" };
                 let priority = task.priority.emoji();
-                println!("{}. {} {} {}", i + 1, status, priority, task.title);
+                let waiting = match task.wait_until {
+                    Some(w) if task.is_waiting() => format!(" 💤 until {}", w.format("%Y-%m-%d")),
+                    _ => String::new(),
+                };
+                let claimed = match task.claimed_by() {
+                    Some(owner) => format!(" 🔒 {}", owner),
+                    None => String::new(),
+                };
+                println!(
+                    "{}. {} {} {}{}{}",
+                    i + 1,
+                    status,
+                    priority,
+                    task.title,
+                    waiting,
+                    claimed
+                );
             }
             Ok(())
         }
@@ -133,9 +172,23 @@ fn handle_command(command: cli::Commands, store: &Arc<dyn store::Store>, json: b
                     println!("Priority: {}", t.priority);
                     println!("Tags: {:?}", t.tags);
                     println!("Created: {}", t.created_formatted());
+                    if let Some(wait) = t.wait_formatted() {
+                        println!("Waiting until: {}", wait);
+                    }
+                    if let Some(source) = &t.source {
+                        println!("Source: {}/{}", source.root, source.location());
+                    }
+                    if let Some(claim) = t.active_claim(chrono::Utc::now()) {
+                        println!(
+                            "Claimed by: {} (until {})",
+                            claim.owner,
+                            claim.expires_at.format("%Y-%m-%d %H:%M")
+                        );
+                    }
                     if let Some(desc) = &t.description {
                         println!("\nDescription:\n{}", desc);
                     }
+                    print_context_notes(&t)?;
                     Ok(())
                 }
                 None => {
@@ -153,14 +206,26 @@ fn handle_command(command: cli::Commands, store: &Arc<dyn store::Store>, json: b
             project,
             due: _,
             sprint,
+            wait,
+            estimate,
         } => {
             let mut task = models::Task::new(title);
             task.description = description;
+            task.estimate = estimate;
             if let Some(p) = priority {
                 task.priority = p.into();
             }
             task.tags = tags;
             task.project = project;
+            if let Some(wait) = wait {
+                match models::parse_wait_until(&wait, chrono::Utc::now()) {
+                    Some(until) => task.snooze(until),
+                    None => anyhow::bail!(
+                        "Invalid wait time '{}'. Use e.g. 3d, 4h, 2w, tomorrow, or YYYY-MM-DD",
+                        wait
+                    ),
+                }
+            }
 
             let created = store.add(&task)?;
 
@@ -186,6 +251,7 @@ fn handle_command(command: cli::Commands, store: &Arc<dyn store::Store>, json: b
             priority,
             tags,
             project,
+            estimate,
         } => {
             let existing = store.get(&id)?;
             match existing {
@@ -209,6 +275,9 @@ fn handle_command(command: cli::Commands, store: &Arc<dyn store::Store>, json: b
                             t.project = Some(proj);
                         }
                     }
+                    if let Some(points) = estimate {
+                        t.estimate = (points > 0).then_some(points);
+                    }
 
                     let updated = store.update(&t)?;
                     match updated {
@@ -260,6 +329,36 @@ fn handle_command(command: cli::Commands, store: &Arc<dyn store::Store>, json: b
             }
         }
 
+        cli::Commands::Snooze { id, until, clear } => match store.get(&id)? {
+            Some(mut t) => {
+                if clear {
+                    t.unsnooze();
+                    store.update(&t)?;
+                    println!("Task unsnoozed: {}", t.title);
+                    return Ok(());
+                }
+                let wait_until = match models::parse_wait_until(&until, chrono::Utc::now()) {
+                    Some(w) => w,
+                    None => anyhow::bail!(
+                        "Invalid wait time '{}'. Use e.g. 3d, 4h, 2w, tomorrow, or YYYY-MM-DD",
+                        until
+                    ),
+                };
+                t.snooze(wait_until);
+                store.update(&t)?;
+                println!(
+                    "Task snoozed until {}: {}",
+                    wait_until.format("%Y-%m-%d %H:%M"),
+                    t.title
+                );
+                Ok(())
+            }
+            None => {
+                eprintln!("Task not found: {}", id);
+                Ok(())
+            }
+        },
+
         cli::Commands::Delete { id } => {
             let deleted = store.delete(&id)?;
             if deleted {
@@ -434,16 +533,379 @@ fn handle_command(command: cli::Commands, store: &Arc<dyn store::Store>, json: b
             Ok(())
         }
 
-        cli::Commands::Sprint { command } => handle_sprint_command(command, store),
+        cli::Commands::Sprint { command } => handle_sprint_command(command, store, json),
+        cli::Commands::Session { command } => handle_session_command(command, store, json),
+        cli::Commands::Init => {
+            let path = store::layered::init_local_store(&std::env::current_dir()?)?;
+            if json {
+                println!("{}", serde_json::json!({ "path": path }));
+            } else {
+                println!("✓ Local task store: {}", path.display());
+                println!(
+                    "  New tasks created here go to this store; use --store global to override."
+                );
+            }
+            Ok(())
+        }
+
+        cli::Commands::Report {
+            kind,
+            project,
+            sprint,
+            since,
+            until,
+            template,
+            output,
+        } => {
+            let now = chrono::Utc::now();
+            let parse_date =
+                |input: Option<String>| -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+                    match input {
+                        Some(s) => match report::parse_report_date(&s, now) {
+                            Some(date) => Ok(Some(date)),
+                            None => anyhow::bail!("Invalid date: {}", s),
+                        },
+                        None => Ok(None),
+                    }
+                };
+            let kind = report::ReportKind::from(kind);
+            let options = report::ReportOptions {
+                project,
+                sprint_id: sprint,
+                since: parse_date(since)?,
+                until: parse_date(until)?,
+            };
+            let report = report::generate(store.as_ref(), kind, &options)?;
+
+            let rendered = if json {
+                serde_json::to_string_pretty(&report.to_json())?
+            } else {
+                report.render(&report::load_template(kind, template.as_deref())?)
+            };
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, rendered)?;
+                    eprintln!("Wrote {} to {}", report.title(), path.display());
+                }
+                None => println!("{}", rendered.trim_end()),
+            }
+            Ok(())
+        }
+
+        cli::Commands::Scan {
+            dir,
+            project,
+            dry_run,
+        } => {
+            let options = scan::ScanOptions { project, dry_run };
+            let report = scan::scan(store.as_ref(), &dir, &options)?;
+
+            let location = |t: &Task| t.source.as_ref().map(|a| a.location()).unwrap_or_default();
+
+            if json {
+                let summary = |tasks: &[Task]| -> Vec<serde_json::Value> {
+                    tasks
+                        .iter()
+                        .map(|t| {
+                            serde_json::json!({
+                                "id": t.id,
+                                "title": t.title,
+                                "location": location(t)
+                            })
+                        })
+                        .collect()
+                };
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "root": report.root,
+                        "project": report.project,
+                        "comments": report.comments,
+                        "created": summary(&report.created),
+                        "updated": summary(&report.updated),
+                        "completed": summary(&report.completed),
+                        "dry_run": dry_run
+                    })
+                );
+                return Ok(());
+            }
+
+            println!(
+                "Scanned {} ({} comments, project: {}){}\n",
+                report.root.display(),
+                report.comments,
+                report.project,
+                if dry_run { " [dry run]" } else { "" }
+            );
+            for task in &report.created {
+                println!(
+                    "  + {} {} ({})",
+                    task.priority.emoji(),
+                    task.title,
+                    location(task)
+                );
+            }
+            for task in &report.updated {
+                println!("  ~ {} ({})", task.title, location(task));
+            }
+            for task in &report.completed {
+                println!("  ✓ {} ({})", task.title, location(task));
+            }
+            println!(
+                "\n{} created, {} updated, {} completed",
+                report.created.len(),
+                report.updated.len(),
+                report.completed.len()
+            );
+            Ok(())
+        }
+        #[cfg(feature = "vault")]
+        cli::Commands::Vault { command } => handle_vault_command(command, store, json),
+    }
+}
+
+/// Print where a sprint's unfinished tasks went
+fn print_rollover(rollover: &store::planning::Rollover) {
+    if rollover.moved.is_empty() {
+        println!("No unfinished tasks to carry over.");
+        return;
+    }
+    println!(
+        "Carried {} unfinished task(s) over to {}:",
+        rollover.moved.len(),
+        rollover.target_name()
+    );
+    for task in &rollover.moved {
+        println!("  {} {}", task.priority.emoji(), task.title);
+    }
+}
+
+/// Print a task's linked vault notes (titles resolved with the vault feature)
+fn print_context_notes(task: &Task) -> Result<()> {
+    if !task.has_context_notes() {
+        return Ok(());
+    }
+    println!("\nContext notes:");
+
+    #[cfg(feature = "vault")]
+    if let Some(vault) = vault::Vault::open_default()? {
+        for id in &task.context_notes {
+            match vault.resolve(id)? {
+                Some(note) => println!("  📝 {} ({})", note.title, note.path),
+                None => println!("  ✗ {} (missing from vault)", id),
+            }
+        }
+        return Ok(());
+    }
+
+    for id in &task.context_notes {
+        println!("  📝 {}", id);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "vault")]
+fn handle_vault_command(
+    command: cli::VaultCommands,
+    store: &Arc<dyn store::Store>,
+    json: bool,
+) -> Result<()> {
+    use cli::VaultCommands;
+
+    let Some(vault) = vault::Vault::open_default()? else {
+        anyhow::bail!(
+            "No vault found at {}. Run `vulcan-vault init` first.",
+            vulcan_vault::db_path().display()
+        );
+    };
+
+    match command {
+        VaultCommands::Check { prune } => {
+            let dangling = vault::check_links(store.as_ref(), &vault, prune)?;
+
+            if json {
+                let tasks: Vec<_> = dangling
+                    .iter()
+                    .map(|d| {
+                        serde_json::json!({
+                            "task_id": d.task_id,
+                            "title": d.title,
+                            "missing": d.missing
+                        })
+                    })
+                    .collect();
+                println!(
+                    "{}",
+                    serde_json::json!({ "dangling": tasks, "pruned": prune })
+                );
+            } else if dangling.is_empty() {
+                println!("All context note links resolve.");
+            } else {
+                let links: usize = dangling.iter().map(|d| d.missing.len()).sum();
+                let verb = if prune { "Removed" } else { "Found" };
+                println!(
+                    "{} {} dangling link(s) in {} task(s):\n",
+                    verb,
+                    links,
+                    dangling.len()
+                );
+                for d in &dangling {
+                    println!("  {} {}", &d.task_id[..8.min(d.task_id.len())], d.title);
+                    for id in &d.missing {
+                        println!("      ✗ {}", id);
+                    }
+                }
+                if !prune {
+                    println!("\nRun with --prune to remove them.");
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn handle_session_command(
+    command: cli::SessionCommands,
+    store: &Arc<dyn store::Store>,
+    json: bool,
+) -> Result<()> {
+    use cli::SessionCommands;
+    use store::session;
+
+    match command {
+        SessionCommands::List => {
+            let sessions = session::list_sessions(store.as_ref())?;
+
+            if json {
+                let sessions: Vec<_> = sessions
+                    .iter()
+                    .map(|s| {
+                        serde_json::json!({
+                            "id": s.id,
+                            "total": s.total,
+                            "active": s.active,
+                            "done": s.done,
+                            "last_activity": s.last_activity.to_rfc3339()
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::json!({ "sessions": sessions }));
+            } else if sessions.is_empty() {
+                println!("No session-scoped tasks.");
+            } else {
+                let current = std::env::var("OPENCODE_SESSION_ID").ok();
+                println!("Sessions ({}):\n", sessions.len());
+                println!(
+                    "  {:<24} {:>6} {:>6} {:>6}  LAST ACTIVITY",
+                    "SESSION", "OPEN", "DONE", "TOTAL"
+                );
+                println!("  {:-<64}", "-");
+                for s in sessions {
+                    let marker = if current.as_deref() == Some(s.id.as_str()) {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    println!(
+                        "{} {:<24} {:>6} {:>6} {:>6}  {}",
+                        marker,
+                        s.id,
+                        s.active,
+                        s.done,
+                        s.total,
+                        s.last_activity.format("%Y-%m-%d %H:%M")
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        SessionCommands::Show { id } => {
+            let tasks = session::session_tasks(store.as_ref(), &id)?;
+            if tasks.is_empty() {
+                eprintln!("No tasks in session: {}", id);
+                return Ok(());
+            }
+            println!("Tasks in session {} ({}):\n", id, tasks.len());
+            for task in tasks {
+                let status = match task.status {
+                    models::Status::Done => "[✓]",
+                    models::Status::Archived => "[▣]",
+                    _ => "[ ]",
+                };
+                println!(
+                    "  {} {} {} ({})",
+                    status,
+                    task.priority.emoji(),
+                    task.title,
+                    &task.id[..8]
+                );
+            }
+            Ok(())
+        }
+
+        SessionCommands::Promote { id, project, all } => {
+            let promoted = session::promote_session(store.as_ref(), &id, project.as_deref(), all)?;
+            if promoted.is_empty() {
+                println!("No tasks to promote in session: {}", id);
+            } else {
+                let target = project
+                    .map(|p| format!("project '{}'", p))
+                    .unwrap_or_else(|| "global scope".to_string());
+                println!("Promoted {} task(s) to {}:", promoted.len(), target);
+                for task in promoted {
+                    println!("  {} {}", &task.id[..8], task.title);
+                }
+            }
+            Ok(())
+        }
+
+        SessionCommands::Expire { after, dry_run } => {
+            let inactive_for = match models::parse_duration(&after) {
+                Some(d) => d,
+                None => anyhow::bail!("Invalid duration: {} (use 12h, 7d, 2w)", after),
+            };
+            let current = std::env::var("OPENCODE_SESSION_ID").ok();
+            let expired = session::expire_sessions(
+                store.as_ref(),
+                inactive_for,
+                chrono::Utc::now(),
+                current.as_deref(),
+                dry_run,
+            )?;
+
+            if expired.is_empty() {
+                println!("No sessions inactive for more than {}.", after);
+                return Ok(());
+            }
+
+            let verb = if dry_run { "Would expire" } else { "Expired" };
+            println!("{} {} session(s):\n", verb, expired.len());
+            for s in expired {
+                println!(
+                    "  {} (last active {}): {} completed and {} unfinished archived",
+                    s.id,
+                    s.last_activity.format("%Y-%m-%d"),
+                    s.archived_done,
+                    s.archived.len()
+                );
+                for task in s.archived {
+                    println!("    ▣ {} {}", &task.id[..8], task.title);
+                }
+            }
+            Ok(())
+        }
     }
 }
 
 fn handle_sprint_command(
     command: cli::SprintCommands,
     store: &Arc<dyn store::Store>,
+    json: bool,
 ) -> Result<()> {
     use cli::SprintCommands;
     use models::Sprint;
+    use store::planning;
 
     match command {
         SprintCommands::List { project, status } => {
@@ -504,9 +966,11 @@ fn handle_sprint_command(
             goal,
             start: _,
             end: _,
+            capacity,
         } => {
             let mut sprint = Sprint::new(name.clone(), project);
             sprint.goal = goal;
+            sprint.capacity = capacity;
             // TODO: Parse start/end dates if provided
 
             store.add_sprint(&sprint)?;
@@ -516,6 +980,9 @@ fn handle_sprint_command(
             if let Some(g) = &sprint.goal {
                 println!("Goal: {}", g);
             }
+            if let Some(c) = sprint.capacity {
+                println!("Capacity: {} points", c);
+            }
             Ok(())
         }
 
@@ -530,6 +997,9 @@ fn handle_sprint_command(
                     if let Some(goal) = &s.goal {
                         println!("Goal: {}", goal);
                     }
+                    if let Some(capacity) = s.capacity {
+                        println!("Capacity: {} pts", capacity);
+                    }
                     if let Some(start) = s.start_date {
                         println!("Started: {}", start.format("%Y-%m-%d"));
                     }
@@ -571,20 +1041,79 @@ fn handle_sprint_command(
             }
         }
 
-        SprintCommands::Complete { id } => {
-            let sprint = store.get_sprint(&id)?;
-            match sprint {
-                Some(mut s) => {
-                    s.complete();
-                    store.update_sprint(&s)?;
-                    println!("Sprint completed: {}", s.name);
-                    Ok(())
-                }
-                None => {
-                    eprintln!("Sprint not found: {}", id);
-                    Ok(())
-                }
+        SprintCommands::Complete {
+            id,
+            to,
+            no_rollover,
+        } => {
+            let (sprint, rollover) =
+                planning::complete_sprint(store.as_ref(), &id, !no_rollover, to.as_deref())?;
+            println!("Sprint completed: {}", sprint.name);
+            if let Some(rollover) = rollover {
+                print_rollover(&rollover);
             }
+            Ok(())
+        }
+
+        SprintCommands::Rollover { id, to } => {
+            let rollover = planning::rollover_sprint(store.as_ref(), &id, to.as_deref())?;
+            print_rollover(&rollover);
+            Ok(())
+        }
+
+        SprintCommands::Plan { id, budget, apply } => {
+            let plan = planning::plan_sprint(store.as_ref(), &id, budget, apply)?;
+
+            if json {
+                let tasks: Vec<_> = plan
+                    .proposed
+                    .iter()
+                    .map(|t| {
+                        serde_json::json!({
+                            "id": t.id,
+                            "title": t.title,
+                            "priority": t.priority.to_string(),
+                            "estimate": t.points()
+                        })
+                    })
+                    .collect();
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "sprint_id": plan.sprint.id,
+                        "budget": plan.budget,
+                        "committed": plan.committed,
+                        "planned": plan.planned,
+                        "skipped": plan.skipped,
+                        "applied": plan.applied,
+                        "tasks": tasks
+                    })
+                );
+                return Ok(());
+            }
+
+            println!(
+                "Plan for {}: {} committed + {} planned / {} points\n",
+                plan.sprint.name, plan.committed, plan.planned, plan.budget
+            );
+            for (i, task) in plan.proposed.iter().enumerate() {
+                println!(
+                    "{}. {} {} ({} pt)",
+                    i + 1,
+                    task.priority.emoji(),
+                    task.title,
+                    task.points()
+                );
+            }
+            if plan.skipped > 0 {
+                println!("\n{} backlog task(s) didn't fit the budget.", plan.skipped);
+            }
+            if plan.applied {
+                println!("\nAdded {} task(s) to the sprint.", plan.proposed.len());
+            } else if !plan.proposed.is_empty() {
+                println!("\nRun with --apply to add them to the sprint.");
+            }
+            Ok(())
         }
 
         SprintCommands::Delete { id } => {
@@ -7,12 +7,14 @@ use crate::mcp::protocol::{
     ListToolsResult, ServerCapabilities, ServerInfo, ToolCallResult, ToolDefinition,
     PROTOCOL_VERSION,
 };
-use crate::mcp::tools::{get_tools, ToolContext};
+use crate::mcp::tools::{get_tools, workspace_args, ToolContext};
+use crate::store::layered::{self, WorkspaceOptions};
 use crate::store::Store;
 use anyhow::Result;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::env;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tracing::{debug, error, info};
@@ -20,9 +22,16 @@ use tracing::{debug, error, info};
 // D-Bus imports (for future session detection)
 // use zbus::{Connection, ConnectionBuilder};
 
+/// Id of the `roots/list` request the server sends the client
+const ROOTS_REQUEST_ID: &str = "vulcan-todo-roots";
+
 /// MCP Server for vulcan-todo
 pub struct McpServer {
     ctx: ToolContext,
+    /// The client declared the roots capability in initialize
+    client_roots: bool,
+    /// A workspace was chosen with set_workspace; client roots don't replace it
+    workspace_pinned: bool,
 }
 
 /// Detect OpenCode session ID from environment variable or D-Bus
@@ -43,6 +52,8 @@ impl McpServer {
     pub fn new(store: Arc<dyn Store>) -> Self {
         Self {
             ctx: ToolContext::new(store),
+            client_roots: false,
+            workspace_pinned: false,
         }
     }
 
@@ -63,6 +74,14 @@ impl McpServer {
                 continue;
             }
 
+            // Responses to our own requests (roots/list) have no method
+            if let Ok(message) = serde_json::from_str::<Value>(line) {
+                if message.get("method").is_none() && message.get("id").is_some() {
+                    self.handle_client_response(&message);
+                    continue;
+                }
+            }
+
             // Parse request
             let parse_result: Result<JsonRpcRequest, _> = serde_json::from_str(&line);
 
@@ -95,6 +114,7 @@ impl McpServer {
 
             // Check if this is a notification (no response needed)
             let is_notification = req.is_notification();
+            let roots_request = self.roots_request(&req.method);
 
             // Handle request
             let response = self.handle_request(req).await;
@@ -116,6 +136,19 @@ impl McpServer {
                     break;
                 }
             }
+
+            // Ask the client where it is working once it's ready
+            if let Some(request) = roots_request {
+                let request_json = serde_json::to_string(&request)? + "\n";
+                if let Err(e) = stdout.write_all(request_json.as_bytes()).await {
+                    error!("Failed to write roots request: {}", e);
+                    break;
+                }
+                if let Err(e) = stdout.flush().await {
+                    error!("Failed to flush roots request: {}", e);
+                    break;
+                }
+            }
         }
 
         info!("MCP Server shutting down");
@@ -163,6 +196,7 @@ impl McpServer {
 
         // Store session_id in context for tool functions
         self.ctx.session_id = session_id;
+        self.client_roots = req.params.pointer("/capabilities/roots").is_some();
 
         let tools = get_tools();
         let capabilities = ServerCapabilities {
@@ -217,6 +251,54 @@ impl McpServer {
         JsonRpcResponse::success(req.id, serde_json::to_value(result).unwrap())
     }
 
+    /// Reopen the store for the workspace named in `set_workspace` args
+    fn switch_workspace(&mut self, arguments: &Value) -> Result<(), String> {
+        let (path, options) = workspace_args(&self.ctx, arguments)?;
+        self.ctx.store = layered::open_workspace(&path, options).map_err(|e| e.to_string())?;
+        self.workspace_pinned = true;
+        info!("Switched workspace to {}", path.display());
+        Ok(())
+    }
+
+    /// `roots/list` request to send after `method`, if the client's roots
+    /// may still choose the workspace
+    fn roots_request(&self, method: &str) -> Option<Value> {
+        let ready = matches!(
+            method,
+            "notifications/initialized" | "notifications/roots/list_changed"
+        );
+        (ready && self.client_roots && self.ctx.workspace.is_some() && !self.workspace_pinned)
+            .then(|| json!({ "jsonrpc": "2.0", "id": ROOTS_REQUEST_ID, "method": "roots/list" }))
+    }
+
+    /// Handle a response from the client: `roots/list` opens the workspace
+    /// of the first root, unless `set_workspace` chose one since
+    fn handle_client_response(&mut self, message: &Value) {
+        if message.get("id").and_then(|id| id.as_str()) != Some(ROOTS_REQUEST_ID) {
+            debug!("Ignoring unexpected client response: {}", message);
+            return;
+        }
+        let (Some(options), false) = (self.ctx.workspace, self.workspace_pinned) else {
+            return;
+        };
+        let Some(path) = message
+            .pointer("/result/roots/0/uri")
+            .and_then(|uri| uri.as_str())
+            .and_then(root_path)
+        else {
+            debug!("Client sent no usable roots: {}", message);
+            return;
+        };
+
+        match layered::open_workspace(&path, options) {
+            Ok(store) => {
+                self.ctx.store = store;
+                info!("Using workspace from client root {}", path.display());
+            }
+            Err(e) => error!("Failed to open workspace {}: {}", path.display(), e),
+        }
+    }
+
     /// Handle tools/call request
     async fn handle_tool_call(&mut self, req: JsonRpcRequest) -> JsonRpcResponse {
         let tool_name = match req.tool_name() {
@@ -243,7 +325,19 @@ impl McpServer {
         match tool {
             Some(tool) => {
                 info!("Executing tool: {}", tool_name);
-                let result = (tool.function)(&self.ctx, arguments);
+                let mut result = (tool.function)(&self.ctx, arguments.clone());
+
+                // Hook stderr can't go to stdout, which carries the protocol
+                for line in self.ctx.store.take_hook_output() {
+                    info!("Hook output: {}", line);
+                }
+
+                // Tools only see the context; switching stores happens here
+                if tool.name == "set_workspace" && result.success {
+                    if let Err(e) = self.switch_workspace(&arguments) {
+                        result = crate::mcp::tools::ToolResult::error(e);
+                    }
+                }
 
                 let content = vec![ContentItem::text(if result.success {
                     serde_json::to_string_pretty(&result.to_json())
@@ -266,9 +360,39 @@ impl McpServer {
     }
 }
 
+/// Directory of a `file://` root URI
+fn root_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| encoded.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    let path = PathBuf::from(String::from_utf8(decoded).ok()?);
+    path.is_dir().then_some(path)
+}
+
 /// Run MCP server
-pub async fn run_mcp_server(store: Arc<dyn Store>) -> Result<()> {
+pub async fn run_mcp_server(
+    store: Arc<dyn Store>,
+    workspace: Option<WorkspaceOptions>,
+) -> Result<()> {
     let mut server = McpServer::new(store);
+    server.ctx.workspace = workspace;
     server.run_stdio().await
 }
 
@@ -331,4 +455,43 @@ mod tests {
             _ => panic!("Expected success response"),
         }
     }
+
+    #[test]
+    fn test_root_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let spaced = dir.path().join("my repo");
+        std::fs::create_dir(&spaced).unwrap();
+        let uri = format!("file://{}", dir.path().display()) + "/my%20repo";
+        assert_eq!(root_path(&uri), Some(spaced));
+        assert_eq!(root_path("https://example.com/repo"), None);
+        assert_eq!(root_path("file:///no/such/dir"), None);
+    }
+
+    #[tokio::test]
+    async fn test_roots_request_until_workspace_set() {
+        let store = Arc::new(MemoryStore::new()) as Arc<dyn Store>;
+        let mut server = McpServer::new(store);
+        server.ctx.workspace = Some(WorkspaceOptions::default());
+
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Value::Number(1.into()),
+            method: "initialize".to_string(),
+            params: serde_json::json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": { "roots": { "listChanged": true } },
+                "clientInfo": { "name": "test-client" }
+            }),
+        };
+        server.handle_initialize(request).await;
+        assert!(server.roots_request("tools/list").is_none());
+        let roots = server.roots_request("notifications/initialized").unwrap();
+        assert_eq!(roots["method"], "roots/list");
+
+        // set_workspace wins over the client's roots
+        server.workspace_pinned = true;
+        assert!(server
+            .roots_request("notifications/roots/list_changed")
+            .is_none());
+    }
 }
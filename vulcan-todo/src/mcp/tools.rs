@@ -2,15 +2,25 @@
 //!
 //! Defines all available MCP tools for task management operations.
 
-use crate::models::{Priority, Sprint, SprintStatus, Status, Task};
+use crate::models::search::Query;
+use crate::models::{parse_wait_until, Priority, Sprint, SprintStatus, Status, Task};
+use crate::report::{self, ReportKind, ReportOptions};
+use crate::store::claim;
+use crate::store::layered::{self, StoreTarget, WorkspaceOptions};
+use crate::store::planning;
+use crate::store::session;
 use crate::store::Store;
 use serde_json::{json, Value};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// Context for MCP tool execution
 pub struct ToolContext {
     pub store: Arc<dyn Store>,
     pub session_id: Option<String>,
+    /// How to reopen the store for another working directory
+    /// (None when the store path is fixed)
+    pub workspace: Option<WorkspaceOptions>,
 }
 
 impl ToolContext {
@@ -19,6 +29,7 @@ impl ToolContext {
         Self {
             store,
             session_id: None,
+            workspace: None,
         }
     }
 }
@@ -117,6 +128,11 @@ pub fn get_tools() -> Vec<Tool> {
                         "type": "integer",
                         "description": "Maximum number of tasks to return",
                         "default": 50
+                    },
+                    "include_waiting": {
+                        "type": "boolean",
+                        "description": "Include tasks snoozed until a later date (default: false)",
+                        "default": false
                     }
                 }
             }),
@@ -169,6 +185,14 @@ pub fn get_tools() -> Vec<Tool> {
                         "type": "string",
                         "description": "Due date in ISO format (YYYY-MM-DD)"
                     },
+                    "wait_until": {
+                        "type": "string",
+                        "description": "Hide the task until then: relative (3d, 4h, 2w), 'tomorrow', or YYYY-MM-DD"
+                    },
+                    "estimate": {
+                        "type": "integer",
+                        "description": "Estimate in story points, used by plan_sprint"
+                    },
                     "auto_fetch_context": {
                         "type": "boolean",
                         "description": "Auto-fetch context from vulcan-vault when task starts (default: false)"
@@ -177,6 +201,12 @@ pub fn get_tools() -> Vec<Tool> {
                         "type": "array",
                         "items": {"type": "string"},
                         "description": "UUIDs of linked vulcan-vault notes"
+                    },
+                    "scope": {
+                        "type": "string",
+                        "enum": ["session", "global"],
+                        "description": "'session' scopes the task to the current session; 'global' (default) makes it visible everywhere",
+                        "default": "global"
                     }
                 },
                 "required": ["title"]
@@ -215,6 +245,14 @@ pub fn get_tools() -> Vec<Tool> {
                         "type": "string",
                         "description": "New project name (set to null to remove)"
                     },
+                    "wait_until": {
+                        "type": "string",
+                        "description": "Hide the task until then: relative (3d, 4h, 2w), 'tomorrow', or YYYY-MM-DD (null to clear)"
+                    },
+                    "estimate": {
+                        "type": "integer",
+                        "description": "Estimate in story points (0 or null to clear)"
+                    },
                     "auto_fetch_context": {
                         "type": "boolean",
                         "description": "Auto-fetch context from vulcan-vault when task starts"
@@ -290,7 +328,10 @@ pub fn get_tools() -> Vec<Tool> {
         ),
         Tool::new(
             "search_tasks".to_string(),
-            "Search tasks by title, description, or tags".to_string(),
+            "Fuzzy search across task title, tags, project, description and source location. \
+             Tolerates typos and matches word prefixes; results are ranked best first with a \
+             relevance score from 0 to 1."
+                .to_string(),
             json!({
                 "type": "object",
                 "properties": {
@@ -308,6 +349,87 @@ pub fn get_tools() -> Vec<Tool> {
             }),
             search_tasks,
         ),
+        Tool::new(
+            "snooze_task".to_string(),
+            "Snooze a task: hide it from list_tasks, get_next_task and get_context until a later time. \
+             Accepts relative durations like '3d', '4h', '2w', 'tomorrow', or a YYYY-MM-DD date."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "The task ID to snooze"
+                    },
+                    "until": {
+                        "type": "string",
+                        "description": "How long to wait (e.g., '3d', '4h', 'tomorrow', '2026-01-15')",
+                        "default": "1d"
+                    },
+                    "clear": {
+                        "type": "boolean",
+                        "description": "Clear the wait date so the task shows up again",
+                        "default": false
+                    }
+                },
+                "required": ["id"]
+            }),
+            snooze_task,
+        ),
+        Tool::new(
+            "list_sessions".to_string(),
+            "List sessions that own session-scoped tasks, with open/done counts and last activity."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {}
+            }),
+            list_sessions,
+        ),
+        Tool::new(
+            "end_session".to_string(),
+            "End a session: archives its completed scratch tasks and returns the unfinished ones \
+             for handoff. Unfinished tasks stay in the session (adopt them later with \
+             adopt_session_tasks) unless promote is true, which moves them to the global scope."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "session_id": {
+                        "type": "string",
+                        "description": "Session to end (default: the current session)"
+                    },
+                    "promote": {
+                        "type": "boolean",
+                        "description": "Move unfinished tasks to the global scope",
+                        "default": false
+                    }
+                }
+            }),
+            end_session,
+        ),
+        Tool::new(
+            "adopt_session_tasks".to_string(),
+            "Adopt unfinished tasks from another (usually ended) session into the current session, \
+             or into the global scope when not running in a session."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "from_session": {
+                        "type": "string",
+                        "description": "Session ID to take tasks from"
+                    },
+                    "task_ids": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Only adopt these tasks (default: all unfinished tasks)"
+                    }
+                },
+                "required": ["from_session"]
+            }),
+            adopt_session_tasks,
+        ),
         Tool::new(
             "get_stats".to_string(),
             "Get task statistics (pending, completed counts)".to_string(),
@@ -355,7 +477,8 @@ pub fn get_tools() -> Vec<Tool> {
         ),
         Tool::new(
             "get_next_task".to_string(),
-            "Get the highest priority pending task. Ideal for agents that need to pick up the most important next item. Returns task with highest priority level among pending tasks."
+            "Get the highest priority pending task. Ideal for agents that need to pick up the most important next item. Returns task with highest priority level among pending tasks. \
+             Tasks claimed by other agents are skipped; expired claims are released first."
                 .to_string(),
             json!({
                 "type": "object",
@@ -363,6 +486,14 @@ pub fn get_tools() -> Vec<Tool> {
                     "project": {
                         "type": "string",
                         "description": "Optional: get next task from specific project"
+                    },
+                    "claim": {
+                        "type": "boolean",
+                        "description": "Claim the returned task for this session (default: false)"
+                    },
+                    "ttl_minutes": {
+                        "type": "integer",
+                        "description": "Lease length in minutes when claiming (default: 30, max: 1440)"
                     }
                 }
             }),
@@ -388,6 +519,76 @@ pub fn get_tools() -> Vec<Tool> {
             }),
             complete_and_get_next,
         ),
+        Tool::new(
+            "claim_task".to_string(),
+            "Claim a task so other agents sharing the store skip it. Claims are leases: \
+             renew them with heartbeat before ttl_minutes runs out or they are released \
+             automatically. Claiming a task you already hold renews the lease."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Task ID to claim"
+                    },
+                    "ttl_minutes": {
+                        "type": "integer",
+                        "description": "Lease length in minutes (default: 30, max: 1440)"
+                    },
+                    "owner": {
+                        "type": "string",
+                        "description": "Claim owner (default: current session ID)"
+                    }
+                },
+                "required": ["id"]
+            }),
+            claim_task,
+        ),
+        Tool::new(
+            "heartbeat".to_string(),
+            "Renew the leases held by this session. Call periodically during long work \
+             so claimed tasks aren't released."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "task_id": {
+                        "type": "string",
+                        "description": "Optional: renew only this task (default: all claims of the owner)"
+                    },
+                    "ttl_minutes": {
+                        "type": "integer",
+                        "description": "New lease length in minutes from now (default: 30, max: 1440)"
+                    },
+                    "owner": {
+                        "type": "string",
+                        "description": "Claim owner (default: current session ID)"
+                    }
+                }
+            }),
+            heartbeat,
+        ),
+        Tool::new(
+            "release_task".to_string(),
+            "Release a claim so other agents can pick the task up. An in-progress task goes back to pending."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "Task ID to release"
+                    },
+                    "owner": {
+                        "type": "string",
+                        "description": "Claim owner (default: current session ID)"
+                    }
+                },
+                "required": ["id"]
+            }),
+            release_task,
+        ),
         Tool::new(
             "suggest_project".to_string(),
             "Suggest appropriate project based on task title. Uses keyword matching to recommend project names. Helpful for agents when creating tasks."
@@ -408,6 +609,7 @@ pub fn get_tools() -> Vec<Tool> {
             "start_task".to_string(),
             "Mark a task as in-progress. Use this when you begin working on a task. \
              This helps track which tasks are actively being worked on. \
+             If auto_fetch_context is set, the linked vault notes are returned in `context`. \
              Optionally enable ralph loop mode for iterative self-correction."
                 .to_string(),
             json!({
@@ -529,6 +731,10 @@ pub fn get_tools() -> Vec<Tool> {
                     "end_date": {
                         "type": "string",
                         "description": "Optional end date in ISO format (YYYY-MM-DD)"
+                    },
+                    "capacity": {
+                        "type": "integer",
+                        "description": "Point budget for plan_sprint (sum of task estimates)"
                     }
                 },
                 "required": ["name", "project"]
@@ -573,7 +779,7 @@ pub fn get_tools() -> Vec<Tool> {
         ),
         Tool::new(
             "update_sprint".to_string(),
-            "Update sprint details (name, status, dates, goal)."
+            "Update sprint details (name, status, dates, goal, capacity)."
                 .to_string(),
             json!({
                 "type": "object",
@@ -602,6 +808,10 @@ pub fn get_tools() -> Vec<Tool> {
                     "end_date": {
                         "type": "string",
                         "description": "New end date (YYYY-MM-DD)"
+                    },
+                    "capacity": {
+                        "type": "integer",
+                        "description": "New point budget (0 to clear)"
                     }
                 },
                 "required": ["id"]
@@ -642,7 +852,8 @@ pub fn get_tools() -> Vec<Tool> {
         ),
         Tool::new(
             "complete_sprint".to_string(),
-            "Mark a sprint as completed. Sets status to 'completed' and records end date if not set."
+            "Mark a sprint as completed. Sets status to 'completed' and records end date if not set. \
+             Unfinished tasks are carried over to the project's next planning sprint (or the backlog), keeping their order."
                 .to_string(),
             json!({
                 "type": "object",
@@ -650,12 +861,103 @@ pub fn get_tools() -> Vec<Tool> {
                     "id": {
                         "type": "string",
                         "description": "The sprint ID to complete"
+                    },
+                    "rollover": {
+                        "type": "boolean",
+                        "description": "Carry unfinished tasks over (default: true)"
+                    },
+                    "target_sprint_id": {
+                        "type": "string",
+                        "description": "Optional: sprint to carry unfinished tasks into (default: next planning sprint, else backlog)"
                     }
                 },
                 "required": ["id"]
             }),
             complete_sprint,
         ),
+        Tool::new(
+            "plan_sprint".to_string(),
+            "Propose backlog tasks to fill a sprint, by priority then due date, until the point budget \
+             (sum of task estimates; unestimated tasks count as 1) is reached. Set apply to add them to the sprint."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "sprint_id": {
+                        "type": "string",
+                        "description": "The sprint ID to plan"
+                    },
+                    "budget": {
+                        "type": "integer",
+                        "description": "Point budget (default: sprint capacity)"
+                    },
+                    "apply": {
+                        "type": "boolean",
+                        "description": "Add the proposed tasks to the sprint (default: false, only propose)"
+                    }
+                },
+                "required": ["sprint_id"]
+            }),
+            plan_sprint,
+        ),
+        Tool::new(
+            "generate_report".to_string(),
+            "Generate a standup or sprint review report: completed, started, in progress, blocked (tagged 'blocked') \
+             and overdue tasks, a per-project breakdown and sprint goal progress. Returns Markdown (from the user's \
+             template, if any) ready to post as a vault note, plus the same data as JSON."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "kind": {
+                        "type": "string",
+                        "enum": ["standup", "review"],
+                        "description": "standup (default range: last 24 hours) or review (default: the sprint, else the last 7 days)"
+                    },
+                    "project": {
+                        "type": "string",
+                        "description": "Only tasks in this project (reviews use the project's active sprint)"
+                    },
+                    "sprint_id": {
+                        "type": "string",
+                        "description": "Only tasks in this sprint"
+                    },
+                    "since": {
+                        "type": "string",
+                        "description": "Start of the range: YYYY-MM-DD or a duration ago (1d, 2w)"
+                    },
+                    "until": {
+                        "type": "string",
+                        "description": "End of the range (default: now)"
+                    }
+                },
+                "required": ["kind"]
+            }),
+            generate_report,
+        ),
+        Tool::new(
+            "set_workspace".to_string(),
+            "Switch to the task store for a working directory: its repository's .vulcan-todo/ store \
+             (if any) merged with the global store. Call this with the client's working directory \
+             when it differs from where the server was started."
+                .to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Absolute path of the working directory"
+                    },
+                    "store": {
+                        "type": "string",
+                        "enum": ["local", "global"],
+                        "description": "Where new tasks go (default: local if the repository has a store)"
+                    }
+                },
+                "required": ["path"]
+            }),
+            set_workspace,
+        ),
         Tool::new(
             "assign_task_to_sprint".to_string(),
             "Add a task to a sprint. The task will be added at the end of the sprint's task order."
@@ -763,6 +1065,10 @@ fn list_tasks(ctx: &ToolContext, args: Value) -> ToolResult {
     let project_filter = args.get("project").and_then(|v| v.as_str());
     let search_query = args.get("search").and_then(|v| v.as_str());
     let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(50) as usize;
+    let include_waiting = args
+        .get("include_waiting")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
 
     let tasks_result = ctx.store.get_all();
 
@@ -770,6 +1076,11 @@ fn list_tasks(ctx: &ToolContext, args: Value) -> ToolResult {
         Ok(all_tasks) => {
             let mut tasks: Vec<Task> = all_tasks;
 
+            // Hide snoozed tasks unless explicitly requested
+            if !include_waiting {
+                tasks.retain(|t| !t.is_waiting());
+            }
+
             // Apply filters
             if let Some(status) = status_filter {
                 if status != "all" {
@@ -826,7 +1137,9 @@ fn list_tasks(ctx: &ToolContext, args: Value) -> ToolResult {
                         "tags": t.tags,
                         "project": t.project,
                         "created_at": t.created_formatted(),
-                        "description": t.description
+                        "description": t.description,
+                        "wait_until": t.wait_formatted(),
+                        "claimed_by": t.claimed_by()
                     })
                 })
                 .collect();
@@ -865,7 +1178,11 @@ fn get_task(ctx: &ToolContext, args: Value) -> ToolResult {
                     "project": task.project,
                     "created_at": task.created_formatted(),
                     "completed_at": task.completed_at.map(|d| d.to_string()),
-                    "due_date": task.due_formatted()
+                    "due_date": task.due_formatted(),
+                    "wait_until": task.wait_formatted(),
+                    "waiting": task.is_waiting(),
+                    "scope": task.scope,
+                    "claim": claim_json(&task)
                 }
             })),
         ),
@@ -915,6 +1232,14 @@ fn create_task(ctx: &ToolContext, args: Value) -> ToolResult {
         .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
         .map(|d| d.with_timezone(&chrono::Utc));
 
+    let wait_until = match args.get("wait_until").and_then(|v| v.as_str()) {
+        Some(s) => match parse_wait_until(s, chrono::Utc::now()) {
+            Some(w) => Some(w),
+            None => return ToolResult::error(format!("Invalid wait_until: {}", s)),
+        },
+        None => None,
+    };
+
     // vulcan-vault integration fields
     let auto_fetch_context = args
         .get("auto_fetch_context")
@@ -931,12 +1256,22 @@ fn create_task(ctx: &ToolContext, args: Value) -> ToolResult {
         })
         .unwrap_or_default();
 
-    let mut task = Task::new(title);
+    let scope = match args.get("scope").and_then(|v| v.as_str()) {
+        Some("session") => ctx.session_id.as_deref().map(session::session_scope),
+        _ => None,
+    };
+
+    let mut task = Task::new_with_scope(title, scope);
     task.description = description;
     task.priority = priority;
     task.tags = tags;
     task.project = project;
     task.due_date = due_date;
+    task.wait_until = wait_until;
+    task.estimate = match points_arg(args, "estimate") {
+        Ok(estimate) => estimate,
+        Err(e) => return ToolResult::error(e),
+    };
     task.auto_fetch_context = auto_fetch_context;
     task.context_notes = context_notes;
 
@@ -949,7 +1284,10 @@ fn create_task(ctx: &ToolContext, args: Value) -> ToolResult {
                 "status": created.status.to_string(),
                 "priority": created.priority.to_string(),
                 "project": created.project,
-                "tags": created.tags
+                "tags": created.tags,
+                "wait_until": created.wait_formatted(),
+                "estimate": created.estimate,
+                "scope": created.scope
             })),
         ),
         Err(e) => ToolResult::error(format!("Failed to create task: {}", e)),
@@ -996,6 +1334,22 @@ fn update_task(ctx: &ToolContext, args: Value) -> ToolResult {
             task.project = Some(p.to_string());
         }
     }
+    if let Some(wait) = args.get("wait_until") {
+        if wait.is_null() {
+            task.unsnooze();
+        } else if let Some(w) = wait.as_str() {
+            match parse_wait_until(w, chrono::Utc::now()) {
+                Some(until) => task.snooze(until),
+                None => return ToolResult::error(format!("Invalid wait_until: {}", w)),
+            }
+        }
+    }
+    if args.contains_key("estimate") {
+        task.estimate = match points_arg(args, "estimate") {
+            Ok(estimate) => estimate.filter(|e| *e > 0),
+            Err(e) => return ToolResult::error(e),
+        };
+    }
     // vulcan-vault integration fields
     if let Some(auto_fetch) = args.get("auto_fetch_context").and_then(|v| v.as_bool()) {
         task.auto_fetch_context = auto_fetch;
@@ -1033,6 +1387,8 @@ fn update_task(ctx: &ToolContext, args: Value) -> ToolResult {
                 "priority": updated.priority.to_string(),
                 "project": updated.project,
                 "tags": updated.tags,
+                "wait_until": updated.wait_formatted(),
+                "estimate": updated.estimate,
                 "ralph_mode": updated.ralph_mode,
                 "success_criteria": updated.success_criteria,
                 "quality_gates": updated.quality_gates
@@ -1142,30 +1498,228 @@ fn search_tasks(ctx: &ToolContext, args: Value) -> ToolResult {
         Ok(mut tasks) => {
             tasks.truncate(limit);
 
+            let terms = Query::parse(query);
             let results: Vec<Value> = tasks
                 .iter()
                 .map(|t| {
+                    let score = terms.score(t).unwrap_or(0.0);
                     json!({
                         "id": t.id,
                         "title": t.title,
                         "status": t.status.to_string(),
                         "priority": t.priority.to_string(),
                         "tags": t.tags,
-                        "project": t.project
+                        "project": t.project,
+                        "score": (score * 1000.0).round() / 1000.0
+                    })
+                })
+                .collect();
+
+            ToolResult::success(
+                format!("Found {} tasks matching '{}'", results.len(), query),
+                Some(json!({
+                    "tasks": results,
+                    "query": query,
+                    "total": results.len()
+                })),
+            )
+        }
+        Err(e) => ToolResult::error(format!("Search failed: {}", e)),
+    }
+}
+
+fn snooze_task(ctx: &ToolContext, args: Value) -> ToolResult {
+    let empty_map = serde_json::map::Map::new();
+    let args = args.as_object().unwrap_or(&empty_map);
+
+    let id = match args.get("id").and_then(|v| v.as_str()) {
+        Some(id) => id,
+        None => return ToolResult::error("Missing required parameter: id".to_string()),
+    };
+    let clear = args.get("clear").and_then(|v| v.as_bool()).unwrap_or(false);
+    let until = args.get("until").and_then(|v| v.as_str()).unwrap_or("1d");
+
+    let mut task = match ctx.store.get(id) {
+        Ok(Some(task)) => task,
+        Ok(None) => return ToolResult::error(format!("Task not found: {}", id)),
+        Err(e) => return ToolResult::error(format!("Failed to get task: {}", e)),
+    };
+
+    if clear {
+        task.unsnooze();
+    } else {
+        match parse_wait_until(until, chrono::Utc::now()) {
+            Some(wait_until) => task.snooze(wait_until),
+            None => {
+                return ToolResult::error(format!(
+                    "Invalid duration '{}'. Use e.g. 3d, 4h, 2w, tomorrow, or YYYY-MM-DD",
+                    until
+                ))
+            }
+        }
+    }
+
+    match ctx.store.update(&task) {
+        Ok(_) => ToolResult::success(
+            if clear {
+                format!("Task unsnoozed: {}", task.title)
+            } else {
+                format!(
+                    "Task snoozed until {}: {}",
+                    task.wait_formatted().unwrap_or_default(),
+                    task.title
+                )
+            },
+            Some(json!({
+                "id": task.id,
+                "title": task.title,
+                "wait_until": task.wait_formatted(),
+                "waiting": task.is_waiting()
+            })),
+        ),
+        Err(e) => ToolResult::error(format!("Failed to snooze task: {}", e)),
+    }
+}
+
+/// Compact task summary for session handoffs
+fn session_task_json(task: &Task) -> Value {
+    json!({
+        "id": task.id,
+        "title": task.title,
+        "description": task.description,
+        "status": task.status.to_string(),
+        "priority": task.priority.to_string(),
+        "project": task.project,
+        "scope": task.scope
+    })
+}
+
+fn list_sessions(ctx: &ToolContext, _args: Value) -> ToolResult {
+    match session::list_sessions(ctx.store.as_ref()) {
+        Ok(sessions) => {
+            let sessions: Vec<Value> = sessions
+                .iter()
+                .map(|s| {
+                    json!({
+                        "id": s.id,
+                        "active": s.active,
+                        "done": s.done,
+                        "total": s.total,
+                        "last_activity": s.last_activity.to_rfc3339(),
+                        "current": ctx.session_id.as_deref() == Some(s.id.as_str())
                     })
                 })
                 .collect();
+            ToolResult::success(
+                format!("Found {} sessions", sessions.len()),
+                Some(json!({ "sessions": sessions })),
+            )
+        }
+        Err(e) => ToolResult::error(format!("Failed to list sessions: {}", e)),
+    }
+}
+
+fn end_session(ctx: &ToolContext, args: Value) -> ToolResult {
+    let empty_map = serde_json::map::Map::new();
+    let args = args.as_object().unwrap_or(&empty_map);
+
+    let session_id = match args
+        .get("session_id")
+        .and_then(|v| v.as_str())
+        .or(ctx.session_id.as_deref())
+    {
+        Some(id) => id.to_string(),
+        None => {
+            return ToolResult::error(
+                "No current session; pass session_id to end a specific session".to_string(),
+            )
+        }
+    };
+    let promote = args
+        .get("promote")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    match session::end_session(ctx.store.as_ref(), &session_id, promote) {
+        Ok(handoff) => {
+            let unfinished: Vec<Value> = handoff.unfinished.iter().map(session_task_json).collect();
+            let handoff_note = if handoff.unfinished.is_empty() {
+                "No unfinished tasks.".to_string()
+            } else if handoff.promoted {
+                format!(
+                    "{} unfinished task(s) moved to the global scope.",
+                    unfinished.len()
+                )
+            } else {
+                format!(
+                    "{} unfinished task(s) left for handoff; continue them with \
+                     adopt_session_tasks(from_session: \"{}\").",
+                    unfinished.len(),
+                    session_id
+                )
+            };
+
+            ToolResult::success(
+                format!(
+                    "Session {} ended: {} completed task(s) archived. {}",
+                    session_id, handoff.archived_done, handoff_note
+                ),
+                Some(json!({
+                    "session_id": session_id,
+                    "archived_done": handoff.archived_done,
+                    "promoted": handoff.promoted,
+                    "unfinished": unfinished
+                })),
+            )
+        }
+        Err(e) => ToolResult::error(format!("Failed to end session: {}", e)),
+    }
+}
+
+fn adopt_session_tasks(ctx: &ToolContext, args: Value) -> ToolResult {
+    let empty_map = serde_json::map::Map::new();
+    let args = args.as_object().unwrap_or(&empty_map);
+
+    let from_session = match args.get("from_session").and_then(|v| v.as_str()) {
+        Some(id) => id,
+        None => return ToolResult::error("Missing required parameter: from_session".to_string()),
+    };
+    let task_ids: Vec<String> = args
+        .get("task_ids")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
 
+    match session::adopt_session_tasks(
+        ctx.store.as_ref(),
+        from_session,
+        ctx.session_id.as_deref(),
+        &task_ids,
+    ) {
+        Ok(adopted) => {
+            let target = match &ctx.session_id {
+                Some(id) => format!("session {}", id),
+                None => "global scope".to_string(),
+            };
+            let tasks: Vec<Value> = adopted.iter().map(session_task_json).collect();
             ToolResult::success(
-                format!("Found {} tasks matching '{}'", results.len(), query),
+                format!(
+                    "Adopted {} task(s) from session {} into {}",
+                    tasks.len(),
+                    from_session,
+                    target
+                ),
                 Some(json!({
-                    "tasks": results,
-                    "query": query,
-                    "total": results.len()
+                    "adopted": tasks,
+                    "total": tasks.len()
                 })),
             )
         }
-        Err(e) => ToolResult::error(format!("Search failed: {}", e)),
+        Err(e) => ToolResult::error(format!("Failed to adopt tasks: {}", e)),
     }
 }
 
@@ -1286,23 +1840,96 @@ fn migrate_projects(ctx: &ToolContext, _args: Value) -> ToolResult {
     }
 }
 
+/// Optional point count (estimate, capacity, budget) from a whole-number argument
+fn points_arg(args: &serde_json::Map<String, Value>, name: &str) -> Result<Option<u32>, String> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .and_then(|n| u32::try_from(n).ok())
+            .map(Some)
+            .ok_or_else(|| format!("Invalid {}: expected a whole number of points", name)),
+    }
+}
+
+/// Claim owner for a tool call: explicit `owner` argument or the session ID
+fn claim_owner(ctx: &ToolContext, args: &serde_json::Map<String, Value>) -> Option<String> {
+    args.get("owner")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .or_else(|| ctx.session_id.clone())
+}
+
+/// Lease length from `ttl_minutes` (default 30, at most a day)
+fn lease_ttl(args: &serde_json::Map<String, Value>) -> chrono::Duration {
+    let minutes = args
+        .get("ttl_minutes")
+        .and_then(|v| v.as_i64())
+        .filter(|m| *m > 0)
+        .unwrap_or(claim::DEFAULT_LEASE_MINUTES)
+        .min(claim::MAX_LEASE_MINUTES);
+    chrono::Duration::minutes(minutes)
+}
+
+/// Claim details for task output (null when unclaimed or expired)
+fn claim_json(task: &Task) -> Value {
+    match task.active_claim(chrono::Utc::now()) {
+        Some(c) => json!({
+            "owner": c.owner,
+            "claimed_at": c.claimed_at.to_rfc3339(),
+            "expires_at": c.expires_at.to_rfc3339()
+        }),
+        None => Value::Null,
+    }
+}
+
+/// Highest priority pending task that isn't snoozed or claimed by someone else.
+/// Expired claims are released first.
+fn next_available_task(
+    ctx: &ToolContext,
+    project: Option<&str>,
+    owner: Option<&str>,
+) -> anyhow::Result<Option<Task>> {
+    let now = chrono::Utc::now();
+    claim::release_expired_claims(ctx.store.as_ref(), now)?;
+
+    Ok(ctx
+        .store
+        .get_all()?
+        .into_iter()
+        .filter(|t| t.is_pending() && !t.is_waiting() && !t.is_claimed_by_other(owner, now))
+        .filter(|t| project.map(|p| t.belongs_to_project(p)).unwrap_or(true))
+        .max_by_key(|t| t.priority.level()))
+}
+
 fn get_next_task(ctx: &ToolContext, args: Value) -> ToolResult {
     let empty_map = serde_json::map::Map::new();
     let args = args.as_object().unwrap_or(&empty_map);
     let project = args.get("project").and_then(|v| v.as_str());
+    let take_claim = args.get("claim").and_then(|v| v.as_bool()).unwrap_or(false);
+    let owner = claim_owner(ctx, args);
 
-    match ctx.store.get_all() {
-        Ok(all_tasks) => {
-            let filtered: Vec<Task> = match project {
-                Some(p) => all_tasks
-                    .into_iter()
-                    .filter(|t| t.is_pending() && t.belongs_to_project(p))
-                    .collect(),
-                None => all_tasks.into_iter().filter(|t| t.is_pending()).collect(),
-            };
+    if take_claim && owner.is_none() {
+        return ToolResult::error("No session to claim for; pass owner".to_string());
+    }
 
-            // Get highest priority pending task
-            let next = filtered.into_iter().max_by_key(|t| t.priority.level());
+    match next_available_task(ctx, project, owner.as_deref()) {
+        Ok(next) => {
+            let next = match (next, owner.as_deref()) {
+                (Some(task), Some(owner)) if take_claim => {
+                    match claim::claim_task(
+                        ctx.store.as_ref(),
+                        &task.id,
+                        owner,
+                        lease_ttl(args),
+                        chrono::Utc::now(),
+                    ) {
+                        Ok(claimed) => Some(claimed),
+                        Err(e) => return ToolResult::error(format!("Failed to claim task: {}", e)),
+                    }
+                }
+                (next, _) => next,
+            };
 
             match next {
                 Some(task) => ToolResult::success(
@@ -1315,7 +1942,8 @@ fn get_next_task(ctx: &ToolContext, args: Value) -> ToolResult {
                             "status": task.status.to_string(),
                             "priority": task.priority.to_string(),
                             "tags": task.tags,
-                            "project": task.project
+                            "project": task.project,
+                            "claim": claim_json(&task)
                         }
                     })),
                 ),
@@ -1351,32 +1979,20 @@ fn complete_and_get_next(ctx: &ToolContext, args: Value) -> ToolResult {
     match ctx.store.update(&task) {
         Ok(Some(_)) => {
             // Task completed, now get next
-            match ctx.store.get_all() {
-                Ok(all_tasks) => {
-                    let filtered: Vec<Task> = match project {
-                        Some(p) => all_tasks
-                            .into_iter()
-                            .filter(|t| t.is_pending() && t.belongs_to_project(p))
-                            .collect(),
-                        None => all_tasks.into_iter().filter(|t| t.is_pending()).collect(),
-                    };
-
-                    let next = filtered.into_iter().max_by_key(|t| t.priority.level());
-
-                    ToolResult::success(
-                        format!("Task completed: {}. Next task retrieved.", task.title),
-                        Some(json!({
-                            "completed_id": completed_id,
-                            "completed_title": task.title,
-                            "next_task": next.as_ref().map(|t| json!({
-                                "id": t.id,
-                                "title": t.title,
-                                "priority": t.priority.to_string(),
-                                "project": t.project
-                            }))
-                        })),
-                    )
-                }
+            match next_available_task(ctx, project, ctx.session_id.as_deref()) {
+                Ok(next) => ToolResult::success(
+                    format!("Task completed: {}. Next task retrieved.", task.title),
+                    Some(json!({
+                        "completed_id": completed_id,
+                        "completed_title": task.title,
+                        "next_task": next.as_ref().map(|t| json!({
+                            "id": t.id,
+                            "title": t.title,
+                            "priority": t.priority.to_string(),
+                            "project": t.project
+                        }))
+                    })),
+                ),
                 Err(e) => ToolResult::error(format!("Failed to get next task: {}", e)),
             }
         }
@@ -1385,6 +2001,97 @@ fn complete_and_get_next(ctx: &ToolContext, args: Value) -> ToolResult {
     }
 }
 
+fn claim_task(ctx: &ToolContext, args: Value) -> ToolResult {
+    let empty_map = serde_json::map::Map::new();
+    let args = args.as_object().unwrap_or(&empty_map);
+    let id = match args.get("id").and_then(|v| v.as_str()) {
+        Some(id) => id,
+        None => return ToolResult::error("Missing required parameter: id".to_string()),
+    };
+    let Some(owner) = claim_owner(ctx, args) else {
+        return ToolResult::error("No current session; pass owner to claim a task".to_string());
+    };
+
+    match claim::claim_task(
+        ctx.store.as_ref(),
+        id,
+        &owner,
+        lease_ttl(args),
+        chrono::Utc::now(),
+    ) {
+        Ok(task) => ToolResult::success(
+            format!("Task claimed by {}: {}", owner, task.title),
+            Some(json!({
+                "id": task.id,
+                "title": task.title,
+                "status": task.status.to_string(),
+                "claim": claim_json(&task)
+            })),
+        ),
+        Err(e) => ToolResult::error(format!("Failed to claim task: {}", e)),
+    }
+}
+
+fn heartbeat(ctx: &ToolContext, args: Value) -> ToolResult {
+    let empty_map = serde_json::map::Map::new();
+    let args = args.as_object().unwrap_or(&empty_map);
+    let task_id = args.get("task_id").and_then(|v| v.as_str());
+    let Some(owner) = claim_owner(ctx, args) else {
+        return ToolResult::error("No current session; pass owner to renew claims".to_string());
+    };
+
+    match claim::heartbeat(
+        ctx.store.as_ref(),
+        &owner,
+        task_id,
+        lease_ttl(args),
+        chrono::Utc::now(),
+    ) {
+        Ok(renewed) => {
+            let claims: Vec<Value> = renewed
+                .iter()
+                .map(|t| {
+                    json!({
+                        "id": t.id,
+                        "title": t.title,
+                        "claim": claim_json(t)
+                    })
+                })
+                .collect();
+            ToolResult::success(
+                format!("Renewed {} claim(s) for {}", claims.len(), owner),
+                Some(json!({
+                    "renewed": claims,
+                    "total": claims.len()
+                })),
+            )
+        }
+        Err(e) => ToolResult::error(format!("Failed to renew claims: {}", e)),
+    }
+}
+
+fn release_task(ctx: &ToolContext, args: Value) -> ToolResult {
+    let empty_map = serde_json::map::Map::new();
+    let args = args.as_object().unwrap_or(&empty_map);
+    let id = match args.get("id").and_then(|v| v.as_str()) {
+        Some(id) => id,
+        None => return ToolResult::error("Missing required parameter: id".to_string()),
+    };
+    let owner = claim_owner(ctx, args);
+
+    match claim::release_task(ctx.store.as_ref(), id, owner.as_deref()) {
+        Ok(task) => ToolResult::success(
+            format!("Task released: {}", task.title),
+            Some(json!({
+                "id": task.id,
+                "title": task.title,
+                "status": task.status.to_string()
+            })),
+        ),
+        Err(e) => ToolResult::error(format!("Failed to release task: {}", e)),
+    }
+}
+
 fn suggest_project(ctx: &ToolContext, args: Value) -> ToolResult {
     let empty_map = serde_json::map::Map::new();
     let args = args.as_object().unwrap_or(&empty_map);
@@ -1429,6 +2136,43 @@ fn suggest_project(ctx: &ToolContext, args: Value) -> ToolResult {
     )
 }
 
+/// Content of the task's linked vault notes, when `auto_fetch_context` is set.
+/// Notes missing from the vault are listed separately.
+#[cfg(feature = "vault")]
+fn fetch_context(task: &Task) -> Value {
+    if !task.should_fetch_context() || !task.has_context_notes() {
+        return Value::Null;
+    }
+    let vault = match crate::vault::Vault::open_default() {
+        Ok(Some(vault)) => vault,
+        Ok(None) => return Value::Null,
+        Err(e) => return json!({ "error": e.to_string() }),
+    };
+
+    let mut notes = Vec::new();
+    let mut missing = Vec::new();
+    for id in &task.context_notes {
+        match vault.resolve(id) {
+            Ok(Some(note)) => notes.push(json!({
+                "id": note.id,
+                "title": note.title,
+                "path": note.path,
+                "note_type": note.note_type,
+                "content": vault.content(&note)
+            })),
+            Ok(None) => missing.push(id.clone()),
+            Err(e) => return json!({ "error": e.to_string() }),
+        }
+    }
+    json!({ "notes": notes, "missing": missing })
+}
+
+/// Without the vault feature the agent fetches `context_notes` itself
+#[cfg(not(feature = "vault"))]
+fn fetch_context(_task: &Task) -> Value {
+    Value::Null
+}
+
 fn start_task(ctx: &ToolContext, args: Value) -> ToolResult {
     let empty_map = serde_json::map::Map::new();
     let args = args.as_object().unwrap_or(&empty_map);
@@ -1437,6 +2181,9 @@ fn start_task(ctx: &ToolContext, args: Value) -> ToolResult {
         None => return ToolResult::error("Missing required parameter: id".to_string()),
     };
 
+    let owner = claim_owner(ctx, args);
+    let now = chrono::Utc::now();
+
     match ctx.store.get(id) {
         Ok(Some(mut task)) => {
             if task.is_done() {
@@ -1444,6 +2191,16 @@ fn start_task(ctx: &ToolContext, args: Value) -> ToolResult {
                     "Cannot start a completed task. Reopen it first.".to_string(),
                 );
             }
+            if let Some(holder) = task
+                .active_claim(now)
+                .filter(|c| Some(c.owner.as_str()) != owner.as_deref())
+            {
+                return ToolResult::error(format!(
+                    "Task is claimed by {} until {}. Pick another task with get_next_task.",
+                    holder.owner,
+                    holder.expires_at.to_rfc3339()
+                ));
+            }
             if task.is_in_progress() {
                 // Include context hints even for already in-progress tasks
                 return ToolResult::success(
@@ -1454,6 +2211,7 @@ fn start_task(ctx: &ToolContext, args: Value) -> ToolResult {
                         "status": "in_progress",
                         "auto_fetch_context": task.auto_fetch_context,
                         "context_notes": task.context_notes,
+                        "context": fetch_context(&task),
                         "project": task.project,
                         "ralph_mode": task.ralph_mode,
                         "success_criteria": task.success_criteria,
@@ -1479,18 +2237,43 @@ fn start_task(ctx: &ToolContext, args: Value) -> ToolResult {
                     .collect();
             }
 
-            task.start();
+            // Check the claim again under the store's write lock: another
+            // agent may have claimed the task since we read it
+            let ttl = lease_ttl(args);
+            let started = ctx.store.modify(id, &mut |current| {
+                if let Some(holder) = current
+                    .active_claim(now)
+                    .filter(|c| Some(c.owner.as_str()) != owner.as_deref())
+                {
+                    anyhow::bail!(
+                        "Task is claimed by {} until {}. Pick another task with get_next_task.",
+                        holder.owner,
+                        holder.expires_at.to_rfc3339()
+                    );
+                }
+                current.ralph_mode = task.ralph_mode;
+                current.success_criteria = task.success_criteria.clone();
+                current.quality_gates = task.quality_gates.clone();
+                current.start();
+                // Agents with a session claim what they start
+                if let Some(owner) = owner.as_deref() {
+                    let _ = current.claim(owner, ttl, now);
+                }
+                Ok(())
+            });
 
-            match ctx.store.update(&task) {
+            match started {
                 Ok(Some(updated)) => ToolResult::success(
                     format!("Task started: {}", updated.title),
                     Some(json!({
                         "id": id,
                         "title": updated.title,
                         "status": "in_progress",
+                        "claim": claim_json(&updated),
                         // Context hints for agent to call vulcan-vault
                         "auto_fetch_context": updated.auto_fetch_context,
                         "context_notes": updated.context_notes,
+                        "context": fetch_context(&updated),
                         "project": updated.project,
                         // Ralph loop fields
                         "ralph_mode": updated.ralph_mode,
@@ -1525,6 +2308,10 @@ fn get_context(ctx: &ToolContext, args: Value) -> ToolResult {
                 tasks.retain(|t| t.belongs_to_project(project));
             }
 
+            // Snoozed tasks stay out of the context until their wait date passes
+            let total_waiting = tasks.iter().filter(|t| t.is_waiting()).count();
+            tasks.retain(|t| !t.is_waiting());
+
             // Get in-progress tasks (highest priority - these are being worked on)
             let in_progress: Vec<&Task> = tasks.iter().filter(|t| t.is_in_progress()).collect();
 
@@ -1581,6 +2368,7 @@ fn get_context(ctx: &ToolContext, args: Value) -> ToolResult {
                         "total_pending": total_pending,
                         "total_in_progress": total_in_progress,
                         "total_done": total_done,
+                        "total_waiting": total_waiting,
                         "project_filter": project_filter
                     },
                     "in_progress": in_progress.iter().map(|t| format_task(t)).collect::<Vec<_>>(),
@@ -1799,6 +2587,11 @@ fn create_sprint(ctx: &ToolContext, args: Value) -> ToolResult {
         }
     }
 
+    sprint.capacity = match points_arg(args, "capacity") {
+        Ok(capacity) => capacity,
+        Err(e) => return ToolResult::error(e),
+    };
+
     match ctx.store.add_sprint(&sprint) {
         Ok(created) => ToolResult::success(
             format!("Sprint created: {}", created.name),
@@ -1808,6 +2601,7 @@ fn create_sprint(ctx: &ToolContext, args: Value) -> ToolResult {
                 "project": created.project,
                 "status": created.status.to_string(),
                 "goal": created.goal,
+                "capacity": created.capacity,
                 "start_date": created.start_date.map(|d| d.format("%Y-%m-%d").to_string()),
                 "end_date": created.end_date.map(|d| d.format("%Y-%m-%d").to_string())
             })),
@@ -1887,7 +2681,8 @@ fn get_sprint(ctx: &ToolContext, args: Value) -> ToolResult {
                         "title": t.title,
                         "status": t.status.to_string(),
                         "priority": t.priority.to_string(),
-                        "sprint_order": t.sprint_order
+                        "sprint_order": t.sprint_order,
+                        "estimate": t.estimate
                     })
                 })
                 .collect();
@@ -1901,6 +2696,7 @@ fn get_sprint(ctx: &ToolContext, args: Value) -> ToolResult {
                         "project": sprint.project,
                         "status": sprint.status.to_string(),
                         "goal": sprint.goal,
+                        "capacity": sprint.capacity,
                         "start_date": sprint.start_date.map(|d| d.format("%Y-%m-%d").to_string()),
                         "end_date": sprint.end_date.map(|d| d.format("%Y-%m-%d").to_string()),
                         "created_at": sprint.created_at.format("%Y-%m-%d %H:%M").to_string()
@@ -1951,6 +2747,12 @@ fn update_sprint(ctx: &ToolContext, args: Value) -> ToolResult {
                 }
             }
 
+            match points_arg(args, "capacity") {
+                Ok(Some(capacity)) => sprint.capacity = (capacity > 0).then_some(capacity),
+                Ok(None) => {}
+                Err(e) => return ToolResult::error(e),
+            }
+
             match ctx.store.update_sprint(&sprint) {
                 Ok(Some(updated)) => ToolResult::success(
                     format!("Sprint updated: {}", updated.name),
@@ -1959,7 +2761,8 @@ fn update_sprint(ctx: &ToolContext, args: Value) -> ToolResult {
                         "name": updated.name,
                         "project": updated.project,
                         "status": updated.status.to_string(),
-                        "goal": updated.goal
+                        "goal": updated.goal,
+                        "capacity": updated.capacity
                     })),
                 ),
                 Ok(None) => ToolResult::error(format!("Sprint not found: {}", id)),
@@ -2029,26 +2832,207 @@ fn complete_sprint(ctx: &ToolContext, args: Value) -> ToolResult {
         Some(id) => id,
         None => return ToolResult::error("Missing required parameter: id".to_string()),
     };
+    let rollover = args
+        .get("rollover")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let target = args.get("target_sprint_id").and_then(|v| v.as_str());
 
-    match ctx.store.get_sprint(id) {
-        Ok(Some(mut sprint)) => {
-            sprint.complete();
-            match ctx.store.update_sprint(&sprint) {
-                Ok(Some(updated)) => ToolResult::success(
-                    format!("Sprint completed: {}", updated.name),
-                    Some(json!({
-                        "id": updated.id,
-                        "name": updated.name,
-                        "status": updated.status.to_string(),
-                        "end_date": updated.end_date.map(|d| d.format("%Y-%m-%d").to_string())
-                    })),
+    match planning::complete_sprint(ctx.store.as_ref(), id, rollover, target) {
+        Ok((updated, rollover)) => {
+            let carried = rollover.as_ref().map(|r| {
+                json!({
+                    "target_sprint_id": r.target.as_ref().map(|s| s.id.clone()),
+                    "target": r.target_name(),
+                    "tasks": r.moved.iter().map(|t| json!({
+                        "id": t.id,
+                        "title": t.title,
+                        "sprint_order": t.sprint_order
+                    })).collect::<Vec<_>>()
+                })
+            });
+            let note = match &rollover {
+                Some(r) if !r.moved.is_empty() => format!(
+                    " {} unfinished task(s) carried over to {}.",
+                    r.moved.len(),
+                    r.target_name()
                 ),
-                Ok(None) => ToolResult::error(format!("Sprint not found: {}", id)),
-                Err(e) => ToolResult::error(format!("Failed to complete sprint: {}", e)),
+                _ => String::new(),
+            };
+            ToolResult::success(
+                format!("Sprint completed: {}.{}", updated.name, note),
+                Some(json!({
+                    "id": updated.id,
+                    "name": updated.name,
+                    "status": updated.status.to_string(),
+                    "end_date": updated.end_date.map(|d| d.format("%Y-%m-%d").to_string()),
+                    "rollover": carried
+                })),
+            )
+        }
+        Err(e) => ToolResult::error(format!("Failed to complete sprint: {}", e)),
+    }
+}
+
+fn generate_report(ctx: &ToolContext, args: Value) -> ToolResult {
+    let empty_map = serde_json::map::Map::new();
+    let args = args.as_object().unwrap_or(&empty_map);
+
+    let kind: ReportKind = match args.get("kind").and_then(|v| v.as_str()) {
+        Some(kind) => match kind.parse() {
+            Ok(kind) => kind,
+            Err(e) => return ToolResult::error(e.to_string()),
+        },
+        None => return ToolResult::error("Missing required parameter: kind".to_string()),
+    };
+
+    let now = chrono::Utc::now();
+    let mut dates = [None, None];
+    for (slot, name) in dates.iter_mut().zip(["since", "until"]) {
+        if let Some(s) = args.get(name).and_then(|v| v.as_str()) {
+            match report::parse_report_date(s, now) {
+                Some(date) => *slot = Some(date),
+                None => return ToolResult::error(format!("Invalid {}: {}", name, s)),
             }
         }
-        Ok(None) => ToolResult::error(format!("Sprint not found: {}", id)),
-        Err(e) => ToolResult::error(format!("Failed to get sprint: {}", e)),
+    }
+    let [since, until] = dates;
+
+    let options = ReportOptions {
+        project: args
+            .get("project")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        sprint_id: args
+            .get("sprint_id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+        since,
+        until,
+    };
+
+    let report = match report::generate(ctx.store.as_ref(), kind, &options) {
+        Ok(report) => report,
+        Err(e) => return ToolResult::error(format!("Failed to generate report: {}", e)),
+    };
+    let markdown = match report::load_template(kind, None) {
+        Ok(template) => report.render(&template),
+        Err(e) => return ToolResult::error(format!("Failed to load template: {}", e)),
+    };
+
+    ToolResult::success(
+        format!(
+            "{}: {} completed, {} in progress, {} blocked, {} overdue",
+            report.title(),
+            report.completed.len(),
+            report.in_progress.len(),
+            report.blocked.len(),
+            report.overdue.len()
+        ),
+        Some(json!({
+            "title": report.title(),
+            "markdown": markdown,
+            "report": report.to_json()
+        })),
+    )
+}
+
+/// Options for the store of the workspace named in `set_workspace` args
+pub fn workspace_args(
+    ctx: &ToolContext,
+    args: &Value,
+) -> Result<(PathBuf, WorkspaceOptions), String> {
+    let Some(options) = ctx.workspace else {
+        return Err("The task store path is fixed for this server (--path)".to_string());
+    };
+    let path = match args.get("path").and_then(|v| v.as_str()) {
+        Some(path) => PathBuf::from(path),
+        None => return Err("Missing required parameter: path".to_string()),
+    };
+    if !path.is_dir() {
+        return Err(format!("Not a directory: {}", path.display()));
+    }
+    let target = match args.get("store").and_then(|v| v.as_str()) {
+        Some(store) => Some(store.parse::<StoreTarget>().map_err(|e| e.to_string())?),
+        None => options.target,
+    };
+    Ok((path, WorkspaceOptions { target, ..options }))
+}
+
+/// Validates the workspace; the server swaps in its store on success
+fn set_workspace(ctx: &ToolContext, args: Value) -> ToolResult {
+    let (path, options) = match workspace_args(ctx, &args) {
+        Ok(workspace) => workspace,
+        Err(e) => return ToolResult::error(e),
+    };
+    if let Err(e) = layered::open_workspace(&path, options) {
+        return ToolResult::error(format!("Failed to open store: {}", e));
+    }
+    let local = layered::find_local_store(&path);
+    ToolResult::success(
+        match &local {
+            Some(local) => format!("Using {} merged with the global store", local.display()),
+            None => "No repository store found; using the global store".to_string(),
+        },
+        Some(json!({
+            "workspace": path,
+            "local_store": local
+        })),
+    )
+}
+
+fn plan_sprint(ctx: &ToolContext, args: Value) -> ToolResult {
+    let empty_map = serde_json::map::Map::new();
+    let args = args.as_object().unwrap_or(&empty_map);
+
+    let sprint_id = match args.get("sprint_id").and_then(|v| v.as_str()) {
+        Some(id) => id,
+        None => return ToolResult::error("Missing required parameter: sprint_id".to_string()),
+    };
+    let budget = match points_arg(args, "budget") {
+        Ok(budget) => budget,
+        Err(e) => return ToolResult::error(e),
+    };
+    let apply = args.get("apply").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    match planning::plan_sprint(ctx.store.as_ref(), sprint_id, budget, apply) {
+        Ok(plan) => {
+            let tasks: Vec<Value> = plan
+                .proposed
+                .iter()
+                .map(|t| {
+                    json!({
+                        "id": t.id,
+                        "title": t.title,
+                        "priority": t.priority.to_string(),
+                        "due_date": t.due_formatted(),
+                        "estimate": t.points()
+                    })
+                })
+                .collect();
+            let action = if plan.applied { "Added" } else { "Proposed" };
+            ToolResult::success(
+                format!(
+                    "{} {} task(s) for {}: {} committed + {} planned of {} points",
+                    action,
+                    tasks.len(),
+                    plan.sprint.name,
+                    plan.committed,
+                    plan.planned,
+                    plan.budget
+                ),
+                Some(json!({
+                    "sprint_id": plan.sprint.id,
+                    "budget": plan.budget,
+                    "committed": plan.committed,
+                    "planned": plan.planned,
+                    "skipped": plan.skipped,
+                    "applied": plan.applied,
+                    "tasks": tasks
+                })),
+            )
+        }
+        Err(e) => ToolResult::error(format!("Failed to plan sprint: {}", e)),
     }
 }
 
@@ -2301,6 +3285,15 @@ mod tests {
         assert!(result.success);
         let count = result.data.unwrap().get("total").unwrap().as_u64().unwrap();
         assert_eq!(count, 2);
+
+        // Typo tolerant and ranked: the exact word beats the prefix match
+        create_task(&ctx, json!({"title": "Shop for groceries"}));
+        let result = search_tasks(&ctx, json!({"query": "grocrey"}));
+        let data = result.data.unwrap();
+        let tasks = data["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[2]["title"], "Shop for groceries");
+        assert!(tasks[0]["score"].as_f64().unwrap() > tasks[2]["score"].as_f64().unwrap());
     }
 
     #[test]
@@ -2315,4 +3308,172 @@ mod tests {
         let data = result.data.unwrap();
         assert_eq!(data.get("pending").unwrap().as_u64().unwrap(), 2);
     }
+
+    #[test]
+    fn test_snoozed_tasks_hidden() {
+        let ctx = create_test_context();
+
+        create_task(&ctx, json!({"title": "Visible", "priority": "low"}));
+        let created = create_task(&ctx, json!({"title": "Snoozed", "priority": "urgent"}));
+        let id = created.data.unwrap()["id"].as_str().unwrap().to_string();
+
+        let result = snooze_task(&ctx, json!({"id": id, "until": "3d"}));
+        assert!(result.success);
+
+        // Hidden from default listings and next-task selection
+        let list = list_tasks(&ctx, json!({}));
+        assert_eq!(list.data.unwrap()["total"], 1);
+        let list = list_tasks(&ctx, json!({"include_waiting": true}));
+        assert_eq!(list.data.unwrap()["total"], 2);
+
+        let next = get_next_task(&ctx, json!({}));
+        assert_eq!(next.data.unwrap()["task"]["title"], "Visible");
+
+        let context = get_context(&ctx, json!({}));
+        let data = context.data.unwrap();
+        assert_eq!(data["summary"]["total_waiting"], 1);
+        assert_eq!(data["high_priority"].as_array().unwrap().len(), 0);
+
+        // Clearing brings it back
+        snooze_task(&ctx, json!({"id": id, "clear": true}));
+        let next = get_next_task(&ctx, json!({}));
+        assert_eq!(next.data.unwrap()["task"]["title"], "Snoozed");
+    }
+
+    #[test]
+    fn test_session_handoff() {
+        let store = Arc::new(MemoryStore::new()) as Arc<dyn Store>;
+        let mut old_session = ToolContext::new(store.clone());
+        old_session.session_id = Some("old".to_string());
+
+        let created = create_task(
+            &old_session,
+            json!({"title": "Unfinished", "scope": "session"}),
+        );
+        assert_eq!(created.data.unwrap()["scope"], "session:old");
+        let done = create_task(
+            &old_session,
+            json!({"title": "Finished", "scope": "session"}),
+        );
+        let done_id = done.data.unwrap()["id"].as_str().unwrap().to_string();
+        complete_task(&old_session, json!({"id": done_id}));
+        let global = create_task(&old_session, json!({"title": "Shared"}));
+        assert!(global.data.unwrap()["scope"].is_null());
+
+        let ended = end_session(&old_session, json!({}));
+        assert!(ended.success);
+        let data = ended.data.unwrap();
+        assert_eq!(data["archived_done"], 1);
+        assert_eq!(data["unfinished"].as_array().unwrap().len(), 1);
+
+        let mut new_session = ToolContext::new(store);
+        new_session.session_id = Some("new".to_string());
+        let adopted = adopt_session_tasks(&new_session, json!({"from_session": "old"}));
+        assert_eq!(adopted.data.unwrap()["total"], 1);
+
+        let sessions = list_sessions(&new_session, json!({}));
+        let sessions = sessions.data.unwrap()["sessions"].clone();
+        assert_eq!(sessions.as_array().unwrap().len(), 1);
+        assert_eq!(sessions[0]["id"], "new");
+        assert_eq!(sessions[0]["current"], true);
+    }
+
+    #[test]
+    fn test_claims_between_agents() {
+        let store = Arc::new(MemoryStore::new()) as Arc<dyn Store>;
+        let mut agent_a = ToolContext::new(store.clone());
+        agent_a.session_id = Some("agent-a".to_string());
+        let mut agent_b = ToolContext::new(store);
+        agent_b.session_id = Some("agent-b".to_string());
+
+        let urgent = create_task(
+            &agent_a,
+            json!({"title": "Urgent", "priority": "high", "scope": "global"}),
+        );
+        let urgent_id = urgent.data.unwrap()["id"].as_str().unwrap().to_string();
+        create_task(&agent_a, json!({"title": "Later", "scope": "global"}));
+
+        let started = start_task(&agent_a, json!({"id": urgent_id}));
+        assert_eq!(started.data.unwrap()["claim"]["owner"], "agent-a");
+
+        // Agent B can't start it and is handed the other task
+        assert!(!start_task(&agent_b, json!({"id": urgent_id})).success);
+        assert!(!claim_task(&agent_b, json!({"id": urgent_id})).success);
+        let next = get_next_task(&agent_b, json!({"claim": true}));
+        let next = next.data.unwrap()["task"].clone();
+        assert_eq!(next["title"], "Later");
+        assert_eq!(next["claim"]["owner"], "agent-b");
+
+        let beat = heartbeat(&agent_a, json!({"ttl_minutes": 60}));
+        assert_eq!(beat.data.unwrap()["total"], 1);
+
+        let released = release_task(&agent_a, json!({"id": urgent_id}));
+        assert_eq!(released.data.unwrap()["status"], "pending");
+        assert!(claim_task(&agent_b, json!({"id": urgent_id})).success);
+    }
+
+    #[test]
+    fn test_plan_and_rollover_sprint() {
+        let ctx = create_test_context();
+        let sprint = create_sprint(
+            &ctx,
+            json!({"name": "Sprint 1", "project": "vulcan-os", "capacity": 3}),
+        );
+        let sprint_id = sprint.data.unwrap()["id"].as_str().unwrap().to_string();
+        let next = create_sprint(&ctx, json!({"name": "Sprint 2", "project": "vulcan-os"}));
+        let next_id = next.data.unwrap()["id"].as_str().unwrap().to_string();
+
+        for (title, priority, estimate) in [("A", "high", 2), ("B", "low", 2), ("C", "low", 1)] {
+            create_task(
+                &ctx,
+                json!({"title": title, "priority": priority, "estimate": estimate, "project": "vulcan-os", "scope": "global"}),
+            );
+        }
+
+        // Point values must fit in a u32
+        let too_big = create_task(&ctx, json!({"title": "D", "estimate": 5_000_000_000u64}));
+        assert!(!too_big.success);
+        assert!(!plan_sprint(&ctx, json!({"sprint_id": sprint_id, "budget": -1})).success);
+
+        let plan = plan_sprint(&ctx, json!({"sprint_id": sprint_id, "apply": true}));
+        assert!(plan.success);
+        let data = plan.data.unwrap();
+        assert_eq!(data["planned"], 3);
+        assert_eq!(data["skipped"], 1);
+        let titles: Vec<&str> = data["tasks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(titles, vec!["A", "C"]);
+
+        let completed = complete_sprint(&ctx, json!({"id": sprint_id}));
+        assert!(completed.success);
+        let rollover = completed.data.unwrap()["rollover"].clone();
+        assert_eq!(rollover["target_sprint_id"], next_id.as_str());
+        assert_eq!(rollover["tasks"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_generate_report() {
+        let ctx = create_test_context();
+        let created = create_task(&ctx, json!({"title": "Write docs", "project": "vulcan-os"}));
+        let id = created.data.unwrap()["id"].as_str().unwrap().to_string();
+        complete_task(&ctx, json!({"id": id}));
+        create_task(
+            &ctx,
+            json!({"title": "Waiting on review", "project": "vulcan-os", "tags": ["blocked"]}),
+        );
+
+        let result = generate_report(&ctx, json!({"kind": "standup", "project": "vulcan-os"}));
+        assert!(result.success);
+        let data = result.data.unwrap();
+        assert_eq!(data["report"]["completed"][0]["title"], "Write docs");
+        assert_eq!(data["report"]["blocked"][0]["title"], "Waiting on review");
+        assert!(data["markdown"].as_str().unwrap().contains("## Blocked\n"));
+
+        assert!(!generate_report(&ctx, json!({"kind": "retro"})).success);
+        assert!(!generate_report(&ctx, json!({"kind": "review", "since": "someday"})).success);
+    }
 }
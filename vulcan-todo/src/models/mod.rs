@@ -1,7 +1,8 @@
 // Core data models for vulcan-todo
 
 pub use sprint::{Sprint, SprintStatus};
-pub use task::{Priority, Status, Task, TaskStore};
+pub use task::{parse_duration, parse_wait_until, Priority, SourceAnchor, Status, Task, TaskStore};
 
+pub mod search;
 pub mod sprint;
 mod task;
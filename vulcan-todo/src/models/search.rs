@@ -0,0 +1,285 @@
+//! Fuzzy ranked task search
+//!
+//! A query is split into tokens, and every token has to match a word in one
+//! of the task's fields: exactly, as a word prefix, inside a word, or within
+//! a small edit distance to tolerate typos. Each token takes its best match,
+//! weighted by field (title over tags, project, description, success
+//! criteria, the source comment location and linked note IDs), and a task
+//! scores the average over its tokens (0-1). Tasks have no comment thread;
+//! a scanned comment's text is the title, so it is weighted as one.
+//! A query with text but no words (e.g. `!!`) matches nothing.
+
+use crate::models::Task;
+use std::borrow::Borrow;
+use std::ops::Range;
+
+const TITLE_WEIGHT: f64 = 1.0;
+const TAG_WEIGHT: f64 = 0.8;
+const PROJECT_WEIGHT: f64 = 0.7;
+const DESCRIPTION_WEIGHT: f64 = 0.5;
+const CRITERIA_WEIGHT: f64 = 0.5;
+const SOURCE_WEIGHT: f64 = 0.4;
+const CONTEXT_NOTE_WEIGHT: f64 = 0.3;
+
+/// A parsed search query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    tokens: Vec<String>,
+    /// The input had text but no words, so nothing can match
+    no_words: bool,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let tokens: Vec<String> = words(input).into_iter().map(|(_, word)| word).collect();
+        Self {
+            no_words: tokens.is_empty() && !input.trim().is_empty(),
+            tokens,
+        }
+    }
+
+    /// Check if the query was blank (and so filters nothing)
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty() && !self.no_words
+    }
+
+    /// Relevance of a task, or None if a token matches nothing. An empty
+    /// query matches every task with a score of 0.
+    pub fn score(&self, task: &Task) -> Option<f64> {
+        if self.no_words {
+            return None;
+        }
+        if self.tokens.is_empty() {
+            return Some(0.0);
+        }
+
+        let fields = fields(task);
+        let mut total = 0.0;
+        for token in &self.tokens {
+            total += fields
+                .iter()
+                .filter_map(|(text, weight)| best_word_score(token, text).map(|s| s * weight))
+                .max_by(f64::total_cmp)?;
+        }
+        Some(total / self.tokens.len() as f64)
+    }
+
+    /// Byte ranges of the words in `text` matched by the query
+    pub fn highlights(&self, text: &str) -> Vec<Range<usize>> {
+        if self.tokens.is_empty() {
+            return Vec::new();
+        }
+        words(text)
+            .into_iter()
+            .filter(|(_, word)| self.tokens.iter().any(|t| word_score(t, word).is_some()))
+            .map(|(range, _)| range)
+            .collect()
+    }
+}
+
+/// Tasks matching a query with their scores, best first. Equal scores keep
+/// higher priority, then newer tasks, first.
+pub fn rank<T: Borrow<Task>>(tasks: impl IntoIterator<Item = T>, query: &str) -> Vec<(T, f64)> {
+    let query = Query::parse(query);
+    let mut ranked: Vec<(T, f64)> = tasks
+        .into_iter()
+        .filter_map(|task| {
+            let score = query.score(task.borrow())?;
+            Some((task, score))
+        })
+        .collect();
+    ranked.sort_by(|(a, a_score), (b, b_score)| {
+        let (a, b) = (a.borrow(), b.borrow());
+        b_score
+            .total_cmp(a_score)
+            .then_with(|| b.priority.level().cmp(&a.priority.level()))
+            .then_with(|| b.created_at.cmp(&a.created_at))
+    });
+    ranked
+}
+
+/// Searchable text of a task with its field weight
+fn fields(task: &Task) -> Vec<(&str, f64)> {
+    let mut fields = vec![(task.title.as_str(), TITLE_WEIGHT)];
+    fields.extend(task.tags.iter().map(|tag| (tag.as_str(), TAG_WEIGHT)));
+    if let Some(project) = &task.project {
+        fields.push((project, PROJECT_WEIGHT));
+    }
+    if let Some(description) = &task.description {
+        fields.push((description, DESCRIPTION_WEIGHT));
+    }
+    fields.extend(
+        task.success_criteria
+            .iter()
+            .map(|criterion| (criterion.as_str(), CRITERIA_WEIGHT)),
+    );
+    if let Some(source) = &task.source {
+        fields.push((&source.path, SOURCE_WEIGHT));
+        fields.push((&source.marker, SOURCE_WEIGHT));
+    }
+    fields.extend(
+        task.context_notes
+            .iter()
+            .map(|note| (note.as_str(), CONTEXT_NOTE_WEIGHT)),
+    );
+    fields
+}
+
+/// Lowercased alphanumeric words of a text with their byte ranges
+fn words(text: &str) -> Vec<(Range<usize>, String)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                words.push((s..i, text[s..i].to_lowercase()));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push((s..text.len(), text[s..].to_lowercase()));
+    }
+    words
+}
+
+fn best_word_score(token: &str, text: &str) -> Option<f64> {
+    words(text)
+        .iter()
+        .filter_map(|(_, word)| word_score(token, word))
+        .max_by(f64::total_cmp)
+}
+
+/// How well a query token matches a word (0-1)
+fn word_score(token: &str, word: &str) -> Option<f64> {
+    if word == token {
+        return Some(1.0);
+    }
+
+    let token_len = token.chars().count();
+    let word_len = word.chars().count();
+    if word.starts_with(token) {
+        // Longer prefixes are closer to the whole word
+        return Some(0.6 + 0.3 * token_len as f64 / word_len as f64);
+    }
+    if token_len >= 3 && word.contains(token) {
+        return Some(0.5);
+    }
+
+    let max_typos = match token_len {
+        0..=3 => return None,
+        4..=6 => 1,
+        _ => 2,
+    };
+    let typos = edit_distance(token, word);
+    if typos <= max_typos {
+        return Some(0.7 - 0.2 * (typos - 1) as f64);
+    }
+    // Typo in a prefix ("instlal" for "installation")
+    if word_len > token_len {
+        let prefix: String = word.chars().take(token_len).collect();
+        if edit_distance(token, &prefix) <= max_typos {
+            return Some(0.4);
+        }
+    }
+    None
+}
+
+/// Edit distance counting insertions, deletions, substitutions and
+/// transpositions of adjacent characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str) -> Task {
+        Task::new(title.to_string())
+    }
+
+    #[test]
+    fn test_fuzzy_matching() {
+        let query = Query::parse("grocery");
+        assert_eq!(query.score(&task("Buy grocery items")), Some(1.0));
+        assert!(query.score(&task("Finish report")).is_none());
+
+        // Typos, prefixes and multiple tokens
+        assert!(Query::parse("grocrey")
+            .score(&task("Grocery run"))
+            .is_some());
+        assert!(Query::parse("instlal")
+            .score(&task("Installation guide"))
+            .is_some());
+        assert!(Query::parse("gro shop")
+            .score(&task("Grocery shopping"))
+            .is_some());
+        assert!(Query::parse("gro report")
+            .score(&task("Grocery shopping"))
+            .is_none());
+
+        assert_eq!(edit_distance("grocrey", "grocery"), 1);
+        assert_eq!(
+            Query::parse("shop").highlights("Grocery shopping"),
+            vec![8..16]
+        );
+    }
+
+    #[test]
+    fn test_rank_weights_fields() {
+        let mut in_tags = task("Write notes");
+        in_tags.tags.push("release".to_string());
+        let mut in_description = task("Plan week");
+        in_description.description = Some("Prepare the release".to_string());
+        let in_title = task("Release 1.0");
+
+        let ranked = rank(vec![in_description, in_tags, in_title], "release");
+        let titles: Vec<&str> = ranked.iter().map(|(t, _)| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Release 1.0", "Write notes", "Plan week"]);
+        assert!(ranked[0].1 > ranked[1].1 && ranked[1].1 > ranked[2].1);
+    }
+
+    #[test]
+    fn test_other_fields_and_punctuation() {
+        let mut ralph = task("Fix login");
+        ralph.success_criteria.push("clippy is clean".to_string());
+        ralph
+            .context_notes
+            .push("3f2a9c10-77aa-4d1e-9b3e-0c5d8e1f2a3b".to_string());
+        assert!(Query::parse("clippy").score(&ralph).is_some());
+        assert!(Query::parse("3f2a9c10").score(&ralph).is_some());
+
+        // Punctuation only: no words, no matches
+        let query = Query::parse("!!");
+        assert!(!query.is_empty());
+        assert!(query.score(&ralph).is_none());
+        assert!(rank(vec![ralph.clone()], "?!").is_empty());
+        assert!(Query::parse("  ").is_empty());
+        assert_eq!(Query::parse("").score(&ralph), Some(0.0));
+    }
+}
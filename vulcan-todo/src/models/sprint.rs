@@ -100,6 +100,9 @@ pub struct Sprint {
     /// Sprint goal or description
     #[serde(default)]
     pub goal: Option<String>,
+    /// Point budget for planning (sum of task estimates)
+    #[serde(default)]
+    pub capacity: Option<u32>,
     /// Creation timestamp
     #[serde(default)]
     pub created_at: chrono::DateTime<chrono::Utc>,
@@ -116,6 +119,7 @@ impl Sprint {
             start_date: None,
             end_date: None,
             goal: None,
+            capacity: None,
             created_at: chrono::Utc::now(),
         }
     }
@@ -127,6 +127,47 @@ impl From<String> for Priority {
     }
 }
 
+/// A lease on a task held by one agent (session) for coordination.
+/// Expired leases are treated as released.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Claim {
+    /// Owner of the lease (MCP session ID or agent name)
+    pub owner: String,
+    /// When the lease was first taken
+    pub claimed_at: chrono::DateTime<chrono::Utc>,
+    /// When the lease lapses unless renewed by a heartbeat
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Claim {
+    /// Check if the lease is still held at `now`
+    pub fn is_live(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at > now
+    }
+}
+
+/// Location of the source comment a task was imported from (`vulcan-todo scan`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceAnchor {
+    /// Stable key of the comment (file + marker + text), survives line moves
+    pub fingerprint: String,
+    /// Repository root the comment was found in
+    pub root: String,
+    /// File path relative to the root
+    pub path: String,
+    /// 1-based line number at the last scan
+    pub line: usize,
+    /// Comment marker (TODO, FIXME, HACK)
+    pub marker: String,
+}
+
+impl SourceAnchor {
+    /// `path:line` reference
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.path, self.line)
+    }
+}
+
 /// Main Task struct
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
@@ -152,9 +193,18 @@ pub struct Task {
     /// Completion timestamp (None if pending)
     #[serde(default)]
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the task was last saved through `Store::update`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When work on the task first started (None if never started)
+    #[serde(default)]
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
     /// Due date (optional)
     #[serde(default)]
     pub due_date: Option<chrono::DateTime<chrono::Utc>>,
+    /// Hide the task from default listings until this time (snooze / scheduled start)
+    #[serde(default, alias = "scheduled")]
+    pub wait_until: Option<chrono::DateTime<chrono::Utc>>,
     /// Project for organization (e.g., "vulcan-os", "personal", "work")
     /// Can be auto-assigned from tags with format "project:name"
     #[serde(default)]
@@ -184,6 +234,15 @@ pub struct Task {
     /// Quality gates to run before completion (e.g., "test", "typecheck", "lint")
     #[serde(default)]
     pub quality_gates: Vec<String>,
+    /// Lease held by an agent working on this task (multi-agent coordination)
+    #[serde(default)]
+    pub claim: Option<Claim>,
+    /// Source comment this task tracks (imported by `scan`)
+    #[serde(default)]
+    pub source: Option<SourceAnchor>,
+    /// Effort estimate in points, used by the sprint planner
+    #[serde(default)]
+    pub estimate: Option<u32>,
 }
 
 impl Task {
@@ -198,7 +257,10 @@ impl Task {
             tags: Vec::new(),
             created_at: chrono::Utc::now(),
             completed_at: None,
+            updated_at: None,
+            started_at: None,
             due_date: None,
+            wait_until: None,
             project: None,
             scope: None,
             sprint_id: None,
@@ -208,6 +270,9 @@ impl Task {
             ralph_mode: false,
             success_criteria: Vec::new(),
             quality_gates: Vec::new(),
+            claim: None,
+            source: None,
+            estimate: None,
         }
     }
 
@@ -222,7 +287,10 @@ impl Task {
             tags: Vec::new(),
             created_at: chrono::Utc::now(),
             completed_at: None,
+            updated_at: None,
+            started_at: None,
             due_date: None,
+            wait_until: None,
             project: None,
             scope,
             sprint_id: None,
@@ -232,6 +300,9 @@ impl Task {
             ralph_mode: false,
             success_criteria: Vec::new(),
             quality_gates: Vec::new(),
+            claim: None,
+            source: None,
+            estimate: None,
         }
     }
 
@@ -255,15 +326,19 @@ impl Task {
         self.status.is_active()
     }
 
-    /// Mark task as in progress
+    /// Mark task as in progress (records the first start time)
     pub fn start(&mut self) {
         self.status = Status::InProgress;
+        if self.started_at.is_none() {
+            self.started_at = Some(chrono::Utc::now());
+        }
     }
 
-    /// Mark task as complete
+    /// Mark task as complete (ends any claim)
     pub fn complete(&mut self) {
         self.status = Status::Done;
         self.completed_at = Some(chrono::Utc::now());
+        self.claim = None;
     }
 
     /// Reopen a completed task
@@ -320,6 +395,31 @@ impl Task {
         }
     }
 
+    /// Check if task is waiting (snoozed until a future time)
+    pub fn is_waiting(&self) -> bool {
+        self.is_active()
+            && self
+                .wait_until
+                .map(|w| w > chrono::Utc::now())
+                .unwrap_or(false)
+    }
+
+    /// Hide the task until the given time
+    pub fn snooze(&mut self, until: chrono::DateTime<chrono::Utc>) {
+        self.wait_until = Some(until);
+    }
+
+    /// Clear any wait date so the task shows up again
+    pub fn unsnooze(&mut self) {
+        self.wait_until = None;
+    }
+
+    /// Format wait date for display
+    pub fn wait_formatted(&self) -> Option<String> {
+        self.wait_until
+            .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+    }
+
     /// Set project by extracting from tags with format "project:name"
     /// Only sets if project is not already set
     pub fn set_project_from_tags(&mut self) {
@@ -333,11 +433,140 @@ impl Task {
         }
     }
 
+    /// Undo an edit: set every field that differs between `before` and
+    /// `after` back to its `before` value, leaving the other fields as they
+    /// are now
+    pub fn revert_changes(&mut self, before: &Task, after: &Task) -> serde_json::Result<()> {
+        let before = serde_json::to_value(before)?;
+        let after = serde_json::to_value(after)?;
+        let mut current = serde_json::to_value(&*self)?;
+        if let (Some(before), Some(after), Some(current)) = (
+            before.as_object(),
+            after.as_object(),
+            current.as_object_mut(),
+        ) {
+            for (field, old) in before {
+                if field != "updated_at" && after.get(field) != Some(old) {
+                    current.insert(field.clone(), old.clone());
+                }
+            }
+        }
+        *self = serde_json::from_value(current)?;
+        Ok(())
+    }
+
+    /// Apply tag edits: `+tag` appends, `-tag` removes, and bare words
+    /// (space or comma separated) replace the existing tags
+    pub fn edit_tags(&mut self, input: &str) {
+        let words: Vec<&str> = input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words
+            .iter()
+            .any(|w| !w.starts_with('+') && !w.starts_with('-'))
+        {
+            self.tags.clear();
+        }
+
+        for word in words {
+            if let Some(tag) = word.strip_prefix('-') {
+                self.tags.retain(|t| t != tag);
+                continue;
+            }
+            let tag = word.strip_prefix('+').unwrap_or(word);
+            if !tag.is_empty() && !self.tags.iter().any(|t| t == tag) {
+                self.tags.push(tag.to_string());
+            }
+        }
+    }
+
     /// Check if task belongs to a specific project
     pub fn belongs_to_project(&self, project: &str) -> bool {
         self.project.as_ref().map(|p| p == project).unwrap_or(false)
     }
 
+    /// Session ID if the task is session-scoped ("session:<id>")
+    pub fn session_id(&self) -> Option<&str> {
+        self.scope.as_deref()?.strip_prefix("session:")
+    }
+
+    /// Last time the task was touched (created, updated or completed)
+    pub fn last_activity(&self) -> chrono::DateTime<chrono::Utc> {
+        [self.completed_at, self.updated_at]
+            .into_iter()
+            .flatten()
+            .fold(self.created_at, |latest, t| latest.max(t))
+    }
+
+    /// Stamp `updated_at` with the current time
+    pub fn touch(&mut self) {
+        self.updated_at = Some(chrono::Utc::now());
+    }
+
+    /// Claim that is still live at `now` (expired leases don't count)
+    pub fn active_claim(&self, now: chrono::DateTime<chrono::Utc>) -> Option<&Claim> {
+        self.claim.as_ref().filter(|c| c.is_live(now))
+    }
+
+    /// Owner of the live claim, if any
+    pub fn claimed_by(&self) -> Option<&str> {
+        self.active_claim(chrono::Utc::now())
+            .map(|c| c.owner.as_str())
+    }
+
+    /// Check if someone other than `owner` holds a live claim
+    pub fn is_claimed_by_other(
+        &self,
+        owner: Option<&str>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        self.active_claim(now)
+            .map(|c| Some(c.owner.as_str()) != owner)
+            .unwrap_or(false)
+    }
+
+    /// Take or renew the lease for `owner`. Fails with the current owner if
+    /// someone else holds a live claim.
+    pub fn claim(
+        &mut self,
+        owner: &str,
+        ttl: chrono::Duration,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), String> {
+        let expires_at = now
+            .checked_add_signed(ttl)
+            .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC);
+        match self.active_claim(now) {
+            Some(c) if c.owner != owner => Err(c.owner.clone()),
+            Some(_) => {
+                // Heartbeat: extend the existing lease
+                if let Some(c) = self.claim.as_mut() {
+                    c.expires_at = expires_at;
+                }
+                Ok(())
+            }
+            None => {
+                self.claim = Some(Claim {
+                    owner: owner.to_string(),
+                    claimed_at: now,
+                    expires_at,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// Drop the claim (returns false if there was none)
+    pub fn release(&mut self) -> bool {
+        self.claim.take().is_some()
+    }
+
+    /// Estimate in points; unestimated tasks count as one point
+    pub fn points(&self) -> u32 {
+        self.estimate.unwrap_or(1)
+    }
+
     /// Check if task belongs to a specific sprint
     pub fn belongs_to_sprint(&self, sprint_id: &str) -> bool {
         self.sprint_id
@@ -416,7 +645,7 @@ pub struct TaskStore {
 
 impl TaskStore {
     /// Current schema version (bump when adding new fields)
-    pub const CURRENT_VERSION: u32 = 4;
+    pub const CURRENT_VERSION: u32 = 9;
 
     /// Current schema version (for serde default)
     fn current_version() -> u32 {
@@ -456,6 +685,37 @@ impl TaskStore {
             // No data transformation needed, serde defaults handle it
             self.version = 4;
         }
+        if self.version < 5 {
+            // Migration from v4 to v5:
+            // - Added wait_until: Option<DateTime> to Task (defaults to None)
+            // No data transformation needed, serde defaults handle it
+            self.version = 5;
+        }
+        if self.version < 6 {
+            // Migration from v5 to v6:
+            // - Added claim: Option<Claim> to Task (defaults to None)
+            // No data transformation needed, serde defaults handle it
+            self.version = 6;
+        }
+        if self.version < 7 {
+            // Migration from v6 to v7:
+            // - Added source: Option<SourceAnchor> to Task (defaults to None)
+            // No data transformation needed, serde defaults handle it
+            self.version = 7;
+        }
+        if self.version < 8 {
+            // Migration from v7 to v8:
+            // - Added estimate: Option<u32> to Task (defaults to None)
+            // - Added capacity: Option<u32> to Sprint (defaults to None)
+            // No data transformation needed, serde defaults handle it
+            self.version = 8;
+        }
+        if self.version < 9 {
+            // Migration from v8 to v9:
+            // - Added started_at: Option<DateTime> to Task (defaults to None)
+            // No data transformation needed, serde defaults handle it
+            self.version = 9;
+        }
         // Future migrations go here
     }
 
@@ -513,19 +773,12 @@ impl TaskStore {
         (pending, done)
     }
 
-    /// Search tasks by title, description, or tags
+    /// Fuzzy search across task fields, best matches first
     pub fn search(&self, query: &str) -> Vec<&Task> {
-        let query_lower = query.to_lowercase();
-        self.filter(|t| {
-            t.title.to_lowercase().contains(&query_lower)
-                || t.description
-                    .as_ref()
-                    .map(|d| d.to_lowercase().contains(&query_lower))
-                    .unwrap_or(false)
-                || t.tags
-                    .iter()
-                    .any(|tag| tag.to_lowercase().contains(&query_lower))
-        })
+        super::search::rank(&self.tasks, query)
+            .into_iter()
+            .map(|(task, _)| task)
+            .collect()
     }
 
     /// Get tasks by tag
@@ -664,6 +917,57 @@ impl TaskStore {
     }
 }
 
+/// Parse a wait/snooze time relative to `now`.
+///
+/// Accepts relative durations (`30m`, `4h`, `3d`, `2w`), `tomorrow`,
+/// `YYYY-MM-DD` (start of that day, UTC) or a full RFC 3339 timestamp.
+/// Returns `None` for times chrono can't represent.
+pub fn parse_wait_until(
+    input: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    let raw = input.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let input = raw.to_lowercase();
+
+    match input.as_str() {
+        "tomorrow" => {
+            let date = now.date_naive().succ_opt()?;
+            return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+        }
+        "next-week" | "nextweek" => return now.checked_add_signed(chrono::Duration::weeks(1)),
+        _ => {}
+    }
+
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.with_timezone(&chrono::Utc));
+    }
+
+    now.checked_add_signed(parse_duration(&input)?)
+}
+
+/// Parse a relative duration: `30m`, `4h`, `3d`, `2w`
+///
+/// Returns `None` for amounts too large for a `chrono::Duration`.
+pub fn parse_duration(input: &str) -> Option<chrono::Duration> {
+    let input = input.trim().to_lowercase();
+    let split = input.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = input.split_at(split);
+    let amount: i64 = amount.parse().ok()?;
+    match unit {
+        "m" | "min" | "mins" | "minutes" => chrono::Duration::try_minutes(amount),
+        "h" | "hr" | "hrs" | "hours" => chrono::Duration::try_hours(amount),
+        "d" | "day" | "days" => chrono::Duration::try_days(amount),
+        "w" | "wk" | "weeks" => chrono::Duration::try_weeks(amount),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -727,4 +1031,93 @@ mod tests {
         let results = store.search("grocery");
         assert_eq!(results.len(), 2);
     }
+
+    #[test]
+    fn test_task_waiting() {
+        let mut task = Task::new("Later".to_string());
+        assert!(!task.is_waiting());
+
+        task.snooze(chrono::Utc::now() + chrono::Duration::days(1));
+        assert!(task.is_waiting());
+
+        task.snooze(chrono::Utc::now() - chrono::Duration::hours(1));
+        assert!(!task.is_waiting()); // Wait date has passed
+
+        task.snooze(chrono::Utc::now() + chrono::Duration::days(1));
+        task.complete();
+        assert!(!task.is_waiting()); // Done tasks are never waiting
+
+        task.unsnooze();
+        assert!(task.wait_until.is_none());
+    }
+
+    #[test]
+    fn test_edit_tags() {
+        let mut task = Task::new("Tagged".to_string());
+        task.edit_tags("ui, backend");
+        assert_eq!(task.tags, vec!["ui", "backend"]);
+
+        task.edit_tags("+urgent -ui +backend");
+        assert_eq!(task.tags, vec!["backend", "urgent"]);
+
+        task.edit_tags("docs");
+        assert_eq!(task.tags, vec!["docs"]);
+    }
+
+    #[test]
+    fn test_parse_wait_until() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-10T15:30:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        assert_eq!(
+            parse_wait_until("3d", now),
+            Some(now + chrono::Duration::days(3))
+        );
+        assert_eq!(
+            parse_wait_until("4h", now),
+            Some(now + chrono::Duration::hours(4))
+        );
+        assert_eq!(
+            parse_wait_until("2w", now),
+            Some(now + chrono::Duration::weeks(2))
+        );
+        assert_eq!(
+            parse_wait_until("tomorrow", now).unwrap().to_rfc3339(),
+            "2026-03-11T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_wait_until("2026-04-01", now).unwrap().to_rfc3339(),
+            "2026-04-01T00:00:00+00:00"
+        );
+        assert!(parse_wait_until("soon", now).is_none());
+        assert!(parse_wait_until("3x", now).is_none());
+        assert!(parse_wait_until("", now).is_none());
+
+        // Out of range amounts are rejected rather than panicking
+        assert!(parse_duration("99999999999999999w").is_none());
+        assert!(parse_wait_until("99999999999d", now).is_none());
+        assert!(parse_wait_until("99999999999999999w", now).is_none());
+    }
+
+    #[test]
+    fn test_wait_until_scheduled_alias() {
+        let json = r#"{"id":"t1","title":"Scheduled","scheduled":"2030-01-01T00:00:00Z"}"#;
+        let task: Task = serde_json::from_str(json).unwrap();
+        assert!(task.is_waiting());
+    }
+
+    #[test]
+    fn test_revert_changes() {
+        let before = Task::new("Write docs".to_string());
+        let mut after = before.clone();
+        after.priority = Priority::High;
+
+        // Edited elsewhere since: the title change is kept
+        let mut current = after.clone();
+        current.title = "Write the docs".to_string();
+        current.revert_changes(&before, &after).unwrap();
+        assert_eq!(current.priority, Priority::None);
+        assert_eq!(current.title, "Write the docs");
+    }
 }
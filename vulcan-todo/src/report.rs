@@ -0,0 +1,656 @@
+//! Standup and sprint review reports (`vulcan-todo report`)
+//!
+//! A report covers a date range or a sprint: tasks completed and started,
+//! what is in progress, blocked or overdue, a per-project breakdown and
+//! sprint goal progress. It renders to Markdown through a template users can
+//! override (`~/.config/vulcan-todo/templates/<kind>.md`), or to JSON.
+
+use crate::models::{parse_duration, parse_wait_until, Sprint, SprintStatus, Task};
+use crate::store::planning::total_points;
+use crate::store::Store;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Tag marking a task as blocked
+pub const BLOCKED_TAG: &str = "blocked";
+
+const STANDUP_TEMPLATE: &str = "# {{title}}
+
+_{{since}} → {{until}}_
+
+## Done
+{{completed}}
+
+## In progress
+{{in_progress}}
+
+## Blocked
+{{blocked}}
+
+## Overdue
+{{overdue}}
+";
+
+const REVIEW_TEMPLATE: &str = "# {{title}}
+
+_{{since}} → {{until}}_
+
+{{sprint}}
+
+## Completed ({{completed_count}})
+{{completed}}
+
+## Started ({{started_count}})
+{{started}}
+
+## Blocked ({{blocked_count}})
+{{blocked}}
+
+## Overdue ({{overdue_count}})
+{{overdue}}
+
+## By project
+{{projects}}
+";
+
+/// Kind of report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportKind {
+    /// Daily standup (default range: last 24 hours)
+    Standup,
+    /// Sprint review (default range: the sprint, else the last 7 days)
+    Review,
+}
+
+impl ReportKind {
+    /// Name, also used for the template file (`standup.md`, `review.md`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportKind::Standup => "standup",
+            ReportKind::Review => "review",
+        }
+    }
+
+    fn default_template(&self) -> &'static str {
+        match self {
+            ReportKind::Standup => STANDUP_TEMPLATE,
+            ReportKind::Review => REVIEW_TEMPLATE,
+        }
+    }
+
+    fn default_range(&self) -> Duration {
+        match self {
+            ReportKind::Standup => Duration::days(1),
+            ReportKind::Review => Duration::days(7),
+        }
+    }
+}
+
+impl std::str::FromStr for ReportKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "standup" | "daily" => Ok(ReportKind::Standup),
+            "review" | "sprint-review" => Ok(ReportKind::Review),
+            _ => bail!("Unknown report kind: {} (expected standup or review)", s),
+        }
+    }
+}
+
+/// What a report covers
+#[derive(Debug, Clone, Default)]
+pub struct ReportOptions {
+    /// Only tasks in this project
+    pub project: Option<String>,
+    /// Only tasks in this sprint; its dates set the default range.
+    /// Reviews of a project default to the project's active sprint.
+    pub sprint_id: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    /// End of the range (default: now)
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Completion of a sprint's tasks
+#[derive(Debug, Clone)]
+pub struct SprintProgress {
+    pub sprint: Sprint,
+    pub done: usize,
+    pub total: usize,
+    pub done_points: u32,
+    pub total_points: u32,
+}
+
+impl SprintProgress {
+    /// Percentage of points done, at most 100
+    pub fn percent(&self) -> u32 {
+        let percent = (u64::from(self.done_points) * 100)
+            .checked_div(u64::from(self.total_points))
+            .unwrap_or(0);
+        percent.min(100) as u32
+    }
+
+    /// e.g. "5/8 tasks, 13/21 pts (61%)"
+    pub fn summary(&self) -> String {
+        format!(
+            "{}/{} tasks, {}/{} pts ({}%)",
+            self.done,
+            self.total,
+            self.done_points,
+            self.total_points,
+            self.percent()
+        )
+    }
+}
+
+/// Per-project counts for a report
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectBreakdown {
+    pub project: String,
+    pub completed: usize,
+    pub started: usize,
+    /// Still pending or in progress
+    pub open: usize,
+    pub blocked: usize,
+    pub overdue: usize,
+}
+
+/// A generated report
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub kind: ReportKind,
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub project: Option<String>,
+    pub sprint: Option<SprintProgress>,
+    /// Completed within the range
+    pub completed: Vec<Task>,
+    /// First started within the range
+    pub started: Vec<Task>,
+    /// Currently in progress
+    pub in_progress: Vec<Task>,
+    /// Open tasks tagged `blocked`
+    pub blocked: Vec<Task>,
+    /// Open tasks due before the end of the range
+    pub overdue: Vec<Task>,
+    pub projects: Vec<ProjectBreakdown>,
+}
+
+/// Parse a report date: `YYYY-MM-DD`, RFC 3339, or a duration ago (`3d`, `2w`)
+pub fn parse_report_date(input: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    match parse_duration(input) {
+        Some(ago) => now.checked_sub_signed(ago),
+        None => parse_wait_until(input, now),
+    }
+}
+
+fn in_range(at: Option<DateTime<Utc>>, since: DateTime<Utc>, until: DateTime<Utc>) -> bool {
+    at.is_some_and(|at| at >= since && at <= until)
+}
+
+fn is_blocked(task: &Task) -> bool {
+    task.is_active()
+        && task
+            .tags
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(BLOCKED_TAG))
+}
+
+fn is_overdue_at(task: &Task, at: DateTime<Utc>) -> bool {
+    task.is_active() && task.due_date.is_some_and(|due| due < at)
+}
+
+/// Build a report from the store
+pub fn generate(store: &dyn Store, kind: ReportKind, options: &ReportOptions) -> Result<Report> {
+    let until = options.until.unwrap_or_else(Utc::now);
+
+    let sprint = match (&options.sprint_id, &options.project) {
+        (Some(id), _) => match store.get_sprint(id)? {
+            Some(sprint) => Some(sprint),
+            None => bail!("Sprint not found: {}", id),
+        },
+        (None, Some(project)) if kind == ReportKind::Review => store
+            .get_sprints_by_project(project)?
+            .into_iter()
+            .find(|s| s.status == SprintStatus::Active),
+        _ => None,
+    };
+
+    let since = options
+        .since
+        .or_else(|| match kind {
+            ReportKind::Review => sprint.as_ref().and_then(|s| s.start_date),
+            ReportKind::Standup => None,
+        })
+        .unwrap_or(until - kind.default_range());
+    if since > until {
+        bail!("Report range starts after it ends");
+    }
+
+    let tasks: Vec<Task> = store
+        .get_all()?
+        .into_iter()
+        .filter(|t| match (&sprint, &options.project) {
+            (Some(sprint), _) => t.belongs_to_sprint(&sprint.id),
+            (None, Some(project)) => t.belongs_to_project(project),
+            (None, None) => true,
+        })
+        .collect();
+
+    let select = |f: &dyn Fn(&Task) -> bool| -> Vec<Task> {
+        let mut selected: Vec<Task> = tasks.iter().filter(|t| f(t)).cloned().collect();
+        selected.sort_by_key(|t| (Reverse(t.priority.level()), t.created_at));
+        selected
+    };
+
+    let mut completed = select(&|t| t.is_done() && in_range(t.completed_at, since, until));
+    completed.sort_by_key(|t| t.completed_at);
+    let mut started = select(&|t| in_range(t.started_at, since, until));
+    started.sort_by_key(|t| t.started_at);
+    let in_progress = select(&|t| t.is_in_progress());
+    let blocked = select(&is_blocked);
+    let overdue = select(&|t| is_overdue_at(t, until));
+
+    let mut projects: BTreeMap<String, ProjectBreakdown> = BTreeMap::new();
+    for task in &tasks {
+        let name = task.project.clone().unwrap_or_else(|| "(none)".to_string());
+        let entry = projects
+            .entry(name.clone())
+            .or_insert_with(|| ProjectBreakdown {
+                project: name,
+                ..Default::default()
+            });
+        if task.is_done() && in_range(task.completed_at, since, until) {
+            entry.completed += 1;
+        }
+        if in_range(task.started_at, since, until) {
+            entry.started += 1;
+        }
+        if task.is_active() {
+            entry.open += 1;
+        }
+        if is_blocked(task) {
+            entry.blocked += 1;
+        }
+        if is_overdue_at(task, until) {
+            entry.overdue += 1;
+        }
+    }
+    let projects = projects
+        .into_values()
+        .filter(|p| p.completed + p.started + p.open > 0)
+        .collect();
+
+    let sprint = sprint.map(|sprint| {
+        let in_sprint: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| t.belongs_to_sprint(&sprint.id))
+            .collect();
+        let done: Vec<&&Task> = in_sprint.iter().filter(|t| t.is_done()).collect();
+        SprintProgress {
+            done: done.len(),
+            total: in_sprint.len(),
+            done_points: total_points(done.iter().map(|t| **t)),
+            total_points: total_points(in_sprint.iter().copied()),
+            sprint,
+        }
+    });
+
+    Ok(Report {
+        kind,
+        since,
+        until,
+        project: options.project.clone(),
+        sprint,
+        completed,
+        started,
+        in_progress,
+        blocked,
+        overdue,
+        projects,
+    })
+}
+
+/// Directory for user templates (`VULCAN_TODO_TEMPLATES_DIR`, or
+/// `~/.config/vulcan-todo/templates`)
+pub fn templates_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("VULCAN_TODO_TEMPLATES_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    let config_dir = dirs::config_dir()
+        .context("Could not determine config directory")?
+        .join("vulcan-todo");
+
+    Ok(config_dir.join("templates"))
+}
+
+/// Template for a report: `path` if given, else the user's `<kind>.md` in
+/// the templates directory, else the built-in one
+pub fn load_template(kind: ReportKind, path: Option<&Path>) -> Result<String> {
+    if let Some(path) = path {
+        return std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template: {}", path.display()));
+    }
+    if let Ok(dir) = templates_dir() {
+        let user = dir.join(format!("{}.md", kind.name()));
+        if user.is_file() {
+            return std::fs::read_to_string(&user)
+                .with_context(|| format!("Failed to read template: {}", user.display()));
+        }
+    }
+    Ok(kind.default_template().to_string())
+}
+
+impl Report {
+    /// Report title, e.g. "Standup 2026-03-14" or "Sprint Review: Sprint 4"
+    pub fn title(&self) -> String {
+        let scope = self
+            .project
+            .as_ref()
+            .map(|p| format!(" ({})", p))
+            .unwrap_or_default();
+        match (self.kind, &self.sprint) {
+            (ReportKind::Review, Some(progress)) => {
+                format!("Sprint Review: {}{}", progress.sprint.name, scope)
+            }
+            (ReportKind::Review, None) => format!(
+                "Review {} to {}{}",
+                self.since.format("%Y-%m-%d"),
+                self.until.format("%Y-%m-%d"),
+                scope
+            ),
+            (ReportKind::Standup, _) => {
+                format!("Standup {}{}", self.until.format("%Y-%m-%d"), scope)
+            }
+        }
+    }
+
+    fn task_line(&self, task: &Task) -> String {
+        let mut line = format!("- {} {}", task.priority.emoji(), task.title);
+        if self.project.is_none() {
+            if let Some(project) = &task.project {
+                line.push_str(&format!(" _({})_", project));
+            }
+        }
+        if let Some(due) = task
+            .due_formatted()
+            .filter(|_| is_overdue_at(task, self.until))
+        {
+            line.push_str(&format!(" — due {}", due));
+        }
+        line
+    }
+
+    fn task_list(&self, tasks: &[Task]) -> String {
+        if tasks.is_empty() {
+            return "_None_".to_string();
+        }
+        tasks
+            .iter()
+            .map(|t| self.task_line(t))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn project_table(&self) -> String {
+        if self.projects.is_empty() {
+            return "_None_".to_string();
+        }
+        let mut table = String::from(
+            "| Project | Completed | Started | Open | Blocked | Overdue |\n\
+             |---------|-----------|---------|------|---------|---------|",
+        );
+        for p in &self.projects {
+            table.push_str(&format!(
+                "\n| {} | {} | {} | {} | {} | {} |",
+                p.project, p.completed, p.started, p.open, p.blocked, p.overdue
+            ));
+        }
+        table
+    }
+
+    fn sprint_block(&self) -> String {
+        let Some(progress) = &self.sprint else {
+            return String::new();
+        };
+        let mut block = format!("**Sprint:** {}", progress.sprint.name);
+        if let Some(goal) = &progress.sprint.goal {
+            block.push_str(&format!("  \n**Goal:** {}", goal));
+        }
+        block.push_str(&format!("  \n**Progress:** {}", progress.summary()));
+        block
+    }
+
+    /// Render Markdown through a template. Placeholders: `{{title}}`,
+    /// `{{since}}`, `{{until}}`, `{{project}}`, `{{sprint}}` (name, goal and
+    /// progress), `{{sprint_name}}`, `{{goal}}`, `{{progress}}`, `{{projects}}`,
+    /// and a list plus `_count` for each of `completed`, `started`,
+    /// `in_progress`, `blocked` and `overdue`.
+    pub fn render(&self, template: &str) -> String {
+        let sprint = self.sprint.as_ref();
+        let mut values: Vec<(&str, String)> = vec![
+            ("title", self.title()),
+            ("since", self.since.format("%Y-%m-%d %H:%M").to_string()),
+            ("until", self.until.format("%Y-%m-%d %H:%M").to_string()),
+            ("project", self.project.clone().unwrap_or_default()),
+            ("sprint", self.sprint_block()),
+            (
+                "sprint_name",
+                sprint.map(|p| p.sprint.name.clone()).unwrap_or_default(),
+            ),
+            (
+                "goal",
+                sprint
+                    .and_then(|p| p.sprint.goal.clone())
+                    .unwrap_or_default(),
+            ),
+            ("progress", sprint.map(|p| p.summary()).unwrap_or_default()),
+            ("projects", self.project_table()),
+        ];
+        for (name, tasks) in self.sections() {
+            values.push((name, self.task_list(tasks)));
+        }
+
+        let mut output = template.to_string();
+        for (name, tasks) in self.sections() {
+            output = output.replace(&format!("{{{{{}_count}}}}", name), &tasks.len().to_string());
+        }
+        for (name, value) in values {
+            output = output.replace(&format!("{{{{{}}}}}", name), &value);
+        }
+
+        // Empty placeholders (e.g. no sprint) leave runs of blank lines
+        while output.contains("\n\n\n") {
+            output = output.replace("\n\n\n", "\n\n");
+        }
+        output
+    }
+
+    fn sections(&self) -> [(&'static str, &[Task]); 5] {
+        [
+            ("completed", &self.completed),
+            ("started", &self.started),
+            ("in_progress", &self.in_progress),
+            ("blocked", &self.blocked),
+            ("overdue", &self.overdue),
+        ]
+    }
+
+    /// JSON form of the report
+    pub fn to_json(&self) -> Value {
+        let summary = |tasks: &[Task]| -> Vec<Value> {
+            tasks
+                .iter()
+                .map(|t| {
+                    json!({
+                        "id": t.id,
+                        "title": t.title,
+                        "project": t.project,
+                        "priority": t.priority.to_string(),
+                        "status": t.status.to_string(),
+                        "due_date": t.due_formatted(),
+                        "completed_at": t.completed_at,
+                        "started_at": t.started_at
+                    })
+                })
+                .collect()
+        };
+        let mut report = json!({
+            "kind": self.kind.name(),
+            "title": self.title(),
+            "since": self.since,
+            "until": self.until,
+            "project": self.project,
+            "sprint": self.sprint.as_ref().map(|p| json!({
+                "id": p.sprint.id,
+                "name": p.sprint.name,
+                "goal": p.sprint.goal,
+                "status": p.sprint.status.to_string(),
+                "done": p.done,
+                "total": p.total,
+                "done_points": p.done_points,
+                "total_points": p.total_points,
+                "percent": p.percent()
+            })),
+            "projects": self.projects.iter().map(|p| json!({
+                "project": p.project,
+                "completed": p.completed,
+                "started": p.started,
+                "open": p.open,
+                "blocked": p.blocked,
+                "overdue": p.overdue
+            })).collect::<Vec<_>>()
+        });
+        for (name, tasks) in self.sections() {
+            report[name] = json!(summary(tasks));
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    fn add_task(store: &MemoryStore, title: &str, f: impl FnOnce(&mut Task)) -> Task {
+        let mut task = Task::new(title.to_string());
+        task.project = Some("vulcan-os".to_string());
+        f(&mut task);
+        store.add(&task).unwrap()
+    }
+
+    #[test]
+    fn test_sprint_review_report() {
+        let store = MemoryStore::new();
+        let now = Utc::now();
+        let mut sprint = Sprint::new("Sprint 4".to_string(), "vulcan-os".to_string());
+        sprint.goal = Some("Ship the installer".to_string());
+        sprint.start_date = Some(now - Duration::days(3));
+        sprint.status = SprintStatus::Active;
+        let sprint = store.add_sprint(&sprint).unwrap();
+
+        let done = add_task(&store, "Partition disks", |t| {
+            t.status = crate::models::Status::Done;
+            t.started_at = Some(now - Duration::days(2));
+            t.completed_at = Some(now - Duration::days(1));
+            t.estimate = Some(3);
+        });
+        let old = add_task(&store, "Old work", |t| {
+            t.status = crate::models::Status::Done;
+            t.completed_at = Some(now - Duration::days(10));
+        });
+        let blocked = add_task(&store, "Sign ISO", |t| {
+            t.tags = vec!["Blocked".to_string()];
+            t.due_date = Some(now - Duration::hours(1));
+        });
+        add_task(&store, "Outside sprint", |_| {});
+        for task in [&done, &old, &blocked] {
+            store.assign_task_to_sprint(&task.id, &sprint.id).unwrap();
+        }
+
+        let options = ReportOptions {
+            project: Some("vulcan-os".to_string()),
+            ..Default::default()
+        };
+        let report = generate(&store, ReportKind::Review, &options).unwrap();
+        assert_eq!(report.since, sprint.start_date.unwrap());
+        assert_eq!(report.completed.len(), 1);
+        assert_eq!(report.started.len(), 1);
+        assert_eq!(report.blocked.len(), 1);
+        assert_eq!(report.overdue.len(), 1);
+        let progress = report.sprint.as_ref().unwrap();
+        assert_eq!((progress.done, progress.total), (2, 3));
+        assert_eq!((progress.done_points, progress.total_points), (4, 5));
+        assert_eq!(
+            report.projects,
+            vec![ProjectBreakdown {
+                project: "vulcan-os".to_string(),
+                completed: 1,
+                started: 1,
+                open: 1,
+                blocked: 1,
+                overdue: 1,
+            }]
+        );
+
+        let markdown = report.render(REVIEW_TEMPLATE);
+        assert!(markdown.starts_with("# Sprint Review: Sprint 4 (vulcan-os)\n"));
+        assert!(markdown.contains("**Goal:** Ship the installer"));
+        assert!(markdown.contains("4/5 pts (80%)"));
+        assert!(markdown.contains("## Completed (1)\n- ⚪ Partition disks\n"));
+        assert!(!markdown.contains("Old work"));
+        assert!(!markdown.contains("{{"));
+
+        let custom = report.render("{{title}}: {{completed_count}} done, {{blocked}}");
+        assert_eq!(
+            custom,
+            format!(
+                "Sprint Review: Sprint 4 (vulcan-os): 1 done, - ⚪ Sign ISO — due {}",
+                blocked.due_formatted().unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_standup_defaults_to_last_day() {
+        let store = MemoryStore::new();
+        let mut task = Task::new("Write docs".to_string());
+        task.start();
+        store.add(&task).unwrap();
+
+        let report = generate(&store, ReportKind::Standup, &ReportOptions::default()).unwrap();
+        assert_eq!(report.until - report.since, Duration::days(1));
+        assert_eq!(report.started.len(), 1);
+        assert_eq!(report.in_progress.len(), 1);
+        assert!(report.sprint.is_none());
+        let markdown = report.render(STANDUP_TEMPLATE);
+        assert!(markdown.contains("## Done\n_None_\n"));
+        assert_eq!(report.to_json()["in_progress"][0]["title"], "Write docs");
+
+        assert_eq!("Review".parse::<ReportKind>().unwrap(), ReportKind::Review);
+        assert!("retro".parse::<ReportKind>().is_err());
+    }
+
+    #[test]
+    fn test_sprint_percent_does_not_overflow() {
+        let progress = |done_points, total_points| SprintProgress {
+            sprint: Sprint::new("Sprint".to_string(), "vulcan-os".to_string()),
+            done: 0,
+            total: 0,
+            done_points,
+            total_points,
+        };
+        assert_eq!(progress(u32::MAX, u32::MAX).percent(), 100);
+        assert_eq!(progress(u32::MAX / 2, u32::MAX).percent(), 49);
+        assert_eq!(progress(0, 0).percent(), 0);
+        // Never more than 100, even if done somehow exceeds total
+        assert_eq!(progress(3, 2).percent(), 100);
+
+        assert!(parse_report_date("100000000d", Utc::now()).is_none());
+    }
+}
@@ -0,0 +1,449 @@
+//! Import tasks from TODO/FIXME/HACK comments (`vulcan-todo scan`)
+//!
+//! Each comment becomes a task keyed by a fingerprint of its file, marker
+//! and text, so re-scanning updates the task (new line number) instead of
+//! duplicating it. Tasks whose comment disappeared are marked done.
+
+use crate::models::{Priority, SourceAnchor, Task};
+use crate::store::Store;
+use anyhow::{bail, Result};
+use ignore::WalkBuilder;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Comment markers that become tasks
+pub const MARKERS: [&str; 3] = ["TODO", "FIXME", "HACK"];
+
+/// Files larger than this are skipped (generated or vendored code)
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Tokens that start a comment in the languages we care about
+const COMMENT_LEADERS: [&str; 6] = ["//", "#", "/*", "--", ";", "<!--"];
+
+/// Extensions of files whose comments start with `#`. Elsewhere `#` is a
+/// Markdown heading, a C preprocessor line or a Rust attribute.
+const HASH_COMMENT_EXTENSIONS: [&str; 18] = [
+    "py", "sh", "bash", "zsh", "fish", "rb", "pl", "pm", "r", "yaml", "yml", "toml", "ini", "cfg",
+    "conf", "nix", "tf", "ps1",
+];
+
+/// Files without one of those extensions that still use `#` comments
+const HASH_COMMENT_FILES: [&str; 6] = [
+    "Makefile",
+    "Dockerfile",
+    "CMakeLists.txt",
+    "PKGBUILD",
+    ".gitignore",
+    ".env",
+];
+
+/// A TODO-style comment found in a source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceComment {
+    /// Path relative to the repository root
+    pub path: String,
+    /// 1-based line number
+    pub line: usize,
+    pub marker: String,
+    pub text: String,
+    pub fingerprint: String,
+}
+
+impl SourceComment {
+    /// Task title: the comment text, or a placeholder for bare markers
+    pub fn title(&self) -> String {
+        if self.text.is_empty() {
+            format!("{} in {}", self.marker, self.path)
+        } else {
+            self.text.clone()
+        }
+    }
+
+    fn anchor(&self, root: &str) -> SourceAnchor {
+        SourceAnchor {
+            fingerprint: self.fingerprint.clone(),
+            root: root.to_string(),
+            path: self.path.clone(),
+            line: self.line,
+            marker: self.marker.clone(),
+        }
+    }
+}
+
+/// Options for [`scan`]
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    /// Project for imported tasks (default: repository directory name)
+    pub project: Option<String>,
+    /// Report changes without writing them
+    pub dry_run: bool,
+}
+
+/// Outcome of a scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    pub root: PathBuf,
+    pub project: String,
+    pub comments: usize,
+    pub created: Vec<Task>,
+    /// Tasks whose comment moved, changed project or reappeared
+    pub updated: Vec<Task>,
+    /// Tasks completed because their comment is gone
+    pub completed: Vec<Task>,
+}
+
+/// Scan `dir` for TODO comments and sync them into the store
+pub fn scan(store: &dyn Store, dir: &Path, options: &ScanOptions) -> Result<ScanReport> {
+    if !dir.is_dir() {
+        bail!("Not a directory: {}", dir.display());
+    }
+    let dir = dir.canonicalize()?;
+    let root = repo_root(&dir);
+    let root_str = root.to_string_lossy().to_string();
+    let project = options.project.clone().unwrap_or_else(|| repo_name(&root));
+
+    // Only tasks under the scanned subdirectory can be completed
+    let prefix = dir
+        .strip_prefix(&root)
+        .map(relative_path)
+        .unwrap_or_default();
+
+    let comments = collect_comments(&root, &dir)?;
+    let mut report = ScanReport {
+        root: root.clone(),
+        project: project.clone(),
+        comments: comments.len(),
+        ..Default::default()
+    };
+
+    let existing: HashMap<String, Task> = store
+        .get_all()?
+        .into_iter()
+        .filter_map(|t| match &t.source {
+            Some(anchor) if anchor.root == root_str => Some((anchor.fingerprint.clone(), t)),
+            _ => None,
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    for comment in &comments {
+        seen.insert(comment.fingerprint.clone());
+        let anchor = comment.anchor(&root_str);
+
+        match existing.get(&comment.fingerprint) {
+            Some(task) => {
+                let unchanged = task.source.as_ref() == Some(&anchor)
+                    && task.project.as_deref() == Some(project.as_str())
+                    && !task.is_done();
+                if unchanged {
+                    continue;
+                }
+                let mut task = task.clone();
+                // Keep descriptions the user wrote; only refresh our own
+                let generated = task.source.as_ref().map(anchor_description);
+                if task.description.is_some() && task.description == generated {
+                    task.description = Some(anchor_description(&anchor));
+                }
+                task.source = Some(anchor);
+                task.project = Some(project.clone());
+                if task.is_done() {
+                    // The comment is back: the work isn't finished
+                    task.uncomplete();
+                }
+                if !options.dry_run {
+                    store.update(&task)?;
+                }
+                report.updated.push(task);
+            }
+            None => {
+                let mut task = Task::new(comment.title());
+                task.description = Some(anchor_description(&anchor));
+                task.project = Some(project.clone());
+                task.tags = vec![comment.marker.to_lowercase()];
+                task.priority = marker_priority(&comment.marker);
+                task.source = Some(anchor);
+                let task = if options.dry_run {
+                    task
+                } else {
+                    store.add(&task)?
+                };
+                report.created.push(task);
+            }
+        }
+    }
+
+    for (fingerprint, task) in existing {
+        let in_scope = task
+            .source
+            .as_ref()
+            .is_some_and(|a| prefix.is_empty() || a.path.starts_with(&format!("{}/", prefix)));
+        if seen.contains(&fingerprint) || !in_scope || !task.is_active() {
+            continue;
+        }
+        let mut task = task;
+        task.complete();
+        if !options.dry_run {
+            store.update(&task)?;
+        }
+        report.completed.push(task);
+    }
+
+    Ok(report)
+}
+
+/// Walk `dir` (respecting .gitignore) and extract TODO comments.
+/// Paths are reported relative to `root`.
+pub fn collect_comments(root: &Path, dir: &Path) -> Result<Vec<SourceComment>> {
+    let mut comments = Vec::new();
+
+    let walker = WalkBuilder::new(dir).require_git(false).build();
+    for entry in walker.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        if entry
+            .metadata()
+            .map(|m| m.len() > MAX_FILE_SIZE)
+            .unwrap_or(true)
+        {
+            continue;
+        }
+        // Binary or non-UTF-8 files are skipped
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let rel = relative_path(path.strip_prefix(root).unwrap_or(path));
+        comments.extend(extract_comments(&rel, &content));
+    }
+
+    comments.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    Ok(comments)
+}
+
+/// Extract TODO comments from file content. Identical comments in one file
+/// get distinct fingerprints by occurrence.
+pub fn extract_comments(path: &str, content: &str) -> Vec<SourceComment> {
+    let mut comments = Vec::new();
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    let hash_comments = uses_hash_comments(path);
+
+    for (index, line) in content.lines().enumerate() {
+        let Some((marker, text)) = parse_comment(line, hash_comments) else {
+            continue;
+        };
+        let key = format!("{}\0{}\0{}", path, marker, normalize(&text));
+        let occurrence = occurrences.entry(key.clone()).or_insert(0);
+        *occurrence += 1;
+
+        comments.push(SourceComment {
+            path: path.to_string(),
+            line: index + 1,
+            marker: marker.to_string(),
+            text,
+            fingerprint: fingerprint(&format!("{}\0{}", key, occurrence)),
+        });
+    }
+
+    comments
+}
+
+/// Check whether `#` starts a comment in the file at `path`
+fn uses_hash_comments(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    if HASH_COMMENT_FILES.contains(&name) {
+        return true;
+    }
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| HASH_COMMENT_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Parse `// TODO(name): text` style comments on one line. `#` only opens
+/// a comment when `hash_comments` is set.
+fn parse_comment(line: &str, hash_comments: bool) -> Option<(&'static str, String)> {
+    for marker in MARKERS {
+        let mut search_from = 0;
+        while let Some(found) = line[search_from..].find(marker) {
+            let start = search_from + found;
+            let end = start + marker.len();
+            search_from = end;
+
+            // The marker must open the comment: `// TODO: x`, not `// see TODO.md`
+            let before = &line[..start];
+            let word_end = line[end..]
+                .chars()
+                .next()
+                .is_none_or(|c| c.is_whitespace() || matches!(c, ':' | '(' | '-' | '*'));
+            if !word_end || !opens_comment(before, hash_comments) {
+                continue;
+            }
+
+            let mut rest = &line[end..];
+            // Optional owner: TODO(kevan)
+            if let Some(after_paren) = rest.strip_prefix('(') {
+                rest = after_paren.split_once(')').map(|(_, r)| r).unwrap_or("");
+            }
+            let rest = rest.trim_start_matches([':', '-', ' ', '\t']);
+            let text = rest
+                .trim()
+                .trim_end_matches("*/")
+                .trim_end_matches("-->")
+                .trim();
+            return Some((marker, text.to_string()));
+        }
+    }
+    None
+}
+
+/// Check whether the text before a marker ends with a comment leader
+/// (optionally followed by whitespace), or is a `*` block continuation
+fn opens_comment(before: &str, hash_comments: bool) -> bool {
+    let code = before.trim_end_matches(|c: char| c.is_whitespace() || "/*!#;-<".contains(c));
+    let leader = &before[code.len()..];
+    if code.trim().is_empty() && leader.contains('*') {
+        return true;
+    }
+    COMMENT_LEADERS
+        .iter()
+        .filter(|l| hash_comments || **l != "#")
+        .any(|l| leader.contains(l))
+}
+
+/// Collapse whitespace so reformatting doesn't change the fingerprint
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// FNV-1a hash as hex; stable across builds and platforms
+fn fingerprint(key: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in key.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn marker_priority(marker: &str) -> Priority {
+    match marker {
+        "FIXME" => Priority::High,
+        "HACK" => Priority::Medium,
+        _ => Priority::default(),
+    }
+}
+
+/// Description given to imported tasks
+fn anchor_description(anchor: &SourceAnchor) -> String {
+    format!("{} at {}:{}", anchor.marker, anchor.path, anchor.line)
+}
+
+/// Nearest ancestor containing `.git`, or the directory itself
+fn repo_root(dir: &Path) -> PathBuf {
+    dir.ancestors()
+        .find(|d| d.join(".git").exists())
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+fn repo_name(root: &Path) -> String {
+    root.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "general".to_string())
+}
+
+/// Forward-slash relative path for stable anchors across platforms
+fn relative_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    #[test]
+    fn test_extract_comments() {
+        let content = r#"fn main() {
+    // TODO: handle errors
+    let s = "TODO: not a comment";
+    /* FIXME(kevan) leaks memory */
+    # HACKY is not a marker
+    //! See TODO.md for the roadmap
+    // TODO: handle errors
+}
+"#;
+        let comments = extract_comments("src/main.rs", content);
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[0].line, 2);
+        assert_eq!(comments[0].text, "handle errors");
+        assert_eq!(comments[1].marker, "FIXME");
+        assert_eq!(comments[1].text, "leaks memory");
+        // Duplicate comments are tracked separately
+        assert_ne!(comments[0].fingerprint, comments[2].fingerprint);
+
+        // Moving a comment keeps its fingerprint
+        let moved = extract_comments("src/main.rs", "\n\n\n// TODO:  handle   errors\n");
+        assert_eq!(moved[0].fingerprint, comments[0].fingerprint);
+
+        // `#` is a comment in scripts, a heading in Markdown
+        let hashed = "# TODO: tidy up\n<!-- FIXME: broken link -->\n";
+        assert_eq!(extract_comments("deploy.sh", hashed).len(), 2);
+        assert_eq!(extract_comments("Makefile", hashed).len(), 2);
+        let markdown = extract_comments("README.md", hashed);
+        assert_eq!(markdown.len(), 1);
+        assert_eq!(markdown[0].marker, "FIXME");
+    }
+
+    #[test]
+    fn test_scan_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("my-repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::create_dir_all(repo.join("target")).unwrap();
+        std::fs::write(repo.join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(repo.join("src/lib.rs"), "// TODO: one\n// FIXME: two\n").unwrap();
+        std::fs::write(repo.join("target/gen.rs"), "// TODO: ignored\n").unwrap();
+
+        let store = MemoryStore::new();
+        let report = scan(&store, &repo, &ScanOptions::default()).unwrap();
+        assert_eq!(report.created.len(), 2);
+        assert_eq!(report.project, "my-repo");
+        let fixme = report.created.iter().find(|t| t.title == "two").unwrap();
+        assert_eq!(fixme.priority, Priority::High);
+        assert_eq!(fixme.source.as_ref().unwrap().location(), "src/lib.rs:2");
+
+        // Rescan: one comment moved, the other removed
+        std::fs::write(repo.join("src/lib.rs"), "\n\n// FIXME: two\n").unwrap();
+        let report = scan(&store, &repo, &ScanOptions::default()).unwrap();
+        assert!(report.created.is_empty());
+        assert_eq!(report.updated.len(), 1);
+        assert_eq!(report.updated[0].source.as_ref().unwrap().line, 3);
+        assert_eq!(report.completed.len(), 1);
+        assert_eq!(report.completed[0].title, "one");
+        assert_eq!(store.get_all().unwrap().len(), 2);
+        let fixme = store.get(&fixme.id).unwrap().unwrap();
+        assert_eq!(fixme.description.as_deref(), Some("FIXME at src/lib.rs:3"));
+
+        // A description the user wrote survives the next move
+        let mut fixme = fixme;
+        fixme.description = Some("Leaks on reconnect".to_string());
+        store.update(&fixme).unwrap();
+        std::fs::write(repo.join("src/lib.rs"), "// FIXME: two\n").unwrap();
+        let report = scan(&store, &repo, &ScanOptions::default()).unwrap();
+        assert_eq!(report.updated.len(), 1);
+        let fixme = store.get(&fixme.id).unwrap().unwrap();
+        assert_eq!(fixme.source.as_ref().unwrap().line, 1);
+        assert_eq!(fixme.description.as_deref(), Some("Leaks on reconnect"));
+
+        // Nothing changed
+        let report = scan(&store, &repo, &ScanOptions::default()).unwrap();
+        assert!(report.created.is_empty() && report.updated.is_empty());
+        assert!(report.completed.is_empty());
+    }
+}
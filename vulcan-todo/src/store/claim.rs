@@ -0,0 +1,231 @@
+//! Task claims for multi-agent coordination
+//!
+//! Agents sharing one store claim a task before working on it. A claim is a
+//! lease: it lapses after its TTL unless the owner renews it with a
+//! heartbeat, so a crashed agent doesn't hold its tasks forever.
+
+use crate::models::{Status, Task};
+use crate::store::Store;
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, Utc};
+
+/// Default lease length
+pub const DEFAULT_LEASE_MINUTES: i64 = 30;
+
+/// Longest lease a single claim or heartbeat can take (one day)
+pub const MAX_LEASE_MINUTES: i64 = 24 * 60;
+
+/// Claim a task for `owner` (renews the lease if `owner` already holds it).
+/// Fails if another owner holds a live claim. The check and the claim are
+/// one [`Store::modify`], so two agents can't both win the same task.
+pub fn claim_task(
+    store: &dyn Store,
+    id: &str,
+    owner: &str,
+    ttl: Duration,
+    now: DateTime<Utc>,
+) -> Result<Task> {
+    store
+        .modify(id, &mut |task| {
+            if !task.is_active() {
+                bail!(
+                    "Task {} is {}, only open tasks can be claimed",
+                    id,
+                    task.status
+                );
+            }
+            task.claim(owner, ttl, now)
+                .map_err(|holder| anyhow!("Task {} is claimed by {}", id, holder))
+        })?
+        .ok_or_else(|| anyhow!("Task not found: {}", id))
+}
+
+/// Renew the leases held by `owner`: one task when `id` is given, otherwise
+/// every live claim of the owner. Returns the renewed tasks.
+pub fn heartbeat(
+    store: &dyn Store,
+    owner: &str,
+    id: Option<&str>,
+    ttl: Duration,
+    now: DateTime<Utc>,
+) -> Result<Vec<Task>> {
+    let mut renewed = Vec::new();
+    for task in store.get_all()? {
+        if id.is_some_and(|id| id != task.id) {
+            continue;
+        }
+        if task.active_claim(now).map(|c| c.owner.as_str()) != Some(owner) {
+            continue;
+        }
+        // The lease may have lapsed and been taken since get_all
+        let renew = store.modify(&task.id, &mut |task| {
+            if task.active_claim(now).map(|c| c.owner.as_str()) != Some(owner) {
+                bail!("Task {} is no longer claimed by {}", task.id, owner);
+            }
+            task.claim(owner, ttl, now)
+                .map_err(|holder| anyhow!(holder))
+        });
+        if let Ok(Some(updated)) = renew {
+            renewed.push(updated);
+        }
+    }
+    Ok(renewed)
+}
+
+/// Release a claim. With `owner` set, only that owner's claim is released;
+/// without it the claim is dropped unconditionally (manual override).
+pub fn release_task(store: &dyn Store, id: &str, owner: Option<&str>) -> Result<Task> {
+    store
+        .modify(id, &mut |task| {
+            if let (Some(owner), Some(claim)) = (owner, task.active_claim(Utc::now())) {
+                if claim.owner != owner {
+                    bail!("Task {} is claimed by {}", id, claim.owner);
+                }
+            }
+            if task.release() && task.status == Status::InProgress {
+                task.status = Status::Pending;
+            }
+            Ok(())
+        })?
+        .ok_or_else(|| anyhow!("Task not found: {}", id))
+}
+
+/// Drop leases that expired before `now`. Tasks left in progress by the
+/// lapsed owner go back to pending so another agent can pick them up.
+pub fn release_expired_claims(store: &dyn Store, now: DateTime<Utc>) -> Result<Vec<Task>> {
+    let mut released = Vec::new();
+    for task in store.get_all()? {
+        let expired = task.claim.as_ref().is_some_and(|c| !c.is_live(now));
+        if !expired {
+            continue;
+        }
+        if let Some(updated) = release_expired_claim(store, &task.id, now)? {
+            released.push(updated);
+        }
+    }
+    Ok(released)
+}
+
+/// Release one task's lease if it is still expired under the store lock;
+/// it may have been renewed or taken over since it was read
+fn release_expired_claim(store: &dyn Store, id: &str, now: DateTime<Utc>) -> Result<Option<Task>> {
+    let mut live = false;
+    let result = store.modify(id, &mut |task| {
+        if task.claim.as_ref().is_none_or(|c| c.is_live(now)) {
+            live = true;
+            bail!("Task {} is no longer expired", id);
+        }
+        task.release();
+        if task.status == Status::InProgress {
+            task.status = Status::Pending;
+        }
+        Ok(())
+    });
+    match result {
+        Err(_) if live => Ok(None),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    #[test]
+    fn test_claim_and_heartbeat() {
+        let store = MemoryStore::new();
+        let task = store.add(&Task::new("Shared".to_string())).unwrap();
+        let now = Utc::now();
+        let ttl = Duration::minutes(DEFAULT_LEASE_MINUTES);
+
+        let claimed = claim_task(&store, &task.id, "agent-a", ttl, now).unwrap();
+        assert_eq!(claimed.claim.as_ref().unwrap().owner, "agent-a");
+        assert!(claim_task(&store, &task.id, "agent-b", ttl, now).is_err());
+        assert!(release_task(&store, &task.id, Some("agent-b")).is_err());
+
+        let later = now + Duration::minutes(20);
+        let renewed = heartbeat(&store, "agent-a", None, ttl, later).unwrap();
+        assert_eq!(renewed.len(), 1);
+        assert_eq!(renewed[0].claim.as_ref().unwrap().expires_at, later + ttl);
+        // The original claim time is kept
+        assert_eq!(renewed[0].claim.as_ref().unwrap().claimed_at, now);
+        assert!(heartbeat(&store, "agent-b", None, ttl, later)
+            .unwrap()
+            .is_empty());
+
+        let released = release_task(&store, &task.id, Some("agent-a")).unwrap();
+        assert!(released.claim.is_none());
+        assert!(claim_task(&store, &task.id, "agent-b", ttl, now).is_ok());
+    }
+
+    #[test]
+    fn test_concurrent_claims_have_one_winner() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("tasks.json");
+        let store = crate::store::JsonStore::with_path(path.clone()).unwrap();
+        let task = store.add(&Task::new("Contested".to_string())).unwrap();
+        let now = Utc::now();
+        let ttl = Duration::minutes(DEFAULT_LEASE_MINUTES);
+
+        // One store per agent, as with separate MCP server processes
+        let winners: Vec<bool> = std::thread::scope(|s| {
+            let agents: Vec<_> = (0..8)
+                .map(|i| {
+                    let store = crate::store::JsonStore::with_path(path.clone()).unwrap();
+                    let id = task.id.clone();
+                    s.spawn(move || {
+                        // Warm the cache so a stale read would let a second claim through
+                        store.get(&id).unwrap();
+                        claim_task(&store, &id, &format!("agent-{}", i), ttl, now).is_ok()
+                    })
+                })
+                .collect();
+            agents.into_iter().map(|a| a.join().unwrap()).collect()
+        });
+        assert_eq!(winners.iter().filter(|w| **w).count(), 1);
+    }
+
+    #[test]
+    fn test_release_expired_claims() {
+        let store = MemoryStore::new();
+        let mut task = Task::new("Abandoned".to_string());
+        task.start();
+        let task = store.add(&task).unwrap();
+        let now = Utc::now();
+
+        claim_task(&store, &task.id, "crashed", Duration::minutes(5), now).unwrap();
+        assert!(release_expired_claims(&store, now).unwrap().is_empty());
+
+        let later = now + Duration::minutes(10);
+        let stored = store.get(&task.id).unwrap().unwrap();
+        // An expired lease no longer blocks others, even before the sweep
+        assert!(!stored.is_claimed_by_other(Some("agent-b"), later));
+
+        let released = release_expired_claims(&store, later).unwrap();
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].status, Status::Pending);
+        assert!(released[0].claim.is_none());
+    }
+
+    #[test]
+    fn test_expired_claim_taken_over_before_release() {
+        let store = MemoryStore::new();
+        let task = store.add(&Task::new("Contested".to_string())).unwrap();
+        let now = Utc::now();
+        let ttl = Duration::minutes(5);
+        claim_task(&store, &task.id, "crashed", ttl, now).unwrap();
+
+        // The sweep sees the lapsed lease, then another agent claims the task
+        let later = now + Duration::minutes(10);
+        let scanned = store.get(&task.id).unwrap().unwrap();
+        assert!(scanned.claim.as_ref().is_some_and(|c| !c.is_live(later)));
+        claim_task(&store, &task.id, "agent-b", ttl, later).unwrap();
+
+        assert!(release_expired_claim(&store, &task.id, later)
+            .unwrap()
+            .is_none());
+        let stored = store.get(&task.id).unwrap().unwrap();
+        assert_eq!(stored.claim.as_ref().unwrap().owner, "agent-b");
+    }
+}
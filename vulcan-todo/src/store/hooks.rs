@@ -0,0 +1,478 @@
+//! Taskwarrior-style hooks for vulcan-todo
+//!
+//! Hooks are executables in the hooks directory (default:
+//! `~/.config/vulcan-todo/hooks`, override with `VULCAN_TODO_HOOKS_DIR`)
+//! whose file name starts with the event name, e.g. `on-add`,
+//! `on-complete.notify` or `on-modify-slack.sh`. Hooks for an event run
+//! in file name order.
+//!
+//! Protocol:
+//! - `on-add` receives the new task as one JSON line on stdin.
+//! - `on-modify`, `on-start` and `on-complete` receive the original task
+//!   and the modified task as two JSON lines.
+//! - `on-sprint-complete` receives the original and completed sprint.
+//! - A non-zero exit code vetoes the change; stdout lines that aren't
+//!   JSON are reported as the reason.
+//! - On success, a JSON object line on stdout replaces the task (or sprint)
+//!   being written, letting hooks modify the change. No JSON = unchanged.
+//! - stderr is captured: it explains a veto when stdout doesn't, and is
+//!   otherwise kept for [`Hooks::take_output`] so the CLI can print it and
+//!   the TUI can show it in the status line.
+//! - A hook still running after the timeout (default 30 seconds, override
+//!   with `VULCAN_TODO_HOOK_TIMEOUT` in seconds) is killed, and the change
+//!   is vetoed.
+//!
+//! Stores run no hooks unless given them with `JsonStore::with_hooks`.
+
+use crate::models::{Sprint, SprintStatus, Status, Task};
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Environment variable set for hook processes (prevents recursive hooks)
+const IN_HOOK_ENV: &str = "VULCAN_TODO_IN_HOOK";
+
+/// How long a hook may run before it is killed
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Hook events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Add,
+    Modify,
+    Start,
+    Complete,
+    SprintComplete,
+}
+
+impl HookEvent {
+    /// File name prefix used to discover hooks for this event
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::Add => "on-add",
+            HookEvent::Modify => "on-modify",
+            HookEvent::Start => "on-start",
+            HookEvent::Complete => "on-complete",
+            HookEvent::SprintComplete => "on-sprint-complete",
+        }
+    }
+
+    /// Events triggered by a task write (original is None for new tasks)
+    pub fn for_task_change(old: Option<&Task>, new: &Task) -> Vec<HookEvent> {
+        let old = match old {
+            Some(old) => old,
+            None => return vec![HookEvent::Add],
+        };
+
+        let mut events = vec![HookEvent::Modify];
+        if old.status != Status::InProgress && new.status == Status::InProgress {
+            events.push(HookEvent::Start);
+        }
+        if old.status != Status::Done && new.status == Status::Done {
+            events.push(HookEvent::Complete);
+        }
+        events
+    }
+}
+
+/// Hook runner bound to a hooks directory
+#[derive(Debug, Clone)]
+pub struct Hooks {
+    /// Hooks directory (None = hooks disabled)
+    dir: Option<PathBuf>,
+    /// How long each hook may run
+    timeout: Duration,
+    /// stderr of hooks that allowed their change, not yet taken
+    output: Arc<Mutex<Vec<String>>>,
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            timeout: DEFAULT_TIMEOUT,
+            output: Arc::default(),
+        }
+    }
+}
+
+impl Hooks {
+    /// Hooks from `VULCAN_TODO_HOOKS_DIR` or the default config location.
+    /// Disabled inside a running hook so hooks can't trigger themselves.
+    pub fn discover() -> Self {
+        if std::env::var_os(IN_HOOK_ENV).is_some() {
+            return Self::disabled();
+        }
+
+        let hooks = match std::env::var_os("VULCAN_TODO_HOOKS_DIR")
+            .map(PathBuf::from)
+            .or_else(|| Self::default_dir().ok())
+        {
+            Some(dir) => Self::with_dir(dir),
+            None => return Self::disabled(),
+        };
+
+        match std::env::var("VULCAN_TODO_HOOK_TIMEOUT")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+        {
+            Some(secs) => hooks.with_timeout(Duration::from_secs(secs)),
+            None => hooks,
+        }
+    }
+
+    /// Hooks from a specific directory
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Kill hooks (and veto their change) after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// No hooks
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Take the stderr lines hooks printed since the last call
+    pub fn take_output(&self) -> Vec<String> {
+        std::mem::take(&mut *self.output.lock().unwrap())
+    }
+
+    /// Default hooks directory
+    pub fn default_dir() -> Result<PathBuf> {
+        let config_dir = dirs::config_dir()
+            .context("Could not determine config directory")?
+            .join("vulcan-todo");
+
+        Ok(config_dir.join("hooks"))
+    }
+
+    /// Hook executables for an event, sorted by file name
+    pub fn scripts_for(&self, event: HookEvent) -> Vec<PathBuf> {
+        let dir = match &self.dir {
+            Some(dir) if dir.is_dir() => dir,
+            _ => return Vec::new(),
+        };
+
+        let mut scripts: Vec<PathBuf> = match std::fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|n| matches_event(n, event))
+                        .unwrap_or(false)
+                        && is_executable(p)
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        scripts.sort();
+        scripts
+    }
+
+    /// Run hooks for a task write. Returns the (possibly modified) task,
+    /// or an error if a hook vetoed the change.
+    pub fn run_task_hooks(&self, old: Option<&Task>, new: &Task) -> Result<Task> {
+        if self.dir.is_none() {
+            return Ok(new.clone());
+        }
+
+        let mut task = new.clone();
+        for event in HookEvent::for_task_change(old, new) {
+            for script in self.scripts_for(event) {
+                let mut input = Vec::new();
+                if let Some(old) = old {
+                    input.push(serde_json::to_string(old)?);
+                }
+                input.push(serde_json::to_string(&task)?);
+
+                if let Some(modified) = self.run_hook::<Task>(&script, event, &input)? {
+                    if modified.id != task.id {
+                        bail!(
+                            "Hook {} changed the task id ({} -> {})",
+                            script.display(),
+                            task.id,
+                            modified.id
+                        );
+                    }
+                    task = modified;
+                }
+            }
+        }
+        Ok(task)
+    }
+
+    /// Run hooks for a sprint write (on-sprint-complete when a sprint completes)
+    pub fn run_sprint_hooks(&self, old: Option<&Sprint>, new: &Sprint) -> Result<Sprint> {
+        let completing = new.status == SprintStatus::Completed
+            && old
+                .map(|o| o.status != SprintStatus::Completed)
+                .unwrap_or(true);
+        if self.dir.is_none() || !completing {
+            return Ok(new.clone());
+        }
+
+        let event = HookEvent::SprintComplete;
+        let mut sprint = new.clone();
+        for script in self.scripts_for(event) {
+            let mut input = Vec::new();
+            if let Some(old) = old {
+                input.push(serde_json::to_string(old)?);
+            }
+            input.push(serde_json::to_string(&sprint)?);
+
+            if let Some(modified) = self.run_hook::<Sprint>(&script, event, &input)? {
+                if modified.id != sprint.id {
+                    bail!("Hook {} changed the sprint id", script.display());
+                }
+                sprint = modified;
+            }
+        }
+        Ok(sprint)
+    }
+
+    /// Run one hook. Returns the replacement value if the hook printed one.
+    fn run_hook<T: DeserializeOwned>(
+        &self,
+        script: &Path,
+        event: HookEvent,
+        input: &[String],
+    ) -> Result<Option<T>> {
+        let mut child = Command::new(script)
+            .env(IN_HOOK_ENV, event.name())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to run hook: {}", script.display()))?;
+
+        // Feed and drain the pipes on threads so a hook that never reads or
+        // floods its output can't block us past the timeout
+        let stdin = child.stdin.take().map(|mut stdin| {
+            let input = input.join("\n") + "\n";
+            // A hook may exit without reading stdin; ignore broken pipes
+            thread::spawn(move || drop(stdin.write_all(input.as_bytes())))
+        });
+        let stdout = child.stdout.take().map(read_pipe);
+        let stderr = child.stderr.take().map(read_pipe);
+
+        let name = script.file_name().unwrap_or_default().to_string_lossy();
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            let waited = child
+                .try_wait()
+                .with_context(|| format!("Failed to wait for hook: {}", script.display()))?;
+            if let Some(status) = waited {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!(
+                    "{} hook {} timed out after {}s",
+                    event.name(),
+                    name,
+                    self.timeout.as_secs_f32()
+                );
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        if let Some(stdin) = stdin {
+            let _ = stdin.join();
+        }
+        let join = |pipe: Option<thread::JoinHandle<Vec<u8>>>| {
+            pipe.and_then(|pipe| pipe.join().ok()).unwrap_or_default()
+        };
+        let (stdout, stderr) = (join(stdout), join(stderr));
+
+        let stdout = String::from_utf8_lossy(&stdout);
+        let stderr: Vec<String> = String::from_utf8_lossy(&stderr)
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect();
+
+        let mut replacement = None;
+        let mut feedback = Vec::new();
+        for line in stdout.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if replacement.is_none() && line.starts_with('{') {
+                replacement =
+                    Some(serde_json::from_str::<T>(line).with_context(|| {
+                        format!("Hook {} printed invalid JSON", script.display())
+                    })?);
+            } else {
+                feedback.push(line.to_string());
+            }
+        }
+
+        if !status.success() {
+            let reason = if !feedback.is_empty() {
+                feedback.join("; ")
+            } else if !stderr.is_empty() {
+                stderr.join("; ")
+            } else {
+                format!("exit code {}", status.code().unwrap_or(-1))
+            };
+            bail!(
+                "{} hook {} rejected the change: {}",
+                event.name(),
+                name,
+                reason
+            );
+        }
+
+        self.output
+            .lock()
+            .unwrap()
+            .extend(stderr.into_iter().map(|line| format!("{}: {}", name, line)));
+        Ok(replacement)
+    }
+}
+
+/// Read a hook's pipe to the end on its own thread
+fn read_pipe(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+/// Check if a hook file name belongs to an event ("on-add", "on-add.sh", "on-add-notify")
+fn matches_event(file_name: &str, event: HookEvent) -> bool {
+    match file_name.strip_prefix(event.name()) {
+        Some(rest) => rest.is_empty() || rest.starts_with('.') || rest.starts_with('-'),
+        None => false,
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn write_hook(dir: &Path, name: &str, body: &str) {
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_event_matching() {
+        assert!(matches_event("on-add", HookEvent::Add));
+        assert!(matches_event("on-add.sh", HookEvent::Add));
+        assert!(matches_event("on-add-notify", HookEvent::Add));
+        assert!(!matches_event("on-address", HookEvent::Add));
+        assert!(!matches_event("on-modify", HookEvent::Add));
+    }
+
+    #[test]
+    fn test_events_for_task_change() {
+        let old = Task::new("Task".to_string());
+        let mut new = old.clone();
+        new.start();
+        assert_eq!(
+            HookEvent::for_task_change(Some(&old), &new),
+            vec![HookEvent::Modify, HookEvent::Start]
+        );
+        assert_eq!(HookEvent::for_task_change(None, &new), vec![HookEvent::Add]);
+    }
+
+    #[test]
+    fn test_hook_veto() {
+        let dir = TempDir::new().unwrap();
+        write_hook(
+            dir.path(),
+            "on-add",
+            "echo 'titles must not be empty'; exit 1",
+        );
+
+        let hooks = Hooks::with_dir(dir.path());
+        let err = hooks
+            .run_task_hooks(None, &Task::new("x".to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("titles must not be empty"));
+    }
+
+    #[test]
+    fn test_hook_modifies_task() {
+        let dir = TempDir::new().unwrap();
+        // Read original + modified, echo modified back with a tag added
+        write_hook(
+            dir.path(),
+            "on-complete",
+            r#"read old; read new; echo "$new" | sed 's/"tags":\[\]/"tags":["hooked"]/'"#,
+        );
+
+        let hooks = Hooks::with_dir(dir.path());
+        let old = Task::new("Ship it".to_string());
+        let mut new = old.clone();
+        new.complete();
+
+        let result = hooks.run_task_hooks(Some(&old), &new).unwrap();
+        assert_eq!(result.tags, vec!["hooked"]);
+        assert!(result.is_done());
+    }
+
+    #[test]
+    fn test_hook_stderr_captured() {
+        let dir = TempDir::new().unwrap();
+        write_hook(dir.path(), "on-add", "echo 'synced to calendar' >&2");
+        write_hook(
+            dir.path(),
+            "on-add.veto",
+            "echo 'no titles in caps' >&2; exit 1",
+        );
+
+        let hooks = Hooks::with_dir(dir.path());
+        let err = hooks
+            .run_task_hooks(None, &Task::new("x".to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("no titles in caps"));
+        assert_eq!(hooks.take_output(), vec!["on-add: synced to calendar"]);
+        assert!(hooks.take_output().is_empty());
+    }
+
+    #[test]
+    fn test_hook_timeout() {
+        let dir = TempDir::new().unwrap();
+        write_hook(dir.path(), "on-add", "sleep 5");
+
+        let hooks = Hooks::with_dir(dir.path()).with_timeout(Duration::from_millis(200));
+        let started = Instant::now();
+        let err = hooks
+            .run_task_hooks(None, &Task::new("x".to_string()))
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
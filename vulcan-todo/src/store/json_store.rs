@@ -1,4 +1,5 @@
-use crate::models::{Sprint, SprintStatus, Task, TaskStore};
+use crate::models::{search, Sprint, SprintStatus, Task, TaskStore};
+use crate::store::hooks::Hooks;
 use anyhow::{Context, Result};
 use fs4::FileExt;
 use serde::{Deserialize, Serialize};
@@ -14,16 +15,18 @@ pub struct JsonStore {
     path: PathBuf,
     /// In-memory cache with file locking
     cache: Arc<Mutex<Option<TaskStore>>>,
+    /// Hooks run on every task/sprint write
+    hooks: Hooks,
 }
 
 impl JsonStore {
-    /// Create a new JSON store at the default location
+    /// Create a new JSON store at the default location, without hooks
     pub fn new() -> Result<Self> {
         let path = Self::default_path()?;
         Self::with_path(path)
     }
 
-    /// Create a new JSON store at a specific path
+    /// Create a new JSON store at a specific path, without hooks
     pub fn with_path(path: PathBuf) -> Result<Self> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
@@ -43,9 +46,16 @@ impl JsonStore {
         Ok(Self {
             path,
             cache: Arc::new(Mutex::new(None)),
+            hooks: Hooks::disabled(),
         })
     }
 
+    /// Run `hooks` on every write (e.g. `Hooks::discover()`)
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
     /// Get the default store path
     pub fn default_path() -> Result<PathBuf> {
         let config_dir = dirs::config_dir()
@@ -115,6 +125,25 @@ impl JsonStore {
     /// Save store to disk (with caching)
     fn save(&self, store: &TaskStore) -> Result<()> {
         let mut file = self.lock_file(true)?;
+        self.write_locked(&mut file, store)
+    }
+
+    /// Read the store from a file we hold the lock on, bypassing the cache
+    fn read_locked(file: &mut File) -> Result<TaskStore> {
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .with_context(|| "Failed to read store file".to_string())?;
+
+        let mut store: TaskStore = serde_json::from_str(&contents)
+            .with_context(|| "Failed to parse store file".to_string())?;
+        if store.needs_migration() {
+            store.migrate();
+        }
+        Ok(store)
+    }
+
+    /// Write the store to a file we hold the exclusive lock on
+    fn write_locked(&self, file: &mut File, store: &TaskStore) -> Result<()> {
         let contents = serde_json::to_string_pretty(store)
             .with_context(|| "Failed to serialize store".to_string())?;
         let contents = contents + "\n";
@@ -178,26 +207,74 @@ impl crate::store::Store for JsonStore {
     /// Add a new task
     fn add(&self, task: &Task) -> Result<Task> {
         let mut store = self.load()?;
+        let task = self.hooks.run_task_hooks(None, task)?;
         store.add(task.clone());
         self.save(&store)?;
-        Ok(task.clone())
+        Ok(task)
     }
 
     /// Update an existing task
     fn update(&self, task: &Task) -> Result<Option<Task>> {
         let mut store = self.load()?;
 
-        if store.get_mut(&task.id).is_some() {
+        if let Some(old) = store.get(&task.id) {
+            let mut task = task.clone();
+            task.touch();
+            let task = self.hooks.run_task_hooks(Some(old), &task)?;
             // Replace the task
             store.tasks.retain(|t| t.id != task.id);
             store.add(task.clone());
             self.save(&store)?;
-            Ok(Some(task.clone()))
+            Ok(Some(task))
         } else {
             Ok(None)
         }
     }
 
+    /// Update several tasks with one load and save
+    fn update_many(&self, tasks: &[Task]) -> Result<Vec<Task>> {
+        let mut store = self.load()?;
+        let mut updated = Vec::new();
+
+        for task in tasks {
+            if let Some(old) = store.get(&task.id) {
+                let mut task = task.clone();
+                task.touch();
+                updated.push(self.hooks.run_task_hooks(Some(old), &task)?);
+            }
+        }
+        if updated.is_empty() {
+            return Ok(updated);
+        }
+
+        for task in &updated {
+            store.tasks.retain(|t| t.id != task.id);
+            store.add(task.clone());
+        }
+        self.save(&store)?;
+        Ok(updated)
+    }
+
+    /// Read, change and write the task while holding the exclusive file lock
+    fn modify(&self, id: &str, f: &mut dyn FnMut(&mut Task) -> Result<()>) -> Result<Option<Task>> {
+        let mut file = self.lock_file(true)?;
+        // Another process may have written since we cached
+        let mut store = Self::read_locked(&mut file)?;
+
+        let Some(old) = store.get(id) else {
+            return Ok(None);
+        };
+        let mut task = old.clone();
+        f(&mut task)?;
+        task.touch();
+        let task = self.hooks.run_task_hooks(Some(old), &task)?;
+
+        store.tasks.retain(|t| t.id != id);
+        store.add(task.clone());
+        self.write_locked(&mut file, &store)?;
+        Ok(Some(task))
+    }
+
     /// Delete a task by ID
     fn delete(&self, id: &str) -> Result<bool> {
         let mut store = self.load()?;
@@ -286,10 +363,14 @@ impl crate::store::Store for JsonStore {
         let mut updated: Vec<String> = Vec::new();
 
         for task in &mut store.tasks {
-            let had_project = task.project.is_some();
-            task.set_project_from_tags();
+            if task.project.is_some() {
+                continue;
+            }
+            let mut modified = task.clone();
+            modified.set_project_from_tags();
 
-            if !had_project && task.project.is_some() {
+            if modified.project.is_some() {
+                *task = self.hooks.run_task_hooks(Some(task), &modified)?;
                 updated.push(task.id.clone());
             }
         }
@@ -301,6 +382,10 @@ impl crate::store::Store for JsonStore {
         Ok(updated)
     }
 
+    fn take_hook_output(&self) -> Vec<String> {
+        self.hooks.take_output()
+    }
+
     // ==================== Sprint Methods ====================
 
     /// Get all sprints
@@ -327,12 +412,13 @@ impl crate::store::Store for JsonStore {
     fn update_sprint(&self, sprint: &Sprint) -> Result<Option<Sprint>> {
         let mut store = self.load()?;
 
-        if store.get_sprint(&sprint.id).is_some() {
+        if let Some(old) = store.get_sprint(&sprint.id) {
+            let sprint = self.hooks.run_sprint_hooks(Some(old), sprint)?;
             // Replace the sprint
             store.sprints.retain(|s| s.id != sprint.id);
             store.add_sprint(sprint.clone());
             self.save(&store)?;
-            Ok(Some(sprint.clone()))
+            Ok(Some(sprint))
         } else {
             Ok(None)
         }
@@ -409,7 +495,9 @@ impl crate::store::Store for JsonStore {
 
         // Assign task to sprint
         if let Some(task) = store.get_mut(task_id) {
-            task.assign_to_sprint(sprint_id, next_order);
+            let mut modified = task.clone();
+            modified.assign_to_sprint(sprint_id, next_order);
+            *task = self.hooks.run_task_hooks(Some(task), &modified)?;
             let updated_task = task.clone();
             self.save(&store)?;
             Ok(Some(updated_task))
@@ -424,7 +512,9 @@ impl crate::store::Store for JsonStore {
 
         if let Some(task) = store.get_mut(task_id) {
             let old_sprint_id = task.sprint_id.clone();
-            task.unassign_from_sprint();
+            let mut modified = task.clone();
+            modified.unassign_from_sprint();
+            *task = self.hooks.run_task_hooks(Some(task), &modified)?;
             let updated_task = task.clone();
 
             // Renumber remaining tasks in the sprint
@@ -474,10 +564,18 @@ impl crate::store::Store for JsonStore {
         // Insert at new position
         sprint_task_ids.insert(new_pos, task_id.to_string());
 
-        // Update all task orders
+        // Update all task orders; each task that moves is modified like
+        // any other write, so hooks see (and may veto) the reorder
         for (i, tid) in sprint_task_ids.iter().enumerate() {
             if let Some(task) = store.get_mut(tid) {
-                task.sprint_order = Some((i + 1) as i32);
+                let order = Some((i + 1) as i32);
+                if task.sprint_order == order {
+                    continue;
+                }
+                let mut modified = task.clone();
+                modified.sprint_order = order;
+                modified.touch();
+                *task = self.hooks.run_task_hooks(Some(task), &modified)?;
             }
         }
 
@@ -510,8 +608,11 @@ impl crate::store::Store for JsonStore {
         let next_order = store.next_sprint_order(to_sprint_id);
 
         // Update the task
+        let mut modified = task.clone();
+        modified.assign_to_sprint(to_sprint_id, next_order);
+        let modified = self.hooks.run_task_hooks(Some(&task), &modified)?;
         if let Some(task) = store.get_mut(task_id) {
-            task.assign_to_sprint(to_sprint_id, next_order);
+            *task = modified;
         }
 
         // Renumber tasks in the source sprint
@@ -565,13 +666,41 @@ impl crate::store::Store for MemoryStore {
     fn update(&self, task: &Task) -> Result<Option<Task>> {
         let mut tasks = self.tasks.lock().unwrap();
         if let Some(pos) = tasks.iter().position(|t| t.id == task.id) {
+            let mut task = task.clone();
+            task.touch();
             tasks[pos] = task.clone();
-            Ok(Some(task.clone()))
+            Ok(Some(task))
         } else {
             Ok(None)
         }
     }
 
+    fn update_many(&self, tasks: &[Task]) -> Result<Vec<Task>> {
+        let mut stored = self.tasks.lock().unwrap();
+        let mut updated = Vec::new();
+        for task in tasks {
+            if let Some(pos) = stored.iter().position(|t| t.id == task.id) {
+                let mut task = task.clone();
+                task.touch();
+                stored[pos] = task.clone();
+                updated.push(task);
+            }
+        }
+        Ok(updated)
+    }
+
+    fn modify(&self, id: &str, f: &mut dyn FnMut(&mut Task) -> Result<()>) -> Result<Option<Task>> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let Some(pos) = tasks.iter().position(|t| t.id == id) else {
+            return Ok(None);
+        };
+        let mut task = tasks[pos].clone();
+        f(&mut task)?;
+        task.touch();
+        tasks[pos] = task.clone();
+        Ok(Some(task))
+    }
+
     fn delete(&self, id: &str) -> Result<bool> {
         let mut tasks = self.tasks.lock().unwrap();
         let len_before = tasks.len();
@@ -679,18 +808,10 @@ impl crate::store::Store for MemoryStore {
     }
 
     fn search(&self, query: &str) -> Result<Vec<Task>> {
-        let query_lower = query.to_lowercase();
         let tasks = self.tasks.lock().unwrap();
-        Ok(tasks
-            .iter()
-            .filter(|t| {
-                t.title.to_lowercase().contains(&query_lower)
-                    || t.description
-                        .as_ref()
-                        .map(|d| d.to_lowercase().contains(&query_lower))
-                        .unwrap_or(false)
-            })
-            .cloned()
+        Ok(search::rank(tasks.iter().cloned(), query)
+            .into_iter()
+            .map(|(task, _)| task)
             .collect())
     }
 
@@ -868,29 +989,13 @@ impl crate::store::Store for MemoryStore {
         };
 
         // Get all tasks in the sprint, sorted by current order
-        let mut sprint_task_ids: Vec<String> = tasks
-            .iter()
-            .filter(|t| t.belongs_to_sprint(&sprint_id))
-            .map(|t| (t.id.clone(), t.sprint_order.unwrap_or(i32::MAX)))
-            .collect::<Vec<_>>()
-            .into_iter()
-            .map(|(id, order)| {
-                // Sort inline
-                (id, order)
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .map(|(id, _)| id)
-            .collect();
-
-        // Actually sort properly
         let mut sprint_tasks_with_order: Vec<(String, i32)> = tasks
             .iter()
             .filter(|t| t.belongs_to_sprint(&sprint_id))
             .map(|t| (t.id.clone(), t.sprint_order.unwrap_or(i32::MAX)))
             .collect();
         sprint_tasks_with_order.sort_by_key(|(_, order)| *order);
-        sprint_task_ids = sprint_tasks_with_order
+        let mut sprint_task_ids: Vec<String> = sprint_tasks_with_order
             .into_iter()
             .map(|(id, _)| id)
             .collect();
@@ -913,7 +1018,11 @@ impl crate::store::Store for MemoryStore {
         // Update all task orders
         for (i, tid) in sprint_task_ids.iter().enumerate() {
             if let Some(task) = tasks.iter_mut().find(|t| t.id == *tid) {
-                task.sprint_order = Some((i + 1) as i32);
+                let order = Some((i + 1) as i32);
+                if task.sprint_order != order {
+                    task.sprint_order = order;
+                    task.touch();
+                }
             }
         }
 
@@ -1054,4 +1163,72 @@ mod tests {
         assert_eq!(pending, 2);
         assert_eq!(done, 0);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_json_store_hook_veto() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let hooks_dir = temp_dir.path().join("hooks");
+        std::fs::create_dir(&hooks_dir).unwrap();
+        let hook = hooks_dir.join("on-complete");
+        std::fs::write(&hook, "#!/bin/sh\necho 'not yet'\nexit 1\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let store = JsonStore::with_path(temp_dir.path().join("tasks.json"))
+            .unwrap()
+            .with_hooks(Hooks::with_dir(&hooks_dir));
+
+        let task = store.add(&Task::new("Guarded".to_string())).unwrap();
+        let mut done = task.clone();
+        done.complete();
+
+        // Vetoed write leaves the stored task untouched
+        assert!(store.update(&done).is_err());
+        assert!(store.get(&task.id).unwrap().unwrap().is_pending());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_json_store_reorder_runs_hooks() {
+        use crate::models::Sprint;
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let hooks_dir = temp_dir.path().join("hooks");
+        std::fs::create_dir(&hooks_dir).unwrap();
+
+        // Set up the sprint before any hook is installed
+        let store = JsonStore::with_path(temp_dir.path().join("tasks.json"))
+            .unwrap()
+            .with_hooks(Hooks::with_dir(&hooks_dir));
+        let sprint = store
+            .add_sprint(&Sprint::new("Sprint 1".to_string(), "app".to_string()))
+            .unwrap();
+        let first = store.add(&Task::new("First".to_string())).unwrap();
+        let second = store.add(&Task::new("Second".to_string())).unwrap();
+        store.assign_task_to_sprint(&first.id, &sprint.id).unwrap();
+        store.assign_task_to_sprint(&second.id, &sprint.id).unwrap();
+
+        let hook = hooks_dir.join("on-modify");
+        std::fs::write(
+            &hook,
+            "#!/bin/sh
+echo 'sprint is frozen'
+exit 1
+",
+        )
+        .unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        // Vetoed reorder leaves every task where it was
+        let err = store.reorder_task_in_sprint(&second.id, 1).unwrap_err();
+        assert!(err.to_string().contains("sprint is frozen"));
+        assert_eq!(store.get(&first.id).unwrap().unwrap().sprint_order, Some(1));
+        assert_eq!(
+            store.get(&second.id).unwrap().unwrap().sprint_order,
+            Some(2)
+        );
+    }
 }
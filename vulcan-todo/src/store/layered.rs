@@ -0,0 +1,424 @@
+//! Per-repository stores layered over the global store
+//!
+//! A `.vulcan-todo/` directory in the working directory or one of its
+//! ancestors (found like git finds `.git`) holds a repository-local
+//! `tasks.json`. Reads merge it with the global store; new tasks and sprints
+//! go to the target store (local by default), and changes to existing ones
+//! go to the store that holds them.
+
+use crate::models::{search, Sprint, Task};
+use crate::store::hooks::Hooks;
+use crate::store::{JsonStore, Store};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Directory holding a repository-local store
+pub const LOCAL_STORE_DIR: &str = ".vulcan-todo";
+
+/// Which store receives new tasks and sprints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreTarget {
+    /// The nearest `.vulcan-todo/` store
+    Local,
+    /// `~/.config/vulcan-todo/tasks.json`
+    Global,
+}
+
+impl std::str::FromStr for StoreTarget {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "local" | "repo" => Ok(StoreTarget::Local),
+            "global" => Ok(StoreTarget::Global),
+            _ => bail!("Unknown store: {} (expected global or local)", s),
+        }
+    }
+}
+
+/// How to open the store for a working directory
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WorkspaceOptions {
+    /// Store for new tasks (default: local if there is one)
+    pub target: Option<StoreTarget>,
+    pub no_hooks: bool,
+}
+
+/// Path of the local `tasks.json` for `dir` or its nearest ancestor with a
+/// `.vulcan-todo/` directory
+pub fn find_local_store(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(LOCAL_STORE_DIR))
+        .find(|d| d.is_dir())
+        .map(|d| d.join("tasks.json"))
+}
+
+/// Create a local store in `dir` (no-op if it already exists)
+pub fn init_local_store(dir: &Path) -> Result<PathBuf> {
+    let path = dir.join(LOCAL_STORE_DIR).join("tasks.json");
+    JsonStore::with_path(path.clone())
+        .with_context(|| format!("Failed to create local store in {}", dir.display()))?;
+    Ok(path)
+}
+
+/// Open the store for a working directory: the global store, layered under
+/// the nearest local store if there is one
+pub fn open_workspace(dir: &Path, options: WorkspaceOptions) -> Result<Arc<dyn Store>> {
+    let hooks = |store: JsonStore| {
+        if options.no_hooks {
+            store
+        } else {
+            store.with_hooks(Hooks::discover())
+        }
+    };
+    let global = hooks(JsonStore::new()?);
+
+    match find_local_store(dir) {
+        Some(local) => Ok(Arc::new(LayeredStore::new(
+            Arc::new(hooks(JsonStore::with_path(local)?)),
+            Arc::new(global),
+            options.target.unwrap_or(StoreTarget::Local),
+        ))),
+        None if options.target == Some(StoreTarget::Local) => bail!(
+            "No {} store in {} or its parents (run `vulcan-todo init`)",
+            LOCAL_STORE_DIR,
+            dir.display()
+        ),
+        None => Ok(Arc::new(global)),
+    }
+}
+
+/// A local store merged with the global store
+pub struct LayeredStore {
+    local: Arc<dyn Store>,
+    global: Arc<dyn Store>,
+    target: StoreTarget,
+}
+
+impl LayeredStore {
+    pub fn new(local: Arc<dyn Store>, global: Arc<dyn Store>, target: StoreTarget) -> Self {
+        Self {
+            local,
+            global,
+            target,
+        }
+    }
+
+    fn layers(&self) -> [&dyn Store; 2] {
+        [self.local.as_ref(), self.global.as_ref()]
+    }
+
+    fn target_store(&self) -> &dyn Store {
+        match self.target {
+            StoreTarget::Local => self.local.as_ref(),
+            StoreTarget::Global => self.global.as_ref(),
+        }
+    }
+
+    /// Concatenate results from both layers (local first)
+    fn merged<T>(&self, f: impl Fn(&dyn Store) -> Result<Vec<T>>) -> Result<Vec<T>> {
+        let mut all = f(self.local.as_ref())?;
+        all.extend(f(self.global.as_ref())?);
+        Ok(all)
+    }
+
+    /// Store holding a task
+    fn task_owner(&self, id: &str) -> Result<Option<&dyn Store>> {
+        for store in self.layers() {
+            if store.get(id)?.is_some() {
+                return Ok(Some(store));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Store holding a sprint
+    fn sprint_owner(&self, id: &str) -> Result<Option<&dyn Store>> {
+        for store in self.layers() {
+            if store.get_sprint(id)?.is_some() {
+                return Ok(Some(store));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Store for LayeredStore {
+    fn get_all(&self) -> Result<Vec<Task>> {
+        self.merged(|s| s.get_all())
+    }
+
+    fn reload(&self) -> Result<Vec<Task>> {
+        self.merged(|s| s.reload())
+    }
+
+    fn get(&self, id: &str) -> Result<Option<Task>> {
+        match self.local.get(id)? {
+            Some(task) => Ok(Some(task)),
+            None => self.global.get(id),
+        }
+    }
+
+    fn add(&self, task: &Task) -> Result<Task> {
+        self.target_store().add(task)
+    }
+
+    fn update(&self, task: &Task) -> Result<Option<Task>> {
+        match self.task_owner(&task.id)? {
+            Some(store) => store.update(task),
+            None => Ok(None),
+        }
+    }
+
+    /// One write per store, local first. A batch that spans both stores is
+    /// not atomic: if the global write fails (e.g. a hook rejects a task),
+    /// the local tasks stay updated and the error is returned.
+    fn update_many(&self, tasks: &[Task]) -> Result<Vec<Task>> {
+        let mut local = Vec::new();
+        let mut global = Vec::new();
+        for task in tasks {
+            if self.local.get(&task.id)?.is_some() {
+                local.push(task.clone());
+            } else {
+                global.push(task.clone());
+            }
+        }
+        let mut updated = self.local.update_many(&local)?;
+        updated.extend(self.global.update_many(&global)?);
+        Ok(updated)
+    }
+
+    fn modify(&self, id: &str, f: &mut dyn FnMut(&mut Task) -> Result<()>) -> Result<Option<Task>> {
+        match self.task_owner(id)? {
+            Some(store) => store.modify(id, f),
+            None => Ok(None),
+        }
+    }
+
+    fn delete(&self, id: &str) -> Result<bool> {
+        match self.task_owner(id)? {
+            Some(store) => store.delete(id),
+            None => Ok(false),
+        }
+    }
+
+    fn get_by_status(&self, status: &str) -> Result<Vec<Task>> {
+        self.merged(|s| s.get_by_status(status))
+    }
+
+    fn get_by_priority(&self, priority: &str) -> Result<Vec<Task>> {
+        self.merged(|s| s.get_by_priority(priority))
+    }
+
+    fn get_by_scope(&self, scope: &str) -> Result<Vec<Task>> {
+        self.merged(|s| s.get_by_scope(scope))
+    }
+
+    fn get_global(&self) -> Result<Vec<Task>> {
+        self.merged(|s| s.get_global())
+    }
+
+    fn search(&self, query: &str) -> Result<Vec<Task>> {
+        // Rank both layers' results together
+        Ok(search::rank(self.merged(|s| s.search(query))?, query)
+            .into_iter()
+            .map(|(task, _)| task)
+            .collect())
+    }
+
+    fn count(&self) -> Result<(usize, usize)> {
+        let (local_pending, local_done) = self.local.count()?;
+        let (global_pending, global_done) = self.global.count()?;
+        Ok((local_pending + global_pending, local_done + global_done))
+    }
+
+    fn get_by_project(&self, project: &str) -> Result<Vec<Task>> {
+        self.merged(|s| s.get_by_project(project))
+    }
+
+    fn get_projects(&self) -> Result<Vec<String>> {
+        let mut projects = self.merged(|s| s.get_projects())?;
+        projects.sort();
+        projects.dedup();
+        Ok(projects)
+    }
+
+    fn get_project_stats(&self) -> Result<HashMap<String, (usize, usize)>> {
+        let mut stats = self.local.get_project_stats()?;
+        for (project, (pending, done)) in self.global.get_project_stats()? {
+            let entry = stats.entry(project).or_default();
+            entry.0 += pending;
+            entry.1 += done;
+        }
+        Ok(stats)
+    }
+
+    fn auto_assign_projects_from_tags(&self) -> Result<Vec<String>> {
+        self.merged(|s| s.auto_assign_projects_from_tags())
+    }
+
+    fn take_hook_output(&self) -> Vec<String> {
+        self.layers()
+            .iter()
+            .flat_map(|s| s.take_hook_output())
+            .collect()
+    }
+
+    fn get_all_sprints(&self) -> Result<Vec<Sprint>> {
+        self.merged(|s| s.get_all_sprints())
+    }
+
+    fn get_sprint(&self, id: &str) -> Result<Option<Sprint>> {
+        match self.local.get_sprint(id)? {
+            Some(sprint) => Ok(Some(sprint)),
+            None => self.global.get_sprint(id),
+        }
+    }
+
+    fn add_sprint(&self, sprint: &Sprint) -> Result<Sprint> {
+        self.target_store().add_sprint(sprint)
+    }
+
+    fn update_sprint(&self, sprint: &Sprint) -> Result<Option<Sprint>> {
+        match self.sprint_owner(&sprint.id)? {
+            Some(store) => store.update_sprint(sprint),
+            None => Ok(None),
+        }
+    }
+
+    fn delete_sprint(&self, id: &str) -> Result<bool> {
+        match self.sprint_owner(id)? {
+            Some(store) => store.delete_sprint(id),
+            None => Ok(false),
+        }
+    }
+
+    fn get_sprints_by_project(&self, project: &str) -> Result<Vec<Sprint>> {
+        self.merged(|s| s.get_sprints_by_project(project))
+    }
+
+    fn get_sprints_by_status(&self, status: &str) -> Result<Vec<Sprint>> {
+        self.merged(|s| s.get_sprints_by_status(status))
+    }
+
+    fn get_tasks_in_sprint(&self, sprint_id: &str) -> Result<Vec<Task>> {
+        // Sprint tasks always live in the sprint's store
+        match self.sprint_owner(sprint_id)? {
+            Some(store) => store.get_tasks_in_sprint(sprint_id),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn get_backlog_tasks(&self, project: &str) -> Result<Vec<Task>> {
+        self.merged(|s| s.get_backlog_tasks(project))
+    }
+
+    fn assign_task_to_sprint(&self, task_id: &str, sprint_id: &str) -> Result<Option<Task>> {
+        let Some(store) = self.task_owner(task_id)? else {
+            return Ok(None);
+        };
+        if store.get_sprint(sprint_id)?.is_none() && self.get_sprint(sprint_id)?.is_some() {
+            bail!("Task and sprint are in different stores (local vs global)");
+        }
+        store.assign_task_to_sprint(task_id, sprint_id)
+    }
+
+    fn remove_task_from_sprint(&self, task_id: &str) -> Result<Option<Task>> {
+        match self.task_owner(task_id)? {
+            Some(store) => store.remove_task_from_sprint(task_id),
+            None => Ok(None),
+        }
+    }
+
+    fn reorder_task_in_sprint(&self, task_id: &str, new_position: i32) -> Result<Option<Task>> {
+        match self.task_owner(task_id)? {
+            Some(store) => store.reorder_task_in_sprint(task_id, new_position),
+            None => Ok(None),
+        }
+    }
+
+    fn move_task_to_sprint(
+        &self,
+        task_id: &str,
+        from_sprint_id: &str,
+        to_sprint_id: &str,
+    ) -> Result<Option<Task>> {
+        let Some(store) = self.task_owner(task_id)? else {
+            return Ok(None);
+        };
+        if store.get_sprint(to_sprint_id)?.is_none() && self.get_sprint(to_sprint_id)?.is_some() {
+            bail!("Task and sprint are in different stores (local vs global)");
+        }
+        store.move_task_to_sprint(task_id, from_sprint_id, to_sprint_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    #[test]
+    fn test_layered_reads_and_writes() {
+        let local = Arc::new(MemoryStore::new());
+        let global = Arc::new(MemoryStore::new());
+        let global_task = global.add(&Task::new("Global chore".to_string())).unwrap();
+        let layered = LayeredStore::new(local.clone(), global.clone(), StoreTarget::Local);
+
+        let repo_task = layered.add(&Task::new("Repo bug".to_string())).unwrap();
+        assert_eq!(local.get_all().unwrap().len(), 1);
+        assert_eq!(layered.get_all().unwrap().len(), 2);
+        assert_eq!(layered.count().unwrap(), (2, 0));
+
+        // Updates go to the store holding the task
+        let mut task = layered.get(&global_task.id).unwrap().unwrap();
+        task.complete();
+        layered.update(&task).unwrap();
+        assert!(global.get(&global_task.id).unwrap().unwrap().is_done());
+        assert!(local.get(&global_task.id).unwrap().is_none());
+
+        // Sprints and their tasks stay in one store
+        let sprint = layered
+            .add_sprint(&Sprint::new("Sprint 1".to_string(), "repo".to_string()))
+            .unwrap();
+        assert!(layered
+            .assign_task_to_sprint(&repo_task.id, &sprint.id)
+            .unwrap()
+            .is_some());
+        assert_eq!(layered.get_tasks_in_sprint(&sprint.id).unwrap().len(), 1);
+        assert!(layered
+            .assign_task_to_sprint(&global_task.id, &sprint.id)
+            .is_err());
+        let global_sprint = global
+            .add_sprint(&Sprint::new("Errands".to_string(), "home".to_string()))
+            .unwrap();
+        assert!(layered
+            .move_task_to_sprint(&repo_task.id, &sprint.id, &global_sprint.id)
+            .is_err());
+        assert!(local
+            .get(&repo_task.id)
+            .unwrap()
+            .unwrap()
+            .belongs_to_sprint(&sprint.id));
+
+        let global_first = LayeredStore::new(local.clone(), global.clone(), StoreTarget::Global);
+        global_first.add(&Task::new("Errand".to_string())).unwrap();
+        assert_eq!(global.get_all().unwrap().len(), 2);
+        assert!(layered.delete(&repo_task.id).unwrap());
+        assert!(local.get_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_local_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("src/store");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert!(find_local_store(&nested).is_none());
+
+        let path = init_local_store(dir.path()).unwrap();
+        assert!(path.exists());
+        assert_eq!(find_local_store(&nested), Some(path));
+    }
+}
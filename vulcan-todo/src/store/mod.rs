@@ -25,6 +25,16 @@ pub trait Store: Send + Sync {
     /// Update an existing task
     fn update(&self, task: &Task) -> Result<Option<Task>>;
 
+    /// Update several tasks in a single write. Unknown IDs are skipped; if a
+    /// hook rejects any task, nothing is written. `LayeredStore` writes each
+    /// layer separately, so there this only holds per layer.
+    fn update_many(&self, tasks: &[Task]) -> Result<Vec<Task>>;
+
+    /// Change one task in place under the store's write lock, so no other
+    /// writer can get between what `f` checks and what it writes. Returns
+    /// `None` for unknown IDs; if `f` fails, nothing is written.
+    fn modify(&self, id: &str, f: &mut dyn FnMut(&mut Task) -> Result<()>) -> Result<Option<Task>>;
+
     /// Delete a task by ID
     fn delete(&self, id: &str) -> Result<bool>;
 
@@ -40,7 +50,7 @@ pub trait Store: Send + Sync {
     /// Get global tasks (tasks with no session scope)
     fn get_global(&self) -> Result<Vec<Task>>;
 
-    /// Search tasks
+    /// Fuzzy search tasks, best matches first
     fn search(&self, query: &str) -> Result<Vec<Task>>;
 
     /// Get task count (pending, done)
@@ -58,6 +68,11 @@ pub trait Store: Send + Sync {
     /// Auto-assign projects from tags for all tasks
     fn auto_assign_projects_from_tags(&self) -> Result<Vec<String>>;
 
+    /// Take what hooks printed to stderr since the last call
+    fn take_hook_output(&self) -> Vec<String> {
+        Vec::new()
+    }
+
     // ==================== Sprint Methods ====================
 
     /// Get all sprints
@@ -105,5 +120,10 @@ pub trait Store: Send + Sync {
     ) -> Result<Option<Task>>;
 }
 
+pub mod claim;
+pub mod hooks;
 pub mod json_store;
+pub mod layered;
+pub mod planning;
+pub mod session;
 pub use json_store::{JsonStore, MemoryStore};
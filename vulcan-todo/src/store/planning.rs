@@ -0,0 +1,336 @@
+//! Sprint rollover and capacity planning
+//!
+//! Completing a sprint carries its unfinished tasks over to the project's
+//! next planning sprint (or back to the backlog), keeping their order. The
+//! planner proposes backlog tasks to fill a sprint up to its point budget.
+
+use crate::models::{Sprint, SprintStatus, Task};
+use crate::store::Store;
+use anyhow::{bail, Result};
+use std::cmp::Reverse;
+
+/// Tasks moved out of a sprint by [`rollover_sprint`]
+#[derive(Debug, Clone)]
+pub struct Rollover {
+    /// Sprint the tasks moved to (None = backlog)
+    pub target: Option<Sprint>,
+    /// Moved tasks, in their new order
+    pub moved: Vec<Task>,
+}
+
+impl Rollover {
+    /// Target sprint name, or "backlog"
+    pub fn target_name(&self) -> &str {
+        self.target
+            .as_ref()
+            .map(|s| s.name.as_str())
+            .unwrap_or("backlog")
+    }
+}
+
+fn get_sprint(store: &dyn Store, id: &str) -> Result<Sprint> {
+    match store.get_sprint(id)? {
+        Some(sprint) => Ok(sprint),
+        None => bail!("Sprint not found: {}", id),
+    }
+}
+
+/// The project's next planning sprint after `sprint`: earliest start date,
+/// then oldest, among planning sprints other than `sprint` itself
+pub fn next_planning_sprint(store: &dyn Store, sprint: &Sprint) -> Result<Option<Sprint>> {
+    Ok(store
+        .get_sprints_by_project(&sprint.project)?
+        .into_iter()
+        .filter(|s| s.id != sprint.id && s.status == SprintStatus::Planning)
+        .min_by_key(|s| (s.start_date.is_none(), s.start_date, s.created_at)))
+}
+
+/// Sum of task points, saturating instead of overflowing
+pub fn total_points<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> u32 {
+    tasks
+        .into_iter()
+        .fold(0, |sum, t| sum.saturating_add(t.points()))
+}
+
+/// Sprint a rollover goes to: `target` (a sprint ID), or the next planning
+/// sprint; `None` means the backlog
+fn rollover_target(
+    store: &dyn Store,
+    sprint: &Sprint,
+    target: Option<&str>,
+) -> Result<Option<Sprint>> {
+    match target {
+        Some(id) => {
+            let target = get_sprint(store, id)?;
+            if target.id == sprint.id {
+                bail!("Cannot roll a sprint over into itself");
+            }
+            Ok(Some(target))
+        }
+        None => next_planning_sprint(store, sprint),
+    }
+}
+
+/// Move a sprint's unfinished tasks to `target` (a sprint ID), or by default
+/// to the next planning sprint, falling back to the backlog. Moved tasks are
+/// appended to the target sprint in their original order.
+pub fn rollover_sprint(
+    store: &dyn Store,
+    sprint_id: &str,
+    target: Option<&str>,
+) -> Result<Rollover> {
+    let sprint = get_sprint(store, sprint_id)?;
+    let target = rollover_target(store, &sprint, target)?;
+    move_unfinished(store, &sprint, target)
+}
+
+fn move_unfinished(store: &dyn Store, sprint: &Sprint, target: Option<Sprint>) -> Result<Rollover> {
+    let mut unfinished: Vec<Task> = store
+        .get_tasks_in_sprint(&sprint.id)?
+        .into_iter()
+        .filter(|t| t.is_active())
+        .collect();
+    unfinished.sort_by_key(|t| t.sprint_order.unwrap_or(i32::MAX));
+
+    let mut moved = Vec::new();
+    for task in unfinished {
+        let updated = match &target {
+            Some(target) => store.assign_task_to_sprint(&task.id, &target.id)?,
+            None => store.remove_task_from_sprint(&task.id)?,
+        };
+        moved.extend(updated);
+    }
+
+    Ok(Rollover { target, moved })
+}
+
+/// Complete a sprint, optionally rolling its unfinished tasks over. The
+/// rollover target is checked and the sprint saved before any task moves,
+/// so a failure never leaves tasks moved out of a sprint that is still open.
+pub fn complete_sprint(
+    store: &dyn Store,
+    sprint_id: &str,
+    rollover: bool,
+    target: Option<&str>,
+) -> Result<(Sprint, Option<Rollover>)> {
+    let mut sprint = get_sprint(store, sprint_id)?;
+    let target = if rollover {
+        Some(rollover_target(store, &sprint, target)?)
+    } else {
+        None
+    };
+
+    sprint.complete();
+    let Some(sprint) = store.update_sprint(&sprint)? else {
+        bail!("Sprint not found: {}", sprint_id);
+    };
+    let rollover = match target {
+        Some(target) => Some(move_unfinished(store, &sprint, target)?),
+        None => None,
+    };
+    Ok((sprint, rollover))
+}
+
+/// Proposed sprint fill from [`plan_sprint`]
+#[derive(Debug, Clone)]
+pub struct SprintPlan {
+    pub sprint: Sprint,
+    /// Point budget the plan was made for
+    pub budget: u32,
+    /// Points already in the sprint (unfinished tasks)
+    pub committed: u32,
+    /// Backlog tasks proposed (or added, when applied), in planning order
+    pub proposed: Vec<Task>,
+    /// Points of the proposed tasks
+    pub planned: u32,
+    /// Candidates that didn't fit in the remaining budget
+    pub skipped: usize,
+    pub applied: bool,
+}
+
+/// Backlog tasks in planning order: highest priority first, then earliest
+/// due date, then oldest
+pub fn planning_candidates(store: &dyn Store, project: &str) -> Result<Vec<Task>> {
+    let mut tasks: Vec<Task> = store
+        .get_backlog_tasks(project)?
+        .into_iter()
+        .filter(|t| t.is_active() && !t.is_waiting())
+        .collect();
+    tasks.sort_by_key(|t| {
+        (
+            Reverse(t.priority.level()),
+            t.due_date.is_none(),
+            t.due_date,
+            t.created_at,
+        )
+    });
+    Ok(tasks)
+}
+
+/// Propose backlog tasks to fill a sprint until its point budget is reached.
+/// `budget` defaults to the sprint's capacity. Tasks too large for the
+/// remaining budget are skipped in favor of smaller ones further down.
+/// With `apply`, the proposed tasks are added to the sprint.
+pub fn plan_sprint(
+    store: &dyn Store,
+    sprint_id: &str,
+    budget: Option<u32>,
+    apply: bool,
+) -> Result<SprintPlan> {
+    let sprint = get_sprint(store, sprint_id)?;
+    let Some(budget) = budget.or(sprint.capacity) else {
+        bail!(
+            "Sprint '{}' has no capacity; pass a budget or set the sprint capacity",
+            sprint.name
+        );
+    };
+
+    let committed = total_points(
+        store
+            .get_tasks_in_sprint(sprint_id)?
+            .iter()
+            .filter(|t| t.is_active()),
+    );
+
+    let mut remaining = budget.saturating_sub(committed);
+    let mut proposed = Vec::new();
+    let mut skipped = 0;
+    for task in planning_candidates(store, &sprint.project)? {
+        if task.points() > remaining {
+            skipped += 1;
+            continue;
+        }
+        remaining -= task.points();
+        proposed.push(task);
+    }
+    let planned = total_points(&proposed);
+
+    if apply {
+        let mut added = Vec::new();
+        for task in &proposed {
+            added.extend(store.assign_task_to_sprint(&task.id, sprint_id)?);
+        }
+        proposed = added;
+    }
+
+    Ok(SprintPlan {
+        sprint,
+        budget,
+        committed,
+        proposed,
+        planned,
+        skipped,
+        applied: apply,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Priority;
+    use crate::store::MemoryStore;
+
+    fn add_task(store: &MemoryStore, title: &str, priority: Priority, estimate: u32) -> Task {
+        let mut task = Task::new(title.to_string());
+        task.project = Some("vulcan-os".to_string());
+        task.priority = priority;
+        task.estimate = Some(estimate);
+        store.add(&task).unwrap()
+    }
+
+    #[test]
+    fn test_complete_sprint_rolls_over() {
+        let store = MemoryStore::new();
+        let current = store
+            .add_sprint(&Sprint::new(
+                "Sprint 1".to_string(),
+                "vulcan-os".to_string(),
+            ))
+            .unwrap();
+        let next = store
+            .add_sprint(&Sprint::new(
+                "Sprint 2".to_string(),
+                "vulcan-os".to_string(),
+            ))
+            .unwrap();
+
+        let a = add_task(&store, "A", Priority::Medium, 1);
+        let b = add_task(&store, "B", Priority::Medium, 1);
+        let c = add_task(&store, "C", Priority::Medium, 1);
+        for task in [&a, &b, &c] {
+            store.assign_task_to_sprint(&task.id, &current.id).unwrap();
+        }
+        let mut done = store.get(&b.id).unwrap().unwrap();
+        done.complete();
+        store.update(&done).unwrap();
+        // Reverse the order of the unfinished tasks
+        store.reorder_task_in_sprint(&c.id, 1).unwrap();
+
+        let (sprint, rollover) = complete_sprint(&store, &current.id, true, None).unwrap();
+        assert_eq!(sprint.status, SprintStatus::Completed);
+        let rollover = rollover.unwrap();
+        assert_eq!(rollover.target.unwrap().id, next.id);
+
+        let carried = store.get_tasks_in_sprint(&next.id).unwrap();
+        let titles: Vec<&str> = carried.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["C", "A"]);
+        assert_eq!(store.get_tasks_in_sprint(&current.id).unwrap().len(), 1);
+
+        // No planning sprint left: unfinished tasks go to the backlog
+        let rollover = rollover_sprint(&store, &next.id, None).unwrap();
+        assert!(rollover.target.is_none());
+        assert_eq!(rollover.moved.len(), 2);
+        assert_eq!(store.get_backlog_tasks("vulcan-os").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_complete_sprint_checks_target_first() {
+        let store = MemoryStore::new();
+        let sprint = store
+            .add_sprint(&Sprint::new(
+                "Sprint 1".to_string(),
+                "vulcan-os".to_string(),
+            ))
+            .unwrap();
+        let task = add_task(&store, "Open", Priority::Medium, u32::MAX);
+        store.assign_task_to_sprint(&task.id, &sprint.id).unwrap();
+        add_task(&store, "Huge", Priority::Medium, u32::MAX);
+
+        assert!(complete_sprint(&store, &sprint.id, true, Some("missing")).is_err());
+        let unchanged = store.get_sprint(&sprint.id).unwrap().unwrap();
+        assert_eq!(unchanged.status, SprintStatus::Planning);
+        assert_eq!(store.get_tasks_in_sprint(&sprint.id).unwrap().len(), 1);
+
+        // Points saturate instead of overflowing
+        let tasks = store.get_all().unwrap();
+        assert_eq!(total_points(&tasks), u32::MAX);
+    }
+
+    #[test]
+    fn test_plan_sprint_by_capacity() {
+        let store = MemoryStore::new();
+        let mut sprint = Sprint::new("Sprint 1".to_string(), "vulcan-os".to_string());
+        sprint.capacity = Some(5);
+        let sprint = store.add_sprint(&sprint).unwrap();
+
+        add_task(&store, "Big urgent", Priority::High, 4);
+        add_task(&store, "Too big", Priority::High, 3);
+        add_task(&store, "Small", Priority::Low, 1);
+        add_task(&store, "Another small", Priority::None, 1);
+
+        let plan = plan_sprint(&store, &sprint.id, None, false).unwrap();
+        let titles: Vec<&str> = plan.proposed.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Big urgent", "Small"]);
+        assert_eq!((plan.budget, plan.planned, plan.skipped), (5, 5, 2));
+        assert!(store.get_tasks_in_sprint(&sprint.id).unwrap().is_empty());
+
+        let plan = plan_sprint(&store, &sprint.id, Some(6), true).unwrap();
+        assert_eq!(plan.proposed.len(), 3);
+        assert_eq!(store.get_tasks_in_sprint(&sprint.id).unwrap().len(), 3);
+
+        // Already full
+        let plan = plan_sprint(&store, &sprint.id, Some(6), false).unwrap();
+        assert_eq!(plan.committed, 6);
+        assert!(plan.proposed.is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+//! Session-scoped task lifecycle
+//!
+//! Tasks created from an MCP session are scoped `session:<id>`. These
+//! helpers summarize sessions, hand their tasks off to other sessions or
+//! the global scope, and expire sessions that went quiet.
+
+use crate::models::{Status, Task};
+use crate::store::Store;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+
+/// Scope string for a session ID
+pub fn session_scope(session_id: &str) -> String {
+    format!("session:{}", session_id)
+}
+
+/// Task counts for one session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSummary {
+    pub id: String,
+    pub total: usize,
+    /// Pending or in progress
+    pub active: usize,
+    pub done: usize,
+    /// Latest created/updated/completed timestamp of any of its tasks
+    pub last_activity: DateTime<Utc>,
+}
+
+/// Summarize all sessions that own tasks, most recently active first.
+/// Archived tasks don't count, so ended and expired sessions drop out.
+pub fn list_sessions(store: &dyn Store) -> Result<Vec<SessionSummary>> {
+    let mut sessions: BTreeMap<String, SessionSummary> = BTreeMap::new();
+
+    for task in store.get_all()? {
+        if task.status == Status::Archived {
+            continue;
+        }
+        let Some(id) = task.session_id() else {
+            continue;
+        };
+        let summary = sessions
+            .entry(id.to_string())
+            .or_insert_with(|| SessionSummary {
+                id: id.to_string(),
+                total: 0,
+                active: 0,
+                done: 0,
+                last_activity: task.last_activity(),
+            });
+        summary.total += 1;
+        if task.is_active() {
+            summary.active += 1;
+        } else if task.is_done() {
+            summary.done += 1;
+        }
+        summary.last_activity = summary.last_activity.max(task.last_activity());
+    }
+
+    let mut sessions: Vec<SessionSummary> = sessions.into_values().collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.last_activity));
+    Ok(sessions)
+}
+
+/// Tasks belonging to a session
+pub fn session_tasks(store: &dyn Store, session_id: &str) -> Result<Vec<Task>> {
+    store.get_by_scope(&session_scope(session_id))
+}
+
+/// Move a session's tasks to the global scope, optionally into a project.
+/// Only unfinished tasks are promoted unless `include_done` is set.
+pub fn promote_session(
+    store: &dyn Store,
+    session_id: &str,
+    project: Option<&str>,
+    include_done: bool,
+) -> Result<Vec<Task>> {
+    let mut promoted = Vec::new();
+    for mut task in session_tasks(store, session_id)? {
+        if !include_done && !task.is_active() {
+            continue;
+        }
+        task.scope = None;
+        if let Some(project) = project {
+            task.project = Some(project.to_string());
+        }
+        if let Some(updated) = store.update(&task)? {
+            promoted.push(updated);
+        }
+    }
+    Ok(promoted)
+}
+
+/// Move unfinished tasks from one session into another session's scope
+/// (or the global scope when `to_session` is None). `task_ids` limits the
+/// adoption to specific tasks; empty means all unfinished tasks.
+pub fn adopt_session_tasks(
+    store: &dyn Store,
+    from_session: &str,
+    to_session: Option<&str>,
+    task_ids: &[String],
+) -> Result<Vec<Task>> {
+    let mut adopted = Vec::new();
+    for mut task in session_tasks(store, from_session)? {
+        let wanted = if task_ids.is_empty() {
+            task.is_active()
+        } else {
+            task_ids.contains(&task.id)
+        };
+        if !wanted {
+            continue;
+        }
+        task.scope = to_session.map(session_scope);
+        if let Some(updated) = store.update(&task)? {
+            adopted.push(updated);
+        }
+    }
+    Ok(adopted)
+}
+
+/// Result of ending a session
+#[derive(Debug, Clone)]
+pub struct SessionHandoff {
+    /// Completed scratch tasks that were archived
+    pub archived_done: usize,
+    /// Unfinished tasks left for another session (or promoted)
+    pub unfinished: Vec<Task>,
+    pub promoted: bool,
+}
+
+/// End a session: archive its completed tasks and hand off the unfinished
+/// ones, either left in the session for `adopt_session_tasks` or promoted
+/// to the global scope.
+pub fn end_session(store: &dyn Store, session_id: &str, promote: bool) -> Result<SessionHandoff> {
+    let mut archived_done = 0;
+    let mut unfinished = Vec::new();
+
+    for mut task in session_tasks(store, session_id)? {
+        if task.is_done() {
+            task.status = Status::Archived;
+            if store.update(&task)?.is_some() {
+                archived_done += 1;
+            }
+        } else if task.is_active() {
+            unfinished.push(task);
+        }
+    }
+
+    if promote {
+        unfinished = promote_session(store, session_id, None, false)?;
+    }
+
+    Ok(SessionHandoff {
+        archived_done,
+        unfinished,
+        promoted: promote,
+    })
+}
+
+/// A session removed by [`expire_sessions`]
+#[derive(Debug, Clone)]
+pub struct ExpiredSession {
+    pub id: String,
+    pub last_activity: DateTime<Utc>,
+    /// Completed tasks that were archived
+    pub archived_done: usize,
+    /// Unfinished tasks that were archived (kept for recovery)
+    pub archived: Vec<Task>,
+}
+
+/// Expire sessions with no activity for `inactive_for`: their tasks are
+/// archived, never deleted. `keep` protects the current session; `dry_run`
+/// reports without changing anything.
+pub fn expire_sessions(
+    store: &dyn Store,
+    inactive_for: Duration,
+    now: DateTime<Utc>,
+    keep: Option<&str>,
+    dry_run: bool,
+) -> Result<Vec<ExpiredSession>> {
+    // Anything older than chrono can represent counts as active
+    let Some(cutoff) = now.checked_sub_signed(inactive_for) else {
+        return Ok(Vec::new());
+    };
+    let mut expired = Vec::new();
+
+    for session in list_sessions(store)? {
+        if session.last_activity > cutoff || keep == Some(session.id.as_str()) {
+            continue;
+        }
+
+        let mut archived_done = 0;
+        let mut archived = Vec::new();
+        for mut task in session_tasks(store, &session.id)? {
+            if task.is_done() {
+                task.status = Status::Archived;
+                if dry_run || store.update(&task)?.is_some() {
+                    archived_done += 1;
+                }
+            } else if task.is_active() {
+                task.status = Status::Archived;
+                if !dry_run {
+                    store.update(&task)?;
+                }
+                archived.push(task);
+            }
+        }
+
+        if archived_done > 0 || !archived.is_empty() {
+            expired.push(ExpiredSession {
+                id: session.id,
+                last_activity: session.last_activity,
+                archived_done,
+                archived,
+            });
+        }
+    }
+
+    Ok(expired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    fn add(store: &MemoryStore, title: &str, session: Option<&str>, done: bool) -> Task {
+        let mut task = Task::new_with_scope(title.to_string(), session.map(session_scope));
+        if done {
+            task.complete();
+        }
+        store.add(&task).unwrap()
+    }
+
+    #[test]
+    fn test_list_and_promote_sessions() {
+        let store = MemoryStore::new();
+        add(&store, "Scratch 1", Some("abc"), false);
+        add(&store, "Scratch 2", Some("abc"), true);
+        add(&store, "Other", Some("def"), false);
+        add(&store, "Global", None, false);
+
+        let sessions = list_sessions(&store).unwrap();
+        assert_eq!(sessions.len(), 2);
+        let abc = sessions.iter().find(|s| s.id == "abc").unwrap();
+        assert_eq!((abc.total, abc.active, abc.done), (2, 1, 1));
+
+        let promoted = promote_session(&store, "abc", Some("vulcan-os"), false).unwrap();
+        assert_eq!(promoted.len(), 1);
+        assert_eq!(promoted[0].scope, None);
+        assert_eq!(promoted[0].project.as_deref(), Some("vulcan-os"));
+        // The completed task stays in the session
+        assert_eq!(session_tasks(&store, "abc").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_end_and_adopt_session() {
+        let store = MemoryStore::new();
+        let open = add(&store, "Unfinished", Some("old"), false);
+        add(&store, "Finished", Some("old"), true);
+
+        let handoff = end_session(&store, "old", false).unwrap();
+        assert_eq!(handoff.archived_done, 1);
+        assert_eq!(handoff.unfinished.len(), 1);
+        // The finished task is kept, archived
+        let finished = session_tasks(&store, "old").unwrap();
+        assert_eq!(finished.len(), 2);
+        assert!(finished
+            .iter()
+            .any(|t| t.title == "Finished" && t.status == Status::Archived));
+
+        let adopted = adopt_session_tasks(&store, "old", Some("new"), &[]).unwrap();
+        assert_eq!(adopted.len(), 1);
+        let task = store.get(&open.id).unwrap().unwrap();
+        assert_eq!(task.session_id(), Some("new"));
+        assert!(list_sessions(&store).unwrap().iter().all(|s| s.id != "old"));
+    }
+
+    #[test]
+    fn test_expire_sessions() {
+        let store = MemoryStore::new();
+        add(&store, "Stale", Some("stale"), false);
+        add(&store, "Stale done", Some("stale"), true);
+        add(&store, "Current", Some("current"), false);
+
+        let later = Utc::now() + Duration::days(10);
+
+        let dry = expire_sessions(&store, Duration::days(7), later, Some("current"), true).unwrap();
+        assert_eq!(dry.len(), 1);
+        assert_eq!(store.get_all().unwrap().len(), 3);
+
+        let expired =
+            expire_sessions(&store, Duration::days(7), later, Some("current"), false).unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, "stale");
+        assert_eq!(expired[0].archived_done, 1);
+        assert_eq!(expired[0].archived.len(), 1);
+
+        let stale = session_tasks(&store, "stale").unwrap();
+        assert_eq!(stale.len(), 2);
+        assert!(stale.iter().all(|t| t.status == Status::Archived));
+
+        // Nothing left to expire
+        assert!(
+            expire_sessions(&store, Duration::days(7), later, None, false)
+                .unwrap()
+                .iter()
+                .all(|s| s.id != "stale")
+        );
+    }
+
+    #[test]
+    fn test_updates_count_as_activity() {
+        let store = MemoryStore::new();
+        let mut task = add(&store, "Old but busy", Some("busy"), false);
+        task.created_at = Utc::now() - Duration::days(30);
+        store.update(&task).unwrap();
+
+        let sessions = list_sessions(&store).unwrap();
+        assert!(sessions[0].last_activity > Utc::now() - Duration::minutes(1));
+        assert!(
+            expire_sessions(&store, Duration::days(7), Utc::now(), None, false)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -1,15 +1,38 @@
-use crate::models::{Priority, Sprint, SprintStatus, Status, Task};
+use crate::models::search::Query;
+use crate::models::{parse_wait_until, Priority, Sprint, SprintStatus, Status, Task};
+use crate::store::planning::{self, SprintPlan};
 use crate::store::Store;
+use crate::ui::config::TuiConfig;
+use crate::ui::keymap::{Action, Keymap};
+use crate::ui::theme::Theme;
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style, Stylize},
+    style::{Modifier, Style, Stylize},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs},
     Frame,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+/// Planner budget for sprints without a capacity
+const DEFAULT_PLAN_BUDGET: u32 = 10;
+
+/// Bulk edits kept for undo
+const UNDO_LIMIT: usize = 20;
+
+/// A bulk edit that can be reverted
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    /// What the edit did ("Priority high")
+    pub label: String,
+    /// The edited tasks as they were before the edit
+    pub before: Vec<Task>,
+    /// The same tasks as the edit wrote them
+    pub after: Vec<Task>,
+}
+
 /// Project tab information
 #[derive(Debug, Clone)]
 pub struct ProjectTab {
@@ -43,9 +66,15 @@ pub struct App {
     /// Search query
     pub search_query: String,
 
+    /// Parsed search query, for ranking and highlighting
+    pub search_terms: Query,
+
     /// Show help overlay
     pub show_help: bool,
 
+    /// Help overlay scroll offset
+    pub help_scroll: u16,
+
     /// Input mode state
     pub input_mode: Option<InputMode>,
 
@@ -103,12 +132,21 @@ pub struct App {
     /// Status filter (None = all, Some = specific status)
     pub status_filter: Option<Status>,
 
+    /// Show tasks that are snoozed until a later date
+    pub show_waiting: bool,
+
     /// Multi-select mode
     pub multi_select_mode: bool,
 
     /// Selected task IDs (for multi-select)
     pub selected_tasks: HashSet<String>,
 
+    /// Row of the last toggled task, start of a range selection
+    pub selection_anchor: Option<usize>,
+
+    /// Bulk edits that can be undone, most recent last
+    pub undo_stack: Vec<UndoEntry>,
+
     /// Detail view: currently viewed task ID
     pub detail_task_id: Option<String>,
 
@@ -137,17 +175,89 @@ pub struct App {
     /// Sprint being edited (for edit/delete operations)
     pub editing_sprint_id: Option<String>,
 
+    /// Sprint planner: proposal being reviewed (Some = dialog open)
+    pub sprint_plan: Option<SprintPlan>,
+
     /// Show move-to-sprint dialog
     pub show_move_to_sprint: bool,
 
     /// Move-to-sprint selector index
     pub move_to_sprint_index: usize,
+
+    // ==================== Agenda / Calendar State ====================
+    /// View to return to when the detail view is closed
+    pub return_view: ViewMode,
+
+    /// Calendar view: day under the cursor
+    pub calendar_date: NaiveDate,
+
+    /// Calendar view: open tasks due per day (respects project/search filters)
+    pub calendar_counts: HashMap<NaiveDate, usize>,
+
+    /// Calendar view: task picked up for rescheduling
+    pub reschedule_task_id: Option<String>,
+
+    // ==================== Config ====================
+    /// Active key bindings
+    pub keymap: Keymap,
+
+    /// Color scheme
+    pub theme: Theme,
+
+    // ==================== Integrations ====================
+    /// Knowledge vault for resolving context note titles
+    #[cfg(feature = "vault")]
+    pub vault: Option<Arc<crate::vault::Vault>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ViewMode {
     List,
     Detail,
+    Agenda,
+    Calendar,
+}
+
+/// Agenda grouping for tasks by due date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AgendaBucket {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+    NoDate,
+}
+
+impl AgendaBucket {
+    /// Bucket a task relative to `today`
+    pub fn for_task(task: &Task, today: NaiveDate) -> Self {
+        match task.due_date {
+            None => AgendaBucket::NoDate,
+            Some(due) => {
+                let days = (due.date_naive() - today).num_days();
+                match days {
+                    d if d < 0 => AgendaBucket::Overdue,
+                    0 => AgendaBucket::Today,
+                    1 => AgendaBucket::Tomorrow,
+                    d if d < 7 => AgendaBucket::ThisWeek,
+                    _ => AgendaBucket::Later,
+                }
+            }
+        }
+    }
+
+    /// Section header label
+    pub fn label(&self) -> &'static str {
+        match self {
+            AgendaBucket::Overdue => "⚠️ Overdue",
+            AgendaBucket::Today => "📅 Today",
+            AgendaBucket::Tomorrow => "🌅 Tomorrow",
+            AgendaBucket::ThisWeek => "🗓️ This week",
+            AgendaBucket::Later => "🔭 Later",
+            AgendaBucket::NoDate => "📭 No date",
+        }
+    }
 }
 
 /// Detail view field being highlighted/edited
@@ -193,6 +303,12 @@ pub enum InputMode {
     Search,
     ConfirmDelete,
     ConfirmBulkDelete,
+    Snooze,
+    // Bulk edit modes (multi-select)
+    BulkPriority,
+    BulkTags,
+    BulkProject,
+    BulkDue,
     // Sprint modes
     NewSprint,
     EditSprintName,
@@ -211,7 +327,9 @@ impl App {
             view: ViewMode::List,
             filter: TaskFilter::default(),
             search_query: String::new(),
+            search_terms: Query::default(),
             show_help: false,
+            help_scroll: 0,
             input_mode: None,
             message: None,
             sort_by: SortBy::Priority,
@@ -231,8 +349,11 @@ impl App {
             tabs: Vec::new(),
             active_tab: 0,
             status_filter: None,
+            show_waiting: false,
             multi_select_mode: false,
             selected_tasks: HashSet::new(),
+            selection_anchor: None,
+            undo_stack: Vec::new(),
             detail_task_id: None,
             detail_field: DetailField::Title,
             detail_edit_mode: false,
@@ -243,8 +364,19 @@ impl App {
             show_sprint_selector: false,
             sprint_selector_index: 0,
             editing_sprint_id: None,
+            sprint_plan: None,
             show_move_to_sprint: false,
             move_to_sprint_index: 0,
+            // Agenda / calendar state
+            return_view: ViewMode::List,
+            calendar_date: Utc::now().date_naive(),
+            calendar_counts: HashMap::new(),
+            reschedule_task_id: None,
+            // Config
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            #[cfg(feature = "vault")]
+            vault: None,
         };
 
         app.refresh_tasks();
@@ -331,6 +463,11 @@ impl App {
             tasks.retain(|t| t.belongs_to_project(project));
         }
 
+        // Hide snoozed tasks unless toggled on
+        if !self.show_waiting {
+            tasks.retain(|t| !t.is_waiting());
+        }
+
         // Apply sprint filter (if in sprint view mode)
         if self.sprint_view_mode {
             if let Some(ref sprint_id) = self.selected_sprint {
@@ -352,19 +489,52 @@ impl App {
             tasks.retain(|t| &t.status == status);
         }
 
-        // Apply search filter
-        if !self.search_query.is_empty() {
-            let query = self.search_query.to_lowercase();
-            tasks.retain(|t| {
-                t.title.to_lowercase().contains(&query)
-                    || t.description
-                        .as_ref()
-                        .map(|d| d.to_lowercase().contains(&query))
-                        .unwrap_or(false)
-                    || t.tags.iter().any(|tag| tag.to_lowercase().contains(&query))
+        // Apply search filter (fuzzy, scored for ranking)
+        self.search_terms = Query::parse(&self.search_query);
+        let mut scores = HashMap::new();
+        if !self.search_terms.is_empty() {
+            tasks.retain(|t| match self.search_terms.score(t) {
+                Some(score) => {
+                    scores.insert(t.id.clone(), score);
+                    true
+                }
+                None => false,
             });
         }
 
+        // Agenda/calendar views only show open tasks, arranged by due date
+        match self.main_view() {
+            ViewMode::Agenda => {
+                let today = Utc::now().date_naive();
+                tasks.retain(|t| t.is_active());
+                tasks.sort_by(|a, b| {
+                    AgendaBucket::for_task(a, today)
+                        .cmp(&AgendaBucket::for_task(b, today))
+                        .then_with(|| a.due_date.cmp(&b.due_date))
+                        .then_with(|| b.priority.level().cmp(&a.priority.level()))
+                });
+                self.set_filtered_tasks(tasks);
+                return;
+            }
+            ViewMode::Calendar => {
+                tasks.retain(|t| t.is_active());
+                let mut counts = HashMap::new();
+                for task in &tasks {
+                    if let Some(due) = task.due_date {
+                        *counts.entry(due.date_naive()).or_insert(0) += 1;
+                    }
+                }
+                self.calendar_counts = counts;
+
+                let day = self.calendar_date;
+                tasks.retain(|t| t.due_date.map(|d| d.date_naive()) == Some(day));
+                tasks.sort_by_key(|t| std::cmp::Reverse(t.priority.level()));
+                self.set_filtered_tasks(tasks);
+                return;
+            }
+            _ => {}
+        }
+
         // Sort tasks
         if self.sprint_view_mode && self.selected_sprint.is_some() {
             // In sprint view with a sprint selected, sort by sprint_order
@@ -373,6 +543,13 @@ impl App {
                     .unwrap_or(i32::MAX)
                     .cmp(&b.sprint_order.unwrap_or(i32::MAX))
             });
+        } else if !scores.is_empty() {
+            // Search results are ranked by relevance
+            tasks.sort_by(|a, b| {
+                scores[&b.id]
+                    .total_cmp(&scores[&a.id])
+                    .then_with(|| b.priority.level().cmp(&a.priority.level()))
+            });
         } else {
             // Normal sorting
             match self.sort_by {
@@ -393,6 +570,11 @@ impl App {
             }
         }
 
+        self.set_filtered_tasks(tasks);
+    }
+
+    /// Replace the visible task list, keeping the selection in range
+    fn set_filtered_tasks(&mut self, tasks: Vec<Task>) {
         self.filtered_tasks = tasks;
 
         // Ensure selected index is valid
@@ -425,14 +607,18 @@ impl App {
         if let Some(task) = self.filtered_tasks.get_mut(self.selected) {
             let mut task = task.clone();
             task.toggle();
-            if let Ok(_) = self.store.update(&task) {
-                self.refresh_tasks();
-                let msg = if task.is_done() {
-                    "completed"
-                } else {
-                    "reopened"
-                };
-                self.set_message(msg.to_string());
+            match self.store.update(&task) {
+                Ok(_) => {
+                    self.refresh_tasks();
+                    let msg = if task.is_done() {
+                        "completed"
+                    } else {
+                        "reopened"
+                    };
+                    self.set_message(msg.to_string());
+                }
+                // Hooks can veto a write; show why
+                Err(e) => self.set_message(format!("⚠️ {}", e)),
             }
         }
     }
@@ -459,13 +645,54 @@ impl App {
         }
     }
 
+    /// Snooze current task for a duration like "3d" (empty clears the wait date)
+    pub fn snooze_task(&mut self, input: &str) {
+        if let Some(task) = self.filtered_tasks.get(self.selected) {
+            let mut task = task.clone();
+            if input.is_empty() {
+                task.unsnooze();
+            } else {
+                match parse_wait_until(input, Utc::now()) {
+                    Some(until) => task.snooze(until),
+                    None => {
+                        self.set_message(format!("Invalid duration: {}", input));
+                        return;
+                    }
+                }
+            }
+            if self.store.update(&task).is_ok() {
+                self.refresh_tasks();
+                let msg = match task.wait_formatted() {
+                    Some(until) => format!("Snoozed until {}", until),
+                    None => "Unsnoozed".to_string(),
+                };
+                self.set_message(msg);
+            }
+        }
+    }
+
+    /// Toggle visibility of snoozed tasks
+    pub fn toggle_show_waiting(&mut self) {
+        self.show_waiting = !self.show_waiting;
+        self.apply_filter();
+        let msg = if self.show_waiting {
+            "Showing snoozed tasks"
+        } else {
+            "Hiding snoozed tasks"
+        };
+        self.set_message(msg.to_string());
+    }
+
     /// Add a new task
     pub fn add_task(&mut self, title: String) {
         let task = Task::new(title);
-        if let Ok(_) = self.store.add(&task) {
-            self.refresh_tasks();
-            self.selected = self.filtered_tasks.len().saturating_sub(1);
-            self.set_message("Task created".to_string());
+        match self.store.add(&task) {
+            Ok(_) => {
+                self.refresh_tasks();
+                self.selected = self.filtered_tasks.len().saturating_sub(1);
+                self.set_message("Task created".to_string());
+            }
+            Err(e) => self.set_message(format!("⚠️ {}", e)),
         }
     }
 
@@ -515,6 +742,36 @@ impl App {
         self.message = Some(message);
     }
 
+    /// Apply keymap and theme from the TUI config, reporting problems
+    /// in the status bar
+    pub fn apply_config(&mut self, config: &TuiConfig) {
+        let (keymap, mut warnings) = config.keymap();
+        let (theme, theme_error) = config.theme();
+        warnings.extend(theme_error);
+
+        self.keymap = keymap;
+        self.theme = theme;
+        if !warnings.is_empty() {
+            self.set_message(format!("⚠️ tui.toml: {}", warnings.join("; ")));
+        }
+    }
+
+    /// Display label for a linked context note: the note title when the
+    /// vault is available, otherwise the short ID. The flag is false for
+    /// notes missing from the vault.
+    pub fn context_note_label(&self, id: &str) -> (String, bool) {
+        let short_id: String = id.chars().take(8).collect();
+        #[cfg(feature = "vault")]
+        if let Some(vault) = &self.vault {
+            return match vault.resolve(id) {
+                Ok(Some(note)) => (note.title, true),
+                Ok(None) => (format!("{} (missing)", short_id), false),
+                Err(_) => (format!("{} (vault unavailable)", short_id), true),
+            };
+        }
+        (short_id, true)
+    }
+
     /// Clear message
     pub fn clear_message(&mut self) {
         self.message = None;
@@ -523,6 +780,13 @@ impl App {
     /// Toggle help
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
+        self.help_scroll = 0;
+    }
+
+    /// Scroll the help overlay
+    pub fn scroll_help(&mut self, delta: i32) {
+        let max = self.keymap.help_text().lines().count() as i32;
+        self.help_scroll = (self.help_scroll as i32 + delta).clamp(0, max) as u16;
     }
 
     /// Enter input mode
@@ -539,6 +803,10 @@ impl App {
                 self.input_buffer = self.search_query.clone();
                 self.input_cursor = self.input_buffer.len();
             }
+            InputMode::Snooze => {
+                self.input_buffer = "1d".to_string();
+                self.input_cursor = self.input_buffer.len();
+            }
             _ => {
                 self.input_buffer.clear();
                 self.input_cursor = 0;
@@ -669,6 +937,21 @@ impl App {
                     self.set_message("Bulk delete cancelled".to_string());
                 }
             }
+            Some(InputMode::Snooze) => {
+                self.snooze_task(input.trim());
+            }
+            Some(InputMode::BulkPriority) => {
+                self.bulk_set_priority(input.trim());
+            }
+            Some(InputMode::BulkTags) => {
+                self.bulk_edit_tags(input.trim());
+            }
+            Some(InputMode::BulkProject) => {
+                self.bulk_set_project(input.trim());
+            }
+            Some(InputMode::BulkDue) => {
+                self.bulk_set_due(input.trim());
+            }
             // Sprint modes
             Some(InputMode::NewSprint) => {
                 self.create_sprint();
@@ -807,9 +1090,14 @@ impl App {
         self.multi_select_mode = !self.multi_select_mode;
         if !self.multi_select_mode {
             self.selected_tasks.clear();
+            self.selection_anchor = None;
         }
         let msg = if self.multi_select_mode {
-            "Multi-select ON (Space to select, v to exit)".to_string()
+            format!(
+                "Multi-select ON ({} to select, {} to exit)",
+                self.keymap.hint(Action::ToggleSelect),
+                self.keymap.hint(Action::MultiSelect)
+            )
         } else {
             "Multi-select OFF".to_string()
         };
@@ -825,7 +1113,29 @@ impl App {
             } else {
                 self.selected_tasks.insert(id);
             }
+            self.selection_anchor = Some(self.selected);
+        }
+    }
+
+    /// Select every task between the anchor (last toggled task) and the cursor
+    pub fn select_range(&mut self) {
+        if self.filtered_tasks.is_empty() {
+            return;
+        }
+        let anchor = self
+            .selection_anchor
+            .unwrap_or(self.selected)
+            .min(self.filtered_tasks.len() - 1);
+        let (start, end) = if anchor <= self.selected {
+            (anchor, self.selected)
+        } else {
+            (self.selected, anchor)
+        };
+        for task in &self.filtered_tasks[start..=end] {
+            self.selected_tasks.insert(task.id.clone());
         }
+        self.selection_anchor = Some(self.selected);
+        self.set_message(format!("Selected {} tasks", self.selected_tasks.len()));
     }
 
     /// Select all visible tasks
@@ -844,23 +1154,202 @@ impl App {
         self.selected_tasks.contains(task_id)
     }
 
-    /// Complete all selected tasks
-    pub fn complete_selected_tasks(&mut self) {
-        let mut completed = 0;
-        for id in self.selected_tasks.clone() {
-            if let Ok(Some(mut task)) = self.store.get(&id) {
-                if task.is_pending() {
-                    task.toggle();
-                    if self.store.update(&task).is_ok() {
-                        completed += 1;
-                    }
+    /// Selected tasks in list order (selected tasks hidden by the filter last)
+    fn selected_task_list(&self) -> Vec<Task> {
+        let position = |id: &str| {
+            self.filtered_tasks
+                .iter()
+                .position(|t| t.id == id)
+                .unwrap_or(usize::MAX)
+        };
+        let mut tasks: Vec<Task> = self
+            .tasks
+            .iter()
+            .filter(|t| self.selected_tasks.contains(&t.id))
+            .cloned()
+            .collect();
+        tasks.sort_by_key(|t| position(&t.id));
+        tasks
+    }
+
+    /// Apply an edit to every selected task in a single store write, and
+    /// remember the previous versions so the whole edit can be undone
+    fn apply_bulk_edit(&mut self, label: String, mut edit: impl FnMut(&mut Task)) {
+        let before = self.selected_task_list();
+        if before.is_empty() {
+            self.set_message("No tasks selected".to_string());
+            return;
+        }
+
+        let edited: Vec<Task> = before
+            .iter()
+            .cloned()
+            .map(|mut task| {
+                edit(&mut task);
+                task
+            })
+            .collect();
+
+        match self.store.update_many(&edited) {
+            Ok(updated) => {
+                self.undo_stack.push(UndoEntry {
+                    label: label.clone(),
+                    before,
+                    after: updated.clone(),
+                });
+                if self.undo_stack.len() > UNDO_LIMIT {
+                    self.undo_stack.remove(0);
                 }
+                self.refresh_tasks();
+                let undo_key = self.keymap.hint(Action::Undo);
+                self.set_message(format!(
+                    "{}: {} tasks ({} to undo)",
+                    label,
+                    updated.len(),
+                    undo_key
+                ));
             }
+            Err(e) => self.set_message(format!("Bulk edit failed: {}", e)),
         }
-        self.selected_tasks.clear();
-        self.multi_select_mode = false;
-        self.refresh_tasks();
-        self.set_message(format!("Completed {} tasks", completed));
+    }
+
+    /// Revert the most recent bulk edit. Only the fields the edit changed
+    /// are restored, and tasks modified since the edit are left alone.
+    pub fn undo(&mut self) {
+        let Some(entry) = self.undo_stack.pop() else {
+            self.set_message("Nothing to undo".to_string());
+            return;
+        };
+
+        let mut reverted = Vec::new();
+        let mut skipped = 0;
+        for after in &entry.after {
+            let before = entry.before.iter().find(|t| t.id == after.id);
+            let current = self.store.get(&after.id).ok().flatten();
+            let (Some(before), Some(mut current)) = (before, current) else {
+                skipped += 1;
+                continue;
+            };
+            if current.updated_at != after.updated_at
+                || current.revert_changes(before, after).is_err()
+            {
+                skipped += 1;
+                continue;
+            }
+            reverted.push(current);
+        }
+
+        match self.store.update_many(&reverted) {
+            Ok(restored) => {
+                self.refresh_tasks();
+                let skipped = match skipped {
+                    0 => String::new(),
+                    n => format!(", {} changed since and skipped", n),
+                };
+                self.set_message(format!(
+                    "Undid {} ({} tasks{})",
+                    entry.label.to_lowercase(),
+                    restored.len(),
+                    skipped
+                ));
+            }
+            Err(e) => {
+                self.set_message(format!("Undo failed: {}", e));
+                self.undo_stack.push(entry);
+            }
+        }
+    }
+
+    /// Set the priority of all selected tasks
+    pub fn bulk_set_priority(&mut self, input: &str) {
+        let priority: Priority = input.to_string().into();
+        if priority == Priority::None && !input.eq_ignore_ascii_case("none") {
+            self.set_message(format!("Unknown priority: {}", input));
+            return;
+        }
+        self.apply_bulk_edit(format!("Priority {}", priority), |task| {
+            task.priority = priority
+        });
+    }
+
+    /// Edit the tags of all selected tasks (see [`Task::edit_tags`])
+    pub fn bulk_edit_tags(&mut self, input: &str) {
+        let input = input.to_string();
+        self.apply_bulk_edit("Tags updated".to_string(), |task| task.edit_tags(&input));
+    }
+
+    /// Move all selected tasks to a project (empty clears the project)
+    pub fn bulk_set_project(&mut self, input: &str) {
+        let project = (!input.is_empty()).then(|| input.to_string());
+        let label = match &project {
+            Some(project) => format!("Moved to {}", project),
+            None => "Project cleared".to_string(),
+        };
+        self.apply_bulk_edit(label, |task| task.project = project.clone());
+    }
+
+    /// Set the due date of all selected tasks (empty clears it)
+    pub fn bulk_set_due(&mut self, input: &str) {
+        let due = if input.is_empty() {
+            None
+        } else {
+            match parse_wait_until(input, Utc::now()) {
+                Some(due) => Some(due),
+                None => {
+                    self.set_message(format!("Invalid date: {}", input));
+                    return;
+                }
+            }
+        };
+        let label = match due {
+            Some(due) => format!("Due {}", due.format("%Y-%m-%d")),
+            None => "Due date cleared".to_string(),
+        };
+        self.apply_bulk_edit(label, |task| task.due_date = due);
+    }
+
+    /// Start all selected pending tasks
+    pub fn start_selected_tasks(&mut self) {
+        self.apply_bulk_edit("Started".to_string(), |task| {
+            if task.is_pending() {
+                task.start();
+            }
+        });
+    }
+
+    /// Complete all selected tasks
+    pub fn complete_selected_tasks(&mut self) {
+        self.apply_bulk_edit("Completed".to_string(), |task| {
+            if task.is_active() {
+                task.complete();
+            }
+        });
+    }
+
+    /// Assign all selected tasks to a sprint (None = backlog), appended in
+    /// list order
+    fn move_selected_to_sprint(&mut self, sprint: Option<&Sprint>) {
+        let Some(sprint) = sprint else {
+            self.apply_bulk_edit("Moved to backlog".to_string(), |task| {
+                task.unassign_from_sprint()
+            });
+            return;
+        };
+
+        let mut next_order = self
+            .tasks
+            .iter()
+            .filter(|t| t.belongs_to_sprint(&sprint.id))
+            .filter_map(|t| t.sprint_order)
+            .max()
+            .unwrap_or(0);
+        let sprint_id = sprint.id.clone();
+        self.apply_bulk_edit(format!("Moved to '{}'", sprint.name), |task| {
+            if !task.belongs_to_sprint(&sprint_id) {
+                next_order += 1;
+                task.assign_to_sprint(&sprint_id, next_order);
+            }
+        });
     }
 
     /// Prompt for confirmation before deleting selected tasks
@@ -898,6 +1387,7 @@ impl App {
             self.detail_task_id = Some(task.id.clone());
             self.detail_field = DetailField::Title;
             self.detail_edit_mode = false;
+            self.return_view = self.main_view();
             self.view = ViewMode::Detail;
         }
     }
@@ -906,7 +1396,7 @@ impl App {
     pub fn close_detail_view(&mut self) {
         self.detail_task_id = None;
         self.detail_edit_mode = false;
-        self.view = ViewMode::List;
+        self.view = self.return_view;
     }
 
     /// Check if in detail view
@@ -952,14 +1442,17 @@ impl App {
         if let Some(ref id) = self.detail_task_id.clone() {
             if let Ok(Some(mut task)) = self.store.get(id) {
                 task.toggle();
-                if self.store.update(&task).is_ok() {
-                    self.refresh_tasks();
-                    let msg = if task.is_done() {
-                        "Task completed"
-                    } else {
-                        "Task reopened"
-                    };
-                    self.set_message(msg.to_string());
+                match self.store.update(&task) {
+                    Ok(_) => {
+                        self.refresh_tasks();
+                        let msg = if task.is_done() {
+                            "Task completed"
+                        } else {
+                            "Task reopened"
+                        };
+                        self.set_message(msg.to_string());
+                    }
+                    Err(e) => self.set_message(format!("⚠️ {}", e)),
                 }
             }
         }
@@ -1548,7 +2041,8 @@ impl App {
         }
     }
 
-    /// Complete the selected sprint (change status to Completed)
+    /// Complete the selected sprint (change status to Completed), carrying
+    /// unfinished tasks over to the next planning sprint or the backlog
     pub fn complete_sprint(&mut self) {
         let sprints = self.get_project_sprints();
         if self.sprint_selector_index == 0 {
@@ -1557,29 +2051,97 @@ impl App {
         }
 
         if let Some(sprint) = sprints.get(self.sprint_selector_index - 1) {
+            if sprint.status == SprintStatus::Completed {
+                self.set_message("Sprint is already completed".to_string());
+                return;
+            }
             let sprint_id = sprint.id.clone();
-            if let Ok(Some(mut s)) = self.store.get_sprint(&sprint_id) {
-                if s.status == SprintStatus::Completed {
-                    self.set_message("Sprint is already completed".to_string());
-                    return;
-                }
-                s.status = SprintStatus::Completed;
-                s.end_date = Some(chrono::Utc::now());
-                if let Err(e) = self.store.update_sprint(&s) {
-                    self.set_message(format!("Failed to complete sprint: {}", e));
-                } else {
+            match planning::complete_sprint(self.store.as_ref(), &sprint_id, true, None) {
+                Ok((s, rollover)) => {
                     self.refresh_sprints();
-                    self.set_message(format!("Sprint '{}' completed", s.name));
+                    self.refresh_tasks();
+                    match rollover {
+                        Some(r) if !r.moved.is_empty() => self.set_message(format!(
+                            "Sprint '{}' completed, {} task(s) carried over to {}",
+                            s.name,
+                            r.moved.len(),
+                            r.target_name()
+                        )),
+                        _ => self.set_message(format!("Sprint '{}' completed", s.name)),
+                    }
                 }
+                Err(e) => self.set_message(format!("Failed to complete sprint: {}", e)),
+            }
+        }
+    }
+
+    // ==================== Sprint Planner ====================
+
+    /// Open the planner for the selected sprint, starting from its capacity
+    pub fn enter_sprint_plan(&mut self) {
+        let sprints = self.get_project_sprints();
+        if self.sprint_selector_index == 0 {
+            self.set_message("Cannot plan backlog".to_string());
+            return;
+        }
+
+        if let Some(sprint) = sprints.get(self.sprint_selector_index - 1) {
+            let budget = sprint.capacity.unwrap_or(DEFAULT_PLAN_BUDGET);
+            let sprint_id = sprint.id.clone();
+            self.update_sprint_plan(&sprint_id, budget);
+        }
+    }
+
+    fn update_sprint_plan(&mut self, sprint_id: &str, budget: u32) {
+        match planning::plan_sprint(self.store.as_ref(), sprint_id, Some(budget), false) {
+            Ok(plan) => self.sprint_plan = Some(plan),
+            Err(e) => self.set_message(format!("Failed to plan sprint: {}", e)),
+        }
+    }
+
+    /// Grow or shrink the planner budget and re-propose
+    pub fn adjust_sprint_plan(&mut self, delta: i32) {
+        if let Some(plan) = &self.sprint_plan {
+            let budget = plan.budget.saturating_add_signed(delta).max(1);
+            let sprint_id = plan.sprint.id.clone();
+            self.update_sprint_plan(&sprint_id, budget);
+        }
+    }
+
+    /// Add the proposed tasks to the sprint and close the planner
+    pub fn apply_sprint_plan(&mut self) {
+        let Some(plan) = self.sprint_plan.take() else {
+            return;
+        };
+        match planning::plan_sprint(
+            self.store.as_ref(),
+            &plan.sprint.id,
+            Some(plan.budget),
+            true,
+        ) {
+            Ok(applied) => {
+                self.refresh_tasks();
+                self.set_message(format!(
+                    "Added {} task(s) ({} pts) to '{}'",
+                    applied.proposed.len(),
+                    applied.planned,
+                    applied.sprint.name
+                ));
             }
+            Err(e) => self.set_message(format!("Failed to plan sprint: {}", e)),
         }
     }
 
+    /// Close the planner without changes
+    pub fn cancel_sprint_plan(&mut self) {
+        self.sprint_plan = None;
+    }
+
     // ==================== Move Task to Sprint ====================
 
     /// Toggle move-to-sprint dialog
     pub fn toggle_move_to_sprint(&mut self) {
-        if self.current_task().is_none() {
+        if self.current_task().is_none() && self.selected_tasks.is_empty() {
             self.set_message("No task selected".to_string());
             return;
         }
@@ -1609,6 +2171,18 @@ impl App {
 
     /// Execute move task to sprint
     pub fn execute_move_to_sprint(&mut self) {
+        if self.multi_select_mode && !self.selected_tasks.is_empty() {
+            let sprint = match self.move_to_sprint_index {
+                0 => None,
+                index => self.get_project_sprints().get(index - 1).cloned().cloned(),
+            };
+            if self.move_to_sprint_index == 0 || sprint.is_some() {
+                self.move_selected_to_sprint(sprint.as_ref());
+            }
+            self.show_move_to_sprint = false;
+            return;
+        }
+
         let task = match self.current_task() {
             Some(t) => t.clone(),
             None => return,
@@ -1651,44 +2225,217 @@ impl App {
             .find(|s| s.id == sprint_id)
             .map(|s| s.name.clone())
     }
-}
 
-/// Render the main TUI
-pub fn render(app: &mut App, frame: &mut Frame) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(1), // Tab bar
-            Constraint::Length(1), // Title bar
-            Constraint::Min(1),    // Content
-            Constraint::Length(1), // Status bar
-        ])
-        .split(frame.size());
+    // === Agenda / Calendar ===
 
-    render_tab_bar(app, frame, chunks[0]);
-    render_title_bar(app, frame, chunks[1]);
-    render_content(app, frame, chunks[2]);
-    render_status_bar(app, frame, chunks[3]);
+    /// The list-like view underneath the detail overlay
+    pub fn main_view(&self) -> ViewMode {
+        if self.view == ViewMode::Detail {
+            self.return_view
+        } else {
+            self.view
+        }
+    }
 
-    // Render overlays (order matters - later overlays appear on top)
-    if app.is_detail_view() {
-        render_detail_view(app, frame);
+    /// Toggle the agenda view (tasks grouped by due date)
+    pub fn toggle_agenda_view(&mut self) {
+        self.set_main_view(if self.view == ViewMode::Agenda {
+            ViewMode::List
+        } else {
+            ViewMode::Agenda
+        });
     }
 
-    if app.show_filter_builder {
-        render_filter_builder(app, frame);
-    } else if app.show_sort_selector {
-        render_sort_selector(app, frame);
+    /// Toggle the month calendar view
+    pub fn toggle_calendar_view(&mut self) {
+        self.set_main_view(if self.view == ViewMode::Calendar {
+            ViewMode::List
+        } else {
+            ViewMode::Calendar
+        });
+    }
+
+    /// Switch between list, agenda and calendar views
+    pub fn set_main_view(&mut self, view: ViewMode) {
+        self.view = view;
+        self.return_view = view;
+        self.reschedule_task_id = None;
+        self.selected = 0;
+        if view == ViewMode::Calendar {
+            self.refresh_sprints();
+        }
+        self.apply_filter();
+    }
+
+    /// Check if in agenda or calendar view
+    pub fn is_date_view(&self) -> bool {
+        matches!(self.view, ViewMode::Agenda | ViewMode::Calendar)
+    }
+
+    /// Move the calendar cursor by a number of days
+    pub fn calendar_move_days(&mut self, days: i64) {
+        self.calendar_set_date(self.calendar_date + Duration::days(days));
+    }
+
+    /// Move the calendar cursor by a number of months (clamped to month end)
+    pub fn calendar_move_months(&mut self, months: i32) {
+        let date = if months >= 0 {
+            self.calendar_date
+                .checked_add_months(Months::new(months as u32))
+        } else {
+            self.calendar_date
+                .checked_sub_months(Months::new(months.unsigned_abs()))
+        };
+        if let Some(date) = date {
+            self.calendar_set_date(date);
+        }
+    }
+
+    /// Jump the calendar cursor to today
+    pub fn calendar_today(&mut self) {
+        self.calendar_set_date(Utc::now().date_naive());
+    }
+
+    fn calendar_set_date(&mut self, date: NaiveDate) {
+        self.calendar_date = date;
+        self.selected = 0;
+        self.apply_filter();
+    }
+
+    /// Pick up the current task to drop it on another calendar day
+    pub fn begin_reschedule(&mut self) {
+        if let Some(task) = self.current_task() {
+            let (id, title, due) = (task.id.clone(), task.title.clone(), task.due_date);
+            if self.view != ViewMode::Calendar {
+                if let Some(due) = due {
+                    self.calendar_date = due.date_naive();
+                }
+                self.set_main_view(ViewMode::Calendar);
+            }
+            self.reschedule_task_id = Some(id);
+            self.set_message(format!("Moving '{}': pick a day, Enter to drop", title));
+        }
+    }
+
+    /// Drop the picked-up task on the calendar cursor day
+    pub fn finish_reschedule(&mut self) {
+        if let Some(id) = self.reschedule_task_id.take() {
+            let date = self.calendar_date;
+            self.reschedule_task(&id, date);
+        }
+    }
+
+    /// Cancel a pending reschedule
+    pub fn cancel_reschedule(&mut self) {
+        if self.reschedule_task_id.take().is_some() {
+            self.set_message("Reschedule cancelled".to_string());
+        }
+    }
+
+    /// Title of the task being rescheduled (if any)
+    pub fn reschedule_task_title(&self) -> Option<String> {
+        let id = self.reschedule_task_id.as_ref()?;
+        self.tasks
+            .iter()
+            .find(|t| &t.id == id)
+            .map(|t| t.title.clone())
+    }
+
+    /// Shift the current task's due date by a number of days (undated tasks start from today)
+    pub fn shift_current_due_date(&mut self, days: i64) {
+        if let Some(task) = self.current_task() {
+            let base = task
+                .due_date
+                .map(|d| d.date_naive())
+                .unwrap_or_else(|| Utc::now().date_naive());
+            let id = task.id.clone();
+            self.reschedule_task(&id, base + Duration::days(days));
+        }
+    }
+
+    /// Set a task's due date to the given day
+    pub fn reschedule_task(&mut self, id: &str, date: NaiveDate) {
+        if let Ok(Some(mut task)) = self.store.get(id) {
+            task.due_date = Some(date.and_hms_opt(12, 0, 0).unwrap().and_utc());
+            if self.store.update(&task).is_ok() {
+                self.refresh_tasks();
+                if self.view == ViewMode::Calendar {
+                    self.calendar_set_date(date);
+                }
+                self.set_message(format!("Due {}", date.format("%a %b %-d")));
+            }
+        }
+    }
+
+    /// Sprints starting or ending on a day, as (sprint, is_start) pairs
+    pub fn sprint_markers_on(&self, date: NaiveDate) -> Vec<(&Sprint, bool)> {
+        let project = self
+            .tabs
+            .get(self.active_tab)
+            .filter(|_| self.active_tab > 0)
+            .map(|t| t.name.as_str())
+            .or(self.project_filter.as_deref());
+
+        let mut markers = Vec::new();
+        for sprint in &self.sprints {
+            if sprint.status == SprintStatus::Archived {
+                continue;
+            }
+            if let Some(project) = project {
+                if sprint.project != project {
+                    continue;
+                }
+            }
+            if sprint.start_date.map(|d| d.date_naive()) == Some(date) {
+                markers.push((sprint, true));
+            }
+            if sprint.end_date.map(|d| d.date_naive()) == Some(date) {
+                markers.push((sprint, false));
+            }
+        }
+        markers
+    }
+}
+
+/// Render the main TUI
+pub fn render(app: &mut App, frame: &mut Frame) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Tab bar
+            Constraint::Length(1), // Title bar
+            Constraint::Min(1),    // Content
+            Constraint::Length(1), // Status bar
+        ])
+        .split(frame.size());
+
+    render_tab_bar(app, frame, chunks[0]);
+    render_title_bar(app, frame, chunks[1]);
+    render_content(app, frame, chunks[2]);
+    render_status_bar(app, frame, chunks[3]);
+
+    // Render overlays (order matters - later overlays appear on top)
+    if app.is_detail_view() {
+        render_detail_view(app, frame);
+    }
+
+    if app.show_filter_builder {
+        render_filter_builder(app, frame);
+    } else if app.show_sort_selector {
+        render_sort_selector(app, frame);
     } else if app.show_project_selector {
         render_project_selector(app, frame);
     } else if app.show_sprint_selector {
         render_sprint_selector(app, frame);
+        if let Some(plan) = &app.sprint_plan {
+            render_sprint_plan(app, plan, frame);
+        }
     } else if app.show_move_to_sprint {
         render_move_to_sprint(app, frame);
     }
 
     if app.show_help {
-        render_help_overlay(frame);
+        render_help_overlay(app, frame);
     }
 
     if app.is_input_mode() {
@@ -1716,10 +2463,10 @@ fn render_tab_bar(app: &App, frame: &mut Frame, area: Rect) {
 
     let tabs = Tabs::new(tab_titles)
         .select(app.active_tab)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(app.theme.fg_muted))
         .highlight_style(
             Style::default()
-                .fg(Color::Yellow)
+                .fg(app.theme.warning)
                 .add_modifier(Modifier::BOLD),
         )
         .divider(Span::raw(" | "));
@@ -1775,12 +2522,12 @@ fn render_title_bar(app: &App, frame: &mut Frame, area: Rect) {
     };
 
     let title_line = Line::from(vec![
-        Span::styled(left, Style::default().fg(Color::White)),
+        Span::styled(left, Style::default().fg(app.theme.fg)),
         Span::raw(padding),
-        Span::styled(right, Style::default().fg(Color::DarkGray)),
+        Span::styled(right, Style::default().fg(app.theme.fg_muted)),
     ]);
 
-    let block = Block::default().style(Style::default().bg(Color::Rgb(40, 40, 50)));
+    let block = Block::default().style(Style::default().bg(app.theme.bg_title));
 
     let paragraph = Paragraph::new(title_line).block(block);
 
@@ -1789,6 +2536,18 @@ fn render_title_bar(app: &App, frame: &mut Frame, area: Rect) {
 
 /// Render main content
 fn render_content(app: &mut App, frame: &mut Frame, area: Rect) {
+    match app.main_view() {
+        ViewMode::Agenda => {
+            render_agenda(app, frame, area);
+            return;
+        }
+        ViewMode::Calendar => {
+            render_calendar(app, frame, area);
+            return;
+        }
+        _ => {}
+    }
+
     if app.filtered_tasks.is_empty() {
         let message = if !app.search_query.is_empty() {
             format!(
@@ -1800,12 +2559,12 @@ fn render_content(app: &mut App, frame: &mut Frame, area: Rect) {
         };
 
         let paragraph = Paragraph::new(message)
-            .style(Style::default().fg(Color::Gray))
+            .style(Style::default().fg(app.theme.fg_secondary))
             .block(
                 Block::default()
                     .title("Tasks")
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::DarkGray)),
+                    .border_style(Style::default().fg(app.theme.fg_muted)),
             )
             .alignment(ratatui::layout::Alignment::Center);
 
@@ -1820,7 +2579,13 @@ fn render_content(app: &mut App, frame: &mut Frame, area: Rect) {
         .enumerate()
         .map(|(_, task)| {
             let is_multi_selected = app.multi_select_mode && app.is_task_selected(&task.id);
-            ListItem::new(render_task_row(task, is_multi_selected, list_width))
+            ListItem::new(render_task_row(
+                task,
+                is_multi_selected,
+                &app.search_terms,
+                list_width,
+                &app.theme,
+            ))
         })
         .collect();
 
@@ -1841,15 +2606,15 @@ fn render_content(app: &mut App, frame: &mut Frame, area: Rect) {
                 .title(title)
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(if app.sprint_view_mode {
-                    Color::Magenta
+                    app.theme.accent_alt
                 } else {
-                    Color::DarkGray
+                    app.theme.fg_muted
                 })),
         )
         .highlight_style(
             Style::default()
-                .bg(Color::Blue)
-                .fg(Color::White)
+                .bg(app.theme.selection)
+                .fg(app.theme.fg)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol("▶ ");
@@ -1861,8 +2626,254 @@ fn render_content(app: &mut App, frame: &mut Frame, area: Rect) {
     frame.render_stateful_widget(list, area, &mut list_state);
 }
 
+/// Render agenda view (open tasks grouped by due date)
+fn render_agenda(app: &App, frame: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .title(" 📆 Agenda ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+
+    if app.filtered_tasks.is_empty() {
+        let paragraph = Paragraph::new("Nothing on the agenda. Press 'a' to return to the list.")
+            .style(Style::default().fg(app.theme.fg_secondary))
+            .block(block)
+            .alignment(ratatui::layout::Alignment::Center);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let today = Utc::now().date_naive();
+    let list_width = area.width.saturating_sub(4);
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut selected_row = 0;
+    let mut current_bucket = None;
+
+    for (i, task) in app.filtered_tasks.iter().enumerate() {
+        let bucket = AgendaBucket::for_task(task, today);
+        if current_bucket != Some(bucket) {
+            let count = app
+                .filtered_tasks
+                .iter()
+                .filter(|t| AgendaBucket::for_task(t, today) == bucket)
+                .count();
+            let header_style = if bucket == AgendaBucket::Overdue {
+                Style::default()
+                    .fg(app.theme.error)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+                    .fg(app.theme.warning)
+                    .add_modifier(Modifier::BOLD)
+            };
+            items.push(ListItem::new(Line::from(Span::styled(
+                format!("{} ({})", bucket.label(), count),
+                header_style,
+            ))));
+            current_bucket = Some(bucket);
+        }
+        if i == app.selected {
+            selected_row = items.len();
+        }
+        let is_multi_selected = app.multi_select_mode && app.is_task_selected(&task.id);
+        items.push(ListItem::new(render_task_row(
+            task,
+            is_multi_selected,
+            &app.search_terms,
+            list_width,
+            &app.theme,
+        )));
+    }
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection)
+                .fg(app.theme.fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(selected_row));
+
+    frame.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// Render month calendar with the cursor day's tasks below
+fn render_calendar(app: &App, frame: &mut Frame, area: Rect) {
+    let markers = app.sprint_markers_on(app.calendar_date);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(10), // Month grid
+            Constraint::Length(if markers.is_empty() { 0 } else { 1 }),
+            Constraint::Min(1), // Day tasks
+        ])
+        .split(area);
+
+    render_month_grid(app, frame, chunks[0]);
+
+    if !markers.is_empty() {
+        let spans: Vec<Span> = markers
+            .iter()
+            .map(|(sprint, is_start)| {
+                let text = if *is_start {
+                    format!(" ▶ {} starts ", sprint.name)
+                } else {
+                    format!(" ◀ {} ends ", sprint.name)
+                };
+                Span::styled(text, Style::default().fg(app.theme.accent_alt))
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(Line::from(spans)), chunks[1]);
+    }
+
+    let title = match app.reschedule_task_title() {
+        Some(title) => format!(
+            " 📅 {} │ moving '{}' ",
+            app.calendar_date.format("%a %b %-d, %Y"),
+            title
+        ),
+        None => format!(" 📅 {} ", app.calendar_date.format("%a %b %-d, %Y")),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if app.reschedule_task_id.is_some() {
+            app.theme.warning
+        } else {
+            app.theme.fg_muted
+        }));
+
+    if app.filtered_tasks.is_empty() {
+        let paragraph = Paragraph::new("Nothing due this day.")
+            .style(Style::default().fg(app.theme.fg_secondary))
+            .block(block)
+            .alignment(ratatui::layout::Alignment::Center);
+        frame.render_widget(paragraph, chunks[2]);
+        return;
+    }
+
+    let list_width = chunks[2].width.saturating_sub(4);
+    let items: Vec<ListItem> = app
+        .filtered_tasks
+        .iter()
+        .map(|task| {
+            let is_multi_selected = app.multi_select_mode && app.is_task_selected(&task.id);
+            ListItem::new(render_task_row(
+                task,
+                is_multi_selected,
+                &app.search_terms,
+                list_width,
+                &app.theme,
+            ))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection)
+                .fg(app.theme.fg)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▶ ");
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.selected));
+
+    frame.render_stateful_widget(list, chunks[2], &mut list_state);
+}
+
+/// Render the month grid around the calendar cursor
+fn render_month_grid(app: &App, frame: &mut Frame, area: Rect) {
+    let cursor = app.calendar_date;
+    let today = Utc::now().date_naive();
+    let first = cursor.with_day(1).unwrap();
+    // Weeks start on Monday
+    let grid_start = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+    let cell_width = (area.width.saturating_sub(2) / 7).clamp(4, 12) as usize;
+
+    let mut lines = vec![Line::from(
+        ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"]
+            .iter()
+            .map(|d| {
+                Span::styled(
+                    format!("{:^width$}", d, width = cell_width),
+                    Style::default().fg(app.theme.fg_muted),
+                )
+            })
+            .collect::<Vec<_>>(),
+    )];
+
+    for week in 0..6 {
+        let mut spans = Vec::new();
+        for weekday in 0..7 {
+            let date = grid_start + Duration::days(week * 7 + weekday);
+            if date.month() != cursor.month() {
+                spans.push(Span::raw(" ".repeat(cell_width)));
+                continue;
+            }
+
+            let markers = app.sprint_markers_on(date);
+            let marker = if markers.iter().any(|(_, start)| *start) {
+                "▶"
+            } else if !markers.is_empty() {
+                "◀"
+            } else {
+                " "
+            };
+            let count = app.calendar_counts.get(&date).copied().unwrap_or(0);
+            let count_text = if count > 0 {
+                format!("·{}", count)
+            } else {
+                String::new()
+            };
+            let text = format!("{}{:>2}{}", marker, date.day(), count_text);
+
+            let mut style = if count > 0 && date < today {
+                Style::default().fg(app.theme.error)
+            } else if count > 0 {
+                Style::default().fg(app.theme.accent)
+            } else {
+                Style::default().fg(app.theme.fg_secondary)
+            };
+            if !markers.is_empty() {
+                style = style.fg(app.theme.accent_alt);
+            }
+            if date == today {
+                style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+            }
+            if date == cursor {
+                style = style.bg(app.theme.selection).fg(app.theme.fg);
+            }
+
+            spans.push(Span::styled(
+                format!("{:<width$}", text, width = cell_width),
+                style,
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let block = Block::default()
+        .title(format!(" {} ", cursor.format("%B %Y")))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent));
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
 /// Render a single task row with enhanced display
-fn render_task_row(task: &Task, is_multi_selected: bool, width: u16) -> Line<'static> {
+fn render_task_row(
+    task: &Task,
+    is_multi_selected: bool,
+    search: &Query,
+    width: u16,
+    theme: &Theme,
+) -> Line<'static> {
     let checkbox = match task.status {
         Status::Done => "[✓]",
         Status::InProgress => "[◐]",
@@ -1875,73 +2886,114 @@ fn render_task_row(task: &Task, is_multi_selected: bool, width: u16) -> Line<'st
         None => String::new(),
     };
 
-    // Format due date if present
-    let due_info = format_due_date(task);
+    // Format due date if present (snoozed tasks show their wake-up date instead)
+    let due_info = if task.is_waiting() {
+        format_wait_until(task)
+    } else {
+        format_due_date(task)
+    };
 
     // Format age/completion info
     let age_info = format_task_age(task);
 
+    // Agent holding a lease on the task
+    let claim_info = match task.claimed_by() {
+        Some(owner) => format!(" 🔒{}", owner),
+        None => String::new(),
+    };
+
     // Calculate available width for title
-    // Base: select(2) + checkbox(3) + space + priority(2) + space + project + due + age + padding
-    let fixed_width = 2 + 3 + 1 + 2 + 1 + project.len() + due_info.len() + age_info.len() + 4;
+    // Base: select(2) + checkbox(3) + space + priority(2) + space + project + due + claim + age + padding
+    let fixed_width =
+        2 + 3 + 1 + 2 + 1 + project.len() + due_info.len() + claim_info.len() + age_info.len() + 4;
     let available_width = (width as usize).saturating_sub(fixed_width);
 
     // Truncate title if needed
-    let title = if task.title.len() > available_width && available_width > 3 {
-        format!("{}...", &task.title[..available_width - 3])
+    let (title, visible_len) = if task.title.len() > available_width && available_width > 3 {
+        (
+            format!("{}...", &task.title[..available_width - 3]),
+            available_width - 3,
+        )
     } else {
-        task.title.clone()
+        (task.title.clone(), task.title.len())
     };
 
     let line_style = if task.is_done() {
         Style::default()
-            .fg(Color::DarkGray)
+            .fg(theme.fg_muted)
             .add_modifier(Modifier::CROSSED_OUT)
     } else {
         Style::default()
     };
 
+    // Search matches in the title
+    let match_style = line_style.fg(theme.warning).add_modifier(Modifier::BOLD);
+    let mut title_spans = Vec::new();
+    let mut pos = 0;
+    for range in search.highlights(&task.title) {
+        let end = range.end.min(visible_len);
+        if range.start >= end {
+            break;
+        }
+        title_spans.push(Span::styled(
+            title[pos..range.start].to_string(),
+            line_style,
+        ));
+        title_spans.push(Span::styled(
+            title[range.start..end].to_string(),
+            match_style,
+        ));
+        pos = end;
+    }
+    title_spans.push(Span::styled(title[pos..].to_string(), line_style));
+
     // Multi-select indicator
     let select_indicator = if is_multi_selected { "● " } else { "  " };
 
     // Due date color based on urgency
     let due_style = if task.is_done() {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme.fg_muted)
     } else if let Some(due) = &task.due_date {
         let now = chrono::Utc::now();
         if *due < now {
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD) // Overdue
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD) // Overdue
         } else if *due < now + chrono::Duration::days(1) {
-            Style::default().fg(Color::Yellow) // Due today/tomorrow
+            Style::default().fg(theme.warning) // Due today/tomorrow
         } else if *due < now + chrono::Duration::days(7) {
-            Style::default().fg(Color::Cyan) // Due this week
+            Style::default().fg(theme.accent) // Due this week
         } else {
-            Style::default().fg(Color::DarkGray)
+            Style::default().fg(theme.fg_muted)
         }
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme.fg_muted)
     };
 
-    Line::from(vec![
+    let mut spans = vec![
         Span::styled(
             select_indicator,
             if is_multi_selected {
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(theme.accent)
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
             },
         ),
-        Span::styled(checkbox, Style::default().fg(Color::Green)),
+        Span::styled(checkbox, Style::default().fg(theme.success)),
         Span::raw(" "),
         Span::raw(priority),
         Span::raw(" "),
-        Span::styled(project, Style::default().fg(Color::Magenta)),
-        Span::styled(title, line_style),
+        Span::styled(project, Style::default().fg(theme.accent_alt)),
+    ];
+    spans.extend(title_spans);
+    spans.extend([
         Span::styled(due_info, due_style),
-        Span::styled(age_info, Style::default().fg(Color::DarkGray)),
-    ])
+        Span::styled(claim_info, Style::default().fg(theme.warning)),
+        Span::styled(age_info, Style::default().fg(theme.fg_muted)),
+    ]);
+    Line::from(spans)
 }
 
 /// Format due date for display
@@ -1967,6 +3019,23 @@ fn format_due_date(task: &Task) -> String {
     }
 }
 
+/// Format wait-until date for snoozed tasks
+fn format_wait_until(task: &Task) -> String {
+    match &task.wait_until {
+        Some(until) => {
+            let days_until = (*until - Utc::now()).num_days();
+            if days_until == 0 {
+                format!(" 💤{}", until.format("%H:%M"))
+            } else if days_until < 7 {
+                format!(" 💤{}d", days_until)
+            } else {
+                format!(" 💤{}", until.format("%m/%d"))
+            }
+        }
+        None => String::new(),
+    }
+}
+
 /// Format task age or completion time
 fn format_task_age(task: &Task) -> String {
     let now = chrono::Utc::now();
@@ -2025,6 +3094,12 @@ fn render_status_bar(app: &App, frame: &mut Frame, area: Rect) {
         _ => "",
     };
 
+    let waiting_info = if app.show_waiting {
+        " │ 💤 shown"
+    } else {
+        ""
+    };
+
     let sort_info = match app.sort_by {
         SortBy::Priority => " │ ↓pri",
         SortBy::Date => " │ ↓date",
@@ -2032,17 +3107,69 @@ fn render_status_bar(app: &App, frame: &mut Frame, area: Rect) {
     };
 
     // Build right side (keybindings hint)
+    let key = |action| app.keymap.hint(action);
     let keys = if app.multi_select_mode {
-        "Space:sel │ x:done │ d:del │ v:exit"
+        format!(
+            "{}:sel │ {}:range │ {}/{}/{}/{}/{}:edit │ {}/{}/{} │ {}:undo │ {}:exit",
+            key(Action::ToggleSelect),
+            key(Action::SelectRange),
+            key(Action::BulkPriority),
+            key(Action::BulkTags),
+            key(Action::BulkProject),
+            key(Action::BulkSprint),
+            key(Action::BulkDue),
+            key(Action::BulkStart),
+            key(Action::BulkComplete),
+            key(Action::BulkDelete),
+            key(Action::Undo),
+            key(Action::MultiSelect)
+        )
+    } else if app.reschedule_task_id.is_some() {
+        format!(
+            "{}/{}:day │ Enter:drop │ Esc:cancel",
+            key(Action::DayPrev),
+            key(Action::DayNext)
+        )
+    } else if app.view == ViewMode::Calendar {
+        format!(
+            "{}/{}:day │ {}{}:month │ {}/{}:task │ {}:move │ {}:list",
+            key(Action::DayPrev),
+            key(Action::DayNext),
+            key(Action::MonthPrev),
+            key(Action::MonthNext),
+            key(Action::DayTaskNext),
+            key(Action::DayTaskPrev),
+            key(Action::Reschedule),
+            key(Action::Calendar)
+        )
+    } else if app.view == ViewMode::Agenda {
+        format!(
+            "{}/{}:due ±1d │ {}:move │ {}:list │ {}:help",
+            key(Action::ShiftDueBack),
+            key(Action::ShiftDueForward),
+            key(Action::Reschedule),
+            key(Action::Agenda),
+            key(Action::Help)
+        )
     } else {
-        "?:help │ n:new │ x:done │ /:find │ q:quit"
+        format!(
+            "{}:help │ {}:new │ {}:done │ {}:find │ {}:quit",
+            key(Action::Help),
+            key(Action::NewTask),
+            key(Action::ToggleComplete),
+            key(Action::Search),
+            key(Action::Quit)
+        )
     };
 
     // Calculate spacing
     let left_content = if !message.is_empty() {
         format!(" {}", message)
     } else {
-        format!(" {}{}{}{}", task_info, mode_info, status_info, sort_info)
+        format!(
+            " {}{}{}{}{}",
+            task_info, mode_info, status_info, waiting_info, sort_info
+        )
     };
 
     let right_content = format!("{} ", keys);
@@ -2057,85 +3184,48 @@ fn render_status_bar(app: &App, frame: &mut Frame, area: Rect) {
         Span::styled(
             left_content,
             if !message.is_empty() {
-                Style::default().fg(Color::Yellow)
+                Style::default().fg(app.theme.warning)
             } else {
-                Style::default().fg(Color::Gray)
+                Style::default().fg(app.theme.fg_secondary)
             },
         ),
         Span::raw(padding),
-        Span::styled(right_content, Style::default().fg(Color::DarkGray)),
+        Span::styled(right_content, Style::default().fg(app.theme.fg_muted)),
     ]);
 
-    let block = Block::default().style(Style::default().bg(Color::Rgb(30, 30, 30)));
+    let block = Block::default().style(Style::default().bg(app.theme.bg_status));
 
     let paragraph = Paragraph::new(status_line).block(block);
 
     frame.render_widget(paragraph, area);
 }
 
-/// Render help overlay
-fn render_help_overlay(frame: &mut Frame) {
+/// Render help overlay (generated from the active keymap)
+fn render_help_overlay(app: &App, frame: &mut Frame) {
     let area = frame.size();
-    let popup_width = std::cmp::min(55, area.width.saturating_sub(4));
-    let popup_height = 46;
+    let popup_width = std::cmp::min(64, area.width.saturating_sub(4));
+    let popup_height = std::cmp::min(62, area.height);
     let popup_x = (area.width - popup_width) / 2;
     let popup_y = (area.height - popup_height) / 2;
     let rect = Rect::new(popup_x, popup_y, popup_width, popup_height);
 
     frame.render_widget(Clear, rect);
 
-    let help_text = r#" VulcanOS Todo - Keybindings
-
-  Navigation:
-    j/k, ↑/↓   Next/Prev task
-    g/G        First/Last task
-    Tab        Next project tab
-    Shift+Tab  Previous project tab
-    0-9        Jump to tab by number
-    
-  Task Actions:
-    Enter      Open detail view
-    n          New task
-    e          Edit task title (quick)
-    x/Space    Toggle complete
-    d          Delete (with confirm)
-    p          Cycle priority
-    m          Move task to sprint
-    P          Project selector
-
-  Sprint Management:
-    S          Toggle sprint view mode
-    Shift+S    Open sprint selector
-    J/K        Reorder task in sprint (Ctrl+↑/↓)
-    In sprint selector:
-      n/e/d    New/Edit/Delete sprint
-      s/c      Start/Complete sprint
-      g        Edit sprint goal
-
-  Filtering:
-    /          Search tasks
-    s          Cycle status (all/pending/done)
-    o          Cycle sort order
-    c          Clear all filters
-
-  Multi-select:
-    v          Toggle multi-select mode
-    V          Select all visible
-    Space      Toggle selection (in mode)
-    x/d        Complete/Delete selected
-
-  Other:
-    r          Sync from disk
-    ?          Toggle help | q  Quit
-"#;
+    let help_text = app.keymap.help_text();
 
     let block = Block::default()
-        .title(" Help ")
+        .title(format!(
+            " Help ({} scroll) ",
+            [Action::MoveDown, Action::MoveUp]
+                .map(|a| app.keymap.keys(a))
+                .join(" ")
+        ))
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::Black).fg(Color::White));
+        .style(Style::default().bg(app.theme.bg).fg(app.theme.fg));
 
     let paragraph = Paragraph::new(help_text)
-        .style(Style::default().fg(Color::Gray))
+        .style(Style::default().fg(app.theme.fg_secondary))
+        .scroll((app.help_scroll, 0))
         .block(block);
 
     frame.render_widget(paragraph, rect);
@@ -2158,7 +3248,26 @@ fn render_input_dialog(app: &App, frame: &mut Frame) {
         Some(InputMode::EditDescription) => (" Description ", "Enter description:"),
         Some(InputMode::EditTags) => (" Tags ", "Enter tags (comma-separated):"),
         Some(InputMode::Search) => (" Search ", "Search tasks:"),
+        Some(InputMode::Snooze) => (
+            " Snooze ",
+            "Hide until (3d, 4h, 2w, tomorrow, YYYY-MM-DD; empty clears):",
+        ),
         Some(InputMode::ConfirmDelete) => (" Confirm Delete ", "Delete this task? (y/n):"),
+        Some(InputMode::BulkPriority) => (
+            " Set Priority ",
+            "Priority for selected (urgent, high, medium, low, none):",
+        ),
+        Some(InputMode::BulkTags) => (
+            " Tags ",
+            "Tags for selected (a b replaces, +a appends, -a removes):",
+        ),
+        Some(InputMode::BulkProject) => {
+            (" Move to Project ", "Project for selected (empty clears):")
+        }
+        Some(InputMode::BulkDue) => (
+            " Due Date ",
+            "Due for selected (3d, tomorrow, YYYY-MM-DD; empty clears):",
+        ),
         Some(InputMode::ConfirmBulkDelete) => {
             let count = app.selected_count();
             // We need to return owned strings for this case
@@ -2177,8 +3286,8 @@ fn render_input_dialog(app: &App, frame: &mut Frame) {
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(app.theme.accent))
+        .style(Style::default().bg(app.theme.bg));
 
     frame.render_widget(block.clone(), rect);
 
@@ -2194,7 +3303,7 @@ fn render_input_dialog(app: &App, frame: &mut Frame) {
         .split(inner);
 
     // Prompt text
-    let prompt_widget = Paragraph::new(prompt).style(Style::default().fg(Color::Gray));
+    let prompt_widget = Paragraph::new(prompt).style(Style::default().fg(app.theme.fg_secondary));
     frame.render_widget(prompt_widget, chunks[0]);
 
     // Input field with cursor and horizontal scrolling
@@ -2228,7 +3337,7 @@ fn render_input_dialog(app: &App, frame: &mut Frame) {
 
     // Left scroll indicator
     if has_left_overflow {
-        spans.push(Span::styled("<", Style::default().fg(Color::Yellow)));
+        spans.push(Span::styled("<", Style::default().fg(app.theme.warning)));
     }
 
     // Text before cursor (within visible area)
@@ -2236,7 +3345,7 @@ fn render_input_dialog(app: &App, frame: &mut Frame) {
     if before_len > 0 {
         spans.push(Span::styled(
             &visible_text[..before_len],
-            Style::default().fg(Color::White),
+            Style::default().fg(app.theme.fg),
         ));
     }
 
@@ -2248,7 +3357,7 @@ fn render_input_dialog(app: &App, frame: &mut Frame) {
     };
     spans.push(Span::styled(
         cursor_char,
-        Style::default().bg(Color::White).fg(Color::Black),
+        Style::default().bg(app.theme.fg).fg(app.theme.bg),
     ));
 
     // Text after cursor (within visible area)
@@ -2256,17 +3365,17 @@ fn render_input_dialog(app: &App, frame: &mut Frame) {
     if after_start < visible_text.len() {
         spans.push(Span::styled(
             &visible_text[after_start..],
-            Style::default().fg(Color::White),
+            Style::default().fg(app.theme.fg),
         ));
     }
 
     // Right scroll indicator
     if has_right_overflow {
-        spans.push(Span::styled(">", Style::default().fg(Color::Yellow)));
+        spans.push(Span::styled(">", Style::default().fg(app.theme.warning)));
     }
 
     let input_line = Line::from(spans);
-    let input_widget = Paragraph::new(input_line).style(Style::default().bg(Color::DarkGray));
+    let input_widget = Paragraph::new(input_line).style(Style::default().bg(app.theme.fg_muted));
     frame.render_widget(input_widget, chunks[1]);
 }
 
@@ -2285,8 +3394,8 @@ fn render_bulk_delete_dialog(app: &App, frame: &mut Frame, count: usize) {
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(app.theme.error))
+        .style(Style::default().bg(app.theme.bg));
 
     frame.render_widget(block.clone(), rect);
 
@@ -2302,7 +3411,7 @@ fn render_bulk_delete_dialog(app: &App, frame: &mut Frame, count: usize) {
         .split(inner);
 
     let prompt = format!("Delete {} selected tasks? (y/n):", count);
-    let prompt_widget = Paragraph::new(prompt).style(Style::default().fg(Color::Yellow));
+    let prompt_widget = Paragraph::new(prompt).style(Style::default().fg(app.theme.warning));
     frame.render_widget(prompt_widget, chunks[0]);
 }
 
@@ -2335,11 +3444,11 @@ fn render_detail_view(app: &App, frame: &mut Frame) {
         .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(if app.detail_edit_mode {
-            Color::Yellow
+            app.theme.warning
         } else {
-            Color::Cyan
+            app.theme.accent
         }))
-        .style(Style::default().bg(Color::Black));
+        .style(Style::default().bg(app.theme.bg));
 
     frame.render_widget(block.clone(), rect);
 
@@ -2359,17 +3468,17 @@ fn render_detail_view(app: &App, frame: &mut Frame) {
                         editing: bool|
      -> Line<'static> {
         let is_selected = field == current;
-        let label_style = Style::default().fg(Color::DarkGray);
+        let label_style = Style::default().fg(app.theme.fg_muted);
         let value_style = if is_selected && editing {
             Style::default()
-                .fg(Color::Yellow)
+                .fg(app.theme.warning)
                 .add_modifier(Modifier::BOLD)
         } else if is_selected {
             Style::default()
-                .fg(Color::Cyan)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(app.theme.fg)
         };
         let indicator = if is_selected { "▶ " } else { "  " };
 
@@ -2377,7 +3486,7 @@ fn render_detail_view(app: &App, frame: &mut Frame) {
             Span::styled(
                 indicator,
                 if is_selected {
-                    Style::default().fg(Color::Cyan)
+                    Style::default().fg(app.theme.accent)
                 } else {
                     Style::default()
                 },
@@ -2490,41 +3599,73 @@ fn render_detail_view(app: &App, frame: &mut Frame) {
     // Metadata section
     lines.push(Line::from(vec![Span::styled(
         "─".repeat(inner.width as usize - 2),
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(app.theme.fg_muted),
     )]));
 
     // Status
     let (status_str, status_color) = match task.status {
-        Status::Done => ("✓ Done", Color::Green),
-        Status::InProgress => ("◐ In Progress", Color::Cyan),
-        Status::Pending => ("○ Pending", Color::Yellow),
-        Status::Archived => ("▣ Archived", Color::DarkGray),
+        Status::Done => ("✓ Done", app.theme.success),
+        Status::InProgress => ("◐ In Progress", app.theme.accent),
+        Status::Pending => ("○ Pending", app.theme.warning),
+        Status::Archived => ("▣ Archived", app.theme.fg_muted),
     };
     lines.push(Line::from(vec![
-        Span::styled("  Status:     ", Style::default().fg(Color::DarkGray)),
+        Span::styled("  Status:     ", Style::default().fg(app.theme.fg_muted)),
         Span::styled(status_str, Style::default().fg(status_color)),
     ]));
 
+    // Claim (agent lease)
+    if let Some(claim) = task.active_claim(Utc::now()) {
+        let claim_str = format!(
+            "🔒 {} until {}",
+            claim.owner,
+            claim.expires_at.format("%H:%M")
+        );
+        lines.push(Line::from(vec![
+            Span::styled("  Claimed:    ", Style::default().fg(app.theme.fg_muted)),
+            Span::styled(claim_str, Style::default().fg(app.theme.warning)),
+        ]));
+    }
+
     // Created
     let created = task.created_at.format("%Y-%m-%d %H:%M").to_string();
     lines.push(Line::from(vec![
-        Span::styled("  Created:    ", Style::default().fg(Color::DarkGray)),
-        Span::styled(created, Style::default().fg(Color::DarkGray)),
+        Span::styled("  Created:    ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled(created, Style::default().fg(app.theme.fg_muted)),
     ]));
 
+    // Linked vault notes
+    for (i, id) in task.context_notes.iter().enumerate() {
+        let label = if i == 0 {
+            "  Notes:      "
+        } else {
+            "              "
+        };
+        let (title, found) = app.context_note_label(id);
+        let style = if found {
+            Style::default().fg(app.theme.accent_alt)
+        } else {
+            Style::default().fg(app.theme.error)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(label, Style::default().fg(app.theme.fg_muted)),
+            Span::styled(format!("📝 {}", title), style),
+        ]));
+    }
+
     // Completed (if done)
     if let Some(completed) = &task.completed_at {
         let completed_str = completed.format("%Y-%m-%d %H:%M").to_string();
         lines.push(Line::from(vec![
-            Span::styled("  Completed:  ", Style::default().fg(Color::DarkGray)),
-            Span::styled(completed_str, Style::default().fg(Color::DarkGray)),
+            Span::styled("  Completed:  ", Style::default().fg(app.theme.fg_muted)),
+            Span::styled(completed_str, Style::default().fg(app.theme.fg_muted)),
         ]));
     }
 
     // ID (for debugging/reference)
     lines.push(Line::from(vec![
-        Span::styled("  ID:         ", Style::default().fg(Color::DarkGray)),
-        Span::styled(&task.id[..8], Style::default().fg(Color::DarkGray)),
+        Span::styled("  ID:         ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled(&task.id[..8], Style::default().fg(app.theme.fg_muted)),
     ]));
 
     let paragraph = Paragraph::new(lines);
@@ -2545,8 +3686,8 @@ fn render_filter_builder(app: &App, frame: &mut Frame) {
     let block = Block::default()
         .title(" 🔍 Filter Builder ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(app.theme.accent))
+        .style(Style::default().bg(app.theme.bg));
 
     frame.render_widget(block.clone(), rect);
 
@@ -2560,7 +3701,7 @@ fn render_filter_builder(app: &App, frame: &mut Frame) {
         let indicator = if is_selected { "▶ " } else { "  " };
         let style = if is_selected {
             Style::default()
-                .fg(Color::Cyan)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD)
         } else {
             Style::default()
@@ -2569,12 +3710,15 @@ fn render_filter_builder(app: &App, frame: &mut Frame) {
             Span::styled(
                 indicator,
                 if is_selected {
-                    Style::default().fg(Color::Cyan)
+                    Style::default().fg(app.theme.accent)
                 } else {
                     Style::default()
                 },
             ),
-            Span::styled(format!("{}:", label), Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{}:", label),
+                Style::default().fg(app.theme.fg_muted),
+            ),
             Span::styled(value.to_string(), style),
         ])
     };
@@ -2645,39 +3789,42 @@ fn render_filter_builder(app: &App, frame: &mut Frame) {
 
     lines.push(Line::from(vec![Span::styled(
         "─".repeat(inner.width as usize - 2),
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(app.theme.fg_muted),
     )]));
 
     // Instructions
     lines.push(Line::from(vec![
-        Span::styled("j/k  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("Select field  ", Style::default().fg(Color::Gray)),
-        Span::styled("Enter  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("Apply  ", Style::default().fg(Color::Gray)),
-        Span::styled("Esc  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("Cancel", Style::default().fg(Color::Gray)),
+        Span::styled("j/k  ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled(
+            "Select field  ",
+            Style::default().fg(app.theme.fg_secondary),
+        ),
+        Span::styled("Enter  ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("Apply  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("Esc  ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("Cancel", Style::default().fg(app.theme.fg_secondary)),
     ]));
 
     // Field-specific instructions
     if app.filter_builder_field == FilterField::Status {
         lines.push(Line::from(vec![Span::styled(
             "Press 'j/k' to change status",
-            Style::default().fg(Color::Gray),
+            Style::default().fg(app.theme.fg_secondary),
         )]));
     } else if app.filter_builder_field == FilterField::Priority {
         lines.push(Line::from(vec![Span::styled(
             "Press 'j/k' to change priority",
-            Style::default().fg(Color::Gray),
+            Style::default().fg(app.theme.fg_secondary),
         )]));
     } else if app.filter_builder_field == FilterField::Project {
         lines.push(Line::from(vec![Span::styled(
             "Press 'j/k' to cycle projects",
-            Style::default().fg(Color::Gray),
+            Style::default().fg(app.theme.fg_secondary),
         )]));
     } else if app.filter_builder_field == FilterField::Search {
         lines.push(Line::from(vec![Span::styled(
             "Type to search, 'u' to clear",
-            Style::default().fg(Color::Gray),
+            Style::default().fg(app.theme.fg_secondary),
         )]));
     }
 
@@ -2699,8 +3846,8 @@ fn render_sort_selector(app: &App, frame: &mut Frame) {
     let block = Block::default()
         .title(" ⚙️ Sort By ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(app.theme.accent))
+        .style(Style::default().bg(app.theme.bg));
 
     frame.render_widget(block.clone(), rect);
 
@@ -2716,7 +3863,7 @@ fn render_sort_selector(app: &App, frame: &mut Frame) {
             Span::styled(
                 indicator,
                 if is_selected {
-                    Style::default().fg(Color::Cyan)
+                    Style::default().fg(app.theme.accent)
                 } else {
                     Style::default()
                 },
@@ -2729,7 +3876,7 @@ fn render_sort_selector(app: &App, frame: &mut Frame) {
                 },
                 if is_selected {
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(app.theme.accent)
                         .add_modifier(Modifier::BOLD)
                 } else {
                     Style::default()
@@ -2757,15 +3904,15 @@ fn render_sort_selector(app: &App, frame: &mut Frame) {
     lines.push(Line::from(""));
     lines.push(Line::from(vec![Span::styled(
         "─".repeat(inner.width as usize - 2),
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(app.theme.fg_muted),
     )]));
     lines.push(Line::from(vec![
-        Span::styled("j/k  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("Select  ", Style::default().fg(Color::Gray)),
-        Span::styled("Enter  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("Apply  ", Style::default().fg(Color::Gray)),
-        Span::styled("Esc  ", Style::default().fg(Color::DarkGray)),
-        Span::styled("Cancel", Style::default().fg(Color::Gray)),
+        Span::styled("j/k  ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("Select  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("Enter  ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("Apply  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("Esc  ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("Cancel", Style::default().fg(app.theme.fg_secondary)),
     ]));
 
     let paragraph = Paragraph::new(lines);
@@ -2788,8 +3935,8 @@ fn render_project_selector(app: &App, frame: &mut Frame) {
     let block = Block::default()
         .title(" Select Project ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(app.theme.accent))
+        .style(Style::default().bg(app.theme.bg));
 
     frame.render_widget(block.clone(), rect);
 
@@ -2800,7 +3947,7 @@ fn render_project_selector(app: &App, frame: &mut Frame) {
 
     // Build project list
     let mut items: Vec<Line> = vec![Line::from(vec![
-        ratatui::text::Span::styled("0", Style::default().fg(Color::Yellow)),
+        ratatui::text::Span::styled("0", Style::default().fg(app.theme.warning)),
         ratatui::text::Span::raw(". "),
         ratatui::text::Span::styled(
             if app.project_filter.is_none() {
@@ -2810,10 +3957,10 @@ fn render_project_selector(app: &App, frame: &mut Frame) {
             },
             if app.project_filter.is_none() {
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(app.theme.success)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(app.theme.fg)
             },
         ),
     ])];
@@ -2827,16 +3974,19 @@ fn render_project_selector(app: &App, frame: &mut Frame) {
         };
 
         items.push(Line::from(vec![
-            ratatui::text::Span::styled(format!("{}", i + 1), Style::default().fg(Color::Yellow)),
+            ratatui::text::Span::styled(
+                format!("{}", i + 1),
+                Style::default().fg(app.theme.warning),
+            ),
             ratatui::text::Span::raw(". "),
             ratatui::text::Span::styled(
                 display,
                 if is_selected {
                     Style::default()
-                        .fg(Color::Green)
+                        .fg(app.theme.success)
                         .add_modifier(Modifier::BOLD)
                 } else {
-                    Style::default().fg(Color::White)
+                    Style::default().fg(app.theme.fg)
                 },
             ),
         ]));
@@ -2845,7 +3995,7 @@ fn render_project_selector(app: &App, frame: &mut Frame) {
     items.push(Line::from(""));
     items.push(Line::from(vec![ratatui::text::Span::styled(
         "Press 0-9 to select, Esc to cancel",
-        Style::default().fg(Color::Gray),
+        Style::default().fg(app.theme.fg_secondary),
     )]));
 
     let paragraph = Paragraph::new(items);
@@ -2867,8 +4017,8 @@ fn render_sprint_delete_dialog(app: &App, frame: &mut Frame) {
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(app.theme.error))
+        .style(Style::default().bg(app.theme.bg));
 
     frame.render_widget(block.clone(), rect);
 
@@ -2894,11 +4044,11 @@ fn render_sprint_delete_dialog(app: &App, frame: &mut Frame) {
         .unwrap_or_else(|| "Unknown".to_string());
 
     let warning = format!("Delete sprint '{}'?", sprint_name);
-    let warning_widget = Paragraph::new(warning).style(Style::default().fg(Color::Yellow));
+    let warning_widget = Paragraph::new(warning).style(Style::default().fg(app.theme.warning));
     frame.render_widget(warning_widget, chunks[0]);
 
     let note = "Tasks will be moved to backlog. (y/n):";
-    let note_widget = Paragraph::new(note).style(Style::default().fg(Color::Gray));
+    let note_widget = Paragraph::new(note).style(Style::default().fg(app.theme.fg_secondary));
     frame.render_widget(note_widget, chunks[1]);
 }
 
@@ -2920,8 +4070,8 @@ fn render_move_to_sprint(app: &App, frame: &mut Frame) {
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(app.theme.accent))
+        .style(Style::default().bg(app.theme.bg));
 
     frame.render_widget(block.clone(), rect);
 
@@ -2936,10 +4086,10 @@ fn render_move_to_sprint(app: &App, frame: &mut Frame) {
     // Remove from sprint option (index 0)
     let remove_style = if app.move_to_sprint_index == 0 {
         Style::default()
-            .fg(Color::Cyan)
+            .fg(app.theme.accent)
             .add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(app.theme.fg)
     };
     let remove_indicator = if app.move_to_sprint_index == 0 {
         "▶ "
@@ -2951,7 +4101,7 @@ fn render_move_to_sprint(app: &App, frame: &mut Frame) {
         Span::styled("📋 Backlog", remove_style),
         Span::styled(
             " (remove from sprint)",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(app.theme.fg_muted),
         ),
     ])));
 
@@ -2960,10 +4110,10 @@ fn render_move_to_sprint(app: &App, frame: &mut Frame) {
         let is_selected = app.move_to_sprint_index == i + 1;
         let style = if is_selected {
             Style::default()
-                .fg(Color::Cyan)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(app.theme.fg)
         };
         let indicator = if is_selected { "▶ " } else { "  " };
 
@@ -2996,12 +4146,12 @@ fn render_move_to_sprint(app: &App, frame: &mut Frame) {
     // Instructions
     items.push(ListItem::new(Line::from("")));
     items.push(ListItem::new(Line::from(vec![
-        Span::styled("j/k ", Style::default().fg(Color::DarkGray)),
-        Span::styled("navigate  ", Style::default().fg(Color::Gray)),
-        Span::styled("Enter ", Style::default().fg(Color::DarkGray)),
-        Span::styled("select  ", Style::default().fg(Color::Gray)),
-        Span::styled("Esc ", Style::default().fg(Color::DarkGray)),
-        Span::styled("cancel", Style::default().fg(Color::Gray)),
+        Span::styled("j/k ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("navigate  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("Enter ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("select  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("Esc ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("cancel", Style::default().fg(app.theme.fg_secondary)),
     ])));
 
     let list = List::new(items);
@@ -3031,8 +4181,8 @@ fn render_sprint_selector(app: &App, frame: &mut Frame) {
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Magenta))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(app.theme.accent_alt))
+        .style(Style::default().bg(app.theme.bg));
 
     frame.render_widget(block.clone(), rect);
 
@@ -3047,10 +4197,10 @@ fn render_sprint_selector(app: &App, frame: &mut Frame) {
     // Backlog option (index 0)
     let backlog_style = if app.sprint_selector_index == 0 {
         Style::default()
-            .fg(Color::Cyan)
+            .fg(app.theme.accent)
             .add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(app.theme.fg)
     };
     let backlog_indicator = if app.sprint_selector_index == 0 {
         "▶ "
@@ -3062,7 +4212,7 @@ fn render_sprint_selector(app: &App, frame: &mut Frame) {
         Span::styled("📋 Backlog", backlog_style),
         Span::styled(
             " (tasks not in sprint)",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(app.theme.fg_muted),
         ),
     ])));
 
@@ -3071,10 +4221,10 @@ fn render_sprint_selector(app: &App, frame: &mut Frame) {
         let is_selected = app.sprint_selector_index == i + 1;
         let style = if is_selected {
             Style::default()
-                .fg(Color::Cyan)
+                .fg(app.theme.accent)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(app.theme.fg)
         };
         let indicator = if is_selected { "▶ " } else { "  " };
 
@@ -3090,7 +4240,7 @@ fn render_sprint_selector(app: &App, frame: &mut Frame) {
             Span::styled(format!("{} {}", status_emoji, sprint.name), style),
             Span::styled(
                 format!(" [{}]", sprint.status.to_string()),
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(app.theme.fg_muted),
             ),
         ])));
     }
@@ -3098,26 +4248,268 @@ fn render_sprint_selector(app: &App, frame: &mut Frame) {
     // Add instruction footer
     items.push(ListItem::new(Line::from("")));
     items.push(ListItem::new(Line::from(vec![
-        Span::styled("j/k ", Style::default().fg(Color::DarkGray)),
-        Span::styled("nav  ", Style::default().fg(Color::Gray)),
-        Span::styled("Enter ", Style::default().fg(Color::DarkGray)),
-        Span::styled("select  ", Style::default().fg(Color::Gray)),
-        Span::styled("n ", Style::default().fg(Color::DarkGray)),
-        Span::styled("new  ", Style::default().fg(Color::Gray)),
-        Span::styled("e ", Style::default().fg(Color::DarkGray)),
-        Span::styled("edit", Style::default().fg(Color::Gray)),
+        Span::styled("j/k ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("nav  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("Enter ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("select  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("n ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("new  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("e ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("edit", Style::default().fg(app.theme.fg_secondary)),
     ])));
     items.push(ListItem::new(Line::from(vec![
-        Span::styled("s ", Style::default().fg(Color::DarkGray)),
-        Span::styled("start  ", Style::default().fg(Color::Gray)),
-        Span::styled("c ", Style::default().fg(Color::DarkGray)),
-        Span::styled("complete  ", Style::default().fg(Color::Gray)),
-        Span::styled("d ", Style::default().fg(Color::DarkGray)),
-        Span::styled("delete  ", Style::default().fg(Color::Gray)),
-        Span::styled("Esc ", Style::default().fg(Color::DarkGray)),
-        Span::styled("close", Style::default().fg(Color::Gray)),
+        Span::styled("s ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("start  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("c ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("complete  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("p ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("plan  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("d ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("delete  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("Esc ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("close", Style::default().fg(app.theme.fg_secondary)),
     ])));
 
     let list = List::new(items);
     frame.render_widget(list, inner);
 }
+
+/// Render sprint planner overlay (proposed backlog tasks for the budget)
+fn render_sprint_plan(app: &App, plan: &SprintPlan, frame: &mut Frame) {
+    let area = frame.size();
+
+    // +8 for: border(2) + budget line + empty line(2) + skipped line + footer
+    let popup_height = std::cmp::min(
+        (plan.proposed.len() + 8) as u16,
+        area.height.saturating_sub(4),
+    );
+    let popup_width = std::cmp::min(60, area.width.saturating_sub(4));
+    let popup_x = (area.width - popup_width) / 2;
+    let popup_y = (area.height - popup_height) / 2;
+    let rect = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+    frame.render_widget(Clear, rect);
+
+    let block = Block::default()
+        .title(format!(" Plan Sprint: {} ", plan.sprint.name))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.accent_alt))
+        .style(Style::default().bg(app.theme.bg));
+
+    frame.render_widget(block.clone(), rect);
+
+    let inner = rect.inner(&ratatui::layout::Margin {
+        horizontal: 1,
+        vertical: 1,
+    });
+
+    let mut items: Vec<ListItem> = Vec::new();
+    let total = plan.committed + plan.planned;
+    let budget_style = if total > plan.budget {
+        Style::default().fg(app.theme.warning)
+    } else {
+        Style::default().fg(app.theme.success)
+    };
+    items.push(ListItem::new(Line::from(vec![
+        Span::styled("Budget ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled(
+            format!("{}/{} pts", total, plan.budget),
+            budget_style.add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(
+                "  ({} committed + {} planned)",
+                plan.committed, plan.planned
+            ),
+            Style::default().fg(app.theme.fg_muted),
+        ),
+    ])));
+    items.push(ListItem::new(Line::from("")));
+
+    if plan.proposed.is_empty() {
+        items.push(ListItem::new(Line::from(Span::styled(
+            "  Nothing fits the remaining budget",
+            Style::default().fg(app.theme.fg_muted),
+        ))));
+    }
+    for task in &plan.proposed {
+        items.push(ListItem::new(Line::from(vec![
+            Span::styled(
+                format!("  {:>2} pts ", task.points()),
+                Style::default().fg(app.theme.accent),
+            ),
+            Span::raw(format!("{} ", task.priority.emoji())),
+            Span::styled(task.title.clone(), Style::default().fg(app.theme.fg)),
+        ])));
+    }
+
+    if plan.skipped > 0 {
+        items.push(ListItem::new(Line::from(Span::styled(
+            format!("  {} larger task(s) didn't fit", plan.skipped),
+            Style::default().fg(app.theme.fg_muted),
+        ))));
+    }
+
+    items.push(ListItem::new(Line::from("")));
+    items.push(ListItem::new(Line::from(vec![
+        Span::styled("+/- ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("budget  ", Style::default().fg(app.theme.fg_secondary)),
+        Span::styled("Enter ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled(
+            "add to sprint  ",
+            Style::default().fg(app.theme.fg_secondary),
+        ),
+        Span::styled("Esc ", Style::default().fg(app.theme.fg_muted)),
+        Span::styled("cancel", Style::default().fg(app.theme.fg_secondary)),
+    ])));
+
+    let list = List::new(items);
+    frame.render_widget(list, inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    fn due_task(title: &str, due: Option<NaiveDate>) -> Task {
+        let mut task = Task::new(title.to_string());
+        task.due_date = due.map(|d| d.and_hms_opt(12, 0, 0).unwrap().and_utc());
+        task
+    }
+
+    #[test]
+    fn test_agenda_buckets() {
+        // A Wednesday
+        let today = NaiveDate::from_ymd_opt(2025, 6, 11).unwrap();
+        let bucket = |days: i64| {
+            AgendaBucket::for_task(&due_task("t", Some(today + Duration::days(days))), today)
+        };
+
+        assert_eq!(bucket(-30), AgendaBucket::Overdue);
+        assert_eq!(bucket(-1), AgendaBucket::Overdue);
+        assert_eq!(bucket(0), AgendaBucket::Today);
+        assert_eq!(bucket(1), AgendaBucket::Tomorrow);
+        assert_eq!(bucket(2), AgendaBucket::ThisWeek);
+        assert_eq!(bucket(6), AgendaBucket::ThisWeek);
+        assert_eq!(bucket(7), AgendaBucket::Later);
+        assert_eq!(bucket(365), AgendaBucket::Later);
+        assert_eq!(
+            AgendaBucket::for_task(&due_task("t", None), today),
+            AgendaBucket::NoDate
+        );
+
+        // Any time of day counts for the whole day
+        let mut late = due_task("t", None);
+        late.due_date = Some(today.and_hms_opt(23, 59, 59).unwrap().and_utc());
+        assert_eq!(AgendaBucket::for_task(&late, today), AgendaBucket::Today);
+        late.due_date = Some(today.and_hms_opt(0, 0, 0).unwrap().and_utc());
+        assert_eq!(AgendaBucket::for_task(&late, today), AgendaBucket::Today);
+    }
+
+    #[test]
+    fn test_agenda_view_order() {
+        let today = Utc::now().date_naive();
+        let store = Arc::new(MemoryStore::new());
+        store.add(&due_task("Someday", None)).unwrap();
+        store
+            .add(&due_task("Next month", Some(today + Duration::days(30))))
+            .unwrap();
+        store.add(&due_task("Today", Some(today))).unwrap();
+        store
+            .add(&due_task("Late", Some(today - Duration::days(2))))
+            .unwrap();
+        let mut done = due_task("Finished", Some(today));
+        done.complete();
+        store.add(&done).unwrap();
+
+        let mut app = App::new(store);
+        app.set_main_view(ViewMode::Agenda);
+        let titles: Vec<&str> = app
+            .filtered_tasks
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Late", "Today", "Next month", "Someday"]);
+
+        // The calendar shows the open tasks due on the cursor day
+        app.set_main_view(ViewMode::Calendar);
+        app.calendar_today();
+        let titles: Vec<&str> = app
+            .filtered_tasks
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Today"]);
+        assert_eq!(app.calendar_counts.get(&today), Some(&1));
+    }
+
+    fn list_app(titles: &[&str]) -> App {
+        let store = Arc::new(MemoryStore::new());
+        for title in titles {
+            store.add(&Task::new(title.to_string())).unwrap();
+        }
+        let mut app = App::new(store);
+        app.toggle_multi_select_mode();
+        app
+    }
+
+    fn selected_titles(app: &App) -> Vec<String> {
+        let mut titles: Vec<String> = app
+            .selected_task_list()
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    #[test]
+    fn test_select_range() {
+        let mut app = list_app(&["A", "B", "C", "D", "E"]);
+        let title = |app: &App, i: usize| app.filtered_tasks[i].title.clone();
+
+        app.selected = 1;
+        app.toggle_current_selection();
+        app.selected = 3;
+        app.select_range();
+        let mut expected: Vec<String> = (1..=3).map(|i| title(&app, i)).collect();
+        expected.sort();
+        assert_eq!(selected_titles(&app), expected);
+
+        // Backwards from the new anchor
+        app.selected = 0;
+        app.select_range();
+        assert_eq!(app.selected_count(), 4);
+        assert!(!app.is_task_selected(&app.filtered_tasks[4].id.clone()));
+    }
+
+    #[test]
+    fn test_bulk_edit_and_undo() {
+        let mut app = list_app(&["A", "B", "C"]);
+
+        // Nothing selected: nothing written, nothing to undo
+        app.bulk_set_priority("high");
+        assert!(app.undo_stack.is_empty());
+
+        app.select_all_visible();
+        app.bulk_set_priority("high");
+        assert_eq!(app.undo_stack.len(), 1);
+        let stored = app.store.get_all().unwrap();
+        assert!(stored.iter().all(|t| t.priority == Priority::High));
+
+        // B is edited after the bulk edit, so undo leaves it alone
+        let mut b = stored.iter().find(|t| t.title == "B").unwrap().clone();
+        b.title = "B renamed".to_string();
+        app.store.update(&b).unwrap();
+
+        app.undo();
+        assert!(app.undo_stack.is_empty());
+        for task in app.store.get_all().unwrap() {
+            match task.title.as_str() {
+                "B renamed" => assert_eq!(task.priority, Priority::High),
+                _ => assert_eq!(task.priority, Priority::None),
+            }
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! TUI configuration (`~/.config/vulcan-todo/tui.toml`)
+//!
+//! ```toml
+//! preset = "vim"      # or "emacs"
+//! theme = "auto"      # active VulcanOS theme; or "terminal", a theme id, or a .sh path
+//!
+//! [keys]
+//! quit = ["q", "C-c"]
+//! toggle_complete = "x"
+//! sprint_view = []    # unbind
+//! ```
+
+use crate::ui::keymap::{Action, KeyBinding, Keymap, KeymapPreset};
+use crate::ui::theme::Theme;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// One key or a list of keys for an action
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key.as_str()],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Parsed `tui.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TuiConfig {
+    /// Keymap preset (`vim` or `emacs`)
+    pub preset: Option<String>,
+    /// Color scheme source (see [`Theme::resolve`])
+    pub theme: Option<String>,
+    /// Per-action key overrides
+    pub keys: BTreeMap<String, KeyList>,
+}
+
+impl TuiConfig {
+    /// Default config location
+    pub fn default_path() -> Result<PathBuf> {
+        let config_dir = dirs::config_dir()
+            .context("Could not determine config directory")?
+            .join("vulcan-todo");
+
+        Ok(config_dir.join("tui.toml"))
+    }
+
+    /// Load the config from the default location (missing file = defaults)
+    pub fn load() -> Result<Self> {
+        Self::load_from(&Self::default_path()?)
+    }
+
+    /// Load the config from a file (missing file = defaults)
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid config: {}", path.display()))
+    }
+
+    /// Build the keymap. Unknown presets, actions and keys are reported as
+    /// warnings and skipped so a typo doesn't lock the user out of the TUI.
+    pub fn keymap(&self) -> (Keymap, Vec<String>) {
+        let mut warnings = Vec::new();
+
+        let preset = match self.preset.as_deref() {
+            None => KeymapPreset::default(),
+            Some(name) => KeymapPreset::from_name(name).unwrap_or_else(|| {
+                warnings.push(format!("unknown preset '{}'", name));
+                KeymapPreset::default()
+            }),
+        };
+
+        let mut keymap = Keymap::preset(preset);
+        for (name, keys) in &self.keys {
+            let Some(action) = Action::from_name(name) else {
+                warnings.push(format!("unknown action '{}'", name));
+                continue;
+            };
+
+            let mut bindings = Vec::new();
+            for key in keys.keys() {
+                match KeyBinding::parse(key) {
+                    Some(binding) => bindings.push(binding),
+                    None => warnings.push(format!("invalid key '{}' for {}", key, name)),
+                }
+            }
+            keymap.bind(action, bindings);
+        }
+
+        (keymap, warnings)
+    }
+
+    /// Resolve the color scheme (falls back to terminal colors on error)
+    pub fn theme(&self) -> (Theme, Option<String>) {
+        match Theme::resolve(self.theme.as_deref().unwrap_or("auto")) {
+            Ok(theme) => (theme, None),
+            Err(e) => (Theme::default(), Some(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn test_config_overrides() {
+        let config: TuiConfig = toml::from_str(
+            r#"
+preset = "emacs"
+theme = "terminal"
+
+[keys]
+quit = ["q", "C-c"]
+new_task = "a"
+bogus = "x"
+help = ["Hyper+?"]
+"#,
+        )
+        .unwrap();
+
+        let (keymap, warnings) = config.keymap();
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        let a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        let ctrl_n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL);
+
+        assert!(keymap.matches(Action::Quit, &ctrl_c));
+        assert!(keymap.matches(Action::NewTask, &a));
+        // Preset bindings are kept for actions that aren't overridden
+        assert!(keymap.matches(Action::MoveDown, &ctrl_n));
+        assert_eq!(warnings.len(), 2);
+
+        assert_eq!(config.theme(), (Theme::default(), None));
+    }
+}
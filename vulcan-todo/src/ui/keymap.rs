@@ -0,0 +1,711 @@
+//! Remappable TUI keybindings
+//!
+//! Every list/calendar command is an [`Action`] bound to one or more keys.
+//! The `vim` and `emacs` presets provide the defaults; `[keys]` in
+//! `tui.toml` overrides individual actions. Text input, selector popups
+//! and the 0-9 tab shortcuts keep their fixed keys.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Remappable TUI commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    // Navigation
+    MoveDown,
+    MoveUp,
+    First,
+    Last,
+    NextTab,
+    PrevTab,
+
+    // Task actions
+    OpenDetail,
+    NewTask,
+    EditTitle,
+    ToggleComplete,
+    Delete,
+    DeleteNow,
+    CyclePriority,
+    MoveToSprint,
+    ProjectSelector,
+    Snooze,
+
+    // Sprints
+    SprintView,
+    SprintSelector,
+    ReorderUp,
+    ReorderDown,
+
+    // Agenda & calendar
+    Agenda,
+    Calendar,
+    DayPrev,
+    DayNext,
+    WeekPrev,
+    WeekNext,
+    MonthPrev,
+    MonthNext,
+    Today,
+    DayTaskNext,
+    DayTaskPrev,
+    ShiftDueBack,
+    ShiftDueForward,
+    Reschedule,
+
+    // Filtering
+    Search,
+    CycleStatus,
+    ToggleWaiting,
+    Sort,
+    FilterBuilder,
+    ClearFilters,
+
+    // Multi-select
+    MultiSelect,
+    SelectAll,
+    ToggleSelect,
+    SelectRange,
+    BulkPriority,
+    BulkTags,
+    BulkProject,
+    BulkSprint,
+    BulkDue,
+    BulkStart,
+    BulkComplete,
+    BulkDelete,
+    Undo,
+
+    // Other
+    Refresh,
+    Help,
+    Quit,
+}
+
+impl Action {
+    /// All actions, in help overlay order
+    pub const ALL: [Action; 56] = [
+        Action::MoveDown,
+        Action::MoveUp,
+        Action::First,
+        Action::Last,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::OpenDetail,
+        Action::NewTask,
+        Action::EditTitle,
+        Action::ToggleComplete,
+        Action::Delete,
+        Action::DeleteNow,
+        Action::CyclePriority,
+        Action::MoveToSprint,
+        Action::ProjectSelector,
+        Action::Snooze,
+        Action::SprintView,
+        Action::SprintSelector,
+        Action::ReorderUp,
+        Action::ReorderDown,
+        Action::Agenda,
+        Action::Calendar,
+        Action::DayPrev,
+        Action::DayNext,
+        Action::WeekPrev,
+        Action::WeekNext,
+        Action::MonthPrev,
+        Action::MonthNext,
+        Action::Today,
+        Action::DayTaskNext,
+        Action::DayTaskPrev,
+        Action::ShiftDueBack,
+        Action::ShiftDueForward,
+        Action::Reschedule,
+        Action::Search,
+        Action::CycleStatus,
+        Action::ToggleWaiting,
+        Action::Sort,
+        Action::FilterBuilder,
+        Action::ClearFilters,
+        Action::MultiSelect,
+        Action::SelectAll,
+        Action::ToggleSelect,
+        Action::SelectRange,
+        Action::BulkPriority,
+        Action::BulkTags,
+        Action::BulkProject,
+        Action::BulkSprint,
+        Action::BulkDue,
+        Action::BulkStart,
+        Action::BulkComplete,
+        Action::BulkDelete,
+        Action::Undo,
+        Action::Refresh,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// Name used in the `[keys]` config table
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveDown => "move_down",
+            Action::MoveUp => "move_up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::OpenDetail => "open_detail",
+            Action::NewTask => "new_task",
+            Action::EditTitle => "edit_title",
+            Action::ToggleComplete => "toggle_complete",
+            Action::Delete => "delete",
+            Action::DeleteNow => "delete_now",
+            Action::CyclePriority => "cycle_priority",
+            Action::MoveToSprint => "move_to_sprint",
+            Action::ProjectSelector => "project_selector",
+            Action::Snooze => "snooze",
+            Action::SprintView => "sprint_view",
+            Action::SprintSelector => "sprint_selector",
+            Action::ReorderUp => "reorder_up",
+            Action::ReorderDown => "reorder_down",
+            Action::Agenda => "agenda",
+            Action::Calendar => "calendar",
+            Action::DayPrev => "day_prev",
+            Action::DayNext => "day_next",
+            Action::WeekPrev => "week_prev",
+            Action::WeekNext => "week_next",
+            Action::MonthPrev => "month_prev",
+            Action::MonthNext => "month_next",
+            Action::Today => "today",
+            Action::DayTaskNext => "day_task_next",
+            Action::DayTaskPrev => "day_task_prev",
+            Action::ShiftDueBack => "shift_due_back",
+            Action::ShiftDueForward => "shift_due_forward",
+            Action::Reschedule => "reschedule",
+            Action::Search => "search",
+            Action::CycleStatus => "cycle_status",
+            Action::ToggleWaiting => "toggle_waiting",
+            Action::Sort => "sort",
+            Action::FilterBuilder => "filter_builder",
+            Action::ClearFilters => "clear_filters",
+            Action::MultiSelect => "multi_select",
+            Action::SelectAll => "select_all",
+            Action::ToggleSelect => "toggle_select",
+            Action::SelectRange => "select_range",
+            Action::BulkPriority => "bulk_priority",
+            Action::BulkTags => "bulk_tags",
+            Action::BulkProject => "bulk_project",
+            Action::BulkSprint => "bulk_sprint",
+            Action::BulkDue => "bulk_due",
+            Action::BulkStart => "bulk_start",
+            Action::BulkComplete => "bulk_complete",
+            Action::BulkDelete => "bulk_delete",
+            Action::Undo => "undo",
+            Action::Refresh => "refresh",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// Look up an action by its config name
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|a| a.name() == name)
+    }
+
+    /// Short description for the help overlay
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::MoveDown => "Next task",
+            Action::MoveUp => "Previous task",
+            Action::First => "First task",
+            Action::Last => "Last task",
+            Action::NextTab => "Next project tab",
+            Action::PrevTab => "Previous project tab",
+            Action::OpenDetail => "Open detail view",
+            Action::NewTask => "New task",
+            Action::EditTitle => "Edit task title (quick)",
+            Action::ToggleComplete => "Toggle complete",
+            Action::Delete => "Delete (with confirm)",
+            Action::DeleteNow => "Delete immediately",
+            Action::CyclePriority => "Cycle priority",
+            Action::MoveToSprint => "Move task to sprint",
+            Action::ProjectSelector => "Project selector",
+            Action::Snooze => "Snooze task (3d, 4h, tomorrow…)",
+            Action::SprintView => "Toggle sprint view mode",
+            Action::SprintSelector => "Open sprint selector",
+            Action::ReorderUp => "Move task up in sprint",
+            Action::ReorderDown => "Move task down in sprint",
+            Action::Agenda => "Toggle agenda view",
+            Action::Calendar => "Toggle month calendar",
+            Action::DayPrev => "Previous day (calendar)",
+            Action::DayNext => "Next day (calendar)",
+            Action::WeekPrev => "Previous week (calendar)",
+            Action::WeekNext => "Next week (calendar)",
+            Action::MonthPrev => "Previous month (calendar)",
+            Action::MonthNext => "Next month (calendar)",
+            Action::Today => "Jump to today (calendar)",
+            Action::DayTaskNext => "Next task on day",
+            Action::DayTaskPrev => "Previous task on day",
+            Action::ShiftDueBack => "Move due date back a day",
+            Action::ShiftDueForward => "Move due date forward a day",
+            Action::Reschedule => "Pick up task, Enter drops on day",
+            Action::Search => "Search tasks",
+            Action::CycleStatus => "Cycle status (all/pending/done)",
+            Action::ToggleWaiting => "Show/hide snoozed tasks",
+            Action::Sort => "Sort order",
+            Action::FilterBuilder => "Filter builder",
+            Action::ClearFilters => "Clear all filters",
+            Action::MultiSelect => "Toggle multi-select mode",
+            Action::SelectAll => "Select all visible",
+            Action::ToggleSelect => "Toggle selection (multi-select)",
+            Action::SelectRange => "Select range from last toggled",
+            Action::BulkPriority => "Set priority of selected",
+            Action::BulkTags => "Edit tags of selected",
+            Action::BulkProject => "Move selected to project",
+            Action::BulkSprint => "Move selected to sprint",
+            Action::BulkDue => "Set due date of selected",
+            Action::BulkStart => "Start selected",
+            Action::BulkComplete => "Complete selected",
+            Action::BulkDelete => "Delete selected",
+            Action::Undo => "Undo last bulk edit",
+            Action::Refresh => "Sync from disk",
+            Action::Help => "Toggle help",
+            Action::Quit => "Quit",
+        }
+    }
+
+    /// Help overlay section
+    pub fn section(&self) -> &'static str {
+        match self {
+            Action::MoveDown
+            | Action::MoveUp
+            | Action::First
+            | Action::Last
+            | Action::NextTab
+            | Action::PrevTab => "Navigation",
+            Action::OpenDetail
+            | Action::NewTask
+            | Action::EditTitle
+            | Action::ToggleComplete
+            | Action::Delete
+            | Action::DeleteNow
+            | Action::CyclePriority
+            | Action::MoveToSprint
+            | Action::ProjectSelector
+            | Action::Snooze => "Task Actions",
+            Action::SprintView
+            | Action::SprintSelector
+            | Action::ReorderUp
+            | Action::ReorderDown => "Sprint Management",
+            Action::Agenda
+            | Action::Calendar
+            | Action::DayPrev
+            | Action::DayNext
+            | Action::WeekPrev
+            | Action::WeekNext
+            | Action::MonthPrev
+            | Action::MonthNext
+            | Action::Today
+            | Action::DayTaskNext
+            | Action::DayTaskPrev
+            | Action::ShiftDueBack
+            | Action::ShiftDueForward
+            | Action::Reschedule => "Agenda & Calendar",
+            Action::Search
+            | Action::CycleStatus
+            | Action::ToggleWaiting
+            | Action::Sort
+            | Action::FilterBuilder
+            | Action::ClearFilters => "Filtering",
+            Action::MultiSelect
+            | Action::SelectAll
+            | Action::ToggleSelect
+            | Action::SelectRange
+            | Action::BulkPriority
+            | Action::BulkTags
+            | Action::BulkProject
+            | Action::BulkSprint
+            | Action::BulkDue
+            | Action::BulkStart
+            | Action::BulkComplete
+            | Action::BulkDelete
+            | Action::Undo => "Multi-select",
+            Action::Refresh | Action::Help | Action::Quit => "Other",
+        }
+    }
+}
+
+/// A single key with Ctrl/Alt modifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self {
+            code,
+            modifiers: modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT),
+        }
+    }
+
+    /// Parse a key description: `j`, `J`, `Space`, `Down`, `C-n`,
+    /// `Ctrl+n`, `M-<`, `Alt+x`, `S-Tab`, `F5`...
+    pub fn parse(input: &str) -> Option<KeyBinding> {
+        let mut rest = input.trim();
+        let mut modifiers = KeyModifiers::NONE;
+        let mut shift = false;
+
+        // Strip modifier prefixes (but keep a bare "-" or "+" as the key)
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let prefix_len = [
+                "ctrl+", "ctrl-", "c-", "alt+", "alt-", "m-", "shift+", "shift-", "s-",
+            ]
+            .iter()
+            .find(|p| lower.starts_with(*p) && rest.len() > p.len())
+            .map(|p| p.len());
+
+            let Some(len) = prefix_len else { break };
+            match lower.as_bytes()[0] {
+                b'c' => modifiers |= KeyModifiers::CONTROL,
+                b'a' | b'm' => modifiers |= KeyModifiers::ALT,
+                _ => shift = true,
+            }
+            rest = &rest[len..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" | "ret" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" if shift => KeyCode::BackTab,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" | "bs" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                f if f.starts_with('f') => KeyCode::F(f[1..].parse().ok()?),
+                _ => return None,
+            },
+        };
+
+        Some(KeyBinding::new(code, modifiers))
+    }
+
+    /// Check if a key event triggers this binding
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        let code = match key.code {
+            // Shift+Tab arrives as Tab+SHIFT on some terminals
+            KeyCode::Tab if key.modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        code == self.code && modifiers == self.modifiers
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Built-in keymap presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeymapPreset {
+    #[default]
+    Vim,
+    Emacs,
+}
+
+impl KeymapPreset {
+    pub fn from_name(name: &str) -> Option<KeymapPreset> {
+        match name.to_lowercase().as_str() {
+            "vim" | "vi" => Some(KeymapPreset::Vim),
+            "emacs" => Some(KeymapPreset::Emacs),
+            _ => None,
+        }
+    }
+
+    /// Default key descriptions for an action
+    fn keys(&self, action: Action) -> &'static [&'static str] {
+        match (self, action) {
+            // Vim: hjkl navigation, single-letter commands
+            (KeymapPreset::Vim, Action::MoveDown) => &["j", "Down"],
+            (KeymapPreset::Vim, Action::MoveUp) => &["k", "Up"],
+            (KeymapPreset::Vim, Action::First) => &["g", "Home"],
+            (KeymapPreset::Vim, Action::Last) => &["G", "End"],
+            (KeymapPreset::Vim, Action::Search) => &["/"],
+            (KeymapPreset::Vim, Action::ReorderUp) => &["K", "C-Up"],
+            (KeymapPreset::Vim, Action::ReorderDown) => &["J", "C-Down"],
+            (KeymapPreset::Vim, Action::DayPrev) => &["h", "Left"],
+            (KeymapPreset::Vim, Action::DayNext) => &["l", "Right"],
+            (KeymapPreset::Vim, Action::WeekPrev) => &["k", "Up"],
+            (KeymapPreset::Vim, Action::WeekNext) => &["j", "Down"],
+            (KeymapPreset::Vim, Action::DayTaskNext) => &["J"],
+            (KeymapPreset::Vim, Action::DayTaskPrev) => &["K"],
+
+            // Emacs: Ctrl/Alt motion keys, same single-letter commands
+            (KeymapPreset::Emacs, Action::MoveDown) => &["C-n", "Down"],
+            (KeymapPreset::Emacs, Action::MoveUp) => &["C-p", "Up"],
+            (KeymapPreset::Emacs, Action::First) => &["M-<", "Home"],
+            (KeymapPreset::Emacs, Action::Last) => &["M->", "End"],
+            (KeymapPreset::Emacs, Action::Search) => &["C-s", "/"],
+            (KeymapPreset::Emacs, Action::ReorderUp) => &["M-p", "C-Up"],
+            (KeymapPreset::Emacs, Action::ReorderDown) => &["M-n", "C-Down"],
+            (KeymapPreset::Emacs, Action::DayPrev) => &["C-b", "Left"],
+            (KeymapPreset::Emacs, Action::DayNext) => &["C-f", "Right"],
+            (KeymapPreset::Emacs, Action::WeekPrev) => &["C-p", "Up"],
+            (KeymapPreset::Emacs, Action::WeekNext) => &["C-n", "Down"],
+            (KeymapPreset::Emacs, Action::DayTaskNext) => &["M-n"],
+            (KeymapPreset::Emacs, Action::DayTaskPrev) => &["M-p"],
+
+            // Shared
+            (_, Action::NextTab) => &["Tab"],
+            (_, Action::PrevTab) => &["BackTab"],
+            (_, Action::OpenDetail) => &["Enter"],
+            (_, Action::NewTask) => &["n"],
+            (_, Action::EditTitle) => &["e"],
+            (_, Action::ToggleComplete) => &["x", "Space"],
+            (_, Action::Delete) => &["d"],
+            (_, Action::DeleteNow) => &["D"],
+            (_, Action::CyclePriority) => &["p"],
+            (_, Action::MoveToSprint) => &["m"],
+            (_, Action::ProjectSelector) => &["P"],
+            (_, Action::Snooze) => &["z"],
+            (_, Action::SprintView) => &["b"],
+            (_, Action::SprintSelector) => &["S"],
+            (_, Action::Agenda) => &["a"],
+            (_, Action::Calendar) => &["C"],
+            (_, Action::MonthPrev) => &["["],
+            (_, Action::MonthNext) => &["]"],
+            (_, Action::Today) => &["t"],
+            (_, Action::ShiftDueBack) => &["<"],
+            (_, Action::ShiftDueForward) => &[">"],
+            (_, Action::Reschedule) => &["R"],
+            (_, Action::CycleStatus) => &["s"],
+            (_, Action::ToggleWaiting) => &["W"],
+            (_, Action::Sort) => &["o"],
+            (_, Action::FilterBuilder) => &["O"],
+            (_, Action::ClearFilters) => &["c"],
+            (_, Action::MultiSelect) => &["v"],
+            (_, Action::SelectAll) => &["V"],
+            (_, Action::ToggleSelect) => &["Space"],
+            (_, Action::SelectRange) => &["r"],
+            (_, Action::BulkPriority) => &["p"],
+            (_, Action::BulkTags) => &["t"],
+            (_, Action::BulkProject) => &["P"],
+            (_, Action::BulkSprint) => &["m"],
+            (_, Action::BulkDue) => &["D"],
+            (_, Action::BulkStart) => &["s"],
+            (_, Action::BulkComplete) => &["x"],
+            (_, Action::BulkDelete) => &["d"],
+            (_, Action::Undo) => &["u"],
+            (_, Action::Refresh) => &["r"],
+            (_, Action::Help) => &["?"],
+            (_, Action::Quit) => &["q"],
+        }
+    }
+}
+
+/// Active key bindings for every action
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyBinding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(KeymapPreset::default())
+    }
+}
+
+impl Keymap {
+    /// Keymap with a preset's default bindings
+    pub fn preset(preset: KeymapPreset) -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|&action| {
+                let keys = preset
+                    .keys(action)
+                    .iter()
+                    .filter_map(|k| KeyBinding::parse(k))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+
+    /// Replace the bindings of an action (an empty list unbinds it)
+    pub fn bind(&mut self, action: Action, keys: Vec<KeyBinding>) {
+        self.bindings.insert(action, keys);
+    }
+
+    /// Check if a key event triggers an action
+    pub fn matches(&self, action: Action, key: &KeyEvent) -> bool {
+        self.bindings
+            .get(&action)
+            .map(|keys| keys.iter().any(|k| k.matches(key)))
+            .unwrap_or(false)
+    }
+
+    /// First action among `candidates` bound to a key event
+    pub fn lookup(&self, key: &KeyEvent, candidates: &[Action]) -> Option<Action> {
+        candidates
+            .iter()
+            .copied()
+            .find(|&action| self.matches(action, key))
+    }
+
+    /// Display string for an action's keys ("j/↓"), used in hints and help
+    pub fn keys(&self, action: Action) -> String {
+        match self.bindings.get(&action) {
+            Some(keys) if !keys.is_empty() => keys
+                .iter()
+                .map(|k| k.to_string())
+                .collect::<Vec<_>>()
+                .join("/"),
+            _ => "-".to_string(),
+        }
+    }
+
+    /// Primary key of an action, for compact status bar hints
+    pub fn hint(&self, action: Action) -> String {
+        self.bindings
+            .get(&action)
+            .and_then(|keys| keys.first())
+            .map(|k| k.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Help overlay text generated from the active bindings
+    pub fn help_text(&self) -> String {
+        let width = Action::ALL
+            .iter()
+            .map(|&a| self.keys(a).chars().count() + 1)
+            .max()
+            .unwrap_or(0)
+            .max(11);
+        let line =
+            |keys: &str, desc: &str| format!("    {:<width$}{}\n", keys, desc, width = width);
+
+        let mut text = String::from(" VulcanOS Todo - Keybindings\n");
+        let mut section = "";
+        for action in Action::ALL {
+            if action.section() != section {
+                section = action.section();
+                text.push_str(&format!("\n  {}:\n", section));
+            }
+            text.push_str(&line(&self.keys(action), action.description()));
+
+            // Fixed keys that aren't remappable
+            match action {
+                Action::PrevTab => text.push_str(&line("0-9", "Jump to tab by number")),
+                Action::ReorderDown => {
+                    text.push_str("    In sprint selector:\n");
+                    text.push_str(&line("  n/e/d", "New/Edit/Delete sprint"));
+                    text.push_str(&line("  s/c", "Start/Complete sprint"));
+                    text.push_str(&line("  g", "Edit sprint goal"));
+                }
+                Action::Quit => text.push_str(&line("Esc", "Back / clear filters")),
+                _ => {}
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_bindings() {
+        assert_eq!(
+            KeyBinding::parse("C-n"),
+            Some(KeyBinding::new(KeyCode::Char('n'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            KeyBinding::parse("Alt+<"),
+            Some(KeyBinding::new(KeyCode::Char('<'), KeyModifiers::ALT))
+        );
+        assert_eq!(
+            KeyBinding::parse("space").map(|k| k.code),
+            Some(KeyCode::Char(' '))
+        );
+        assert_eq!(
+            KeyBinding::parse("S-Tab").map(|k| k.code),
+            Some(KeyCode::BackTab)
+        );
+        assert_eq!(
+            KeyBinding::parse("-").map(|k| k.code),
+            Some(KeyCode::Char('-'))
+        );
+        assert_eq!(KeyBinding::parse("F5").map(|k| k.code), Some(KeyCode::F(5)));
+        assert_eq!(KeyBinding::parse("nonsense"), None);
+    }
+
+    #[test]
+    fn test_presets_and_overrides() {
+        let vim = Keymap::preset(KeymapPreset::Vim);
+        let emacs = Keymap::preset(KeymapPreset::Emacs);
+        let j = key(KeyCode::Char('j'), KeyModifiers::NONE);
+        let ctrl_n = key(KeyCode::Char('n'), KeyModifiers::CONTROL);
+        let shift_j = key(KeyCode::Char('J'), KeyModifiers::SHIFT);
+
+        assert!(vim.matches(Action::MoveDown, &j));
+        assert!(!emacs.matches(Action::MoveDown, &j));
+        assert!(emacs.matches(Action::MoveDown, &ctrl_n));
+        // Ctrl+n is not plain n
+        assert!(!emacs.matches(Action::NewTask, &ctrl_n));
+        // Shift is implied by the uppercase char
+        assert!(vim.matches(Action::ReorderDown, &shift_j));
+
+        let mut custom = vim.clone();
+        custom.bind(Action::MoveDown, vec![KeyBinding::parse("n").unwrap()]);
+        assert!(!custom.matches(Action::MoveDown, &j));
+        assert_eq!(custom.keys(Action::MoveDown), "n");
+        assert_eq!(vim.keys(Action::MoveDown), "j/↓");
+        assert_eq!(
+            custom.lookup(&shift_j, &[Action::DayTaskNext, Action::ReorderDown]),
+            Some(Action::DayTaskNext)
+        );
+    }
+}
@@ -3,6 +3,9 @@
 //! Provides the interactive terminal user interface for task management.
 
 pub mod app;
+pub mod config;
+pub mod keymap;
+pub mod theme;
 pub mod tui;
 
 pub use app::{render, App, InputMode, SortBy, TaskFilter, ViewMode};
@@ -0,0 +1,278 @@
+//! TUI color scheme
+//!
+//! Colors come from a VulcanOS theme file (`~/.config/themes/colors/<id>.sh`,
+//! same format as `dotfiles/themes/colors/*.sh`). By default the theme named
+//! in `~/.config/vulcan/current-theme` is used, so the TUI follows
+//! `vulcan-theme set`. Without a theme, the terminal's ANSI colors are used.
+
+use anyhow::{bail, Context, Result};
+use ratatui::style::Color;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Colors used by the TUI, by role
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Main text (`FG_PRIMARY`)
+    pub fg: Color,
+    /// Secondary text (`FG_SECONDARY`)
+    pub fg_secondary: Color,
+    /// Hints, labels, metadata (`FG_MUTED`)
+    pub fg_muted: Color,
+    /// Popup background (`BG_PRIMARY`)
+    pub bg: Color,
+    /// Title bar background (`BG_SECONDARY`)
+    pub bg_title: Color,
+    /// Status bar background (`BG_SURFACE`)
+    pub bg_status: Color,
+    /// Highlighted row background (`SELECTION`)
+    pub selection: Color,
+    /// Borders, active fields, due-this-week (`ACCENT`)
+    pub accent: Color,
+    /// Projects and sprints (`ACCENT_ALT`)
+    pub accent_alt: Color,
+    /// Done / selected (`GREEN`)
+    pub success: Color,
+    /// Messages, due soon (`YELLOW`)
+    pub warning: Color,
+    /// Overdue, destructive dialogs (`RED`)
+    pub error: Color,
+}
+
+impl Default for Theme {
+    /// Terminal ANSI colors
+    fn default() -> Self {
+        Self {
+            fg: Color::White,
+            fg_secondary: Color::Gray,
+            fg_muted: Color::DarkGray,
+            bg: Color::Black,
+            bg_title: Color::Rgb(40, 40, 50),
+            bg_status: Color::Rgb(30, 30, 30),
+            selection: Color::Blue,
+            accent: Color::Cyan,
+            accent_alt: Color::Magenta,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+        }
+    }
+}
+
+impl Theme {
+    /// Resolve a `theme` config value:
+    /// - `auto`: the active VulcanOS theme, or terminal colors if none is set
+    /// - `terminal`: terminal ANSI colors
+    /// - a path to a theme `.sh` file
+    /// - a theme id (`tokyonight`, `nord`, ...)
+    pub fn resolve(setting: &str) -> Result<Theme> {
+        match setting.trim() {
+            "" | "auto" => match Self::active_path() {
+                Some(path) => Self::from_file(&path),
+                None => Ok(Theme::default()),
+            },
+            "terminal" | "default" | "none" => Ok(Theme::default()),
+            s if s.contains('/') || s.ends_with(".sh") => Self::from_file(&expand_home(s)),
+            id => {
+                let path = themes_dir()
+                    .context("Could not determine home directory")?
+                    .join(format!("{}.sh", id));
+                if !path.exists() {
+                    bail!("Theme not found: {}", path.display());
+                }
+                Self::from_file(&path)
+            }
+        }
+    }
+
+    /// Theme file of the active VulcanOS theme (`~/.config/vulcan/current-theme`)
+    pub fn active_path() -> Option<PathBuf> {
+        let home = dirs::home_dir()?;
+        let id = std::fs::read_to_string(home.join(".config/vulcan/current-theme")).ok()?;
+        let path = themes_dir()?.join(format!("{}.sh", id.trim()));
+        path.exists().then_some(path)
+    }
+
+    /// Load a VulcanOS theme file
+    pub fn from_file(path: &Path) -> Result<Theme> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme: {}", path.display()))?;
+        Ok(Self::from_vars(&parse_theme_vars(&content)))
+    }
+
+    /// Build a theme from theme variables; missing or invalid colors keep
+    /// their terminal default
+    pub fn from_vars(vars: &HashMap<String, String>) -> Theme {
+        let mut theme = Theme::default();
+        let slots: [(&str, &mut Color); 12] = [
+            ("FG_PRIMARY", &mut theme.fg),
+            ("FG_SECONDARY", &mut theme.fg_secondary),
+            ("FG_MUTED", &mut theme.fg_muted),
+            ("BG_PRIMARY", &mut theme.bg),
+            ("BG_SECONDARY", &mut theme.bg_title),
+            ("BG_SURFACE", &mut theme.bg_status),
+            ("SELECTION", &mut theme.selection),
+            ("ACCENT", &mut theme.accent),
+            ("ACCENT_ALT", &mut theme.accent_alt),
+            ("GREEN", &mut theme.success),
+            ("YELLOW", &mut theme.warning),
+            ("RED", &mut theme.error),
+        ];
+        for (name, slot) in slots {
+            if let Some(color) = vars.get(name).and_then(|v| parse_hex_color(v)) {
+                *slot = color;
+            }
+        }
+        theme
+    }
+}
+
+/// VulcanOS theme colors directory (`~/.config/themes/colors`)
+fn themes_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config/themes/colors"))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Parse `export NAME="value"  # comment` lines from a theme file.
+/// `$NAME` / `${NAME}` references to earlier variables are expanded.
+pub fn parse_theme_vars(content: &str) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+
+    for line in content.lines() {
+        let Some(assignment) = line.trim().strip_prefix("export ") else {
+            continue;
+        };
+        let Some((name, rest)) = assignment.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        let rest = rest.trim();
+
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => match rest[1..].find(quote) {
+                Some(end) => &rest[1..1 + end],
+                None => continue,
+            },
+            _ => rest.split_whitespace().next().unwrap_or(""),
+        };
+
+        let value = expand_vars(value, &vars);
+        vars.insert(name.to_string(), value);
+    }
+
+    vars
+}
+
+/// Expand `$NAME` and `${NAME}` using already-parsed variables
+fn expand_vars(value: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let (name, consumed) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => (braced, braced.len() + 1),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], end)
+            }
+        };
+        out.push_str(vars.get(name).map(String::as_str).unwrap_or(""));
+        rest = &after[consumed..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Parse `#rrggbb` (or `#rgb`) into an RGB color
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.trim().strip_prefix('#')?;
+    // Channels are sliced by byte offset below
+    if !hex.is_ascii() {
+        return None;
+    }
+    let hex = match hex.len() {
+        6 => hex.to_string(),
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_theme_file() {
+        let content = r##"#!/bin/bash
+# Example Theme - VulcanOS
+export THEME_NAME="Example"
+export BG_PRIMARY="#1a1b26"     # bg
+export ACCENT='#7aa2f7'
+export BORDER_ACTIVE="${ACCENT}"        # Active window border
+export RED=#f7768e
+export GTK_THEME="Adwaita-dark"
+"##;
+
+        let vars = parse_theme_vars(content);
+        assert_eq!(vars["THEME_NAME"], "Example");
+        assert_eq!(vars["ACCENT"], "#7aa2f7");
+        assert_eq!(vars["BORDER_ACTIVE"], "#7aa2f7");
+        assert_eq!(vars["RED"], "#f7768e");
+
+        let theme = Theme::from_vars(&vars);
+        assert_eq!(theme.bg, Color::Rgb(0x1a, 0x1b, 0x26));
+        assert_eq!(theme.accent, Color::Rgb(0x7a, 0xa2, 0xf7));
+        // Not in the file: terminal default
+        assert_eq!(theme.success, Color::Green);
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(
+            parse_hex_color("#7aa2f7"),
+            Some(Color::Rgb(0x7a, 0xa2, 0xf7))
+        );
+        assert_eq!(
+            parse_hex_color(" #fa0 "),
+            Some(Color::Rgb(0xff, 0xaa, 0x00))
+        );
+        assert_eq!(parse_hex_color("7aa2f7"), None);
+        assert_eq!(parse_hex_color("#7aa2f"), None);
+        assert_eq!(parse_hex_color("#zzzzzz"), None);
+        // Six bytes, but not six hex digits
+        assert_eq!(parse_hex_color("#aéabc"), None);
+        assert_eq!(parse_hex_color("#aé"), None);
+    }
+
+    #[test]
+    fn test_bundled_themes_parse() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../dotfiles/themes/colors");
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let theme = Theme::from_file(&entry.path()).unwrap();
+            assert!(
+                matches!(theme.accent, Color::Rgb(..)),
+                "{} has no ACCENT",
+                entry.path().display()
+            );
+        }
+    }
+}
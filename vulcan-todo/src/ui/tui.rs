@@ -1,6 +1,8 @@
 use crate::models::{Priority, Status};
 use crate::store::{JsonStore, Store};
-use crate::ui::app::{App, FilterField, InputMode, SortBy};
+use crate::ui::app::{App, FilterField, InputMode, SortBy, ViewMode};
+use crate::ui::config::TuiConfig;
+use crate::ui::keymap::Action;
 use anyhow::Result;
 use crossterm::{
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
@@ -24,6 +26,15 @@ pub fn run_tui(store: Arc<dyn Store>) -> Result<()> {
     terminal.clear()?;
 
     let mut app = App::new(store);
+    match TuiConfig::load() {
+        Ok(config) => app.apply_config(&config),
+        Err(e) => app.set_message(format!("⚠️ {:#}", e)),
+    }
+    #[cfg(feature = "vault")]
+    match crate::vault::Vault::open_default() {
+        Ok(vault) => app.vault = vault.map(Arc::new),
+        Err(e) => app.set_message(format!("⚠️ {:#}", e)),
+    }
 
     // Setup file watcher for real-time sync with MCP server
     let watch_path = JsonStore::default_path()?;
@@ -67,6 +78,12 @@ fn run_app(
     let debounce_duration = Duration::from_millis(100);
 
     loop {
+        // Hook stderr would corrupt the screen; show it in the status line
+        let hook_output = app.store.take_hook_output();
+        if !hook_output.is_empty() {
+            app.set_message(format!("🪝 {}", hook_output.join("; ")));
+        }
+
         terminal.draw(|f| {
             crate::ui::app::render(app, f);
         })?;
@@ -103,6 +120,9 @@ fn run_app(
                     if handle_project_selector(app, &key) {
                         continue;
                     }
+                } else if app.sprint_plan.is_some() {
+                    handle_sprint_plan(app, &key);
+                    continue;
                 } else if app.show_sprint_selector {
                     if handle_sprint_selector(app, &key) {
                         continue;
@@ -115,10 +135,14 @@ fn run_app(
 
                 if app.show_help {
                     if key.code == KeyCode::Esc
-                        || key.code == KeyCode::Char('?')
-                        || key.code == KeyCode::Char('q')
+                        || app.keymap.matches(Action::Help, &key)
+                        || app.keymap.matches(Action::Quit, &key)
                     {
                         app.toggle_help();
+                    } else if app.keymap.matches(Action::MoveDown, &key) {
+                        app.scroll_help(1);
+                    } else if app.keymap.matches(Action::MoveUp, &key) {
+                        app.scroll_help(-1);
                     }
                     continue;
                 }
@@ -239,6 +263,81 @@ fn handle_sort_selector(app: &mut App, key: &KeyEvent) -> bool {
     false
 }
 
+/// Actions available in the task list
+const LIST_ACTIONS: &[Action] = &[
+    Action::MoveDown,
+    Action::MoveUp,
+    Action::First,
+    Action::Last,
+    Action::NextTab,
+    Action::PrevTab,
+    Action::OpenDetail,
+    Action::NewTask,
+    Action::EditTitle,
+    Action::ToggleComplete,
+    Action::Delete,
+    Action::DeleteNow,
+    Action::CyclePriority,
+    Action::MoveToSprint,
+    Action::ProjectSelector,
+    Action::Snooze,
+    Action::SprintView,
+    Action::SprintSelector,
+    Action::Agenda,
+    Action::Calendar,
+    Action::Search,
+    Action::CycleStatus,
+    Action::ToggleWaiting,
+    Action::Sort,
+    Action::FilterBuilder,
+    Action::ClearFilters,
+    Action::MultiSelect,
+    Action::SelectAll,
+    Action::Undo,
+    Action::Refresh,
+    Action::Help,
+    Action::Quit,
+];
+
+/// Actions available in the agenda and calendar views (checked before list actions)
+const DATE_VIEW_ACTIONS: &[Action] = &[
+    Action::ShiftDueBack,
+    Action::ShiftDueForward,
+    Action::Reschedule,
+    Action::DayTaskNext,
+    Action::DayTaskPrev,
+    Action::DayPrev,
+    Action::DayNext,
+    Action::WeekPrev,
+    Action::WeekNext,
+    Action::MonthPrev,
+    Action::MonthNext,
+    Action::Today,
+];
+
+/// Actions available in multi-select mode
+const MULTI_SELECT_ACTIONS: &[Action] = &[
+    Action::MoveDown,
+    Action::MoveUp,
+    Action::First,
+    Action::Last,
+    Action::MultiSelect,
+    Action::SelectAll,
+    Action::ToggleSelect,
+    Action::SelectRange,
+    Action::BulkPriority,
+    Action::BulkTags,
+    Action::BulkProject,
+    Action::BulkSprint,
+    Action::BulkDue,
+    Action::BulkStart,
+    Action::BulkComplete,
+    Action::BulkDelete,
+    Action::Undo,
+    Action::Help,
+    Action::Quit,
+];
+
 /// Handle normal mode key events. Returns true if exit requested.
 fn handle_normal_mode(app: &mut App, key: &KeyEvent) -> bool {
     // Handle multi-select mode differently
@@ -246,127 +345,125 @@ fn handle_normal_mode(app: &mut App, key: &KeyEvent) -> bool {
         return handle_multi_select_mode(app, key);
     }
 
-    // Sprint task reordering (Ctrl+Up/Down when in sprint view) - check before navigation
+    // Agenda/calendar keys take precedence over list keys
+    if app.is_date_view() && handle_date_view(app, key) {
+        return false;
+    }
+
+    // Sprint task reordering (when in sprint view) - check before navigation
     if app.sprint_view_mode && app.selected_sprint.is_some() {
-        match (key.code, key.modifiers.contains(KeyModifiers::CONTROL)) {
-            (KeyCode::Up, true) | (KeyCode::Char('K'), _) => {
-                app.reorder_current_task(-1);
-                return false;
+        if app.keymap.matches(Action::ReorderUp, key) {
+            app.reorder_current_task(-1);
+            return false;
+        }
+        if app.keymap.matches(Action::ReorderDown, key) {
+            app.reorder_current_task(1);
+            return false;
+        }
+    }
+
+    let Some(action) = app.keymap.lookup(key, LIST_ACTIONS) else {
+        match key.code {
+            // Number keys for tab jumping (0-9)
+            KeyCode::Char(c) if c.is_ascii_digit() => {
+                let index = c.to_digit(10).unwrap() as usize;
+                app.go_to_tab(index);
             }
-            (KeyCode::Down, true) | (KeyCode::Char('J'), _) => {
-                app.reorder_current_task(1);
-                return false;
+
+            // Esc clears filters step by step, then quits
+            KeyCode::Esc => {
+                if !app.search_query.is_empty() {
+                    app.clear_search();
+                } else if app.status_filter.is_some() {
+                    app.status_filter = None;
+                    app.apply_filter();
+                } else if app.active_tab > 0 {
+                    app.go_to_tab(0);
+                } else {
+                    return true;
+                }
             }
+
             _ => {}
         }
-    }
+        return false;
+    };
 
-    match key.code {
+    match action {
         // Navigation
-        KeyCode::Down | KeyCode::Char('j') => app.move_down(),
-        KeyCode::Up | KeyCode::Char('k') => app.move_up(),
-        KeyCode::Home | KeyCode::Char('g') => {
+        Action::MoveDown => app.move_down(),
+        Action::MoveUp => app.move_up(),
+        Action::First => {
             app.selected = 0;
         }
-        KeyCode::End | KeyCode::Char('G') => {
+        Action::Last => {
             if !app.filtered_tasks.is_empty() {
                 app.selected = app.filtered_tasks.len() - 1;
             }
         }
 
         // Tab navigation
-        KeyCode::Tab => {
-            if key.modifiers.contains(KeyModifiers::SHIFT) {
-                app.prev_tab();
-            } else {
-                app.next_tab();
-            }
-        }
-        KeyCode::BackTab => app.prev_tab(),
-
-        // Number keys for tab jumping (0-9)
-        KeyCode::Char(c) if c.is_ascii_digit() && !key.modifiers.contains(KeyModifiers::SHIFT) => {
-            let index = c.to_digit(10).unwrap() as usize;
-            app.go_to_tab(index);
-        }
+        Action::NextTab => app.next_tab(),
+        Action::PrevTab => app.prev_tab(),
 
         // Task actions
-        KeyCode::Char('n') => app.enter_input_mode(InputMode::NewTask),
-        KeyCode::Char('e') => {
+        Action::NewTask => app.enter_input_mode(InputMode::NewTask),
+        Action::EditTitle => {
             if app.current_task().is_some() {
                 app.enter_input_mode(InputMode::EditTitle);
             }
         }
-        KeyCode::Char('x') | KeyCode::Char(' ') => app.toggle_task(),
-        KeyCode::Enter => {
+        Action::ToggleComplete => app.toggle_task(),
+        Action::OpenDetail => {
             // Open detail view for current task
             app.open_detail_view();
         }
-        KeyCode::Char('d') => {
-            if key.modifiers.contains(KeyModifiers::SHIFT) {
-                // Shift+D: delete immediately
-                app.delete_task();
-            } else if app.current_task().is_some() {
-                // d: confirm delete
+        Action::Delete => {
+            if app.current_task().is_some() {
                 app.enter_input_mode(InputMode::ConfirmDelete);
             }
         }
-        KeyCode::Char('p') => {
-            if key.modifiers.contains(KeyModifiers::SHIFT) {
-                // Shift+P: open project selector
-                app.toggle_project_selector();
-            } else {
-                // p: cycle priority
-                app.cycle_priority();
-            }
+        Action::DeleteNow => app.delete_task(),
+        Action::CyclePriority => app.cycle_priority(),
+        Action::ProjectSelector => app.toggle_project_selector(),
+
+        // Snooze
+        Action::Snooze if app.current_task().is_some() => {
+            app.enter_input_mode(InputMode::Snooze);
         }
+        Action::ToggleWaiting => app.toggle_show_waiting(),
 
         // Search and filter
-        KeyCode::Char('/') => app.enter_input_mode(InputMode::Search),
-        KeyCode::Char('s') => app.cycle_status_filter(),
-        KeyCode::Char('o') => app.toggle_sort_selector(),
-        KeyCode::Char('O') => app.toggle_filter_builder(),
-        KeyCode::Char('P') => app.toggle_project_selector(),
-
-        // Sprint view (S key)
-        KeyCode::Char('S') => {
-            if key.modifiers.contains(KeyModifiers::SHIFT) {
-                // Shift+S: open sprint selector
-                app.toggle_sprint_selector();
-            } else {
-                // S: toggle sprint view mode
-                app.toggle_sprint_view();
-            }
-        }
+        Action::Search => app.enter_input_mode(InputMode::Search),
+        Action::CycleStatus => app.cycle_status_filter(),
+        Action::Sort => app.toggle_sort_selector(),
+        Action::FilterBuilder => app.toggle_filter_builder(),
 
-        // Move task to sprint (m key)
-        KeyCode::Char('m') => {
-            app.toggle_move_to_sprint();
-        }
+        // Sprints
+        Action::SprintView => app.toggle_sprint_view(),
+        Action::SprintSelector => app.toggle_sprint_selector(),
+        Action::MoveToSprint => app.toggle_move_to_sprint(),
+
+        // Agenda / calendar views
+        Action::Agenda => app.toggle_agenda_view(),
+        Action::Calendar => app.toggle_calendar_view(),
 
         // Multi-select
-        KeyCode::Char('v') => {
-            if key.modifiers.contains(KeyModifiers::SHIFT) {
-                // Shift+V: select all visible
-                app.select_all_visible();
-            } else {
-                // v: toggle multi-select mode
-                app.toggle_multi_select_mode();
-            }
-        }
-        KeyCode::Char('V') => app.select_all_visible(),
+        Action::MultiSelect => app.toggle_multi_select_mode(),
+        Action::SelectAll => app.select_all_visible(),
+        Action::Undo => app.undo(),
 
         // Help
-        KeyCode::Char('?') => app.toggle_help(),
+        Action::Help => app.toggle_help(),
 
         // Refresh (force reload from disk)
-        KeyCode::Char('r') => {
+        Action::Refresh => {
             app.reload_tasks();
             app.set_message("Synced from disk".to_string());
         }
 
         // Clear filters
-        KeyCode::Char('c') => {
+        Action::ClearFilters => {
             app.clear_search();
             app.status_filter = None;
             app.go_to_tab(0); // Return to "All" tab
@@ -374,26 +471,69 @@ fn handle_normal_mode(app: &mut App, key: &KeyEvent) -> bool {
         }
 
         // Quit
-        KeyCode::Char('q') => return true,
-        KeyCode::Esc => {
-            // Esc clears filters step by step, then quits
-            if !app.search_query.is_empty() {
-                app.clear_search();
-            } else if app.status_filter.is_some() {
-                app.status_filter = None;
-                app.apply_filter();
-            } else if app.active_tab > 0 {
-                app.go_to_tab(0);
-            } else {
-                return true;
-            }
-        }
+        Action::Quit => return true,
 
         _ => {}
     }
     false
 }
 
+/// Calendar date navigation. Returns true if the action moved the cursor.
+fn calendar_motion(app: &mut App, action: Action) -> bool {
+    match action {
+        Action::DayPrev => app.calendar_move_days(-1),
+        Action::DayNext => app.calendar_move_days(1),
+        Action::WeekPrev => app.calendar_move_days(-7),
+        Action::WeekNext => app.calendar_move_days(7),
+        Action::MonthPrev => app.calendar_move_months(-1),
+        Action::MonthNext => app.calendar_move_months(1),
+        Action::Today => app.calendar_today(),
+        _ => return false,
+    }
+    true
+}
+
+/// Handle agenda/calendar key events. Returns true if event was consumed.
+fn handle_date_view(app: &mut App, key: &KeyEvent) -> bool {
+    let calendar = app.view == ViewMode::Calendar;
+    let action = app.keymap.lookup(key, DATE_VIEW_ACTIONS);
+
+    // While a task is picked up, only date navigation, drop and cancel apply
+    if app.reschedule_task_id.is_some() {
+        match action {
+            Some(action) if calendar_motion(app, action) => {}
+            _ if key.code == KeyCode::Enter => app.finish_reschedule(),
+            _ if key.code == KeyCode::Esc || app.keymap.matches(Action::Quit, key) => {
+                app.cancel_reschedule()
+            }
+            _ => {}
+        }
+        return true;
+    }
+
+    match action {
+        // Due date shifting and rescheduling (both views)
+        Some(Action::ShiftDueBack) => app.shift_current_due_date(-1),
+        Some(Action::ShiftDueForward) => app.shift_current_due_date(1),
+        Some(Action::Reschedule) => app.begin_reschedule(),
+
+        // Task selection within the cursor day
+        Some(Action::DayTaskNext) if calendar => app.move_down(),
+        Some(Action::DayTaskPrev) if calendar => app.move_up(),
+
+        // Calendar date navigation
+        Some(action) if calendar && calendar_motion(app, action) => {}
+
+        // Back to the list
+        _ if key.code == KeyCode::Esc => {
+            app.set_main_view(ViewMode::List);
+        }
+
+        _ => return false,
+    }
+    true
+}
+
 /// Handle detail view mode key events. Returns true if exit requested.
 fn handle_detail_mode(app: &mut App, key: &KeyEvent) -> bool {
     // If in edit mode within detail view
@@ -431,94 +571,138 @@ fn handle_detail_mode(app: &mut App, key: &KeyEvent) -> bool {
         return false;
     }
 
-    // Read-only mode (vim-like)
-    match key.code {
+    // Read-only mode
+    let action = app.keymap.lookup(
+        key,
+        &[
+            Action::MoveDown,
+            Action::MoveUp,
+            Action::ToggleComplete,
+            Action::CyclePriority,
+            Action::Help,
+            Action::Quit,
+        ],
+    );
+    match action {
         // Navigation between fields
-        KeyCode::Down | KeyCode::Char('j') => app.detail_next_field(),
-        KeyCode::Up | KeyCode::Char('k') => app.detail_prev_field(),
-
-        // Edit current field
-        KeyCode::Char('i') | KeyCode::Enter => {
-            app.detail_enter_edit();
-        }
+        Some(Action::MoveDown) => app.detail_next_field(),
+        Some(Action::MoveUp) => app.detail_prev_field(),
 
         // Toggle completion
-        KeyCode::Char('x') | KeyCode::Char(' ') => {
+        Some(Action::ToggleComplete) => {
             app.detail_toggle_task();
         }
 
         // Cycle priority
-        KeyCode::Char('p') => {
+        Some(Action::CyclePriority) => {
             app.detail_cycle_priority();
         }
 
+        // Help
+        Some(Action::Help) => app.toggle_help(),
+
         // Close detail view
-        KeyCode::Char('q') | KeyCode::Esc => {
+        Some(Action::Quit) => {
             app.close_detail_view();
         }
 
-        // Help
-        KeyCode::Char('?') => app.toggle_help(),
+        _ => match key.code {
+            // Edit current field
+            KeyCode::Char('i') | KeyCode::Enter => {
+                app.detail_enter_edit();
+            }
 
-        _ => {}
+            KeyCode::Esc => {
+                app.close_detail_view();
+            }
+
+            _ => {}
+        },
     }
     false
 }
 
 /// Handle multi-select mode key events. Returns true if exit requested.
 fn handle_multi_select_mode(app: &mut App, key: &KeyEvent) -> bool {
-    match key.code {
+    match app.keymap.lookup(key, MULTI_SELECT_ACTIONS) {
         // Navigation (same as normal mode)
-        KeyCode::Down | KeyCode::Char('j') => app.move_down(),
-        KeyCode::Up | KeyCode::Char('k') => app.move_up(),
-        KeyCode::Home | KeyCode::Char('g') => {
+        Some(Action::MoveDown) => app.move_down(),
+        Some(Action::MoveUp) => app.move_up(),
+        Some(Action::First) => {
             app.selected = 0;
         }
-        KeyCode::End | KeyCode::Char('G') => {
+        Some(Action::Last) => {
             if !app.filtered_tasks.is_empty() {
                 app.selected = app.filtered_tasks.len() - 1;
             }
         }
 
-        // Toggle selection with Space
-        KeyCode::Char(' ') => {
+        // Exit multi-select mode
+        Some(Action::MultiSelect) => app.toggle_multi_select_mode(),
+
+        // Select all
+        Some(Action::SelectAll) => app.select_all_visible(),
+
+        // Toggle selection and move to the next task
+        Some(Action::ToggleSelect) => {
             app.toggle_current_selection();
-            app.move_down(); // Move to next task after selecting
+            app.move_down();
         }
 
-        // Complete selected tasks
-        KeyCode::Char('x') => {
-            if !app.selected_tasks.is_empty() {
-                app.complete_selected_tasks();
-            }
-        }
+        // Select from the last toggled task to the cursor
+        Some(Action::SelectRange) => app.select_range(),
 
-        // Delete selected tasks
-        KeyCode::Char('d') => {
-            if !app.selected_tasks.is_empty() {
-                app.delete_selected_tasks();
+        // Bulk edits of the selected tasks
+        Some(action) if is_bulk_action(action) => {
+            if app.selected_tasks.is_empty() {
+                app.set_message("No tasks selected".to_string());
+                return false;
+            }
+            match action {
+                Action::BulkPriority => app.enter_input_mode(InputMode::BulkPriority),
+                Action::BulkTags => app.enter_input_mode(InputMode::BulkTags),
+                Action::BulkProject => app.enter_input_mode(InputMode::BulkProject),
+                Action::BulkDue => app.enter_input_mode(InputMode::BulkDue),
+                Action::BulkSprint => app.toggle_move_to_sprint(),
+                Action::BulkStart => app.start_selected_tasks(),
+                Action::BulkComplete => app.complete_selected_tasks(),
+                _ => app.delete_selected_tasks(),
             }
         }
 
-        // Exit multi-select mode
-        KeyCode::Char('v') | KeyCode::Esc => {
-            app.toggle_multi_select_mode();
-        }
-
-        // Select all
-        KeyCode::Char('V') => app.select_all_visible(),
+        // Undo the last bulk edit
+        Some(Action::Undo) => app.undo(),
 
         // Help
-        KeyCode::Char('?') => app.toggle_help(),
+        Some(Action::Help) => app.toggle_help(),
 
         // Quit
-        KeyCode::Char('q') => return true,
+        Some(Action::Quit) => return true,
 
-        _ => {}
+        _ => {
+            if key.code == KeyCode::Esc {
+                app.toggle_multi_select_mode();
+            }
+        }
     }
     false
 }
 
+/// Multi-select actions that edit the selected tasks
+fn is_bulk_action(action: Action) -> bool {
+    matches!(
+        action,
+        Action::BulkPriority
+            | Action::BulkTags
+            | Action::BulkProject
+            | Action::BulkSprint
+            | Action::BulkDue
+            | Action::BulkStart
+            | Action::BulkComplete
+            | Action::BulkDelete
+    )
+}
+
 /// Handle input mode key events
 fn handle_input_mode(app: &mut App, key: &KeyEvent) {
     match key.code {
@@ -665,6 +849,12 @@ fn handle_sprint_selector(app: &mut App, key: &KeyEvent) -> bool {
             false
         }
 
+        // Plan sprint from the backlog
+        KeyCode::Char('p') => {
+            app.enter_sprint_plan();
+            true
+        }
+
         // Cancel
         KeyCode::Esc | KeyCode::Char('S') => {
             app.toggle_sprint_selector();
@@ -675,6 +865,19 @@ fn handle_sprint_selector(app: &mut App, key: &KeyEvent) -> bool {
     }
 }
 
+/// Handle sprint planner key events (the planner consumes all keys)
+fn handle_sprint_plan(app: &mut App, key: &KeyEvent) {
+    match key.code {
+        KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('l') | KeyCode::Right => {
+            app.adjust_sprint_plan(1)
+        }
+        KeyCode::Char('-') | KeyCode::Char('h') | KeyCode::Left => app.adjust_sprint_plan(-1),
+        KeyCode::Enter => app.apply_sprint_plan(),
+        KeyCode::Esc | KeyCode::Char('q') => app.cancel_sprint_plan(),
+        _ => {}
+    }
+}
+
 /// Handle move-to-sprint selector key events. Returns true if event was consumed.
 fn handle_move_to_sprint(app: &mut App, key: &KeyEvent) -> bool {
     match key.code {
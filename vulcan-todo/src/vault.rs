@@ -0,0 +1,202 @@
+//! vulcan-vault integration (`vault` feature)
+//!
+//! Tasks reference vault notes by ID in `context_notes`. This module opens
+//! the vault database to resolve those IDs to titles and content, and to
+//! find links whose notes were deleted.
+
+use crate::models::Task;
+use crate::store::Store;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use vulcan_vault::Store as VaultStore;
+use vulcan_vault::{Note, SqliteStore};
+
+/// A context note resolved from the vault
+#[derive(Debug, Clone)]
+pub struct LinkedNote {
+    pub id: String,
+    pub title: String,
+    /// Path relative to the vault directory
+    pub path: String,
+    pub note_type: String,
+}
+
+/// Handle on the vault database and note files
+pub struct Vault {
+    store: SqliteStore,
+    dir: PathBuf,
+}
+
+impl Vault {
+    /// Open the default vault (`~/.config/vulcan-vault`). Returns `None` if
+    /// the vault hasn't been initialized, so callers can degrade gracefully.
+    pub fn open_default() -> Result<Option<Self>> {
+        let db_path = vulcan_vault::db_path();
+        if !db_path.exists() {
+            return Ok(None);
+        }
+        Self::open(&db_path, vulcan_vault::vault_dir()).map(Some)
+    }
+
+    /// Open a vault database with its note directory
+    pub fn open(db_path: &Path, dir: PathBuf) -> Result<Self> {
+        let store = SqliteStore::new(db_path)
+            .with_context(|| format!("Failed to open vault: {}", db_path.display()))?;
+        Ok(Self { store, dir })
+    }
+
+    /// Wrap an existing vault store
+    #[cfg(test)]
+    pub fn with_store(store: SqliteStore, dir: PathBuf) -> Self {
+        Self { store, dir }
+    }
+
+    /// Look up a note; errors (a busy or broken database) are not "missing"
+    fn note(&self, id: &str) -> Result<Option<Note>> {
+        self.store
+            .get_note(id)
+            .with_context(|| format!("Failed to look up vault note {}", id))
+    }
+
+    /// Resolve a note ID (None if the note no longer exists)
+    pub fn resolve(&self, id: &str) -> Result<Option<LinkedNote>> {
+        Ok(self.note(id)?.map(|note| LinkedNote {
+            id: note.id,
+            title: note.title,
+            path: note.path,
+            note_type: note.note_type.to_string(),
+        }))
+    }
+
+    /// Markdown body of a note, read from its file in the vault directory
+    pub fn content(&self, note: &LinkedNote) -> Option<String> {
+        let markdown = std::fs::read_to_string(self.dir.join(&note.path)).ok()?;
+        Some(strip_frontmatter(&markdown).trim().to_string())
+    }
+
+    /// Context note IDs of a task that no longer exist in the vault. Fails
+    /// on the first lookup error rather than reporting the note missing.
+    pub fn missing_notes(&self, task: &Task) -> Result<Vec<String>> {
+        let mut missing = Vec::new();
+        for id in &task.context_notes {
+            if self.note(id)?.is_none() {
+                missing.push(id.clone());
+            }
+        }
+        Ok(missing)
+    }
+}
+
+/// A task with dangling context note links
+#[derive(Debug, Clone)]
+pub struct DanglingLinks {
+    pub task_id: String,
+    pub title: String,
+    pub missing: Vec<String>,
+}
+
+/// Find tasks linking to notes that no longer exist; with `prune`, remove
+/// those links from the tasks. Stops at the first vault error, so a link is
+/// only pruned once its note is known to be gone.
+pub fn check_links(store: &dyn Store, vault: &Vault, prune: bool) -> Result<Vec<DanglingLinks>> {
+    let mut dangling = Vec::new();
+
+    for mut task in store.get_all()? {
+        let missing = vault.missing_notes(&task)?;
+        if missing.is_empty() {
+            continue;
+        }
+        if prune {
+            for id in &missing {
+                task.remove_context_note(id);
+            }
+            store.update(&task)?;
+        }
+        dangling.push(DanglingLinks {
+            task_id: task.id,
+            title: task.title,
+            missing,
+        });
+    }
+
+    Ok(dangling)
+}
+
+/// Strip a leading `---` YAML frontmatter block
+fn strip_frontmatter(markdown: &str) -> &str {
+    let Some(rest) = markdown.strip_prefix("---") else {
+        return markdown;
+    };
+    match rest.find("\n---") {
+        Some(end) => {
+            let after = &rest[end + 4..];
+            after.strip_prefix('\n').unwrap_or(after)
+        }
+        None => markdown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+    use vulcan_vault::NoteType;
+
+    #[test]
+    fn test_resolve_and_prune_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault_store = SqliteStore::in_memory().unwrap();
+        let note = Note::new("Design notes", NoteType::Project, "Projects/design.md");
+        vault_store.save_note(&note).unwrap();
+        std::fs::create_dir_all(dir.path().join("Projects")).unwrap();
+        std::fs::write(
+            dir.path().join("Projects/design.md"),
+            "---\ntitle: Design notes\n---\n# Design\nUse SQLite.\n",
+        )
+        .unwrap();
+        let vault = Vault::with_store(vault_store, dir.path().to_path_buf());
+
+        let linked = vault.resolve(&note.id).unwrap().unwrap();
+        assert_eq!(linked.title, "Design notes");
+        assert_eq!(vault.content(&linked).unwrap(), "# Design\nUse SQLite.");
+
+        let store = MemoryStore::new();
+        let mut task = Task::new("Build it".to_string());
+        task.add_context_note(&note.id);
+        task.add_context_note("deleted-note");
+        let task = store.add(&task).unwrap();
+
+        let dangling = check_links(&store, &vault, false).unwrap();
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(dangling[0].missing, vec!["deleted-note".to_string()]);
+        assert_eq!(store.get(&task.id).unwrap().unwrap().context_notes.len(), 2);
+
+        check_links(&store, &vault, true).unwrap();
+        assert_eq!(
+            store.get(&task.id).unwrap().unwrap().context_notes,
+            vec![note.id.clone()]
+        );
+        assert!(check_links(&store, &vault, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_vault_errors_do_not_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("vault.db");
+        let vault = Vault::open(&db_path, dir.path().to_path_buf()).unwrap();
+
+        let store = MemoryStore::new();
+        let mut task = Task::new("Build it".to_string());
+        task.add_context_note("some-note");
+        let task = store.add(&task).unwrap();
+
+        // A database that can't be read is an error, not a missing note
+        std::fs::write(&db_path, vec![0xAB; 8192]).unwrap();
+        assert!(vault.resolve("some-note").is_err());
+        assert!(check_links(&store, &vault, true).is_err());
+        assert_eq!(
+            store.get(&task.id).unwrap().unwrap().context_notes,
+            vec!["some-note".to_string()]
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! User configuration, read from `config.toml` in [`config_dir`]
+//!
+//! Every section and key is optional; a missing file means defaults
+//! (Ollama with nomic-embed-text).
+//!
+//! ```toml
+//! [embedding]
+//! # "ollama" (default), "openai" for OpenAI-compatible servers such as
+//! # llama.cpp, LM Studio or vLLM, or "hashing" for offline vectors
+//! provider = "openai"
+//! url = "http://localhost:8080/v1"
+//! model = "nomic-embed-text-v1.5"
+//! dimensions = 768
+//! # Texts per request (default 32)
+//! batch_size = 64
+//! # Read the API key from this environment variable (or set `api_key`)
+//! api_key_env = "EMBEDDING_API_KEY"
+//!
+//! [decay]
+//! # Confidence lost per day a memory goes unused (default 0.01)
+//! decay_rate = 0.02
+//! # Days before a new, never applied memory starts to decay (default 7)
+//! grace_period_days = 14
+//! # Memories below this confidence are archived (default 0.1)
+//! min_confidence = 0.1
+//! # Where archived memories go, relative to the vault
+//! archive_dir = "Agent-Memories/archive"
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::config_dir;
+use crate::memory::DecayConfig;
+use crate::rag::{
+    AnyEmbedder, HashingEmbedder, OllamaEmbedder, OpenAiEmbedder, DEFAULT_BATCH_SIZE,
+    DEFAULT_MODEL, DEFAULT_OLLAMA_URL, DEFAULT_OPENAI_URL, EMBEDDING_DIM,
+};
+
+/// Errors that can occur while loading configuration
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+
+    #[error("Invalid configuration: {0}")]
+    Invalid(String),
+}
+
+/// Result type for configuration operations
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+/// Contents of `config.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub embedding: EmbeddingConfig,
+    pub decay: DecayConfig,
+}
+
+/// Embedding backend to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    #[default]
+    Ollama,
+    #[serde(alias = "openai-compatible")]
+    OpenAi,
+    Hashing,
+}
+
+/// `[embedding]` section
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmbeddingConfig {
+    pub provider: EmbeddingProvider,
+    /// Server URL; for OpenAI-compatible servers the base URL including `/v1`
+    pub url: Option<String>,
+    /// Model name (required for OpenAI-compatible servers)
+    pub model: Option<String>,
+    /// Vector length the model produces (default 768)
+    pub dimensions: Option<usize>,
+    /// Texts sent per embedding request (default 32)
+    pub batch_size: Option<usize>,
+    /// API key for OpenAI-compatible servers
+    pub api_key: Option<String>,
+    /// Environment variable holding the API key
+    pub api_key_env: Option<String>,
+}
+
+impl Config {
+    /// Path of the config file
+    pub fn path() -> PathBuf {
+        config_dir().join("config.toml")
+    }
+
+    /// Load the config file, or defaults if it doesn't exist
+    pub fn load() -> ConfigResult<Self> {
+        Self::load_from(Self::path())
+    }
+
+    /// Load a config file, or defaults if it doesn't exist
+    pub fn load_from(path: impl AsRef<Path>) -> ConfigResult<Self> {
+        let path = path.as_ref();
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(source) => {
+                return Err(ConfigError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        toml::from_str(&text).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+impl EmbeddingConfig {
+    /// Create the configured embedder
+    pub fn build(&self) -> ConfigResult<AnyEmbedder> {
+        let dimensions = self.dimensions.unwrap_or(EMBEDDING_DIM);
+        if dimensions == 0 {
+            return Err(ConfigError::Invalid(
+                "embedding.dimensions must be positive".to_string(),
+            ));
+        }
+        let batch_size = self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+        if batch_size == 0 {
+            return Err(ConfigError::Invalid(
+                "embedding.batch_size must be positive".to_string(),
+            ));
+        }
+
+        Ok(match self.provider {
+            EmbeddingProvider::Ollama => AnyEmbedder::Ollama(
+                OllamaEmbedder::with_config(
+                    self.url.as_deref().unwrap_or(DEFAULT_OLLAMA_URL),
+                    self.model.as_deref().unwrap_or(DEFAULT_MODEL),
+                )
+                .with_dimensions(dimensions)
+                .with_batch_size(batch_size),
+            ),
+            EmbeddingProvider::OpenAi => {
+                let model = self.model.as_deref().ok_or_else(|| {
+                    ConfigError::Invalid(
+                        "embedding.model is required for OpenAI-compatible servers".to_string(),
+                    )
+                })?;
+                let mut embedder =
+                    OpenAiEmbedder::new(self.url.as_deref().unwrap_or(DEFAULT_OPENAI_URL), model)
+                        .with_dimensions(dimensions)
+                        .with_batch_size(batch_size);
+                if let Some(key) = self.api_key()? {
+                    embedder = embedder.with_api_key(key);
+                }
+                AnyEmbedder::OpenAi(embedder)
+            }
+            EmbeddingProvider::Hashing => AnyEmbedder::Hashing(HashingEmbedder::new(dimensions)),
+        })
+    }
+
+    fn api_key(&self) -> ConfigResult<Option<String>> {
+        match (&self.api_key, &self.api_key_env) {
+            (Some(key), _) => Ok(Some(key.clone())),
+            (None, Some(var)) => std::env::var(var).map(Some).map_err(|_| {
+                ConfigError::Invalid(format!("environment variable {} is not set", var))
+            }),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+/// Create the embedder configured in `config.toml`
+pub fn load_embedder() -> ConfigResult<AnyEmbedder> {
+    Config::load()?.embedding.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rag::Embedder;
+
+    fn parse(text: &str) -> Config {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_embedding_config() {
+        let embedder = Config::default().embedding.build().unwrap();
+        assert!(matches!(embedder, AnyEmbedder::Ollama(_)));
+        assert_eq!(embedder.model(), DEFAULT_MODEL);
+
+        let config = parse(
+            "[embedding]\nprovider = \"openai\"\nurl = \"http://localhost:1234/v1\"\n\
+             model = \"bge-small\"\ndimensions = 384\napi_key = \"secret\"\n",
+        );
+        let embedder = config.embedding.build().unwrap();
+        assert!(matches!(embedder, AnyEmbedder::OpenAi(_)));
+        assert_eq!(embedder.dimensions(), 384);
+
+        let config = parse("[embedding]\nprovider = \"hashing\"\n");
+        assert_eq!(config.embedding.build().unwrap().model(), "hashing-v1");
+
+        // OpenAI-compatible servers need a model name
+        let config = parse("[embedding]\nprovider = \"openai\"\n");
+        assert!(matches!(
+            config.embedding.build(),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert!(toml::from_str::<Config>("[embedding]\nprovider = \"cloud\"\n").is_err());
+    }
+
+    #[test]
+    fn test_decay_config() {
+        let config = parse("[decay]\ndecay_rate = 0.02\ngrace_period_days = 14\n");
+        assert_eq!(config.decay.decay_rate, 0.02);
+        assert_eq!(config.decay.grace_period_days, 14);
+        assert_eq!(
+            config.decay.min_confidence,
+            DecayConfig::default().min_confidence
+        );
+        assert!(toml::from_str::<Config>("[decay]\nrate = 0.02\n").is_err());
+    }
+
+    #[test]
+    fn test_load_from() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        assert!(Config::load_from(&path).is_ok());
+
+        std::fs::write(&path, "[embedding]\nmodle = \"typo\"\n").unwrap();
+        assert!(matches!(
+            Config::load_from(&path),
+            Err(ConfigError::Parse { .. })
+        ));
+    }
+}
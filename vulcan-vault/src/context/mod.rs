@@ -0,0 +1,689 @@
+//! Token-budgeted context assembly for agents
+//!
+//! Instead of calling search, task context and memory recall separately and
+//! pasting everything into a prompt, an agent asks for the context of a query
+//! or task within a token budget. Assembly:
+//!
+//! 1. Gathers candidates: note chunks matching the query (keyword and
+//!    vector rankings fused per chunk), the chunks of notes linked to the
+//!    task, notes one link away from the best matches, and memories.
+//! 2. Drops passages that repeat a more relevant one, such as keyword
+//!    excerpts of a chunk or the overlap between adjacent chunks.
+//! 3. Orders the rest by maximal marginal relevance, so a second passage
+//!    saying the same thing ranks below a less relevant but new one.
+//! 4. Packs them into Markdown, each section citing its note path and
+//!    heading (or memory), until the budget is used up.
+
+mod pack;
+
+pub use pack::estimate_tokens;
+
+use std::collections::{HashMap, HashSet};
+
+use thiserror::Error;
+
+use crate::graph::related_notes;
+use crate::models::{Memory, Note};
+use crate::rag::{Embedder, SearchMode, RRF_K};
+use crate::store::{Store, StoreError};
+use pack::{Passage, MIN_TRUNCATED_TOKENS};
+
+/// Default token budget
+pub const DEFAULT_TOKEN_BUDGET: usize = 4000;
+
+/// Default weight of relevance against diversity in MMR ordering
+pub const DEFAULT_DIVERSITY_LAMBDA: f32 = 0.7;
+
+/// Candidates fetched from each search
+const SEARCH_CANDIDATES: usize = 40;
+
+/// Memories considered
+const MEMORY_CANDIDATES: usize = 10;
+
+/// Best matches whose linked notes are pulled in
+const LINK_SEEDS: usize = 3;
+
+/// Linked notes pulled in per seed
+const LINKS_PER_SEED: usize = 3;
+
+/// Relevance of a linked note relative to the note linking to it
+const LINK_DECAY: f32 = 0.5;
+
+/// Relevance lost per chunk further into a task or linked note
+const CHUNK_DECAY: f32 = 0.9;
+
+/// Minimum memory confidence
+const MIN_MEMORY_CONFIDENCE: f32 = 0.3;
+
+/// Errors that can occur while assembling context
+#[derive(Error, Debug)]
+pub enum ContextError {
+    #[error("Store error: {0}")]
+    Store(#[from] StoreError),
+
+    #[error("Nothing to assemble: give a query or a task ID with linked notes")]
+    NoQuery,
+}
+
+/// Result type for context assembly
+pub type ContextResult<T> = Result<T, ContextError>;
+
+/// What to assemble context for
+#[derive(Debug, Clone)]
+pub struct ContextRequest {
+    /// What the agent is working on
+    pub query: Option<String>,
+    /// vulcan-todo task whose linked notes lead the context
+    pub task_id: Option<String>,
+    /// Limit note matches to a project
+    pub project: Option<String>,
+    /// Upper bound for the estimated tokens of the Markdown
+    pub token_budget: usize,
+    /// Include agent memories
+    pub include_memories: bool,
+    /// Include notes linked from the best matches
+    pub include_linked: bool,
+    /// MMR trade-off: 1.0 ranks by relevance only, lower values favour
+    /// passages unlike those already chosen
+    pub lambda: f32,
+}
+
+impl Default for ContextRequest {
+    fn default() -> Self {
+        Self {
+            query: None,
+            task_id: None,
+            project: None,
+            token_budget: DEFAULT_TOKEN_BUDGET,
+            include_memories: true,
+            include_linked: true,
+            lambda: DEFAULT_DIVERSITY_LAMBDA,
+        }
+    }
+}
+
+impl ContextRequest {
+    /// Context for a free-text query
+    pub fn for_query(query: impl Into<String>) -> Self {
+        Self {
+            query: Some(query.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Context for a vulcan-todo task
+    pub fn for_task(task_id: impl Into<String>) -> Self {
+        Self {
+            task_id: Some(task_id.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Where a section of the context came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextSource {
+    /// Note linked to the requested task
+    Task,
+    /// Note chunk matching the query
+    Search,
+    /// Note linked to one of the matches
+    Linked,
+    /// Agent memory
+    Memory,
+}
+
+impl std::fmt::Display for ContextSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContextSource::Task => write!(f, "task"),
+            ContextSource::Search => write!(f, "search"),
+            ContextSource::Linked => write!(f, "linked"),
+            ContextSource::Memory => write!(f, "memory"),
+        }
+    }
+}
+
+/// One passage of the assembled context
+#[derive(Debug, Clone)]
+pub struct ContextSection {
+    pub source: ContextSource,
+    pub title: String,
+    /// Note path and heading (`path#heading`), or `memory:<id>`
+    pub citation: String,
+    pub note_id: Option<String>,
+    pub note_path: Option<String>,
+    pub heading: Option<String>,
+    pub memory_id: Option<String>,
+    pub content: String,
+    /// Relevance before diversity (0-1)
+    pub relevance: f32,
+    /// Whether the content was shortened to fit the budget
+    pub truncated: bool,
+}
+
+/// Context packed into a token budget
+#[derive(Debug, Clone)]
+pub struct AssembledContext {
+    /// Sections in MMR order
+    pub sections: Vec<ContextSection>,
+    /// The sections rendered as Markdown with citations
+    pub markdown: String,
+    /// Estimated tokens of `markdown`
+    pub tokens: usize,
+    pub token_budget: usize,
+    /// Candidates considered after deduplication
+    pub candidates: usize,
+    /// Keyword if the query couldn't be embedded with the active model
+    pub search_mode: SearchMode,
+}
+
+/// A candidate section with its word set for deduplication and MMR
+struct Candidate {
+    section: ContextSection,
+    words: HashSet<String>,
+    tokens: usize,
+}
+
+impl Candidate {
+    fn new(section: ContextSection) -> Self {
+        let words = pack::words(&section.content);
+        let tokens = estimate_tokens(&render(&section));
+        Self {
+            section,
+            words,
+            tokens,
+        }
+    }
+}
+
+impl Passage for Candidate {
+    fn relevance(&self) -> f32 {
+        self.section.relevance
+    }
+
+    fn words(&self) -> &HashSet<String> {
+        &self.words
+    }
+
+    fn tokens(&self) -> usize {
+        self.tokens
+    }
+}
+
+/// Gather, deduplicate, order and pack context for `request`
+///
+/// Vector search and semantic memory recall are skipped when the query
+/// can't be embedded with the index's active model; keyword matches are
+/// used instead.
+pub async fn assemble_context<S: Store, E: Embedder>(
+    store: &S,
+    embedder: &E,
+    request: &ContextRequest,
+) -> ContextResult<AssembledContext> {
+    let task_notes = match &request.task_id {
+        Some(task_id) => store.get_notes_by_task(task_id)?,
+        None => Vec::new(),
+    };
+    let query = match request.query.as_deref().map(str::trim) {
+        Some(query) if !query.is_empty() => query.to_string(),
+        _ if !task_notes.is_empty() => task_notes
+            .iter()
+            .map(|n| n.title.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => return Err(ContextError::NoQuery),
+    };
+
+    let embedding = match embedder.embed(&query).await {
+        Ok(embedding) => Some(embedding),
+        Err(e) => {
+            tracing::warn!("Assembling context from keyword matches: {}", e);
+            None
+        }
+    };
+
+    let mut sections = Vec::new();
+    let mut seeds: Vec<(String, f32)> = Vec::new();
+    for note in &task_notes {
+        sections.extend(note_sections(store, note, ContextSource::Task, 1.0)?);
+        seeds.push((note.id.clone(), 1.0));
+    }
+
+    let (matches, search_mode) =
+        search_sections(store, embedder, &query, embedding.as_deref(), request)?;
+    for section in &matches {
+        let Some(note_id) = &section.note_id else {
+            continue;
+        };
+        if seeds.len() < task_notes.len() + LINK_SEEDS && !seeds.iter().any(|(id, _)| id == note_id)
+        {
+            seeds.push((note_id.clone(), section.relevance));
+        }
+    }
+    sections.extend(matches);
+
+    if request.include_linked {
+        let mut linked: HashSet<String> = seeds.iter().map(|(id, _)| id.clone()).collect();
+        for (seed, relevance) in &seeds {
+            for related in related_notes(store, seed, 1, LINKS_PER_SEED)? {
+                if linked.insert(related.note.id.clone()) {
+                    sections.extend(note_sections(
+                        store,
+                        &related.note,
+                        ContextSource::Linked,
+                        relevance * LINK_DECAY,
+                    )?);
+                }
+            }
+        }
+    }
+
+    if request.include_memories {
+        sections.extend(memory_sections(
+            store,
+            embedder,
+            &query,
+            embedding.as_deref(),
+        )?);
+    }
+
+    let candidates = pack::dedupe(sections.into_iter().map(Candidate::new).collect());
+    let header = format!("## Context: {}\n\n", query);
+    let budget = request
+        .token_budget
+        .saturating_sub(estimate_tokens(&header));
+    let (order, overflow) = pack::select(&candidates, budget, request.lambda.clamp(0.0, 1.0));
+
+    let used: usize = order.iter().map(|&i| candidates[i].tokens).sum();
+    let mut chosen: Vec<ContextSection> = order
+        .iter()
+        .map(|&i| candidates[i].section.clone())
+        .collect();
+    let remaining = budget - used;
+    if let Some(i) = overflow.filter(|_| remaining >= MIN_TRUNCATED_TOKENS) {
+        let mut section = candidates[i].section.clone();
+        let overhead = estimate_tokens(&render(&ContextSection {
+            content: String::new(),
+            ..section.clone()
+        }));
+        section.content =
+            pack::truncate_to_tokens(&section.content, remaining.saturating_sub(overhead));
+        section.truncated = true;
+        chosen.push(section);
+    }
+
+    let markdown = if chosen.is_empty() {
+        String::new()
+    } else {
+        let body: Vec<String> = chosen.iter().map(render).collect();
+        format!("{}{}", header, body.join("\n"))
+    };
+
+    Ok(AssembledContext {
+        tokens: estimate_tokens(&markdown),
+        sections: chosen,
+        markdown,
+        token_budget: request.token_budget,
+        candidates: candidates.len(),
+        search_mode,
+    })
+}
+
+/// Note chunks matching the query, scored by reciprocal rank fusion of the
+/// keyword and vector rankings and normalised to 0-1
+fn search_sections<S: Store, E: Embedder>(
+    store: &S,
+    embedder: &E,
+    query: &str,
+    embedding: Option<&[f32]>,
+    request: &ContextRequest,
+) -> ContextResult<(Vec<ContextSection>, SearchMode)> {
+    let project = request.project.as_deref();
+    let vector = match embedding {
+        Some(embedding) => match store.vector_search(
+            embedder.model(),
+            embedding,
+            None,
+            project,
+            None,
+            SEARCH_CANDIDATES,
+        ) {
+            Ok(vector) => Some(vector),
+            Err(
+                e @ (StoreError::EmbeddingSpaceMismatch { .. }
+                | StoreError::InvalidEmbeddingDimension { .. }),
+            ) => {
+                tracing::warn!("Assembling context from keyword matches: {}", e);
+                None
+            }
+            Err(e) => return Err(e.into()),
+        },
+        None => None,
+    };
+    let mode = if vector.is_some() {
+        SearchMode::Hybrid
+    } else {
+        SearchMode::Keyword
+    };
+    let keyword = store.keyword_search(query, None, project, None, SEARCH_CANDIDATES)?;
+
+    // Keyed by chunk, or by note for excerpt-only keyword matches
+    let mut scores: HashMap<String, (f32, ContextSection)> = HashMap::new();
+    let mut add = |key: String, rank: usize, section: ContextSection| {
+        let rrf = 1.0 / (RRF_K + rank as f32);
+        scores
+            .entry(key)
+            .and_modify(|(score, _)| *score += rrf)
+            .or_insert((rrf, section));
+    };
+
+    for (i, result) in vector.into_iter().flatten().enumerate() {
+        add(
+            result.chunk_id,
+            i + 1,
+            note_section(
+                ContextSource::Search,
+                &result.note_id,
+                &result.note_title,
+                &result.note_path,
+                result.heading,
+                result.content,
+            ),
+        );
+    }
+    for (i, result) in keyword.into_iter().enumerate() {
+        let key = result.chunk_id.unwrap_or_else(|| result.note_id.clone());
+        add(
+            key,
+            i + 1,
+            note_section(
+                ContextSource::Search,
+                &result.note_id,
+                &result.note_title,
+                &result.note_path,
+                result.heading,
+                result.content,
+            ),
+        );
+    }
+
+    // A chunk found by both searches scores 2 / (k + 1)
+    let best = 2.0 / (RRF_K + 1.0);
+    let mut sections: Vec<ContextSection> = scores
+        .into_values()
+        .map(|(score, mut section)| {
+            section.relevance = (score / best).min(1.0);
+            section
+        })
+        .collect();
+    sections.sort_by(|a, b| {
+        b.relevance
+            .total_cmp(&a.relevance)
+            .then_with(|| a.citation.cmp(&b.citation))
+    });
+    Ok((sections, mode))
+}
+
+/// All chunks of a note, relevance decaying through the note
+fn note_sections<S: Store>(
+    store: &S,
+    note: &Note,
+    source: ContextSource,
+    relevance: f32,
+) -> ContextResult<Vec<ContextSection>> {
+    Ok(store
+        .get_chunks(&note.id)?
+        .into_iter()
+        .map(|chunk| {
+            let mut section = note_section(
+                source,
+                &note.id,
+                &note.title,
+                &note.path,
+                chunk.heading,
+                chunk.content,
+            );
+            section.relevance = relevance * CHUNK_DECAY.powi(chunk.chunk_index as i32);
+            section
+        })
+        .collect())
+}
+
+fn note_section(
+    source: ContextSource,
+    note_id: &str,
+    title: &str,
+    path: &str,
+    heading: Option<String>,
+    content: String,
+) -> ContextSection {
+    let citation = match &heading {
+        Some(heading) => format!("{}#{}", path, heading),
+        None => path.to_string(),
+    };
+    ContextSection {
+        source,
+        title: title.to_string(),
+        citation,
+        note_id: Some(note_id.to_string()),
+        note_path: Some(path.to_string()),
+        heading,
+        memory_id: None,
+        content: content.trim().to_string(),
+        relevance: 0.0,
+        truncated: false,
+    }
+}
+
+/// Memories for the query, by embedding when possible, scored like
+/// [`crate::memory::MemoryRetrieval`]: 70% similarity, 30% confidence
+fn memory_sections<S: Store, E: Embedder>(
+    store: &S,
+    embedder: &E,
+    query: &str,
+    embedding: Option<&[f32]>,
+) -> ContextResult<Vec<ContextSection>> {
+    let semantic = match embedding {
+        Some(embedding) => match store.search_memories_semantic(
+            embedder.model(),
+            embedding,
+            MIN_MEMORY_CONFIDENCE,
+            MEMORY_CANDIDATES,
+        ) {
+            Ok(results) => Some(results),
+            Err(
+                StoreError::EmbeddingSpaceMismatch { .. }
+                | StoreError::InvalidEmbeddingDimension { .. },
+            ) => None,
+            Err(e) => return Err(e.into()),
+        },
+        None => None,
+    };
+
+    let scored: Vec<(Memory, f32)> = match semantic {
+        Some(results) => results
+            .into_iter()
+            .map(|(memory, distance)| {
+                let similarity = 1.0 - distance / 2.0;
+                let score = 0.7 * similarity + 0.3 * memory.confidence;
+                (memory, score)
+            })
+            .collect(),
+        None => store
+            .search_memories(query, None, MIN_MEMORY_CONFIDENCE, MEMORY_CANDIDATES)?
+            .into_iter()
+            .map(|memory| {
+                let score = memory.confidence;
+                (memory, score)
+            })
+            .collect(),
+    };
+
+    Ok(scored
+        .into_iter()
+        .map(|(memory, relevance)| ContextSection {
+            source: ContextSource::Memory,
+            title: format!(
+                "{}: {}",
+                capitalize(&memory.memory_type.to_string()),
+                memory.title
+            ),
+            citation: format!("memory:{}", memory.id),
+            note_id: None,
+            note_path: None,
+            heading: None,
+            memory_id: Some(memory.id),
+            content: memory.content.trim().to_string(),
+            relevance: relevance.clamp(0.0, 1.0),
+            truncated: false,
+        })
+        .collect())
+}
+
+/// Render a section as Markdown
+fn render(section: &ContextSection) -> String {
+    let title = match (&section.source, &section.heading) {
+        (ContextSource::Memory, _) => section.title.clone(),
+        (_, Some(heading)) if *heading != section.title => {
+            format!("{} › {}", section.title, heading)
+        }
+        _ => section.title.clone(),
+    };
+    format!(
+        "### {}\n*Source: `{}`*\n\n{}\n",
+        title, section.citation, section.content
+    )
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChunkConfig, NoteType};
+    use crate::rag::{HashingEmbedder, RagPipeline};
+    use crate::store::SqliteStore;
+
+    async fn save(
+        store: &SqliteStore,
+        rag: &RagPipeline<HashingEmbedder>,
+        path: &str,
+        content: &str,
+    ) -> Note {
+        let title = path.trim_end_matches(".md").rsplit('/').next().unwrap();
+        let mut note = Note::new(title, NoteType::Learning, path);
+        note.content = content.to_string();
+        store.save_note(&note).unwrap();
+        rag.process_note(store, &note).await.unwrap();
+        note
+    }
+
+    #[tokio::test]
+    async fn test_assemble_context() {
+        let store = SqliteStore::in_memory().unwrap();
+        let rag = RagPipeline::with_embedder(ChunkConfig::default(), HashingEmbedder::new(64));
+        let ownership = save(
+            &store,
+            &rag,
+            "Learning/rust/Ownership.md",
+            "## Borrowing\nReferences borrow values without taking ownership. See [[Lifetimes]].",
+        )
+        .await;
+        save(
+            &store,
+            &rag,
+            "Learning/rust/Lifetimes.md",
+            "## Elision\nThe compiler infers most lifetimes of references.",
+        )
+        .await;
+        save(
+            &store,
+            &rag,
+            "Learning/cooking/Bread.md",
+            "## Dough\nKnead flour, water and yeast.",
+        )
+        .await;
+        let mut ownership = ownership;
+        ownership.task_id = Some("task-1".to_string());
+        store.save_note(&ownership).unwrap();
+
+        let memory = Memory::lesson(
+            "Prefer borrowing",
+            "Pass references instead of cloning values",
+            "rust",
+            "test-agent",
+        );
+        store.save_memory(&memory).unwrap();
+        store
+            .save_memory_embedding(
+                &memory.id,
+                "hashing-v1",
+                &rag.embedder().embed_sync(&memory.embedding_text()),
+            )
+            .unwrap();
+
+        let request = ContextRequest {
+            query: Some("borrowing references".to_string()),
+            ..ContextRequest::default()
+        };
+        let context = assemble_context(&store, rag.embedder(), &request)
+            .await
+            .unwrap();
+        assert_eq!(context.search_mode, SearchMode::Hybrid);
+        let citations: Vec<&str> = context
+            .sections
+            .iter()
+            .map(|s| s.citation.as_str())
+            .collect();
+        assert_eq!(citations[0], "Learning/rust/Ownership.md#Borrowing");
+        assert!(citations.contains(&"Learning/rust/Lifetimes.md#Elision"));
+        assert!(citations.contains(&format!("memory:{}", memory.id).as_str()));
+        // The same chunk is found by both searches but appears once
+        assert_eq!(
+            citations
+                .iter()
+                .filter(|c| c.starts_with("Learning/rust/Ownership"))
+                .count(),
+            1
+        );
+        assert!(context
+            .markdown
+            .starts_with("## Context: borrowing references"));
+        assert!(context
+            .markdown
+            .contains("*Source: `Learning/rust/Ownership.md#Borrowing`*"));
+        assert!(context.tokens <= request.token_budget);
+
+        // A task alone is enough, and its notes lead
+        let context = assemble_context(&store, rag.embedder(), &ContextRequest::for_task("task-1"))
+            .await
+            .unwrap();
+        assert_eq!(context.sections[0].source, ContextSource::Task);
+
+        // A tight budget shortens the last section instead of overflowing
+        let request = ContextRequest {
+            query: Some("borrowing references".to_string()),
+            token_budget: 70,
+            include_memories: false,
+            include_linked: false,
+            ..ContextRequest::default()
+        };
+        let context = assemble_context(&store, rag.embedder(), &request)
+            .await
+            .unwrap();
+        assert!(context.tokens <= 70, "{} tokens", context.tokens);
+        assert!(!context.sections.is_empty());
+
+        assert!(matches!(
+            assemble_context(&store, rag.embedder(), &ContextRequest::for_task("unknown")).await,
+            Err(ContextError::NoQuery)
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+//! Deduplication, maximal marginal relevance ordering and budget packing
+//!
+//! Redundancy between passages is measured lexically, as the Jaccard overlap
+//! of their word sets. Chunk vectors aren't loaded with search results, and
+//! overlapping chunks or a keyword excerpt of a chunk share most of their
+//! words anyway.
+
+use std::collections::HashSet;
+
+/// Rough characters per token for English text and Markdown
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Word overlap above which two passages count as the same text
+const DUPLICATE_OVERLAP: f32 = 0.8;
+
+/// Smallest remainder of the budget worth filling with a shortened passage
+pub(super) const MIN_TRUNCATED_TOKENS: usize = 48;
+
+/// Estimate the number of tokens in `text`
+///
+/// Models tokenise differently; four characters per token is close enough
+/// for budgeting and errs on the generous side for prose.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Lowercased words of a passage
+pub(super) fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard overlap of two word sets (0 = disjoint, 1 = same words)
+pub(super) fn overlap(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f32 / (a.len() + b.len() - shared) as f32
+}
+
+/// Whether `b` repeats `a`: nearly the same words, or all of its words
+/// appear in `a` (an excerpt of a chunk, or the overlap of adjacent chunks)
+pub(super) fn is_duplicate(a: &HashSet<String>, b: &HashSet<String>) -> bool {
+    if b.is_empty() {
+        return true;
+    }
+    overlap(a, b) >= DUPLICATE_OVERLAP || b.is_subset(a)
+}
+
+/// A passage competing for a place in the context
+pub(super) trait Passage {
+    fn relevance(&self) -> f32;
+    fn words(&self) -> &HashSet<String>;
+    /// Tokens the passage takes up when rendered
+    fn tokens(&self) -> usize;
+}
+
+/// Drop passages that repeat a more relevant one
+pub(super) fn dedupe<P: Passage>(mut passages: Vec<P>) -> Vec<P> {
+    passages.sort_by(|a, b| b.relevance().total_cmp(&a.relevance()));
+    let mut kept: Vec<P> = Vec::with_capacity(passages.len());
+    for passage in passages {
+        if !kept
+            .iter()
+            .any(|k| is_duplicate(k.words(), passage.words()))
+        {
+            kept.push(passage);
+        }
+    }
+    kept
+}
+
+/// Choose passages in maximal marginal relevance order until `budget` runs out
+///
+/// Each step takes the passage maximising
+/// `lambda * relevance - (1 - lambda) * max overlap with those already chosen`
+/// among the passages that still fit. Returns the chosen indices in order;
+/// when nothing fits, the index of the best remaining passage is returned
+/// separately so the caller can shorten it into what's left.
+pub(super) fn select<P: Passage>(
+    passages: &[P],
+    budget: usize,
+    lambda: f32,
+) -> (Vec<usize>, Option<usize>) {
+    let mut chosen: Vec<usize> = Vec::new();
+    let mut remaining = budget;
+    let mut open: Vec<usize> = (0..passages.len()).collect();
+
+    let score = |i: usize, chosen: &[usize]| {
+        let redundancy = chosen
+            .iter()
+            .map(|&c| overlap(passages[i].words(), passages[c].words()))
+            .fold(0.0f32, f32::max);
+        lambda * passages[i].relevance() - (1.0 - lambda) * redundancy
+    };
+
+    loop {
+        let best = open
+            .iter()
+            .copied()
+            .filter(|&i| passages[i].tokens() <= remaining)
+            .max_by(|&a, &b| score(a, &chosen).total_cmp(&score(b, &chosen)));
+        match best {
+            Some(i) => {
+                remaining -= passages[i].tokens();
+                chosen.push(i);
+                open.retain(|&o| o != i);
+            }
+            None => {
+                let truncated = open
+                    .iter()
+                    .copied()
+                    .max_by(|&a, &b| score(a, &chosen).total_cmp(&score(b, &chosen)));
+                return (chosen, truncated);
+            }
+        }
+    }
+}
+
+/// Cut `text` to about `tokens` tokens at a word boundary
+pub(super) fn truncate_to_tokens(text: &str, tokens: usize) -> String {
+    let max_chars = tokens * CHARS_PER_TOKEN;
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    // Leave room for the ellipsis
+    let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    let cut = match cut.rfind(char::is_whitespace) {
+        Some(end) if end > 0 => &cut[..end],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Text {
+        relevance: f32,
+        words: HashSet<String>,
+        tokens: usize,
+    }
+
+    impl Text {
+        fn new(relevance: f32, text: &str) -> Self {
+            Self {
+                relevance,
+                words: words(text),
+                tokens: estimate_tokens(text),
+            }
+        }
+    }
+
+    impl Passage for Text {
+        fn relevance(&self) -> f32 {
+            self.relevance
+        }
+        fn words(&self) -> &HashSet<String> {
+            &self.words
+        }
+        fn tokens(&self) -> usize {
+            self.tokens
+        }
+    }
+
+    #[test]
+    fn test_dedupe() {
+        let passages = dedupe(vec![
+            Text::new(0.5, "references borrow values"),
+            Text::new(
+                0.9,
+                "Rust references borrow values without taking ownership",
+            ),
+            Text::new(0.7, "lifetimes bound how long references live"),
+        ]);
+        let relevance: Vec<f32> = passages.iter().map(|p| p.relevance).collect();
+        assert_eq!(relevance, vec![0.9, 0.7]);
+    }
+
+    #[test]
+    fn test_select_prefers_diversity() {
+        let passages = vec![
+            Text::new(1.0, "ownership moves values between variables"),
+            Text::new(
+                0.95,
+                "ownership moves values between variables and functions",
+            ),
+            Text::new(0.8, "traits describe shared behaviour"),
+        ];
+        let (order, truncated) = select(&passages, 1000, 0.5);
+        assert_eq!(order, vec![0, 2, 1]);
+        assert_eq!(truncated, None);
+
+        // Pure relevance keeps the near-duplicate second
+        let (order, _) = select(&passages, 1000, 1.0);
+        assert_eq!(order, vec![0, 1, 2]);
+
+        // Only what fits is chosen; the best of the rest is offered for
+        // truncation
+        let (order, truncated) = select(&passages, 15, 0.5);
+        assert_eq!(order, vec![0]);
+        assert_eq!(truncated, Some(2));
+    }
+
+    #[test]
+    fn test_tokens_and_truncation() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcde"), 2);
+
+        let text = "one two three four five six seven eight";
+        assert_eq!(truncate_to_tokens(text, 100), text);
+        let cut = truncate_to_tokens(text, 4);
+        assert_eq!(cut, "one two three…");
+        assert!(estimate_tokens(&cut) <= 4);
+    }
+}
@@ -0,0 +1,286 @@
+//! Link and tag extraction from markdown content
+//!
+//! Recognises Obsidian wikilinks (`[[note]]`, `[[note#heading]]`,
+//! `[[note|alias]]`, `![[embed]]`), Markdown links to other notes
+//! (`[text](../other.md#heading)`) and inline `#tags`. Code blocks and inline
+//! code are skipped.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// Syntax a link was written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    Wiki,
+    Markdown,
+}
+
+/// A link found in a note's content, before resolution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedLink {
+    /// Note name or path as written for wikilinks; for Markdown links the
+    /// vault path it points to, resolved against the linking note's folder
+    pub target: String,
+    /// Section heading or block reference after `#`
+    pub heading: Option<String>,
+    /// Display text after `|` (wikilinks) or between brackets (Markdown)
+    pub alias: Option<String>,
+    pub kind: LinkKind,
+}
+
+fn wikilink_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\[\[([^\[\]\n]+?)\]\]").unwrap())
+}
+
+fn markdown_link_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"\[([^\[\]\n]*)\]\(\s*<?([^()<>\s]+)>?(?:\s+"[^"]*")?\s*\)"#).unwrap()
+    })
+}
+
+fn tag_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:^|\s)#([\p{L}\p{N}_][\p{L}\p{N}_/-]*)").unwrap())
+}
+
+fn inline_code_re() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"`[^`\n]*`").unwrap())
+}
+
+/// Extract the links of a note at `source_path` (relative to the vault root)
+///
+/// Links are returned in order of appearance, without duplicates.
+pub fn extract_links(source_path: &str, content: &str) -> Vec<ParsedLink> {
+    let text = strip_code(content);
+    let mut links: Vec<(usize, ParsedLink)> = Vec::new();
+
+    for caps in wikilink_re().captures_iter(&text) {
+        let start = caps.get(0).map_or(0, |m| m.start());
+        if let Some(link) = parse_wikilink(&caps[1]) {
+            links.push((start, link));
+        }
+    }
+
+    for caps in markdown_link_re().captures_iter(&text) {
+        let start = caps.get(0).map_or(0, |m| m.start());
+        if let Some(link) = parse_markdown_link(source_path, &caps[1], &caps[2]) {
+            links.push((start, link));
+        }
+    }
+
+    links.sort_by_key(|(start, _)| *start);
+    let mut unique: Vec<ParsedLink> = Vec::new();
+    for (_, link) in links {
+        if !unique
+            .iter()
+            .any(|l| l.target == link.target && l.heading == link.heading)
+        {
+            unique.push(link);
+        }
+    }
+    unique
+}
+
+/// Extract inline `#tags` (without the `#`), in order and without duplicates
+///
+/// Like Obsidian, purely numeric tags such as `#123` are ignored.
+pub fn extract_tags(content: &str) -> Vec<String> {
+    let text = strip_code(content);
+    let mut tags: Vec<String> = Vec::new();
+    for caps in tag_re().captures_iter(&text) {
+        let tag = caps[1].trim_end_matches(['/', '-']).to_string();
+        if tag
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '/' || c == '-')
+        {
+            continue;
+        }
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+fn parse_wikilink(inner: &str) -> Option<ParsedLink> {
+    // `\|` is how a pipe is escaped inside Markdown tables
+    let (target, alias) = match inner.split_once('|') {
+        Some((target, alias)) => (target.trim_end_matches('\\'), Some(alias.trim())),
+        None => (inner, None),
+    };
+    let (name, heading) = match target.split_once('#') {
+        Some((name, heading)) => (name.trim(), Some(heading.trim())),
+        None => (target.trim(), None),
+    };
+
+    // `[[#heading]]` points into the same note
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(ParsedLink {
+        target: name.to_string(),
+        heading: heading.filter(|h| !h.is_empty()).map(String::from),
+        alias: alias.filter(|a| !a.is_empty()).map(String::from),
+        kind: LinkKind::Wiki,
+    })
+}
+
+fn parse_markdown_link(source_path: &str, text: &str, href: &str) -> Option<ParsedLink> {
+    if href.starts_with('#') || href.contains("://") || href.starts_with("mailto:") {
+        return None;
+    }
+
+    let (path, heading) = match href.split_once('#') {
+        Some((path, heading)) => (path, Some(percent_decode(heading))),
+        None => (href, None),
+    };
+    let mut path = percent_decode(path);
+
+    // Only links to notes, not images or other attachments
+    match std::path::Path::new(&path).extension() {
+        Some(ext) if ext == "md" => {}
+        Some(_) => return None,
+        None => path.push_str(".md"),
+    }
+
+    let target = if let Some(rooted) = path.strip_prefix('/') {
+        normalize(rooted)
+    } else {
+        let dir = source_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+        normalize(&format!("{}/{}", dir, path))
+    }?;
+
+    Some(ParsedLink {
+        target,
+        heading: heading.filter(|h| !h.is_empty()),
+        alias: Some(text.trim().to_string()).filter(|t| !t.is_empty()),
+        kind: LinkKind::Markdown,
+    })
+}
+
+/// Collapse `.` and `..` segments; None if the path escapes the vault
+fn normalize(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            _ => parts.push(part),
+        }
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("/"))
+    }
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok());
+        if bytes[i] == b'%' {
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Blank out fenced code blocks and inline code, keeping line structure
+fn strip_code(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut fence: Option<&str> = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m));
+        match (fence, marker) {
+            (None, Some(m)) => fence = Some(m),
+            (Some(open), Some(m)) if open == m => fence = None,
+            (None, None) => out.push_str(&inline_code_re().replace_all(line, " ")),
+            _ => {}
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(links: &[ParsedLink]) -> Vec<&str> {
+        links.iter().map(|l| l.target.as_str()).collect()
+    }
+
+    #[test]
+    fn test_wikilinks() {
+        let links = extract_links(
+            "Meta/index.md",
+            "See [[Architecture]], [[Projects/vulcanos/setup#Install steps|setup]]\n\
+             and ![[diagram]]. Same note: [[#Intro]]. Again: [[Architecture]]\n\
+             | [[Table\\|alias]] |",
+        );
+        assert_eq!(
+            targets(&links),
+            vec![
+                "Architecture",
+                "Projects/vulcanos/setup",
+                "diagram",
+                "Table"
+            ]
+        );
+        assert_eq!(links[1].heading.as_deref(), Some("Install steps"));
+        assert_eq!(links[1].alias.as_deref(), Some("setup"));
+        assert_eq!(links[3].alias.as_deref(), Some("alias"));
+        assert!(links.iter().all(|l| l.kind == LinkKind::Wiki));
+    }
+
+    #[test]
+    fn test_markdown_links() {
+        let links = extract_links(
+            "Projects/vulcanos/index.md",
+            "[Setup](setup.md#install) [Up](../other/Read%20Me.md) [Root](/Meta/glossary)\n\
+             [Web](https://example.com) [Img](diagram.png) [Anchor](#local) [Out](../../../x.md)",
+        );
+        assert_eq!(
+            targets(&links),
+            vec![
+                "Projects/vulcanos/setup.md",
+                "Projects/other/Read Me.md",
+                "Meta/glossary.md"
+            ]
+        );
+        assert_eq!(links[0].heading.as_deref(), Some("install"));
+        assert_eq!(links[0].kind, LinkKind::Markdown);
+    }
+
+    #[test]
+    fn test_code_is_ignored() {
+        let content =
+            "Real [[A]] and `[[B]]`\n```rust\nlet x = \"[[C]]\"; // #tag\n```\n#real #123 tag#no";
+        assert_eq!(targets(&extract_links("n.md", content)), vec!["A"]);
+        assert_eq!(extract_tags(content), vec!["real"]);
+        assert_eq!(
+            extract_tags("# Heading\n#rust/async, #rust"),
+            vec!["rust/async", "rust"]
+        );
+    }
+}
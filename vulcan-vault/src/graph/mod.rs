@@ -0,0 +1,110 @@
+//! Link graph between notes
+//!
+//! This module handles:
+//! - Extracting wikilinks, Markdown links and inline tags from note content
+//! - Walking the link graph around a note (n-hop neighbourhood)
+//!
+//! Links are resolved and stored by the store when a note with content is
+//! saved; see `Store::save_note`.
+
+mod links;
+
+pub use links::{extract_links, extract_tags, LinkKind, ParsedLink};
+
+use std::collections::{HashSet, VecDeque};
+
+use crate::models::Note;
+use crate::store::{Store, StoreResult};
+
+/// A note reached from another note through links
+#[derive(Debug, Clone)]
+pub struct RelatedNote {
+    pub note: Note,
+    /// Number of links between the two notes (1 = direct link)
+    pub distance: usize,
+}
+
+/// Notes within `max_hops` links of a note, following links in both
+/// directions, nearest first
+///
+/// The starting note itself is not included. At most `limit` notes are
+/// returned.
+pub fn related_notes<S: Store>(
+    store: &S,
+    note_id: &str,
+    max_hops: usize,
+    limit: usize,
+) -> StoreResult<Vec<RelatedNote>> {
+    let mut visited: HashSet<String> = HashSet::from([note_id.to_string()]);
+    let mut queue: VecDeque<(String, usize)> = VecDeque::from([(note_id.to_string(), 0)]);
+    let mut related = Vec::new();
+
+    while let Some((id, distance)) = queue.pop_front() {
+        if distance >= max_hops {
+            continue;
+        }
+
+        // Backlinks report the linking note as their target
+        let mut neighbours: Vec<String> = store
+            .get_outlinks(&id)?
+            .into_iter()
+            .chain(store.get_backlinks(&id)?)
+            .map(|link| link.target_id)
+            .collect();
+        neighbours.sort();
+
+        for neighbour in neighbours {
+            if !visited.insert(neighbour.clone()) {
+                continue;
+            }
+            if let Some(note) = store.get_note(&neighbour)? {
+                related.push(RelatedNote {
+                    note,
+                    distance: distance + 1,
+                });
+                if related.len() >= limit {
+                    return Ok(related);
+                }
+                queue.push_back((neighbour, distance + 1));
+            }
+        }
+    }
+
+    Ok(related)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NoteType;
+    use crate::store::SqliteStore;
+
+    fn save(store: &SqliteStore, title: &str, content: &str) -> Note {
+        let mut note = Note::new(title, NoteType::Meta, format!("Meta/{}.md", title));
+        note.content = content.to_string();
+        store.save_note(&note).unwrap();
+        note
+    }
+
+    #[test]
+    fn test_related_notes() {
+        let store = SqliteStore::in_memory().unwrap();
+        let a = save(&store, "A", "Links to [[B]]");
+        save(&store, "B", "Links to [[C]]");
+        save(&store, "C", "End");
+        save(&store, "D", "Points back at [[A]]");
+        save(&store, "E", "Alone");
+
+        let related = related_notes(&store, &a.id, 1, 10).unwrap();
+        let mut titles: Vec<&str> = related.iter().map(|r| r.note.title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, vec!["B", "D"]);
+
+        let related = related_notes(&store, &a.id, 2, 10).unwrap();
+        let c = related.iter().find(|r| r.note.title == "C").unwrap();
+        assert_eq!(c.distance, 2);
+        assert_eq!(related.len(), 3);
+
+        assert_eq!(related_notes(&store, &a.id, 2, 1).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,297 @@
+//! Frontmatter parsing - turns a markdown file into a Note
+//!
+//! Frontmatter keys use the same names as `Note::to_frontmatter`, but parsing
+//! is lenient so hand-written Obsidian notes index too: tags and aliases may be
+//! a list or a comma-separated string, dates may be RFC 3339 or `YYYY-MM-DD`,
+//! and unknown keys are ignored. Inline `#tags` in the body are added to the
+//! frontmatter tags. Anything missing falls back to the note's zone folder,
+//! its first heading or its file name.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde_yaml::{Mapping, Value};
+
+use super::{IndexError, IndexResult};
+use crate::graph::extract_tags;
+use crate::models::{Note, NoteStatus, NoteType, PrpPhase};
+
+/// Split a markdown file into its frontmatter block (if any) and body
+pub fn split(markdown: &str) -> (Option<&str>, &str) {
+    let rest = match markdown
+        .strip_prefix("---\n")
+        .or_else(|| markdown.strip_prefix("---\r\n"))
+    {
+        Some(rest) => rest,
+        None => return (None, markdown),
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+
+    // Unterminated block - treat the whole file as body
+    (None, markdown)
+}
+
+/// Parse a markdown file at `path` (relative to the vault root) into a Note
+///
+/// The returned note has a fresh id unless the frontmatter declares one, and
+/// carries the body as `content`. Dates missing from the frontmatter default
+/// to `file_modified`; `content_hash` is left to the caller.
+pub fn parse_note(path: &str, markdown: &str, file_modified: DateTime<Utc>) -> IndexResult<Note> {
+    let (frontmatter, body) = split(markdown);
+
+    let map = match frontmatter {
+        Some(yaml) if !yaml.trim().is_empty() => match serde_yaml::from_str::<Value>(yaml) {
+            Ok(Value::Mapping(map)) => map,
+            Ok(Value::Null) => Mapping::new(),
+            Ok(_) => return Err(frontmatter_error(path, "expected a mapping of keys")),
+            Err(e) => return Err(frontmatter_error(path, e)),
+        },
+        _ => Mapping::new(),
+    };
+
+    let note_type = match get_str(&map, "type") {
+        Some(s) => s
+            .parse()
+            .map_err(|_| frontmatter_error(path, format!("unknown note type '{}'", s)))?,
+        None => zone_type(path),
+    };
+
+    let title = get_str(&map, "title")
+        .or_else(|| first_heading(body))
+        .unwrap_or_else(|| file_stem(path));
+
+    let mut note = Note::new(title, note_type, path);
+    note.content = body.to_string();
+
+    if let Some(id) = get_str(&map, "id") {
+        note.id = id;
+    }
+    note.modified = get_date(&map, "modified").unwrap_or(file_modified);
+    note.created = get_date(&map, "created").unwrap_or(note.modified);
+    note.tags = get_list(&map, "tags")
+        .into_iter()
+        .map(|t| t.trim_start_matches('#').to_string())
+        .collect();
+    for tag in extract_tags(body) {
+        if !note.tags.contains(&tag) {
+            note.tags.push(tag);
+        }
+    }
+    note.aliases = get_list(&map, "aliases");
+    note.status = match get_str(&map, "status").as_deref() {
+        Some("draft") => NoteStatus::Draft,
+        Some("archived") => NoteStatus::Archived,
+        _ => NoteStatus::Active,
+    };
+
+    note.project = get_str(&map, "project");
+    note.task_id = get_str(&map, "task_id");
+    note.context_type = get_str(&map, "context_type");
+    note.auto_fetch = map
+        .get("auto_fetch")
+        .and_then(Value::as_bool)
+        .unwrap_or(note.note_type == NoteType::Task && note.task_id.is_some());
+    note.category = get_str(&map, "category");
+    note.source = get_str(&map, "source");
+    note.course = get_str(&map, "course");
+    note.confidence = map
+        .get("confidence")
+        .and_then(Value::as_f64)
+        .map(|c| c as f32);
+    note.review_date = get_date(&map, "review_date");
+    note.memory_type = get_str(&map, "memory_type");
+    note.context = get_str(&map, "context");
+    note.agent = get_str(&map, "agent");
+    note.session_id = get_str(&map, "session_id");
+    note.times_applied = map
+        .get("times_applied")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32;
+    note.last_applied = get_date(&map, "last_applied");
+
+    note.prp_value = get_str(&map, "prp_value");
+    note.prp_scope = get_str(&map, "prp_scope");
+    note.success_criteria = get_list(&map, "success_criteria");
+    if let Some(phases) = map.get("implementation_phases") {
+        note.implementation_phases = serde_yaml::from_value::<Vec<PrpPhase>>(phases.clone())
+            .map_err(|e| frontmatter_error(path, format!("implementation_phases: {}", e)))?;
+    }
+    note.linked_tasks = get_list(&map, "linked_tasks");
+
+    note.checkpoint_name = get_str(&map, "checkpoint_name");
+    note.checkpoint_context = get_str(&map, "checkpoint_context");
+    note.checkpoint_tasks = get_list(&map, "checkpoint_tasks");
+    note.parent_checkpoint = get_str(&map, "parent_checkpoint");
+
+    apply_zone_defaults(&mut note);
+    Ok(note)
+}
+
+/// Note type implied by the zone folder a path lives in
+pub fn zone_type(path: &str) -> NoteType {
+    match path.split('/').next().unwrap_or_default() {
+        "Projects" => NoteType::Project,
+        "Tasks" => NoteType::Task,
+        "Learning" => NoteType::Learning,
+        "Agent-Memories" => NoteType::Memory,
+        "PRPs" => NoteType::Prp,
+        "Checkpoints" => NoteType::Checkpoint,
+        _ => NoteType::Meta,
+    }
+}
+
+/// Fill type-specific fields the frontmatter left out from the zone layout,
+/// e.g. `Projects/<project>/...` or `Learning/<category>/...`
+fn apply_zone_defaults(note: &mut Note) {
+    let parts: Vec<&str> = note.path.split('/').collect();
+    // Only folders count, not the file name itself
+    let folder = if parts.len() > 2 {
+        Some(parts[1].to_string())
+    } else {
+        None
+    };
+
+    match (parts[0], &note.note_type) {
+        ("Projects", NoteType::Project) | ("PRPs", NoteType::Prp) if note.project.is_none() => {
+            note.project = folder;
+        }
+        ("Learning", NoteType::Learning) if note.category.is_none() => note.category = folder,
+        ("Agent-Memories", NoteType::Memory) if note.memory_type.is_none() => {
+            note.memory_type = folder;
+        }
+        _ => {}
+    }
+}
+
+fn frontmatter_error(path: &str, message: impl std::fmt::Display) -> IndexError {
+    IndexError::Frontmatter {
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
+
+/// Text of the first `# ` heading in the body
+fn first_heading(body: &str) -> Option<String> {
+    body.lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+}
+
+fn file_stem(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Scalar value as a string; empty strings and nulls count as missing
+fn get_str(map: &Mapping, key: &str) -> Option<String> {
+    let value = match map.get(key)? {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        _ => return None,
+    };
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+/// List value, also accepting a single comma-separated string
+fn get_list(map: &Mapping, key: &str) -> Vec<String> {
+    match map.get(key) {
+        Some(Value::Sequence(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(s) => Some(s.trim().to_string()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Some(Value::String(s)) => s
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Date value in RFC 3339, `YYYY-MM-DD HH:MM[:SS]` or `YYYY-MM-DD` form
+fn get_date(map: &Mapping, key: &str) -> Option<DateTime<Utc>> {
+    let s = get_str(map, key)?;
+    if let Ok(date) = DateTime::parse_from_rfc3339(&s) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(&s, format) {
+            return Some(date.and_utc());
+        }
+    }
+    NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|d| d.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let (fm, body) = split("---\ntitle: A\n---\n# Body\n");
+        assert_eq!(fm, Some("title: A\n"));
+        assert_eq!(body, "# Body\n");
+
+        assert_eq!(split("# No frontmatter\n"), (None, "# No frontmatter\n"));
+        assert_eq!(split("---\nunterminated\n"), (None, "---\nunterminated\n"));
+    }
+
+    fn parse(path: &str, markdown: &str) -> IndexResult<Note> {
+        parse_note(path, markdown, Utc::now())
+    }
+
+    #[test]
+    fn test_parse_note() {
+        let markdown = "---\nid: abc\ntitle: Architecture\ncreated: 2024-03-01\n\
+                        tags: \"rust, #vault\"\naliases: [Arch]\nstatus: draft\n---\n\nBody text #vault #ideas\n";
+        let note = parse("Projects/vulcanos/architecture.md", markdown).unwrap();
+        assert_eq!(note.id, "abc");
+        assert_eq!(note.title, "Architecture");
+        assert_eq!(note.note_type, NoteType::Project);
+        assert_eq!(note.project.as_deref(), Some("vulcanos"));
+        assert_eq!(note.tags, vec!["rust", "vault", "ideas"]);
+        assert_eq!(note.aliases, vec!["Arch"]);
+        assert_eq!(note.status, NoteStatus::Draft);
+        assert_eq!(note.created.format("%Y-%m-%d").to_string(), "2024-03-01");
+        assert_eq!(note.content, "\nBody text #vault #ideas\n");
+
+        // Title falls back to the first heading, then the file name
+        let note = parse("Learning/topics/ownership.md", "# Borrowing\ntext").unwrap();
+        assert_eq!(note.title, "Borrowing");
+        assert_eq!(note.note_type, NoteType::Learning);
+        assert_eq!(note.category.as_deref(), Some("topics"));
+        assert_eq!(parse("Inbox.md", "text").unwrap().title, "Inbox");
+
+        // Frontmatter type wins over the zone
+        let note = parse("Projects/x/y.md", "---\ntype: meta\n---\n").unwrap();
+        assert_eq!(note.note_type, NoteType::Meta);
+
+        assert!(matches!(
+            parse("a.md", "---\ntype: bogus\n---\n"),
+            Err(IndexError::Frontmatter { .. })
+        ));
+        assert!(parse("a.md", "---\ntitle: [unclosed\n---\n").is_err());
+    }
+}
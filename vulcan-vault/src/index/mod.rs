@@ -0,0 +1,442 @@
+//! Vault indexing - syncs markdown files on disk into vault.db
+//!
+//! This module handles:
+//! - Discovering `.md` files in the vault (skipping `.obsidian/` and other
+//!   hidden folders)
+//! - Parsing YAML frontmatter into Note fields
+//! - Upserting notes by path, skipping files whose content hash is unchanged
+//! - Removing notes whose files were deleted
+//! - Watching the vault and re-embedding changed notes (`VaultWatcher`)
+//! - Resumable, concurrent re-embedding of the whole vault (`Indexer::rebuild`)
+//! - Writing new notes to file, store and embeddings in one go (`NoteWriter`)
+//!
+//! `Indexer` does not touch embeddings; its report says which notes changed
+//! so callers can re-chunk them through the RAG pipeline.
+
+mod frontmatter;
+mod rebuild;
+mod watch;
+mod writer;
+
+pub use frontmatter::{parse_note, split as split_frontmatter, zone_type};
+pub use rebuild::{RebuildEvent, RebuildOptions, RebuildReport, DEFAULT_CONCURRENCY};
+pub use watch::{VaultWatcher, WatchConfig};
+pub use writer::{EmbedStatus, NoteWriter};
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::models::Note;
+use crate::rag::RagError;
+use crate::store::{Store, StoreError};
+
+/// Errors that can occur while indexing the vault
+#[derive(Error, Debug)]
+pub enum IndexError {
+    #[error("Store error: {0}")]
+    Store(#[from] StoreError),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid frontmatter in {path}: {message}")]
+    Frontmatter { path: String, message: String },
+
+    #[error("Watch error: {0}")]
+    Watch(#[from] notify::Error),
+
+    #[error("{0}")]
+    Rag(#[from] RagError),
+
+    #[error("Path is outside the vault: {0}")]
+    OutsideVault(PathBuf),
+}
+
+/// Result type for indexing operations
+pub type IndexResult<T> = Result<T, IndexError>;
+
+/// What indexing a single file did
+#[derive(Debug, Clone)]
+pub enum FileChange {
+    /// A note was created for a new file
+    Added(Note),
+    /// An existing note was refreshed from its changed file
+    Updated(Note),
+    /// A note's file appeared at a new path, recognised by its frontmatter id
+    /// or, failing that, by identical content
+    Moved { from: String, note: Note },
+    /// The file's content hash matched the stored note
+    Unchanged,
+}
+
+/// Summary of an indexing run (paths are relative to the vault)
+#[derive(Debug, Clone, Default)]
+pub struct IndexReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    /// (old path, new path)
+    pub moved: Vec<(String, String)>,
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+    /// (path, error) for files that could not be indexed
+    pub errors: Vec<(String, String)>,
+}
+
+impl IndexReport {
+    /// Number of notes added, updated, moved or removed
+    pub fn changed(&self) -> usize {
+        self.added.len() + self.updated.len() + self.moved.len() + self.removed.len()
+    }
+}
+
+/// Indexes the markdown files of a vault directory into a store
+pub struct Indexer {
+    vault_dir: PathBuf,
+}
+
+impl Indexer {
+    /// Create an indexer for the vault at `vault_dir`
+    pub fn new(vault_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            vault_dir: vault_dir.into(),
+        }
+    }
+
+    /// The vault root
+    pub fn vault_dir(&self) -> &Path {
+        &self.vault_dir
+    }
+
+    /// Index every markdown file in the vault and drop notes whose files are gone
+    ///
+    /// Files that fail to read or parse are listed in the report's errors and
+    /// keep their existing note; store errors abort the run.
+    pub fn index<S: Store>(&self, store: &S) -> IndexResult<IndexReport> {
+        let mut report = IndexReport::default();
+        let mut seen = HashSet::new();
+
+        for file in self.markdown_files(&self.vault_dir)? {
+            let rel = self.relative_path(&file)?;
+            match self.index_file(store, &file) {
+                Ok(FileChange::Added(note)) => report.added.push(note.path),
+                Ok(FileChange::Updated(note)) => report.updated.push(note.path),
+                Ok(FileChange::Moved { from, note }) => report.moved.push((from, note.path)),
+                Ok(FileChange::Unchanged) => report.unchanged += 1,
+                Err(IndexError::Store(e)) => return Err(e.into()),
+                Err(e) => report.errors.push((rel.clone(), e.to_string())),
+            }
+            seen.insert(rel);
+        }
+
+        // A note moved this run no longer lives at its old path
+        let moved_from: HashSet<&String> = report.moved.iter().map(|(from, _)| from).collect();
+        report.removed = self
+            .remove_missing(store, &seen)?
+            .into_iter()
+            .filter(|path| !moved_from.contains(path))
+            .collect();
+
+        Ok(report)
+    }
+
+    /// Index a single markdown file, upserting its note by path
+    pub fn index_file<S: Store>(&self, store: &S, path: &Path) -> IndexResult<FileChange> {
+        let rel = self.relative_path(path)?;
+        let markdown = std::fs::read_to_string(path)?;
+        let hash = content_hash(&markdown);
+
+        let existing = store.get_note_by_path(&rel)?;
+        if let Some(existing) = &existing {
+            if existing.content_hash.as_deref() == Some(hash.as_str()) {
+                return Ok(FileChange::Unchanged);
+            }
+        }
+
+        let file_modified = std::fs::metadata(path)?
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        let mut note = parse_note(&rel, &markdown, file_modified)?;
+        note.content_hash = Some(hash.clone());
+
+        let change = match existing {
+            Some(existing) => {
+                // Keep the id chunks and links are keyed by, and the earliest
+                // creation date if the frontmatter doesn't pin one
+                note.id = existing.id;
+                note.created = note.created.min(existing.created);
+                FileChange::Updated(note)
+            }
+            None => match store.get_note(&note.id)? {
+                // Same id at a path that no longer exists: the file was moved
+                Some(other) if !self.vault_dir.join(&other.path).exists() => FileChange::Moved {
+                    from: other.path,
+                    note,
+                },
+                // Same id in another live file (e.g. a copied note): don't steal it
+                Some(_) => {
+                    note.id = uuid::Uuid::new_v4().to_string();
+                    FileChange::Added(note)
+                }
+                // No id to go by, but identical content whose file is gone
+                None => match self.find_moved(store, &hash)? {
+                    Some(other) => {
+                        note.id = other.id;
+                        note.created = note.created.min(other.created);
+                        FileChange::Moved {
+                            from: other.path,
+                            note,
+                        }
+                    }
+                    None => FileChange::Added(note),
+                },
+            },
+        };
+
+        match &change {
+            FileChange::Added(note)
+            | FileChange::Updated(note)
+            | FileChange::Moved { note, .. } => store.save_note(note)?,
+            FileChange::Unchanged => {}
+        }
+        Ok(change)
+    }
+
+    /// Re-read a stored note's file, keeping the stored id
+    pub fn load_note(&self, stored: &Note) -> IndexResult<Note> {
+        let markdown = std::fs::read_to_string(self.vault_dir.join(&stored.path))?;
+        let mut note = parse_note(&stored.path, &markdown, stored.modified)?;
+        note.id = stored.id.clone();
+        note.content_hash = stored.content_hash.clone();
+        Ok(note)
+    }
+
+    /// Delete indexed notes whose file no longer exists, returning their paths
+    ///
+    /// Only notes that came from a file (those with a content hash) are
+    /// considered, so notes created directly in the store are kept.
+    pub fn remove_missing<S: Store>(
+        &self,
+        store: &S,
+        seen: &HashSet<String>,
+    ) -> IndexResult<Vec<String>> {
+        let mut removed = Vec::new();
+        for note in store.list_notes(None, None, i64::MAX as usize)? {
+            if note.content_hash.is_none() || seen.contains(&note.path) {
+                continue;
+            }
+            if !self.vault_dir.join(&note.path).exists() {
+                store.delete_note(&note.id)?;
+                removed.push(note.path);
+            }
+        }
+        removed.sort();
+        Ok(removed)
+    }
+
+    /// Whether a path is a markdown file the indexer would pick up
+    pub fn is_indexable(&self, path: &Path) -> bool {
+        let Ok(rel) = path.strip_prefix(&self.vault_dir) else {
+            return false;
+        };
+        path.extension().is_some_and(|ext| ext == "md")
+            && !rel
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+    }
+
+    /// Indexed note whose file is gone and whose content hashed to `hash`
+    fn find_moved<S: Store>(&self, store: &S, hash: &str) -> IndexResult<Option<Note>> {
+        Ok(store
+            .get_notes_by_hash(hash)?
+            .into_iter()
+            .find(|note| !self.vault_dir.join(&note.path).exists()))
+    }
+
+    /// Path of a file relative to the vault root, with `/` separators
+    pub fn relative_path(&self, path: &Path) -> IndexResult<String> {
+        let rel = path
+            .strip_prefix(&self.vault_dir)
+            .map_err(|_| IndexError::OutsideVault(path.to_path_buf()))?;
+        Ok(rel
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"))
+    }
+
+    /// All markdown files under `dir` outside hidden folders, sorted
+    pub fn markdown_files(&self, dir: &Path) -> IndexResult<Vec<PathBuf>> {
+        let pattern = format!("{}/**/*.md", glob::Pattern::escape(&dir.to_string_lossy()));
+        let options = glob::MatchOptions {
+            require_literal_leading_dot: true,
+            ..Default::default()
+        };
+
+        let mut files = Vec::new();
+        let paths = glob::glob_with(&pattern, options).map_err(|e| {
+            IndexError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, e))
+        })?;
+        for entry in paths {
+            let path = entry.map_err(glob::GlobError::into_error)?;
+            if path.is_file() && self.is_indexable(&path) {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Stable hash of file contents for change detection (64-bit FNV-1a, hex)
+pub fn content_hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NoteType;
+    use crate::store::SqliteStore;
+    use std::fs;
+
+    fn write(dir: &Path, rel: &str, content: &str) {
+        let path = dir.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_index_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteStore::in_memory().unwrap();
+        let indexer = Indexer::new(dir.path());
+
+        write(
+            dir.path(),
+            "Projects/vulcanos/architecture.md",
+            "---\ntags: [rust]\n---\n# Architecture\n",
+        );
+        write(
+            dir.path(),
+            "Agent-Memories/lessons/retry.md",
+            "# Retry with backoff\n",
+        );
+        write(dir.path(), ".obsidian/plugins/readme.md", "# Ignored\n");
+        write(dir.path(), "Meta/broken.md", "---\ntype: nope\n---\n");
+
+        let report = indexer.index(&store).unwrap();
+        assert_eq!(report.added.len(), 2);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].0, "Meta/broken.md");
+
+        let note = store
+            .get_note_by_path("Projects/vulcanos/architecture.md")
+            .unwrap()
+            .unwrap();
+        assert_eq!(note.title, "Architecture");
+        assert_eq!(note.note_type, NoteType::Project);
+        assert_eq!(note.project.as_deref(), Some("vulcanos"));
+        assert_eq!(note.tags, vec!["rust"]);
+
+        // Re-indexing skips unchanged files and keeps ids of changed ones
+        let report = indexer.index(&store).unwrap();
+        assert_eq!((report.unchanged, report.changed()), (2, 0));
+
+        write(
+            dir.path(),
+            "Projects/vulcanos/architecture.md",
+            "# Architecture v2\n",
+        );
+        fs::remove_file(dir.path().join("Agent-Memories/lessons/retry.md")).unwrap();
+        let report = indexer.index(&store).unwrap();
+        assert_eq!(report.updated, vec!["Projects/vulcanos/architecture.md"]);
+        assert_eq!(report.removed, vec!["Agent-Memories/lessons/retry.md"]);
+
+        let updated = store.get_note(&note.id).unwrap().unwrap();
+        assert_eq!(updated.title, "Architecture v2");
+        assert!(store
+            .get_note_by_path("Agent-Memories/lessons/retry.md")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_moved_file_keeps_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteStore::in_memory().unwrap();
+        let indexer = Indexer::new(dir.path());
+
+        // Notes created outside the indexer have no file and are never removed
+        let mut manual = Note::new("Manual", NoteType::Meta, "Meta/manual.md");
+        manual.content_hash = None;
+        store.save_note(&manual).unwrap();
+
+        write(
+            dir.path(),
+            "Learning/topics/ownership.md",
+            "---\nid: note-1\n---\nBody\n",
+        );
+        indexer.index(&store).unwrap();
+
+        fs::rename(
+            dir.path().join("Learning/topics/ownership.md"),
+            dir.path().join("Learning/topics/borrowing.md"),
+        )
+        .unwrap();
+        let report = indexer.index(&store).unwrap();
+        assert_eq!(
+            report.moved,
+            vec![(
+                "Learning/topics/ownership.md".to_string(),
+                "Learning/topics/borrowing.md".to_string()
+            )]
+        );
+        assert!(report.removed.is_empty());
+
+        let note = store.get_note("note-1").unwrap().unwrap();
+        assert_eq!(note.path, "Learning/topics/borrowing.md");
+        assert!(store.get_note(&manual.id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_moved_file_without_id_matches_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteStore::in_memory().unwrap();
+        let indexer = Indexer::new(dir.path());
+
+        write(dir.path(), "Meta/draft.md", "# Glossary\n");
+        write(dir.path(), "Meta/copy.md", "# Glossary\n");
+        indexer.index(&store).unwrap();
+        let id = store.get_note_by_path("Meta/draft.md").unwrap().unwrap().id;
+
+        fs::create_dir_all(dir.path().join("Learning")).unwrap();
+        fs::rename(
+            dir.path().join("Meta/draft.md"),
+            dir.path().join("Learning/glossary.md"),
+        )
+        .unwrap();
+        let report = indexer.index(&store).unwrap();
+        assert_eq!(report.moved.len(), 1);
+        assert!(report.added.is_empty() && report.removed.is_empty());
+
+        let note = store.get_note(&id).unwrap().unwrap();
+        assert_eq!(note.path, "Learning/glossary.md");
+        assert_eq!(note.note_type, NoteType::Learning);
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_eq!(content_hash("a"), "af63dc4c8601ec8c");
+        assert_ne!(content_hash("note"), content_hash("note "));
+    }
+}
@@ -0,0 +1,237 @@
+//! Rebuilding the embeddings of every note
+//!
+//! Notes are re-read from their files (the store only caches metadata),
+//! chunked and embedded several at a time. Every finished note is recorded
+//! in the store, so a rebuild that is interrupted, or that stops because the
+//! embedding server went away, carries on where it left off when run again.
+
+use futures_util::stream::{self, StreamExt};
+
+use super::{IndexError, IndexResult, Indexer};
+use crate::models::Note;
+use crate::rag::{Embedder, RagError, RagPipeline};
+use crate::store::Store;
+
+/// Notes embedded at the same time by default
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Options for [`Indexer::rebuild`]
+#[derive(Debug, Clone)]
+pub struct RebuildOptions {
+    /// Re-embed notes that already have chunks
+    pub force: bool,
+    /// Ignore the progress of an interrupted rebuild
+    pub restart: bool,
+    /// Notes embedded at the same time
+    pub concurrency: usize,
+}
+
+impl Default for RebuildOptions {
+    fn default() -> Self {
+        Self {
+            force: false,
+            restart: false,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+}
+
+/// Progress of a rebuild, reported as it happens
+#[derive(Debug, Clone)]
+pub enum RebuildEvent<'a> {
+    /// Notes to embed in this run, and notes done by an earlier one
+    Started { total: usize, resumed: usize },
+    /// A note was chunked and embedded
+    Embedded { path: &'a str, chunks: usize },
+    /// A note was skipped because of an error
+    Failed { path: &'a str, error: &'a str },
+}
+
+/// Summary of a rebuild
+#[derive(Debug, Clone, Default)]
+pub struct RebuildReport {
+    /// Notes embedded in this run
+    pub embedded: usize,
+    /// Chunks created in this run
+    pub chunks: usize,
+    /// Notes left alone because they already had chunks
+    pub skipped: usize,
+    /// Notes embedded by an earlier, interrupted run
+    pub resumed: usize,
+    /// (path, error) for notes that could not be embedded
+    pub errors: Vec<(String, String)>,
+}
+
+impl Indexer {
+    /// Chunk and embed the vault's notes, resuming an interrupted rebuild
+    ///
+    /// Errors limited to one note (an unreadable file, text the model
+    /// rejects) are reported and skipped. Store errors, and embedding errors
+    /// that persist through the pipeline's retries, stop the rebuild; its
+    /// progress is kept for the next run.
+    pub async fn rebuild<S: Store, E: Embedder>(
+        &self,
+        store: &S,
+        rag: &RagPipeline<E>,
+        options: &RebuildOptions,
+        mut on_event: impl FnMut(RebuildEvent),
+    ) -> IndexResult<RebuildReport> {
+        let model = rag.embedder().model();
+        if options.restart {
+            store.clear_rebuild_progress()?;
+        }
+        let done = store.rebuild_progress(model)?;
+        if done.is_empty() {
+            // Left over from a rebuild with another model
+            store.clear_rebuild_progress()?;
+        }
+
+        let mut report = RebuildReport {
+            resumed: done.len(),
+            ..RebuildReport::default()
+        };
+        let mut pending = Vec::new();
+        for note in store.list_notes(None, None, i64::MAX as usize)? {
+            if done.contains(&note.id) {
+                continue;
+            }
+            if !options.force && !store.get_chunks(&note.id)?.is_empty() {
+                report.skipped += 1;
+                continue;
+            }
+            pending.push(note);
+        }
+        on_event(RebuildEvent::Started {
+            total: pending.len(),
+            resumed: report.resumed,
+        });
+
+        let mut results = stream::iter(&pending)
+            .map(|stored| async move { (stored, self.embed_note(store, rag, stored).await) })
+            .buffer_unordered(options.concurrency.max(1));
+
+        while let Some((stored, result)) = results.next().await {
+            match result {
+                Ok(chunks) => {
+                    store.mark_rebuilt(&stored.id, model)?;
+                    store.dequeue_embedding(&stored.id)?;
+                    report.embedded += 1;
+                    report.chunks += chunks;
+                    on_event(RebuildEvent::Embedded {
+                        path: &stored.path,
+                        chunks,
+                    });
+                }
+                Err(e) if stops_rebuild(&e) => return Err(e),
+                Err(e) => {
+                    let error = e.to_string();
+                    on_event(RebuildEvent::Failed {
+                        path: &stored.path,
+                        error: &error,
+                    });
+                    report.errors.push((stored.path.clone(), error));
+                }
+            }
+        }
+
+        store.clear_rebuild_progress()?;
+        Ok(report)
+    }
+
+    /// Re-read one note and replace its chunks
+    async fn embed_note<S: Store, E: Embedder>(
+        &self,
+        store: &S,
+        rag: &RagPipeline<E>,
+        stored: &Note,
+    ) -> IndexResult<usize> {
+        let note = self.load_note(stored)?;
+        if note.content.trim().is_empty() {
+            // process_note skips empty notes; drop their stale chunks instead
+            rag.delete_note_chunks(store, &note.id)?;
+            return Ok(0);
+        }
+        Ok(rag.process_note(store, &note).await?)
+    }
+}
+
+/// Errors that would fail every remaining note too
+fn stops_rebuild(error: &IndexError) -> bool {
+    match error {
+        IndexError::Store(_) | IndexError::Rag(RagError::Store(_)) => true,
+        IndexError::Rag(RagError::Embedding(e)) => e.is_transient(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ChunkConfig;
+    use crate::rag::HashingEmbedder;
+    use crate::store::SqliteStore;
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_rebuild_resumes() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path();
+        fs::create_dir_all(vault.join("Learning/rust")).unwrap();
+        for name in ["borrowing", "lifetimes", "traits"] {
+            fs::write(
+                vault.join(format!("Learning/rust/{}.md", name)),
+                format!("# {}\n\nNotes about {} in Rust.", name, name),
+            )
+            .unwrap();
+        }
+        fs::write(vault.join("Learning/rust/empty.md"), "").unwrap();
+
+        let store = SqliteStore::in_memory().unwrap();
+        let indexer = Indexer::new(vault);
+        indexer.index(&store).unwrap();
+        let rag = RagPipeline::with_embedder(ChunkConfig::default(), HashingEmbedder::new(32));
+
+        // Pretend an earlier run embedded one note before it was stopped
+        let borrowing = store
+            .get_note_by_path("Learning/rust/borrowing.md")
+            .unwrap()
+            .unwrap();
+        store.mark_rebuilt(&borrowing.id, "hashing-v1").unwrap();
+
+        let mut events = Vec::new();
+        let options = RebuildOptions {
+            force: true,
+            ..RebuildOptions::default()
+        };
+        let report = indexer
+            .rebuild(&store, &rag, &options, |event| {
+                if let RebuildEvent::Started { total, resumed } = event {
+                    events.push((total, resumed));
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(events, vec![(3, 1)]);
+        assert_eq!((report.embedded, report.resumed), (3, 1));
+        assert_eq!(report.chunks, 2);
+        assert!(store.get_chunks(&borrowing.id).unwrap().is_empty());
+        assert!(store.rebuild_progress("hashing-v1").unwrap().is_empty());
+
+        // Without --force, notes that have chunks are left alone
+        let report = indexer
+            .rebuild(&store, &rag, &RebuildOptions::default(), |_| {})
+            .await
+            .unwrap();
+        assert_eq!((report.embedded, report.skipped), (2, 2));
+
+        // A missing file fails its note only
+        fs::remove_file(vault.join("Learning/rust/traits.md")).unwrap();
+        let report = indexer
+            .rebuild(&store, &rag, &options, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(report.embedded, 3);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].0, "Learning/rust/traits.md");
+    }
+}
@@ -0,0 +1,438 @@
+//! Watch daemon - keeps vault.db and embeddings in sync with the vault
+//!
+//! File events are collected until the vault has been quiet for the debounce
+//! interval, then the touched files are re-indexed and only the notes that
+//! actually changed are re-chunked and re-embedded. Notes waiting for
+//! embeddings stay queued while Ollama is unreachable and are retried
+//! periodically, together with notes the store's embedding queue holds for
+//! [`super::NoteWriter`].
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use super::{FileChange, IndexError, IndexReport, IndexResult, Indexer};
+use crate::models::Note;
+use crate::rag::{Embedder, EmbeddingError, OllamaEmbedder, RagError, RagPipeline};
+use crate::store::Store;
+
+/// Timing for the watch daemon
+#[derive(Debug, Clone)]
+pub struct WatchConfig {
+    /// Quiet period after the last file event before changes are applied
+    pub debounce: Duration,
+    /// How often queued embeddings are retried while Ollama is unreachable
+    pub retry_interval: Duration,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(500),
+            retry_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Watches a vault and keeps the store and its embeddings up to date
+pub struct VaultWatcher<E: Embedder = OllamaEmbedder> {
+    indexer: Indexer,
+    rag: RagPipeline<E>,
+    config: WatchConfig,
+    /// Notes waiting to be (re-)embedded, by id
+    queue: BTreeMap<String, Note>,
+}
+
+impl<E: Embedder> VaultWatcher<E> {
+    /// Create a watcher for the vault at `vault_dir`
+    pub fn new(vault_dir: impl Into<PathBuf>, rag: RagPipeline<E>, config: WatchConfig) -> Self {
+        Self {
+            indexer: Indexer::new(vault_dir),
+            rag,
+            config,
+            queue: BTreeMap::new(),
+        }
+    }
+
+    /// Number of notes waiting for embeddings
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Sync the whole vault, then apply file changes until Ctrl-C
+    ///
+    /// Only setting up the file watcher is an error. Failures while syncing
+    /// or applying changes, such as a database busy with another writer, are
+    /// logged, and the changed files are tried again after the retry
+    /// interval.
+    pub async fn run<S: Store>(&mut self, store: &S) -> IndexResult<()> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<notify::Event, notify::Error>| match res {
+                Ok(event) => {
+                    if !matches!(event.kind, EventKind::Access(_)) {
+                        for path in event.paths {
+                            let _ = tx.send(path);
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("Watch error: {}", e),
+            },
+            notify::Config::default(),
+        )?;
+        watcher.watch(self.indexer.vault_dir(), RecursiveMode::Recursive)?;
+
+        let mut pending: HashSet<PathBuf> = HashSet::new();
+        let mut deadline: Option<Instant> = None;
+        match self.sync(store) {
+            Ok(report) => log_report(&report),
+            Err(e) => {
+                tracing::warn!("Initial sync failed, retrying: {}", e);
+                pending.insert(self.indexer.vault_dir().to_path_buf());
+                deadline = Some(Instant::now() + self.config.retry_interval);
+            }
+        }
+        self.try_flush_queue(store).await;
+        tracing::info!("Watching {}", self.indexer.vault_dir().display());
+
+        let mut retry = tokio::time::interval(self.config.retry_interval);
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        loop {
+            tokio::select! {
+                Some(path) = rx.recv() => {
+                    pending.insert(path);
+                    deadline = Some(Instant::now() + self.config.debounce);
+                }
+                _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    deadline = None;
+                    let paths: Vec<PathBuf> = pending.drain().collect();
+                    match self.apply_changes(store, paths.clone()) {
+                        Ok(report) => log_report(&report),
+                        Err(e) => {
+                            tracing::warn!("Failed to apply changes, retrying: {}", e);
+                            pending.extend(paths);
+                            deadline = Some(Instant::now() + self.config.retry_interval);
+                        }
+                    }
+                    self.try_flush_queue(store).await;
+                }
+                _ = retry.tick(), if !self.queue.is_empty() => {
+                    self.try_flush_queue(store).await;
+                }
+                _ = &mut ctrl_c => {
+                    if !self.queue.is_empty() {
+                        tracing::warn!(
+                            "Stopping with {} notes still waiting for embeddings",
+                            self.queue.len()
+                        );
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Index the whole vault and queue every note that needs embeddings
+    ///
+    /// Besides changed notes, this picks up notes that have no chunks yet,
+    /// e.g. ones still queued when a previous daemon stopped.
+    pub fn sync<S: Store>(&mut self, store: &S) -> IndexResult<IndexReport> {
+        let vault_dir = self.indexer.vault_dir().to_path_buf();
+        let files = self.indexer.markdown_files(&vault_dir)?;
+        let mut report = self.apply_changes(store, files)?;
+        for path in self.indexer.remove_missing(store, &HashSet::new())? {
+            self.queue.retain(|_, note| note.path != path);
+            report.removed.push(path);
+        }
+
+        for note in store.list_notes(None, None, i64::MAX as usize)? {
+            if note.content_hash.is_none()
+                || self.queue.contains_key(&note.id)
+                || !store.get_chunks(&note.id)?.is_empty()
+            {
+                continue;
+            }
+            match self.indexer.load_note(&note) {
+                Ok(loaded) if !loaded.content.trim().is_empty() => {
+                    self.queue.insert(loaded.id.clone(), loaded);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Could not load {}: {}", note.path, e),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Re-index the given paths and queue changed notes for embedding
+    ///
+    /// Paths may be files or directories, existing or deleted; anything that
+    /// isn't a markdown file in the vault is ignored. Existing files are
+    /// indexed before deletions are handled so renames keep their note ids.
+    pub fn apply_changes<S: Store>(
+        &mut self,
+        store: &S,
+        paths: impl IntoIterator<Item = PathBuf>,
+    ) -> IndexResult<IndexReport> {
+        let mut report = IndexReport::default();
+        let mut files = Vec::new();
+        let mut any_missing = false;
+
+        for path in paths {
+            if path.is_dir() {
+                if self.indexer.relative_path(&path).is_ok() {
+                    files.extend(self.indexer.markdown_files(&path)?);
+                }
+            } else if path.exists() {
+                if self.indexer.is_indexable(&path) {
+                    files.push(path);
+                }
+            } else if self.may_hold_notes(&path) {
+                // A deleted file, or a directory that was removed or renamed
+                any_missing = true;
+            }
+        }
+        files.sort();
+        files.dedup();
+
+        for file in &files {
+            let rel = self.indexer.relative_path(file)?;
+            match self.indexer.index_file(store, file) {
+                Ok(FileChange::Added(note)) => {
+                    report.added.push(note.path.clone());
+                    self.queue.insert(note.id.clone(), note);
+                }
+                Ok(FileChange::Updated(note)) => {
+                    report.updated.push(note.path.clone());
+                    self.queue.insert(note.id.clone(), note);
+                }
+                Ok(FileChange::Moved { from, note }) => {
+                    // Chunks carry the note path, so moved notes are re-chunked too
+                    report.moved.push((from, note.path.clone()));
+                    self.queue.insert(note.id.clone(), note);
+                }
+                Ok(FileChange::Unchanged) => report.unchanged += 1,
+                Err(IndexError::Store(e)) => return Err(e.into()),
+                Err(e) => report.errors.push((rel, e.to_string())),
+            }
+        }
+
+        if any_missing {
+            for path in self.indexer.remove_missing(store, &HashSet::new())? {
+                self.queue.retain(|_, note| note.path != path);
+                report.removed.push(path);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// [`Self::flush_queue`], logging a failure instead of returning it
+    async fn try_flush_queue<S: Store>(&mut self, store: &S) {
+        if let Err(e) = self.flush_queue(store).await {
+            tracing::warn!("Failed to embed queued notes: {}", e);
+        }
+    }
+
+    /// Embed queued notes, stopping early if Ollama is unreachable
+    ///
+    /// Returns the number of notes embedded. Notes that fail for reasons a
+    /// retry won't fix are dropped from the queue with a warning.
+    pub async fn flush_queue<S: Store>(&mut self, store: &S) -> IndexResult<usize> {
+        self.load_store_queue(store)?;
+        let mut embedded = 0;
+        let mut unavailable = None;
+        let ids: Vec<String> = self.queue.keys().cloned().collect();
+
+        for id in ids {
+            let Some(note) = self.queue.get(&id) else {
+                continue;
+            };
+
+            let result = if note.content.trim().is_empty() {
+                // process_note skips empty notes; drop their stale chunks instead
+                self.rag.delete_note_chunks(store, &note.id).map(|_| 0)
+            } else if unavailable.is_some() {
+                continue;
+            } else {
+                self.rag.process_note(store, note).await
+            };
+
+            match result {
+                Ok(chunks) => {
+                    tracing::info!("Embedded {} ({} chunks)", note.path, chunks);
+                    self.queue.remove(&id);
+                    store.dequeue_embedding(&id)?;
+                    embedded += 1;
+                }
+                Err(RagError::Embedding(e)) if is_retryable(&e) => unavailable = Some(e),
+                Err(RagError::Store(e)) => return Err(e.into()),
+                Err(e) => {
+                    tracing::warn!("Skipping embeddings for {}: {}", note.path, e);
+                    self.queue.remove(&id);
+                    store.dequeue_embedding(&id)?;
+                }
+            }
+        }
+
+        if let Some(e) = unavailable {
+            tracing::warn!(
+                "Embedding unavailable ({}); {} notes queued, retrying in {:?}",
+                e,
+                self.queue.len(),
+                self.config.retry_interval
+            );
+        }
+        Ok(embedded)
+    }
+
+    /// Add notes queued in the store, e.g. written by the MCP server while
+    /// Ollama was down, to the in-memory queue
+    fn load_store_queue<S: Store>(&mut self, store: &S) -> IndexResult<()> {
+        for id in store.queued_embeddings()? {
+            if self.queue.contains_key(&id) {
+                continue;
+            }
+            let Some(stored) = store.get_note(&id)? else {
+                continue;
+            };
+            match self.indexer.load_note(&stored) {
+                Ok(note) => {
+                    self.queue.insert(id, note);
+                }
+                Err(e) => {
+                    tracing::warn!("Dropping {} from the embedding queue: {}", stored.path, e);
+                    store.dequeue_embedding(&id)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a deleted path could have been a note or a folder of notes
+    fn may_hold_notes(&self, path: &std::path::Path) -> bool {
+        let visible = self
+            .indexer
+            .relative_path(path)
+            .is_ok_and(|rel| !rel.split('/').any(|part| part.starts_with('.')));
+        visible && path.extension().is_none_or(|ext| ext == "md")
+    }
+}
+
+/// Errors that go away once Ollama is running (and the model is pulled)
+pub(super) fn is_retryable(error: &EmbeddingError) -> bool {
+    error.is_transient() || matches!(error, EmbeddingError::ModelNotFound { .. })
+}
+
+fn log_report(report: &IndexReport) {
+    for path in &report.added {
+        tracing::info!("Added {}", path);
+    }
+    for path in &report.updated {
+        tracing::info!("Updated {}", path);
+    }
+    for (from, to) in &report.moved {
+        tracing::info!("Moved {} -> {}", from, to);
+    }
+    for path in &report.removed {
+        tracing::info!("Removed {}", path);
+    }
+    for (path, error) in &report.errors {
+        tracing::warn!("Skipped {}: {}", path, error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ChunkConfig;
+    use crate::rag::RetryPolicy;
+    use crate::store::SqliteStore;
+    use std::fs;
+
+    /// Pipeline pointed at a closed port, as if Ollama were down
+    fn offline_watcher(dir: &std::path::Path) -> VaultWatcher {
+        let rag = RagPipeline::with_config(
+            ChunkConfig::default(),
+            "http://127.0.0.1:9",
+            "nomic-embed-text",
+        )
+        .with_retry(RetryPolicy::none());
+        VaultWatcher::new(dir, rag, WatchConfig::default())
+    }
+
+    #[test]
+    fn test_apply_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteStore::in_memory().unwrap();
+        let mut watcher = offline_watcher(dir.path());
+
+        let projects = dir.path().join("Projects/vulcanos");
+        fs::create_dir_all(&projects).unwrap();
+        fs::write(projects.join("a.md"), "# A\nalpha\n").unwrap();
+        fs::write(projects.join("b.md"), "# B\nbeta\n").unwrap();
+        fs::create_dir_all(dir.path().join(".obsidian")).unwrap();
+        fs::write(dir.path().join(".obsidian/workspace.json"), "{}").unwrap();
+
+        let report = watcher.sync(&store).unwrap();
+        assert_eq!(report.added.len(), 2);
+        assert_eq!(watcher.queued(), 2);
+        let id = store
+            .get_note_by_path("Projects/vulcanos/a.md")
+            .unwrap()
+            .unwrap()
+            .id;
+
+        // Renaming the project folder keeps ids; deleting drops the queued note
+        fs::rename(&projects, dir.path().join("Projects/vulcan")).unwrap();
+        let report = watcher
+            .apply_changes(
+                &store,
+                vec![projects.clone(), dir.path().join("Projects/vulcan")],
+            )
+            .unwrap();
+        assert_eq!(report.moved.len(), 2);
+        let note = store.get_note(&id).unwrap().unwrap();
+        assert_eq!(note.path, "Projects/vulcan/a.md");
+        assert_eq!(note.project.as_deref(), Some("vulcan"));
+
+        let deleted = dir.path().join("Projects/vulcan/b.md");
+        fs::remove_file(&deleted).unwrap();
+        let report = watcher.apply_changes(&store, vec![deleted]).unwrap();
+        assert_eq!(report.removed, vec!["Projects/vulcan/b.md"]);
+        assert_eq!(watcher.queued(), 1);
+
+        // Hidden files never reach the index
+        let report = watcher
+            .apply_changes(&store, vec![dir.path().join(".obsidian/workspace.json")])
+            .unwrap();
+        assert_eq!(report.changed() + report.unchanged, 0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_survives_ollama_outage() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteStore::in_memory().unwrap();
+        let mut watcher = offline_watcher(dir.path());
+
+        fs::write(dir.path().join("Welcome.md"), "# Welcome\nHello\n").unwrap();
+        fs::write(dir.path().join("Empty.md"), "").unwrap();
+        watcher.sync(&store).unwrap();
+        assert_eq!(watcher.queued(), 2);
+
+        // The empty note needs no embedding; the other waits for Ollama
+        assert_eq!(watcher.flush_queue(&store).await.unwrap(), 1);
+        assert_eq!(watcher.queued(), 1);
+
+        // A restarted daemon finds the note without chunks again
+        let mut restarted = offline_watcher(dir.path());
+        restarted.sync(&store).unwrap();
+        assert_eq!(restarted.queued(), 1);
+    }
+}
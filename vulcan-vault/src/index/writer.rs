@@ -0,0 +1,425 @@
+//! Writing notes created by agents and the TUI
+//!
+//! Every note written through [`NoteWriter`] ends up in three places at
+//! once: its Markdown file in the vault, its row (with links) in the store,
+//! and its chunks in the active embedding space. The stored content hash is
+//! that of the written file, so the indexer and the watch daemon treat the
+//! file as unchanged instead of indexing it a second time.
+//!
+//! When the embedding server is unreachable the note is still written and
+//! waits in the store's embedding queue; `vulcan-vault watch` and
+//! [`NoteWriter::embed_queued`] embed it once the server is back.
+
+use std::path::{Component, Path, PathBuf};
+
+use chrono::Utc;
+
+use super::watch::is_retryable;
+use super::{content_hash, IndexError, IndexResult, Indexer};
+use crate::models::Note;
+use crate::rag::{Embedder, OllamaEmbedder, RagError, RagPipeline};
+use crate::store::Store;
+
+/// What happened to a written note's embeddings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmbedStatus {
+    /// Chunked and embedded (no chunks for an empty note)
+    Embedded { chunks: usize },
+    /// The embedding server is unavailable; the note is queued
+    Queued { reason: String },
+    /// The embedder rejected the note; retrying won't help
+    Failed { reason: String },
+}
+
+/// Writes notes to the vault, the store and the embedding index together
+pub struct NoteWriter<E: Embedder = OllamaEmbedder> {
+    indexer: Indexer,
+    rag: RagPipeline<E>,
+}
+
+impl<E: Embedder> NoteWriter<E> {
+    /// Create a writer for the vault at `vault_dir`
+    ///
+    /// Embedding errors are retried according to the pipeline's
+    /// [`crate::rag::RetryPolicy`] before a note is queued.
+    pub fn new(vault_dir: impl Into<std::path::PathBuf>, rag: RagPipeline<E>) -> Self {
+        Self {
+            indexer: Indexer::new(vault_dir),
+            rag,
+        }
+    }
+
+    /// The vault notes are written to
+    pub fn vault_dir(&self) -> &Path {
+        self.indexer.vault_dir()
+    }
+
+    /// Write `note` to its file, save it with its links, and embed it
+    ///
+    /// If another note already lives at `note.path`, a numeric suffix is
+    /// added and `note.path` updated. A note that moved has its old file
+    /// removed once the store has saved it. `note.content_hash` is set to
+    /// the hash of the written file. Paths that would leave the vault are
+    /// refused with [`IndexError::OutsideVault`].
+    pub async fn write<S: Store + ?Sized>(
+        &self,
+        store: &S,
+        note: &mut Note,
+    ) -> IndexResult<EmbedStatus> {
+        self.vault_file(&note.path)?;
+        let previous = store.get_note(&note.id)?;
+        note.path = self.free_path(store, note)?;
+        if previous.is_some() {
+            note.modified = Utc::now();
+        }
+
+        let file = self.vault_file(&note.path)?;
+        // The hash describes the file, so it can't be part of it
+        note.content_hash = None;
+        let markdown = note.to_markdown();
+        std::fs::write(&file, &markdown)?;
+        note.content_hash = Some(content_hash(&markdown));
+
+        // Saving a note with content also replaces its links
+        let new_file = previous.as_ref().is_none_or(|p| p.path != note.path);
+        if let Err(e) = store.save_note(note) {
+            // A new file nothing refers to yet would be indexed as a
+            // separate note
+            if new_file {
+                let _ = std::fs::remove_file(&file);
+            }
+            return Err(e.into());
+        }
+
+        if let Some(previous) = previous.filter(|p| p.path != note.path) {
+            match std::fs::remove_file(self.vault_dir().join(&previous.path)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+
+        self.embed(store, note).await
+    }
+
+    /// Embed notes waiting in the store's embedding queue
+    ///
+    /// Stops at the first error that suggests the server is still down and
+    /// returns the number of notes embedded. Notes that are gone, or that
+    /// the embedder rejects, are dropped from the queue.
+    pub async fn embed_queued<S: Store + ?Sized>(&self, store: &S) -> IndexResult<usize> {
+        let mut embedded = 0;
+        for id in store.queued_embeddings()? {
+            let Some(stored) = store.get_note(&id)? else {
+                store.dequeue_embedding(&id)?;
+                continue;
+            };
+            let note = match self.indexer.load_note(&stored) {
+                Ok(note) => note,
+                Err(e) => {
+                    tracing::warn!("Dropping {} from the embedding queue: {}", stored.path, e);
+                    store.dequeue_embedding(&id)?;
+                    continue;
+                }
+            };
+            match self.embed(store, &note).await? {
+                EmbedStatus::Embedded { .. } => embedded += 1,
+                EmbedStatus::Queued { .. } => break,
+                EmbedStatus::Failed { reason } => {
+                    tracing::warn!(
+                        "Dropping {} from the embedding queue: {}",
+                        note.path,
+                        reason
+                    );
+                    store.dequeue_embedding(&id)?;
+                }
+            }
+        }
+        Ok(embedded)
+    }
+
+    /// Replace a stored note's chunks, queueing it if the server is down
+    async fn embed<S: Store + ?Sized>(&self, store: &S, note: &Note) -> IndexResult<EmbedStatus> {
+        let result = if note.content.trim().is_empty() {
+            // process_note skips empty notes; drop their stale chunks instead
+            self.rag.delete_note_chunks(store, &note.id).map(|_| 0)
+        } else {
+            self.rag.process_note(store, note).await
+        };
+
+        match result {
+            Ok(chunks) => {
+                store.dequeue_embedding(&note.id)?;
+                Ok(EmbedStatus::Embedded { chunks })
+            }
+            Err(RagError::Embedding(e)) if is_retryable(&e) => {
+                let reason = e.to_string();
+                tracing::warn!("Queued embeddings for {}: {}", note.path, reason);
+                store.queue_embedding(&note.id, &reason)?;
+                Ok(EmbedStatus::Queued { reason })
+            }
+            Err(RagError::Store(e)) => Err(e.into()),
+            Err(e) => {
+                store.dequeue_embedding(&note.id)?;
+                Ok(EmbedStatus::Failed {
+                    reason: e.to_string(),
+                })
+            }
+        }
+    }
+
+    /// The file of a vault-relative note path, with its folder created
+    ///
+    /// Absolute paths and `..` are refused, as is a folder that a symlink
+    /// takes out of the vault.
+    fn vault_file(&self, path: &str) -> IndexResult<PathBuf> {
+        let relative = Path::new(path);
+        let outside = || IndexError::OutsideVault(relative.to_path_buf());
+        if relative.as_os_str().is_empty()
+            || !relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(outside());
+        }
+
+        let file = self.vault_dir().join(relative);
+        if let Some(parent) = file.parent() {
+            // Check the deepest folder that exists before creating any, so a
+            // symlink out of the vault never gets folders made behind it
+            std::fs::create_dir_all(self.vault_dir())?;
+            let existing = parent
+                .ancestors()
+                .find(|dir| dir.exists())
+                .unwrap_or(self.vault_dir());
+            if !existing
+                .canonicalize()?
+                .starts_with(self.vault_dir().canonicalize()?)
+            {
+                return Err(outside());
+            }
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(file)
+    }
+
+    /// `note.path`, or the first `-2`, `-3`, ... variant no other note or
+    /// unindexed file occupies
+    fn free_path<S: Store + ?Sized>(&self, store: &S, note: &Note) -> IndexResult<String> {
+        let (stem, extension) = match note.path.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() && !extension.contains('/') => {
+                (stem, format!(".{}", extension))
+            }
+            _ => (note.path.as_str(), String::new()),
+        };
+
+        let mut suffix = 1;
+        loop {
+            let candidate = match suffix {
+                1 => note.path.clone(),
+                n => format!("{}-{}{}", stem, n, extension),
+            };
+            let free = match store.get_note_by_path(&candidate)? {
+                Some(other) => other.id == note.id,
+                None => !self.vault_dir().join(&candidate).exists(),
+            };
+            if free {
+                return Ok(candidate);
+            }
+            suffix += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChunkConfig, NoteType};
+    use crate::rag::{EmbeddingError, HashingEmbedder, RetryPolicy};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    /// Hashing embedder whose server can be switched off
+    #[derive(Clone, Default)]
+    struct FakeEmbedder {
+        inner: HashingEmbedder,
+        offline: Arc<AtomicBool>,
+    }
+
+    impl Embedder for FakeEmbedder {
+        fn model(&self) -> &str {
+            self.inner.model()
+        }
+
+        fn dimensions(&self) -> usize {
+            self.inner.dimensions()
+        }
+
+        async fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+            if self.offline.load(Ordering::SeqCst) {
+                return Err(EmbeddingError::ServiceUnavailable {
+                    url: "http://fake".to_string(),
+                });
+            }
+            Ok(self.inner.embed_sync(text))
+        }
+    }
+
+    fn writer(vault: &Path, embedder: FakeEmbedder) -> NoteWriter<FakeEmbedder> {
+        let rag = RagPipeline::with_embedder(ChunkConfig::default(), embedder)
+            .with_retry(RetryPolicy::none());
+        NoteWriter::new(vault, rag)
+    }
+
+    fn learning_note(title: &str, content: &str) -> Note {
+        let mut note = Note::learning_note(title, "rust");
+        note.content = content.to_string();
+        note
+    }
+
+    #[tokio::test]
+    async fn test_write_indexes_and_embeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = crate::store::SqliteStore::in_memory().unwrap();
+        let writer = writer(dir.path(), FakeEmbedder::default());
+
+        let mut target = learning_note("Lifetimes", "# Lifetimes\n\nHow long references live.");
+        writer.write(&store, &mut target).await.unwrap();
+        let mut note = learning_note(
+            "Ownership",
+            "# Ownership\n\nValues have one owner. See [[Lifetimes]].",
+        );
+        let status = writer.write(&store, &mut note).await.unwrap();
+        assert_eq!(status, EmbedStatus::Embedded { chunks: 1 });
+
+        // The file, the row, the links and the chunks all exist
+        let file = dir.path().join(&note.path);
+        let markdown = std::fs::read_to_string(&file).unwrap();
+        assert_eq!(Some(content_hash(&markdown)), note.content_hash);
+        assert!(markdown.ends_with("See [[Lifetimes]]."));
+        assert!(store.get_note(&note.id).unwrap().is_some());
+        assert_eq!(
+            store.get_outlinks(&note.id).unwrap()[0].target_id,
+            target.id
+        );
+        assert_eq!(store.get_chunks(&note.id).unwrap().len(), 1);
+
+        // The indexer sees the file as already indexed
+        let indexer = Indexer::new(dir.path());
+        assert!(matches!(
+            indexer.index_file(&store, &file).unwrap(),
+            super::super::FileChange::Unchanged
+        ));
+
+        // Notes with the same title don't overwrite each other
+        let mut twin = learning_note("Ownership", "# Ownership\n\nAnother take.");
+        writer.write(&store, &mut twin).await.unwrap();
+        assert_ne!(twin.path, note.path);
+        assert!(twin.path.ends_with("-2.md"));
+
+        // Moving a note removes its old file
+        let old = note.path.clone();
+        note.path = "Learning/rust/moved.md".to_string();
+        writer.write(&store, &mut note).await.unwrap();
+        assert!(!dir.path().join(old).exists());
+        assert!(dir.path().join("Learning/rust/moved.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_write_stays_in_vault() {
+        let root = tempfile::tempdir().unwrap();
+        let vault = root.path().join("vault");
+        let store = crate::store::SqliteStore::in_memory().unwrap();
+        let writer = writer(&vault, FakeEmbedder::default());
+
+        for path in ["../escape.md", "Learning/../../escape.md", "/tmp/escape.md"] {
+            let mut note = Note::new("Escape", NoteType::Meta, path);
+            note.content = "# Escape".to_string();
+            assert!(matches!(
+                writer.write(&store, &mut note).await,
+                Err(IndexError::OutsideVault(_))
+            ));
+            assert!(store.get_note(&note.id).unwrap().is_none());
+        }
+        assert!(!root.path().join("escape.md").exists());
+
+        // Folder names from tool arguments are slugged by the constructors
+        let mut note = Note::learning_note("Escape", "../..");
+        note.content = "# Escape".to_string();
+        writer.write(&store, &mut note).await.unwrap();
+        assert!(vault.join(&note.path).exists());
+
+        // No folders are made behind a symlink that leaves the vault
+        #[cfg(unix)]
+        {
+            let outside = root.path().join("outside");
+            std::fs::create_dir(&outside).unwrap();
+            std::os::unix::fs::symlink(&outside, vault.join("Linked")).unwrap();
+            let mut note = Note::new("Escape", NoteType::Meta, "Linked/deep/escape.md");
+            note.content = "# Escape".to_string();
+            assert!(matches!(
+                writer.write(&store, &mut note).await,
+                Err(IndexError::OutsideVault(_))
+            ));
+            assert!(!outside.join("deep").exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_save_keeps_old_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("vault.db");
+        let vault = dir.path().join("vault");
+        let store = crate::store::SqliteStore::new(&db).unwrap();
+        let writer = writer(&vault, FakeEmbedder::default());
+
+        let mut note = learning_note("Ownership", "# Ownership\n\nValues have one owner.");
+        writer.write(&store, &mut note).await.unwrap();
+        let old = note.path.clone();
+
+        // The store refuses the move: the old file stays, the new one goes
+        rusqlite::Connection::open(&db)
+            .unwrap()
+            .execute_batch(
+                "CREATE TRIGGER no_updates BEFORE UPDATE ON notes
+                 BEGIN SELECT RAISE(ABORT, 'read-only'); END;",
+            )
+            .unwrap();
+        note.path = "Learning/rust/moved.md".to_string();
+        assert!(writer.write(&store, &mut note).await.is_err());
+        assert!(vault.join(&old).exists());
+        assert!(!vault.join("Learning/rust/moved.md").exists());
+        assert_eq!(store.get_note(&note.id).unwrap().unwrap().path, old);
+    }
+
+    #[tokio::test]
+    async fn test_queue_while_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = crate::store::SqliteStore::in_memory().unwrap();
+        let embedder = FakeEmbedder::default();
+        let writer = writer(dir.path(), embedder.clone());
+
+        embedder.offline.store(true, Ordering::SeqCst);
+        let mut note = Note::new("Checkpoint", NoteType::Meta, "Meta/checkpoint.md");
+        note.content = "# Checkpoint\n\nBefore the refactor.".to_string();
+        let status = writer.write(&store, &mut note).await.unwrap();
+        assert!(matches!(status, EmbedStatus::Queued { .. }));
+        assert!(dir.path().join(&note.path).exists());
+        assert!(store.get_chunks(&note.id).unwrap().is_empty());
+        assert_eq!(store.queued_embeddings().unwrap(), vec![note.id.clone()]);
+
+        // Still down: nothing is lost
+        assert_eq!(writer.embed_queued(&store).await.unwrap(), 0);
+        assert_eq!(store.queued_embeddings().unwrap().len(), 1);
+
+        embedder.offline.store(false, Ordering::SeqCst);
+        assert_eq!(writer.embed_queued(&store).await.unwrap(), 1);
+        assert!(store.queued_embeddings().unwrap().is_empty());
+        assert_eq!(store.get_chunks(&note.id).unwrap().len(), 1);
+
+        // Deleting a note takes it out of the queue
+        embedder.offline.store(true, Ordering::SeqCst);
+        writer.write(&store, &mut note).await.unwrap();
+        store.delete_note(&note.id).unwrap();
+        assert!(store.queued_embeddings().unwrap().is_empty());
+    }
+}
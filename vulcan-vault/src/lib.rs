@@ -34,22 +34,38 @@
 //! └─────────────────────────────────────────────────────────────────┘
 //! ```
 
+pub mod config;
 pub mod models;
 pub mod store;
 pub mod mcp;
 pub mod rag;
 pub mod memory;
+pub mod index;
+pub mod graph;
+pub mod context;
+pub mod maintenance;
 
 #[cfg(feature = "tui")]
 pub mod ui;
 
 pub use models::{Note, NoteType, NoteStatus, Chunk, ChunkConfig, Memory, MemoryType, PrpPhase, PhaseStatus};
-pub use store::{Store, SqliteStore, StoreError, SearchResult, LinkInfo, VaultStats};
-pub use rag::{RagPipeline, RagError, RagResult, Chunker, EmbeddingService, EmbeddingError};
+pub use store::{Store, SqliteStore, StoreError, SearchResult, KeywordResult, EmbeddingSpace, LinkInfo, OrphanedRows, UnresolvedLink, VaultStats};
+pub use context::{assemble_context, AssembledContext, ContextError, ContextRequest, ContextResult, ContextSection, ContextSource};
+pub use graph::{extract_links, extract_tags, related_notes, LinkKind, ParsedLink, RelatedNote};
+pub use rag::{
+    RagPipeline, RagError, RagResult, Chunker, Embedder, EmbeddingService, EmbeddingError,
+    AnyEmbedder, OllamaEmbedder, OpenAiEmbedder, HashingEmbedder,
+    SearchFilters, SearchHit, SearchMode, SearchOutcome, ReembedOptions, ReembedReport,
+};
+pub use maintenance::{maintain, MaintainOptions, MaintainReport, MaintainStep};
+pub use config::{Config, ConfigError, ConfigResult, EmbeddingConfig, EmbeddingProvider};
+pub use index::{Indexer, IndexReport, IndexError, IndexResult, FileChange, RebuildOptions, RebuildReport, VaultWatcher, WatchConfig};
 pub use memory::{
     MemoryFormation, FormationError, FormationResult, LessonSource, SessionEvent,
     MemoryRetrieval, RetrievalConfig, RetrievalError, RetrievalResult, ScoredMemory,
     MemoryDecay, DecayConfig, DecayError, DecayReport, DecayResult, CleanupReport,
+    MemoryConsolidation, ConsolidationConfig, ConsolidationError, ConsolidationReport,
+    ConsolidationResult, MemoryConflict, MergedMemories,
 };
 
 /// Re-export for convenience
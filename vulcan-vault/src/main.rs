@@ -2,15 +2,25 @@
 //!
 //! Usage:
 //!   vulcan-vault --mcp          # Run as MCP server
-//!   vulcan-vault query "text"   # Semantic search
+//!   vulcan-vault query "text"   # Hybrid keyword + semantic search
+//!   vulcan-vault context "text" # Token-budgeted context for an agent prompt
 //!   vulcan-vault list           # List notes
 //!   vulcan-vault stats          # Show statistics
+//!   vulcan-vault index          # Sync vault files into the database
+//!   vulcan-vault watch          # Keep the index and embeddings in sync
+//!   vulcan-vault reembed -m X   # Switch the vault to embedding model X
+//!   vulcan-vault memory consolidate  # Merge duplicate memories
+//!   vulcan-vault maintain       # Decay, archive and clean up (for timers)
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use vulcan_vault::{config_dir, db_path, vault_dir, SqliteStore, Store};
+use vulcan_vault::config::load_embedder;
+use vulcan_vault::{
+    config_dir, db_path, vault_dir, ChunkConfig, Embedder, SearchFilters, SearchMode, SqliteStore,
+    Store,
+};
 
 #[derive(Parser)]
 #[command(name = "vulcan-vault")]
@@ -49,28 +59,107 @@ enum Commands {
         limit: usize,
     },
 
-    /// Semantic search query
+    /// Search notes by keywords and meaning
     Query {
         /// Search query text
         query: String,
 
+        /// Search mode: hybrid, semantic or keyword
+        #[arg(short, long, default_value = "hybrid")]
+        mode: SearchMode,
+
+        /// Filter by note type (project, task, learning, memory, meta)
+        #[arg(short = 't', long)]
+        note_type: Option<String>,
+
         /// Filter by project
         #[arg(short, long)]
         project: Option<String>,
 
+        /// Filter by tag (repeatable, matches any)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
         /// Maximum results
         #[arg(short, long, default_value = "10")]
         limit: usize,
     },
 
+    /// Print prompt-ready context for a query or task within a token budget
+    Context {
+        /// What you are working on
+        query: Option<String>,
+
+        /// vulcan-todo task whose linked notes lead the context
+        #[arg(long)]
+        task: Option<String>,
+
+        /// Maximum estimated tokens
+        #[arg(short, long, default_value = "4000")]
+        budget: usize,
+
+        /// Limit note matches to a project
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Leave out agent memories
+        #[arg(long)]
+        no_memories: bool,
+    },
+
     /// Show vault statistics
     Stats,
 
+    /// Index markdown files from the vault into the database
+    Index,
+
+    /// Watch the vault and keep the index and embeddings in sync
+    Watch {
+        /// Quiet period in milliseconds before applying a burst of changes
+        #[arg(long, default_value = "500")]
+        debounce: u64,
+    },
+
     /// Rebuild embeddings for all notes
     Rebuild {
         /// Force rebuild even if unchanged
         #[arg(long)]
         force: bool,
+
+        /// Start over instead of resuming an interrupted rebuild
+        #[arg(long)]
+        restart: bool,
+
+        /// Notes embedded at the same time
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+    },
+
+    /// Re-embed the vault with another model and switch searches to it
+    Reembed {
+        /// Embedding model, served by the provider in config.toml
+        #[arg(short, long)]
+        model: String,
+
+        /// Vector length the model produces (default 768)
+        #[arg(long)]
+        dimensions: Option<usize>,
+
+        /// Texts embedded per request
+        #[arg(long, default_value = "32")]
+        batch_size: usize,
+
+        /// Requests sent at the same time
+        #[arg(long, default_value = "4")]
+        concurrency: usize,
+
+        /// Only backfill; keep searching with the current model
+        #[arg(long)]
+        no_switch: bool,
+
+        /// Delete the previous model's vectors after switching
+        #[arg(long, conflicts_with = "no_switch")]
+        drop_old: bool,
     },
 
     /// Get context for a task
@@ -91,6 +180,10 @@ enum Commands {
         /// Context for retrieval
         #[arg(short, long, default_value = "general")]
         context: String,
+
+        /// ID of an earlier memory this one replaces
+        #[arg(long)]
+        supersedes: Option<String>,
     },
 
     /// Recall memories
@@ -107,11 +200,44 @@ enum Commands {
         limit: usize,
     },
 
+    /// Decay and archive memories, clean up and compact the database
+    ///
+    /// Meant for a systemd timer or cron: exits non-zero if any step failed.
+    Maintain {
+        /// Show what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip compacting the database file
+        #[arg(long)]
+        no_vacuum: bool,
+    },
+
+    /// Maintain agent memories
+    Memory {
+        #[command(subcommand)]
+        command: MemoryCommands,
+    },
+
     /// Launch interactive TUI
     #[cfg(feature = "tui")]
     Tui,
 }
 
+#[derive(Subcommand)]
+enum MemoryCommands {
+    /// Merge duplicate memories and flag contradictory decisions
+    Consolidate {
+        /// Show what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Similarity (0.0-1.0) at which memories count as duplicates
+        #[arg(long, default_value = "0.9")]
+        threshold: f32,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
@@ -169,23 +295,68 @@ async fn main() -> Result<()> {
 
         Some(Commands::Query {
             query,
+            mode,
+            note_type,
             project,
+            tags,
             limit,
         }) => {
-            // For now, do keyword search (semantic search requires embeddings)
-            let notes = store.search_notes(&query, limit)?;
+            let filters = SearchFilters {
+                note_types: note_type.as_ref().and_then(|s| parse_note_type(s)).map(|t| vec![t]),
+                project,
+                tags: if tags.is_empty() { None } else { Some(tags) },
+            };
+            let embedder = load_embedder()?;
+            let outcome =
+                vulcan_vault::rag::search(&store, &embedder, &query, mode, &filters, limit).await?;
 
-            if notes.is_empty() {
+            if mode != outcome.mode {
+                println!("(Embeddings unavailable - showing {} results)\n", outcome.mode);
+            }
+
+            if outcome.hits.is_empty() {
                 println!("No matching notes found.");
             } else {
-                println!("Found {} matching notes:\n", notes.len());
-                for note in notes {
-                    println!("  {} ({})", note.title, note.path);
+                println!("Found {} matching notes:\n", outcome.hits.len());
+                for hit in outcome.hits {
+                    match &hit.heading {
+                        Some(heading) => {
+                            println!("  {} ({}) > {}", hit.note_title, hit.note_path, heading)
+                        }
+                        None => println!("  {} ({})", hit.note_title, hit.note_path),
+                    }
+                    let excerpt = hit.content.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if !excerpt.is_empty() {
+                        println!("      {}", truncate(&excerpt, 100));
+                    }
                 }
             }
+        }
 
-            if project.is_some() {
-                println!("\n(Note: semantic search with project filter requires embeddings)");
+        Some(Commands::Context { query, task, budget, project, no_memories }) => {
+            use vulcan_vault::{assemble_context, ContextRequest};
+
+            let request = ContextRequest {
+                query,
+                task_id: task,
+                project,
+                token_budget: budget,
+                include_memories: !no_memories,
+                ..ContextRequest::default()
+            };
+            let embedder = load_embedder()?;
+            let context = assemble_context(&store, &embedder, &request).await?;
+            if context.sections.is_empty() {
+                eprintln!("No matching notes or memories found.");
+            } else {
+                println!("{}", context.markdown);
+                eprintln!(
+                    "{} sections, ~{} of {} tokens ({} search)",
+                    context.sections.len(),
+                    context.tokens,
+                    context.token_budget,
+                    context.search_mode
+                );
             }
         }
 
@@ -196,6 +367,9 @@ async fn main() -> Result<()> {
             println!("Total Notes:    {}", stats.total_notes);
             println!("Total Chunks:   {}", stats.total_chunks);
             println!("Total Links:    {}", stats.total_links);
+            if stats.unresolved_links > 0 {
+                println!("Unresolved:     {}", stats.unresolved_links);
+            }
             println!("Total Memories: {}", stats.total_memories);
             println!("\nNotes by Type:");
             for (note_type, count) in &stats.notes_by_type {
@@ -204,66 +378,217 @@ async fn main() -> Result<()> {
             if !stats.projects.is_empty() {
                 println!("\nProjects: {}", stats.projects.join(", "));
             }
+            let spaces = store.list_embedding_spaces()?;
+            if !spaces.is_empty() {
+                println!("\nEmbedding Spaces:");
+                for space in spaces {
+                    println!(
+                        "  {} {} ({}d): {} chunks, {} memories",
+                        if space.active { "*" } else { " " },
+                        space.model,
+                        space.dimensions,
+                        space.chunks,
+                        space.memories
+                    );
+                }
+            }
+        }
+
+        Some(Commands::Index) => {
+            use vulcan_vault::Indexer;
+
+            let vault = cli.path.clone().unwrap_or_else(vault_dir);
+            let report = Indexer::new(&vault).index(&store)?;
+
+            for path in &report.added {
+                println!("  + {}", path);
+            }
+            for path in &report.updated {
+                println!("  ~ {}", path);
+            }
+            for (from, to) in &report.moved {
+                println!("  > {} -> {}", from, to);
+            }
+            for path in &report.removed {
+                println!("  - {}", path);
+            }
+            for (path, error) in &report.errors {
+                eprintln!("  ! {}: {}", path, error);
+            }
+
+            println!("\nIndexed {}:", vault.display());
+            println!("  Added:     {}", report.added.len());
+            println!("  Updated:   {}", report.updated.len());
+            println!("  Moved:     {}", report.moved.len());
+            println!("  Removed:   {}", report.removed.len());
+            println!("  Unchanged: {}", report.unchanged);
+            if !report.errors.is_empty() {
+                println!("  Errors:    {}", report.errors.len());
+            }
+        }
+
+        Some(Commands::Watch { debounce }) => {
+            use vulcan_vault::{RagPipeline, VaultWatcher, WatchConfig};
+
+            let vault = cli.path.clone().unwrap_or_else(vault_dir);
+            if !vault.exists() {
+                eprintln!("Error: no vault at {}. Create it with: vulcan-vault init", vault.display());
+                std::process::exit(1);
+            }
+
+            let config = WatchConfig {
+                debounce: std::time::Duration::from_millis(debounce),
+                ..WatchConfig::default()
+            };
+            let rag = RagPipeline::with_embedder(ChunkConfig::default(), load_embedder()?);
+            let mut watcher = VaultWatcher::new(&vault, rag, config);
+            println!("Watching {} (Ctrl-C to stop)", vault.display());
+            watcher.run(&store).await?;
         }
 
-        Some(Commands::Rebuild { force }) => {
+        Some(Commands::Rebuild { force, restart, concurrency }) => {
+            use vulcan_vault::index::{Indexer, RebuildEvent, RebuildOptions};
             use vulcan_vault::RagPipeline;
 
             println!("Rebuilding embeddings{}...", if force { " (force)" } else { "" });
 
-            // Initialize RAG pipeline
-            let rag = RagPipeline::new();
+            // Initialize RAG pipeline with the configured embedder
+            let rag = RagPipeline::with_embedder(ChunkConfig::default(), load_embedder()?);
 
-            // Check if Ollama is available
+            // Check if the embedding service is available
             match rag.health_check().await {
                 Ok(true) => {}
                 Ok(false) => {
-                    eprintln!("Error: Ollama is not available. Start it with: ollama serve");
+                    eprintln!(
+                        "Error: embedding model {} is not available. For Ollama, start it with: ollama serve",
+                        rag.embedder().model()
+                    );
                     std::process::exit(1);
                 }
                 Err(e) => {
-                    eprintln!("Error checking Ollama: {}", e);
+                    eprintln!("Error checking embedding service: {}", e);
                     std::process::exit(1);
                 }
             }
 
-            // Get all notes
-            let notes = store.list_notes(None, None, 10000)?;
-            println!("Found {} notes to process.", notes.len());
-
-            let mut processed = 0;
-            let mut chunks_total = 0;
-            let mut errors = 0;
-
-            for note in &notes {
-                // If not forcing, skip notes that already have chunks (basic check)
-                if !force {
-                    let existing = store.get_chunks(&note.id)?;
-                    if !existing.is_empty() {
-                        continue;
-                    }
-                }
-
-                match rag.process_note(&store, note).await {
-                    Ok(chunk_count) => {
-                        if chunk_count > 0 {
-                            println!("  {} -> {} chunks", truncate(&note.title, 40), chunk_count);
-                            chunks_total += chunk_count;
-                            processed += 1;
+            let vault = cli.path.clone().unwrap_or_else(vault_dir);
+            let options = RebuildOptions { force, restart, concurrency };
+            let bar = progress_bar(0, "notes");
+            let result = Indexer::new(&vault)
+                .rebuild(&store, &rag, &options, |event| match event {
+                    RebuildEvent::Started { total, resumed } => {
+                        if resumed > 0 {
+                            bar.println(format!("Resuming: {} notes already done.", resumed));
                         }
+                        bar.set_length(total as u64);
                     }
-                    Err(e) => {
-                        eprintln!("  Error processing {}: {}", note.title, e);
-                        errors += 1;
+                    RebuildEvent::Embedded { path, .. } => {
+                        bar.set_message(truncate(path, 40));
+                        bar.inc(1);
                     }
+                    RebuildEvent::Failed { path, error } => {
+                        bar.println(format!("  Error processing {}: {}", path, error));
+                        bar.inc(1);
+                    }
+                })
+                .await;
+            bar.finish_and_clear();
+
+            let report = match result {
+                Ok(report) => report,
+                Err(e) => {
+                    eprintln!("Rebuild stopped: {}", e);
+                    eprintln!("Progress is saved; run `vulcan-vault rebuild` again to resume.");
+                    std::process::exit(1);
                 }
-            }
+            };
 
             println!("\nRebuild complete:");
-            println!("  Notes processed: {}", processed);
-            println!("  Chunks created:  {}", chunks_total);
-            if errors > 0 {
-                println!("  Errors:          {}", errors);
+            println!("  Notes processed: {}", report.embedded + report.resumed);
+            println!("  Chunks created:  {}", report.chunks);
+            if report.skipped > 0 {
+                println!("  Already embedded: {}", report.skipped);
+            }
+            if !report.errors.is_empty() {
+                println!("  Errors:          {}", report.errors.len());
+            }
+        }
+
+        Some(Commands::Reembed { model, dimensions, batch_size, concurrency, no_switch, drop_old }) => {
+            use vulcan_vault::rag::reembed;
+            use vulcan_vault::{Config, ReembedOptions};
+
+            let mut embedding = Config::load()?.embedding;
+            let configured = embedding.build().map(|e| (e.model().to_string(), e.dimensions())).ok();
+            // The configured dimension belongs to the configured model
+            if configured.as_ref().map(|(m, _)| m) != Some(&model) {
+                embedding.dimensions = None;
+            }
+            embedding.model = Some(model.clone());
+            embedding.dimensions = dimensions.or(embedding.dimensions);
+            let embedder = embedding.build()?;
+            if embedder.model() != model {
+                eprintln!("Error: the configured provider only serves {}", embedder.model());
+                std::process::exit(1);
+            }
+
+            match embedder.health_check().await {
+                Ok(true) => {}
+                Ok(false) => {
+                    eprintln!("Error: embedding model {} is not available", model);
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Error checking embedding service: {}", e);
+                    std::process::exit(1);
+                }
+            }
+
+            println!("Embedding the vault with {} ({} dimensions)...", model, embedder.dimensions());
+            let options = ReembedOptions {
+                batch_size,
+                activate: !no_switch,
+                concurrency,
+                ..ReembedOptions::default()
+            };
+            let stats = store.get_stats()?;
+            let (chunks_done, memories_done) = store
+                .list_embedding_spaces()?
+                .into_iter()
+                .find(|space| space.model == model)
+                .map_or((0, 0), |space| (space.chunks, space.memories));
+            let missing = stats.total_chunks.saturating_sub(chunks_done)
+                + stats.total_memories.saturating_sub(memories_done);
+            let bar = progress_bar(missing, "texts");
+            let report = reembed(&store, &embedder, &options, |done| bar.set_position(done as u64)).await;
+            bar.finish_and_clear();
+            let report = report?;
+            println!("  Chunks embedded:   {}", report.chunks);
+            println!("  Memories embedded: {}", report.memories);
+
+            if !report.activated {
+                println!(
+                    "\nBackfill complete; searches still use {}.",
+                    report.previous.as_deref().unwrap_or("no model")
+                );
+            } else {
+                println!("\nSearches now use {}.", model);
+                if let Some(previous) = report.previous.filter(|p| *p != model) {
+                    if drop_old {
+                        store.delete_embedding_space(&previous)?;
+                        println!("Deleted the {} vectors.", previous);
+                    } else {
+                        println!("The {} vectors are kept; switch back with: vulcan-vault reembed --model {}", previous, previous);
+                    }
+                }
+                if configured != Some((model.clone(), embedder.dimensions())) {
+                    println!(
+                        "Set model = \"{}\" and dimensions = {} under [embedding] in {} so new notes and queries use it.",
+                        model,
+                        embedder.dimensions(),
+                        Config::path().display()
+                    );
+                }
             }
         }
 
@@ -283,6 +608,7 @@ async fn main() -> Result<()> {
             memory_type,
             content,
             context,
+            supersedes,
         }) => {
             use vulcan_vault::{Memory, MemoryType};
 
@@ -292,7 +618,14 @@ async fn main() -> Result<()> {
                 _ => MemoryType::Lesson,
             };
 
-            let memory = Memory::new(mem_type, &content[..40.min(content.len())], &content, &context, "cli");
+            let mut memory = Memory::new(mem_type, &content[..40.min(content.len())], &content, &context, "cli");
+            if let Some(id) = supersedes {
+                if store.get_memory(&id)?.is_none() {
+                    eprintln!("Error: no memory with ID {}", id);
+                    std::process::exit(1);
+                }
+                memory = memory.with_supersedes(id);
+            }
 
             store.save_memory(&memory)?;
             println!("Memory recorded: {}", memory.id);
@@ -319,6 +652,132 @@ async fn main() -> Result<()> {
             }
         }
 
+        Some(Commands::Maintain { dry_run, no_vacuum }) => {
+            use std::sync::Arc;
+            use vulcan_vault::{maintain, Config, MaintainOptions};
+
+            let vault = cli.path.clone().unwrap_or_else(vault_dir);
+            let options = MaintainOptions { dry_run, vacuum: !no_vacuum };
+            let report = maintain(Arc::new(store), &vault, Config::load()?.decay, &options);
+
+            if dry_run {
+                println!("Dry run - nothing will be changed.\n");
+            }
+            match report.last_run {
+                Some(last) => println!("Last run: {}", last.format("%Y-%m-%d %H:%M:%S UTC")),
+                None => println!("Last run: never"),
+            }
+            if let Some(decay) = &report.decay {
+                println!(
+                    "Decay: {} of {} memories {}, {} below the archive threshold",
+                    decay.decayed,
+                    decay.processed,
+                    if dry_run { "would decay" } else { "decayed" },
+                    decay.below_threshold
+                );
+            }
+            if dry_run {
+                println!("Archive: {} memories below the threshold now", report.archived.len());
+                for memory in &report.archived {
+                    println!(
+                        "  [{}] {} (confidence: {:.2})",
+                        memory.memory_type,
+                        truncate(&memory.title, 50),
+                        memory.confidence
+                    );
+                }
+            } else {
+                println!(
+                    "Archive: {} memories moved to {}",
+                    report.archived.len(),
+                    report.archive_path.display()
+                );
+            }
+            if let Some(orphans) = &report.orphans {
+                println!(
+                    "Orphans: {} rows {} ({} chunks, {} chunk vectors, {} memory vectors, {} full-text)",
+                    orphans.total(),
+                    if dry_run { "found" } else { "removed" },
+                    orphans.chunks,
+                    orphans.chunk_vectors,
+                    orphans.memory_vectors,
+                    orphans.fts_rows
+                );
+            }
+            if report.optimized {
+                println!("Optimised full-text indexes");
+            }
+            if let Some(reclaimed) = report.reclaimed {
+                println!("Vacuum: reclaimed {} KiB", reclaimed / 1024);
+            }
+
+            for (step, error) in &report.failures {
+                eprintln!("Error: {} failed: {}", step, error);
+            }
+            if !report.succeeded() {
+                std::process::exit(1);
+            }
+        }
+
+        Some(Commands::Memory { command: MemoryCommands::Consolidate { dry_run, threshold } }) => {
+            use std::sync::Arc;
+            use vulcan_vault::{ConsolidationConfig, MemoryConsolidation};
+
+            let config = ConsolidationConfig {
+                duplicate_similarity: threshold,
+                ..ConsolidationConfig::default()
+            };
+            let consolidation = MemoryConsolidation::with_config(Arc::new(store), load_embedder()?, config);
+            let report = if dry_run {
+                consolidation.preview().await?
+            } else {
+                consolidation.consolidate().await?
+            };
+
+            let verb = if dry_run { "Would merge" } else { "Merged" };
+            for merged in &report.merged {
+                println!(
+                    "{} {} duplicate(s) into \"{}\" ({}, applied {} times)",
+                    verb,
+                    merged.merged_ids.len(),
+                    truncate(&merged.title, 40),
+                    &merged.kept_id[..8],
+                    merged.times_applied
+                );
+            }
+            for conflict in &report.conflicts {
+                println!(
+                    "Conflicting decisions in {}: \"{}\" ({}) vs \"{}\" ({})",
+                    conflict.context,
+                    truncate(&conflict.first.1, 30),
+                    conflict.first.0,
+                    truncate(&conflict.second.1, 30),
+                    conflict.second.0
+                );
+            }
+            println!(
+                "\n{} memories examined, {} {}, {} conflict(s){}",
+                report.examined,
+                report.removed(),
+                if dry_run { "would be merged away" } else { "merged away" },
+                report.conflicts.len(),
+                if dry_run || report.conflicts.is_empty() {
+                    String::new()
+                } else {
+                    format!(" tagged {}", vulcan_vault::memory::REVIEW_TAG)
+                }
+            );
+            if report.unembedded > 0 {
+                println!(
+                    "{} memories without an embedding were left out; run without --dry-run to embed and compare them.",
+                    report.unembedded
+                );
+            }
+            if !report.conflicts.is_empty() {
+                println!("Resolve a conflict by recording the decision that stands with --supersedes <old-id>.");
+            }
+        }
+
         #[cfg(feature = "tui")]
         Some(Commands::Tui) => {
             use std::sync::Arc;
@@ -423,11 +882,21 @@ fn parse_note_type(s: &str) -> Option<vulcan_vault::NoteType> {
     }
 }
 
+/// Progress bar with an ETA for long-running commands
+fn progress_bar(len: u64, unit: &str) -> indicatif::ProgressBar {
+    let style = indicatif::ProgressStyle::with_template(&format!(
+        "{{bar:40}} {{pos}}/{{len}} {} (ETA {{eta}}) {{msg}}",
+        unit
+    ))
+    .expect("valid progress template");
+    indicatif::ProgressBar::new(len).with_style(style)
+}
+
 /// Truncate string to max length
 fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
+    if s.chars().count() <= max {
         s.to_string()
     } else {
-        format!("{}...", &s[..max - 3])
+        format!("{}...", s.chars().take(max - 3).collect::<String>())
     }
 }
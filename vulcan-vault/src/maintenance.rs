@@ -0,0 +1,220 @@
+//! Scheduled upkeep of the vault database
+//!
+//! `vulcan-vault maintain` runs the steps below, meant to be triggered by a
+//! systemd timer or cron:
+//!
+//! 1. Decay the confidence of unused memories by the time elapsed since the
+//!    last run, recorded in the store
+//! 2. Archive memories that fell below the minimum confidence
+//! 3. Delete index rows whose note, chunk or memory is gone
+//! 4. Optimise the full-text indexes
+//! 5. VACUUM the database
+//!
+//! A failing step doesn't stop the others; the failures are collected in the
+//! report so the command can exit non-zero after doing what it could.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::memory::{DecayConfig, DecayError, DecayReport, MemoryDecay};
+use crate::models::Memory;
+use crate::store::{OrphanedRows, Store};
+
+/// Options for [`maintain`]
+#[derive(Debug, Clone)]
+pub struct MaintainOptions {
+    /// Report what would change without changing anything
+    pub dry_run: bool,
+    /// Rebuild the database file at the end
+    pub vacuum: bool,
+}
+
+impl Default for MaintainOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            vacuum: true,
+        }
+    }
+}
+
+/// A maintenance step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintainStep {
+    Decay,
+    Archive,
+    Orphans,
+    Optimize,
+    Vacuum,
+}
+
+impl std::fmt::Display for MaintainStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaintainStep::Decay => write!(f, "decay"),
+            MaintainStep::Archive => write!(f, "archive"),
+            MaintainStep::Orphans => write!(f, "orphan cleanup"),
+            MaintainStep::Optimize => write!(f, "optimise"),
+            MaintainStep::Vacuum => write!(f, "vacuum"),
+        }
+    }
+}
+
+/// What a maintenance run did, or would do in a dry run
+#[derive(Debug, Clone, Default)]
+pub struct MaintainReport {
+    /// Whether nothing was changed
+    pub dry_run: bool,
+    /// When maintenance last ran before this run
+    pub last_run: Option<DateTime<Utc>>,
+    /// Confidence decay since the last run
+    pub decay: Option<DecayReport>,
+    /// Memories archived; in a dry run, those already below the threshold
+    pub archived: Vec<Memory>,
+    /// Directory archived memories are written to
+    pub archive_path: PathBuf,
+    /// Orphaned index rows removed (or found, in a dry run)
+    pub orphans: Option<OrphanedRows>,
+    /// Whether the full-text indexes were optimised
+    pub optimized: bool,
+    /// Bytes reclaimed by VACUUM, if it ran
+    pub reclaimed: Option<u64>,
+    /// Steps that failed, with their errors
+    pub failures: Vec<(MaintainStep, String)>,
+}
+
+impl MaintainReport {
+    /// Whether every step succeeded
+    pub fn succeeded(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Run every maintenance step on the vault at `vault_dir`
+///
+/// The last-run time is recorded as soon as decay has been applied, so a
+/// later failing step doesn't make the next run decay the same period
+/// twice.
+pub fn maintain<S: Store>(
+    store: Arc<S>,
+    vault_dir: &Path,
+    config: DecayConfig,
+    options: &MaintainOptions,
+) -> MaintainReport {
+    let mut report = MaintainReport {
+        dry_run: options.dry_run,
+        archive_path: vault_dir.join(&config.archive_dir),
+        ..MaintainReport::default()
+    };
+    let decay = MemoryDecay::with_config(store.clone(), config);
+    let started = Utc::now();
+
+    let result = store
+        .last_maintenance()
+        .map_err(DecayError::from)
+        .and_then(|last_run| {
+            report.last_run = last_run;
+            if options.dry_run {
+                return decay.preview_decay_since(last_run);
+            }
+            let decayed = decay.apply_decay_since(last_run)?;
+            store.set_last_maintenance(started)?;
+            Ok(decayed)
+        });
+    match result {
+        Ok(decayed) => report.decay = Some(decayed),
+        Err(e) => report.failures.push((MaintainStep::Decay, e.to_string())),
+    }
+
+    let result = decay.preview_cleanup().and_then(|memories| {
+        if !options.dry_run && !memories.is_empty() {
+            decay.cleanup(vault_dir)?;
+        }
+        Ok(memories)
+    });
+    match result {
+        Ok(memories) => report.archived = memories,
+        Err(e) => report.failures.push((MaintainStep::Archive, e.to_string())),
+    }
+
+    let result = if options.dry_run {
+        store.count_orphaned_rows()
+    } else {
+        store.remove_orphaned_rows()
+    };
+    match result {
+        Ok(orphans) => report.orphans = Some(orphans),
+        Err(e) => report.failures.push((MaintainStep::Orphans, e.to_string())),
+    }
+
+    if options.dry_run {
+        return report;
+    }
+
+    match store.optimize() {
+        Ok(()) => report.optimized = true,
+        Err(e) => report
+            .failures
+            .push((MaintainStep::Optimize, e.to_string())),
+    }
+
+    if options.vacuum {
+        match store.vacuum() {
+            Ok(reclaimed) => report.reclaimed = Some(reclaimed),
+            Err(e) => report.failures.push((MaintainStep::Vacuum, e.to_string())),
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::SqliteStore;
+
+    #[test]
+    fn test_maintain() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let mut fading = Memory::lesson("Fading", "Hardly used", "testing", "agent");
+        fading.created = Utc::now() - chrono::Duration::days(60);
+        fading.confidence = 0.5;
+        let fresh = Memory::lesson("Fresh", "Just learned", "testing", "agent");
+        store.save_memory(&fading).unwrap();
+        store.save_memory(&fresh).unwrap();
+
+        let dry_run = MaintainOptions {
+            dry_run: true,
+            ..MaintainOptions::default()
+        };
+        let report = maintain(store.clone(), dir.path(), DecayConfig::default(), &dry_run);
+        assert!(report.succeeded());
+        assert_eq!(report.decay.as_ref().unwrap().below_threshold, 1);
+        assert!(report.archived.is_empty());
+        assert!(store.last_maintenance().unwrap().is_none());
+
+        let report = maintain(
+            store.clone(),
+            dir.path(),
+            DecayConfig::default(),
+            &MaintainOptions::default(),
+        );
+        assert!(report.succeeded(), "{:?}", report.failures);
+        assert_eq!(report.decay.unwrap().decayed, 1);
+        assert_eq!(report.archived.len(), 1);
+        assert!(report.optimized && report.reclaimed.is_some());
+        assert!(store.get_memory(&fading.id).unwrap().is_none());
+        assert_eq!(std::fs::read_dir(&report.archive_path).unwrap().count(), 1);
+
+        // Right after a run there is nothing left to decay
+        let last_run = store.last_maintenance().unwrap();
+        assert!(last_run.is_some());
+        let report = maintain(store.clone(), dir.path(), DecayConfig::default(), &dry_run);
+        assert_eq!(report.last_run, last_run);
+        assert_eq!(report.decay.unwrap().decayed, 0);
+    }
+}
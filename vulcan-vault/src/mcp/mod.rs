@@ -1,11 +1,14 @@
 //! MCP (Model Context Protocol) server implementation
 //!
-//! Exposes vulcan-vault functionality as MCP tools for AI agents.
+//! Exposes vulcan-vault functionality as MCP tools for AI agents, and vault
+//! content as MCP resources and prompts.
 //! Uses JSON-RPC 2.0 protocol over stdio.
 
 
 mod server;
 mod protocol;
+mod prompts;
+mod resources;
 mod tools;
 
 pub use server::run_server;
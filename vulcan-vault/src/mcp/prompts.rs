@@ -0,0 +1,210 @@
+//! MCP prompts for the common agent workflows
+//!
+//! Each prompt fills in what the vault already knows (project overview,
+//! memories, earlier PRPs and checkpoints) and tells the agent which tools to
+//! call to finish the job.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use crate::store::Store;
+use crate::{Memory, SqliteStore};
+
+use super::protocol::{PromptArgument, PromptDefinition};
+use super::resources::{note_uri, project_overview, project_uri};
+
+/// Get all prompt definitions for the prompts/list response
+pub fn get_prompt_definitions() -> Vec<PromptDefinition> {
+    vec![
+        PromptDefinition {
+            name: "start_task".to_string(),
+            description: "Start a task with the project overview and relevant memories".to_string(),
+            arguments: vec![
+                argument("task", "What you are about to work on", true),
+                argument("project", "Project the task belongs to", false),
+            ],
+        },
+        PromptDefinition {
+            name: "write_prp".to_string(),
+            description: "Write a Product Requirements Prompt (PRP) for a feature".to_string(),
+            arguments: vec![
+                argument("feature", "The feature to plan", true),
+                argument("project", "Project the feature belongs to", true),
+            ],
+        },
+        PromptDefinition {
+            name: "save_checkpoint".to_string(),
+            description: "Summarise the session and save it as a checkpoint".to_string(),
+            arguments: vec![
+                argument("session_id", "Current session ID", true),
+                argument("name", "Checkpoint name", false),
+            ],
+        },
+    ]
+}
+
+fn argument(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        description: description.to_string(),
+        required,
+    }
+}
+
+/// Fill in a prompt for the prompts/get response
+pub fn get_prompt(store: &SqliteStore, name: &str, args: Value) -> Result<Value> {
+    let (description, messages) = match name {
+        "start_task" => start_task(store, &args)?,
+        "write_prp" => write_prp(store, &args)?,
+        "save_checkpoint" => save_checkpoint(store, &args)?,
+        _ => return Err(anyhow::anyhow!("Unknown prompt: {}", name)),
+    };
+
+    Ok(json!({
+        "description": description,
+        "messages": messages
+    }))
+}
+
+fn start_task(store: &SqliteStore, args: &Value) -> Result<(String, Vec<Value>)> {
+    let task = required(args, "task")?;
+    let project = args.get("project").and_then(|v| v.as_str());
+
+    let mut messages = Vec::new();
+    if let Some(project) = project {
+        messages.extend(project_resource(store, project)?);
+    }
+
+    let memories = store.search_memories(project.unwrap_or("development"), None, 0.3, 10)?;
+    if !memories.is_empty() {
+        messages.push(text(format!(
+            "Memories from earlier work:\n\n{}",
+            list_memories(&memories)
+        )));
+    }
+
+    messages.push(text(format!(
+        "I'm starting work on: {}\n\n\
+         Use the context above before changing anything. If something is missing, \
+         search the vault with `semantic_search` or `assemble_context`, and recall more \
+         with `recall_memories`. As you work, record what you learn with `record_lesson` \
+         and the choices you make with `record_decision`.",
+        task
+    )));
+
+    Ok((format!("Start task: {}", task), messages))
+}
+
+fn write_prp(store: &SqliteStore, args: &Value) -> Result<(String, Vec<Value>)> {
+    let feature = required(args, "feature")?;
+    let project = required(args, "project")?;
+
+    let mut messages = Vec::new();
+    messages.extend(project_resource(store, project)?);
+
+    let decisions = store.search_memories(project, Some("decision"), 0.3, 10)?;
+    if !decisions.is_empty() {
+        messages.push(text(format!(
+            "Decisions already made for {}:\n\n{}",
+            project,
+            list_memories(&decisions)
+        )));
+    }
+
+    messages.push(text(format!(
+        "Write a PRP (Product Requirements Prompt) for this feature of {}: {}\n\n\
+         It needs:\n\
+         - **Value**: why we are building it\n\
+         - **Scope**: what exactly is built, and what is left out\n\
+         - **Success criteria**: how we know it works, each one checkable\n\
+         - **Implementation phases**: small steps, each with a name, a description \
+         and an effort (small, medium or large)\n\n\
+         Keep it consistent with the project's earlier PRPs and decisions. When it's \
+         ready, save it with the `create_prp` tool.",
+        project, feature
+    )));
+
+    Ok((format!("Write a PRP for {}", feature), messages))
+}
+
+fn save_checkpoint(store: &SqliteStore, args: &Value) -> Result<(String, Vec<Value>)> {
+    let session_id = required(args, "session_id")?;
+    let name = args.get("name").and_then(|v| v.as_str());
+
+    let mut messages = Vec::new();
+    let previous = store.latest_checkpoint(session_id)?;
+    if let Some(previous) = &previous {
+        messages.push(text(format!(
+            "The last checkpoint of this session is {} (id: {}): {}",
+            previous
+                .checkpoint_name
+                .as_deref()
+                .unwrap_or(&previous.title),
+            previous.id,
+            note_uri(previous)
+        )));
+    }
+
+    let parent = match &previous {
+        Some(previous) => format!(", `parent_checkpoint` \"{}\"", previous.id),
+        None => String::new(),
+    };
+    let name = match name {
+        Some(name) => format!("`name` \"{}\"", name),
+        None => "a short `name` for where the work stands".to_string(),
+    };
+    messages.push(text(format!(
+        "Save a checkpoint of this session so the work can be picked up later.\n\n\
+         Summarise what was done, what is in progress, the open questions and the next \
+         steps, so that someone without this conversation can continue. Then call the \
+         `save_checkpoint` tool with {}, `session_id` \"{}\"{}, the summary as \
+         `context_summary`, and the IDs of the tasks still open as `active_tasks`.",
+        name, session_id, parent
+    )));
+
+    Ok((
+        format!("Save a checkpoint of session {}", session_id),
+        messages,
+    ))
+}
+
+fn required<'a>(args: &'a Value, name: &str) -> Result<&'a str> {
+    args.get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing {}", name))
+}
+
+/// A user message with a text content item
+fn text(text: String) -> Value {
+    json!({
+        "role": "user",
+        "content": { "type": "text", "text": text }
+    })
+}
+
+/// A user message embedding the project overview, unless the project has
+/// no notes yet
+fn project_resource(store: &SqliteStore, project: &str) -> Result<Option<Value>> {
+    if store.get_notes_by_project(project)?.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(json!({
+        "role": "user",
+        "content": {
+            "type": "resource",
+            "resource": {
+                "uri": project_uri(project),
+                "mimeType": "text/markdown",
+                "text": project_overview(store, project)?
+            }
+        }
+    })))
+}
+
+fn list_memories(memories: &[Memory]) -> String {
+    memories
+        .iter()
+        .map(|m| format!("- [{}] {}: {}", m.memory_type, m.title, m.content))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
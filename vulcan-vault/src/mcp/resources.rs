@@ -0,0 +1,398 @@
+//! MCP resources: vault content an agent can read without a tool call
+//!
+//! | URI                       | MIME type          | Content                          |
+//! |---------------------------|--------------------|----------------------------------|
+//! | `vault://note/<path>`     | `text/markdown`    | The note's Markdown file         |
+//! | `vault://project/<name>`  | `text/markdown`    | Overview of the project's notes  |
+//! | `vault://prp/<id>`        | `application/json` | A PRP's fields and content       |
+//! | `vault://checkpoint/<id>` | `application/json` | A checkpoint's fields and content |
+//!
+//! Paths and project names are percent-encoded in URIs.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use crate::index::Indexer;
+use crate::store::Store;
+use crate::{Note, NoteType, SqliteStore};
+
+use super::protocol::{ResourceDefinition, ResourceTemplate};
+
+const MARKDOWN: &str = "text/markdown";
+const JSON: &str = "application/json";
+
+/// Resources per resources/list page
+const PAGE_SIZE: usize = 100;
+
+/// A resource the server knows how to read
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum VaultUri {
+    Note(String),
+    Project(String),
+    Prp(String),
+    Checkpoint(String),
+}
+
+impl VaultUri {
+    fn parse(uri: &str) -> Result<Self> {
+        let (kind, rest) = uri
+            .strip_prefix("vault://")
+            .and_then(|rest| rest.split_once('/'))
+            .ok_or_else(|| anyhow::anyhow!("Not a vault resource: {}", uri))?;
+        let value = decode(rest).ok_or_else(|| anyhow::anyhow!("Invalid resource URI: {}", uri))?;
+        if value.is_empty() {
+            return Err(anyhow::anyhow!("Invalid resource URI: {}", uri));
+        }
+
+        match kind {
+            "note" => Ok(VaultUri::Note(value)),
+            "project" => Ok(VaultUri::Project(value)),
+            "prp" => Ok(VaultUri::Prp(value)),
+            "checkpoint" => Ok(VaultUri::Checkpoint(value)),
+            _ => Err(anyhow::anyhow!("Unknown resource type: {}", kind)),
+        }
+    }
+}
+
+impl std::fmt::Display for VaultUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, value) = match self {
+            VaultUri::Note(path) => ("note", path),
+            VaultUri::Project(name) => ("project", name),
+            VaultUri::Prp(id) => ("prp", id),
+            VaultUri::Checkpoint(id) => ("checkpoint", id),
+        };
+        write!(f, "vault://{}/{}", kind, encode(value))
+    }
+}
+
+/// Percent-encode everything but unreserved characters and `/`
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Undo [`encode`]; `None` for malformed escapes or invalid UTF-8
+fn decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Get the resource templates for the resources/templates/list response
+pub fn get_resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        ResourceTemplate {
+            uri_template: "vault://note/{path}".to_string(),
+            name: "Note".to_string(),
+            description: "A vault note's Markdown file, by its path in the vault".to_string(),
+            mime_type: MARKDOWN.to_string(),
+        },
+        ResourceTemplate {
+            uri_template: "vault://project/{name}".to_string(),
+            name: "Project overview".to_string(),
+            description: "The notes and PRPs of a project, grouped by note type".to_string(),
+            mime_type: MARKDOWN.to_string(),
+        },
+        ResourceTemplate {
+            uri_template: "vault://prp/{id}".to_string(),
+            name: "PRP".to_string(),
+            description: "A Product Requirements Prompt with its phases and success criteria"
+                .to_string(),
+            mime_type: JSON.to_string(),
+        },
+        ResourceTemplate {
+            uri_template: "vault://checkpoint/{id}".to_string(),
+            name: "Checkpoint".to_string(),
+            description: "A saved session checkpoint".to_string(),
+            mime_type: JSON.to_string(),
+        },
+    ]
+}
+
+/// List resources for the resources/list response
+///
+/// Projects come first, then PRPs, checkpoints and every note. The cursor
+/// is the offset of the next page; only the notes on the page are loaded.
+pub fn list_resources(store: &SqliteStore, cursor: Option<&str>) -> Result<Value> {
+    let stats = store.get_stats()?;
+    let projects = stats.projects;
+    let documents = DOCUMENT_TYPES
+        .iter()
+        .filter_map(|t| stats.notes_by_type.get(&t.to_string()))
+        .sum::<u64>() as usize;
+    let total = projects.len() + documents + stats.total_notes as usize;
+
+    let offset = match cursor {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .ok()
+            .filter(|&offset| offset <= total)
+            .ok_or_else(|| anyhow::anyhow!("Invalid cursor: {}", cursor))?,
+        None => 0,
+    };
+
+    let mut resources: Vec<ResourceDefinition> = projects
+        .iter()
+        .skip(offset)
+        .take(PAGE_SIZE)
+        .map(|project| project_resource(project))
+        .collect();
+
+    let start = offset.saturating_sub(projects.len());
+    if resources.len() < PAGE_SIZE && start < documents {
+        let notes =
+            store.list_notes_page(Some(&DOCUMENT_TYPES), start, PAGE_SIZE - resources.len())?;
+        resources.extend(notes.iter().map(document_resource));
+    }
+
+    let start = offset.saturating_sub(projects.len() + documents);
+    if resources.len() < PAGE_SIZE {
+        let notes = store.list_notes_page(None, start, PAGE_SIZE - resources.len())?;
+        resources.extend(notes.iter().map(note_resource));
+    }
+
+    let mut result = json!({ "resources": resources });
+    let next = offset.saturating_add(PAGE_SIZE);
+    if next < total {
+        result["nextCursor"] = json!(next.to_string());
+    }
+    Ok(result)
+}
+
+/// Note types listed as resources of their own, besides their file
+const DOCUMENT_TYPES: [NoteType; 2] = [NoteType::Prp, NoteType::Checkpoint];
+
+fn project_resource(project: &str) -> ResourceDefinition {
+    ResourceDefinition {
+        uri: VaultUri::Project(project.to_string()).to_string(),
+        name: format!("Project: {}", project),
+        description: Some(format!("Overview of the {} project", project)),
+        mime_type: MARKDOWN.to_string(),
+    }
+}
+
+/// The JSON resource of a PRP or checkpoint
+fn document_resource(note: &Note) -> ResourceDefinition {
+    match note.note_type {
+        NoteType::Prp => ResourceDefinition {
+            uri: VaultUri::Prp(note.id.clone()).to_string(),
+            name: format!("PRP: {}", note.title),
+            description: note.prp_value.clone(),
+            mime_type: JSON.to_string(),
+        },
+        _ => ResourceDefinition {
+            uri: VaultUri::Checkpoint(note.id.clone()).to_string(),
+            name: format!(
+                "Checkpoint: {}",
+                note.checkpoint_name.as_deref().unwrap_or(&note.title)
+            ),
+            description: note
+                .session_id
+                .as_ref()
+                .map(|session| format!("Session {}, {}", session, note.created.to_rfc3339())),
+            mime_type: JSON.to_string(),
+        },
+    }
+}
+
+fn note_resource(note: &Note) -> ResourceDefinition {
+    ResourceDefinition {
+        uri: VaultUri::Note(note.path.clone()).to_string(),
+        name: note.title.clone(),
+        description: Some(match &note.project {
+            Some(project) => format!("{} note in {}", note.note_type, project),
+            None => format!("{} note", note.note_type),
+        }),
+        mime_type: MARKDOWN.to_string(),
+    }
+}
+
+/// Read a resource for the resources/read response
+///
+/// Notes are read from `vault_dir`, and only at paths the store knows about.
+pub fn read_resource(store: &SqliteStore, vault_dir: &Path, uri: &str) -> Result<Value> {
+    let (mime_type, text) = match VaultUri::parse(uri)? {
+        VaultUri::Note(path) => {
+            let note = store
+                .get_note_by_path(&path)?
+                .ok_or_else(|| anyhow::anyhow!("Note not found: {}", path))?;
+            let markdown = std::fs::read_to_string(vault_dir.join(&note.path))
+                .map_err(|e| anyhow::anyhow!("Cannot read {}: {}", note.path, e))?;
+            (MARKDOWN, markdown)
+        }
+        VaultUri::Project(name) => (MARKDOWN, project_overview(store, &name)?),
+        VaultUri::Prp(id) => (JSON, note_json(store, vault_dir, &id, NoteType::Prp)?),
+        VaultUri::Checkpoint(id) => (
+            JSON,
+            note_json(store, vault_dir, &id, NoteType::Checkpoint)?,
+        ),
+    };
+
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": mime_type,
+            "text": text
+        }]
+    }))
+}
+
+/// Markdown listing a project's notes by type, linking to their resources
+pub(crate) fn project_overview(store: &SqliteStore, project: &str) -> Result<String> {
+    let notes = store.get_notes_by_project(project)?;
+    if notes.is_empty() {
+        return Err(anyhow::anyhow!("Project not found: {}", project));
+    }
+
+    let mut by_type: BTreeMap<String, Vec<&Note>> = BTreeMap::new();
+    for note in &notes {
+        by_type
+            .entry(note.note_type.to_string())
+            .or_default()
+            .push(note);
+    }
+
+    let count = match notes.len() {
+        1 => "1 note".to_string(),
+        n => format!("{} notes", n),
+    };
+    let mut overview = format!("# Project: {}\n\n{}\n", project, count);
+    for (note_type, notes) in by_type {
+        overview.push_str(&format!("\n## {}\n\n", note_type));
+        for note in notes {
+            overview.push_str(&format!("- [{}]({})", note.title, note_uri(note)));
+            if let Some(value) = &note.prp_value {
+                overview.push_str(&format!(": {}", value));
+            }
+            overview.push('\n');
+        }
+    }
+    Ok(overview)
+}
+
+/// A PRP or checkpoint as JSON, with the body of its file as `content`
+fn note_json(
+    store: &SqliteStore,
+    vault_dir: &Path,
+    id: &str,
+    note_type: NoteType,
+) -> Result<String> {
+    let note = store
+        .get_note(id)?
+        .filter(|n| n.note_type == note_type)
+        .ok_or_else(|| anyhow::anyhow!("{} not found: {}", note_type, id))?;
+
+    // Notes created directly in the store have no file
+    let content = Indexer::new(vault_dir)
+        .load_note(&note)
+        .ok()
+        .map(|n| n.content.trim_start().to_string());
+    let mut value = serde_json::to_value(&note)?;
+    value["content"] = json!(content);
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// URI of a project's overview
+pub(crate) fn project_uri(project: &str) -> String {
+    VaultUri::Project(project.to_string()).to_string()
+}
+
+/// URI of a note's resource, for prompts that embed notes
+pub(crate) fn note_uri(note: &Note) -> String {
+    match note.note_type {
+        NoteType::Prp => VaultUri::Prp(note.id.clone()),
+        NoteType::Checkpoint => VaultUri::Checkpoint(note.id.clone()),
+        _ => VaultUri::Note(note.path.clone()),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uri_round_trip() {
+        let uri = VaultUri::Note("Projects/my app/ideas #1.md".to_string());
+        assert_eq!(
+            uri.to_string(),
+            "vault://note/Projects/my%20app/ideas%20%231.md"
+        );
+        assert_eq!(VaultUri::parse(&uri.to_string()).unwrap(), uri);
+
+        assert!(VaultUri::parse("vault://note/").is_err());
+        assert!(VaultUri::parse("vault://task/123").is_err());
+        assert!(VaultUri::parse("file:///etc/passwd").is_err());
+        assert!(VaultUri::parse("vault://note/bad%2").is_err());
+    }
+
+    #[test]
+    fn test_list_resources_pages() {
+        let store = SqliteStore::in_memory().unwrap();
+        for i in 0..120 {
+            let note = if i % 10 == 0 {
+                Note::prp_note(format!("Plan {}", i), "atlas", "Value", "Scope")
+            } else {
+                Note::project_note(format!("Note {}", i), "atlas")
+            };
+            store.save_note(&note).unwrap();
+        }
+
+        // One project, 12 PRPs and 120 note files
+        let mut uris = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let page = list_resources(&store, cursor.as_deref()).unwrap();
+            let resources = page["resources"].as_array().unwrap();
+            assert!(resources.len() <= PAGE_SIZE);
+            uris.extend(
+                resources
+                    .iter()
+                    .map(|r| r["uri"].as_str().unwrap().to_string()),
+            );
+            match page.get("nextCursor") {
+                Some(next) => cursor = Some(next.as_str().unwrap().to_string()),
+                None => break,
+            }
+        }
+        assert_eq!(uris.len(), 133);
+        assert_eq!(
+            uris.iter().collect::<std::collections::HashSet<_>>().len(),
+            133
+        );
+        assert_eq!(uris[0], "vault://project/atlas");
+        assert!(uris[1..13].iter().all(|u| u.starts_with("vault://prp/")));
+        assert!(uris[13..].iter().all(|u| u.starts_with("vault://note/")));
+
+        // Cursors past the end are refused rather than overflowing
+        let last = list_resources(&store, Some("133")).unwrap();
+        assert!(last["resources"].as_array().unwrap().is_empty());
+        assert!(last.get("nextCursor").is_none());
+        for cursor in ["134", "18446744073709551615", "-1", "next"] {
+            assert!(list_resources(&store, Some(cursor)).is_err(), "{}", cursor);
+        }
+    }
+}
@@ -1,11 +1,12 @@
 //! MCP server implementation
 
 use anyhow::Result;
-use std::io::{BufRead, Write};
+use std::path::Path;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
 
-use crate::{db_path, SqliteStore};
+use crate::{db_path, vault_dir, SqliteStore};
 
 /// JSON-RPC request
 #[derive(Debug, Deserialize)]
@@ -38,19 +39,32 @@ struct RpcError {
 pub async fn run_server() -> Result<()> {
     let store = SqliteStore::new(db_path())?;
 
-    let stdin = std::io::stdin();
-    let mut stdout = std::io::stdout();
-
     tracing::info!("vulcan-vault MCP server started");
 
-    for line in stdin.lock().lines() {
-        let line = line?;
+    let stdin = tokio::io::BufReader::new(tokio::io::stdin());
+    serve(&store, &vault_dir(), stdin, tokio::io::stdout()).await
+}
+
+/// Answer newline-delimited JSON-RPC requests until `reader` is closed
+///
+/// Notes are read from `vault_dir` for resources.
+async fn serve<R, W>(store: &SqliteStore, vault_dir: &Path, reader: R, mut writer: W) -> Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = reader.lines();
+    while let Some(line) = lines.next_line().await? {
         if line.is_empty() {
             continue;
         }
 
         let response = match serde_json::from_str::<Request>(&line) {
-            Ok(request) => handle_request(&store, request).await,
+            // Notifications (like notifications/initialized) get no response
+            Ok(request) if request.id.is_none() && request.method.starts_with("notifications/") => {
+                continue;
+            }
+            Ok(request) => handle_request(store, vault_dir, request).await,
             Err(e) => Response {
                 jsonrpc: "2.0".to_string(),
                 id: None,
@@ -62,19 +76,26 @@ pub async fn run_server() -> Result<()> {
             },
         };
 
-        let response_json = serde_json::to_string(&response)?;
-        writeln!(stdout, "{}", response_json)?;
-        stdout.flush()?;
+        let mut response_json = serde_json::to_string(&response)?;
+        response_json.push('\n');
+        writer.write_all(response_json.as_bytes()).await?;
+        writer.flush().await?;
     }
 
     Ok(())
 }
 
-async fn handle_request(store: &SqliteStore, request: Request) -> Response {
+async fn handle_request(store: &SqliteStore, vault_dir: &Path, request: Request) -> Response {
     let result = match request.method.as_str() {
         "initialize" => handle_initialize(),
+        "ping" => Ok(serde_json::json!({})),
         "tools/list" => handle_tools_list(),
         "tools/call" => handle_tool_call(store, request.params).await,
+        "resources/list" => handle_resources_list(store, request.params),
+        "resources/templates/list" => handle_resource_templates_list(),
+        "resources/read" => handle_resource_read(store, vault_dir, request.params),
+        "prompts/list" => handle_prompts_list(),
+        "prompts/get" => handle_prompt_get(store, request.params),
         _ => Err(anyhow::anyhow!("Method not found: {}", request.method)),
     };
 
@@ -101,7 +122,9 @@ fn handle_initialize() -> Result<Value> {
     Ok(serde_json::json!({
         "protocolVersion": "2024-11-05",
         "capabilities": {
-            "tools": {}
+            "tools": {},
+            "resources": {},
+            "prompts": {}
         },
         "serverInfo": {
             "name": "vulcan-vault",
@@ -127,3 +150,256 @@ async fn handle_tool_call(store: &SqliteStore, params: Option<Value>) -> Result<
 
     super::tools::call_tool(store, name, arguments).await
 }
+
+fn handle_resources_list(store: &SqliteStore, params: Option<Value>) -> Result<Value> {
+    let cursor = params.as_ref()
+        .and_then(|p| p.get("cursor"))
+        .and_then(|v| v.as_str());
+
+    super::resources::list_resources(store, cursor)
+}
+
+fn handle_resource_templates_list() -> Result<Value> {
+    Ok(serde_json::json!({
+        "resourceTemplates": super::resources::get_resource_templates()
+    }))
+}
+
+fn handle_resource_read(store: &SqliteStore, vault_dir: &Path, params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| anyhow::anyhow!("Missing params"))?;
+    let uri = params.get("uri")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing resource uri"))?;
+
+    super::resources::read_resource(store, vault_dir, uri)
+}
+
+fn handle_prompts_list() -> Result<Value> {
+    Ok(serde_json::json!({
+        "prompts": super::prompts::get_prompt_definitions()
+    }))
+}
+
+fn handle_prompt_get(store: &SqliteStore, params: Option<Value>) -> Result<Value> {
+    let params = params.ok_or_else(|| anyhow::anyhow!("Missing params"))?;
+    let name = params.get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("Missing prompt name"))?;
+    let arguments = params.get("arguments")
+        .cloned()
+        .unwrap_or(Value::Object(Default::default()));
+
+    super::prompts::get_prompt(store, name, arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::NoteWriter;
+    use crate::rag::{HashingEmbedder, RagPipeline};
+    use crate::store::Store;
+    use crate::{ChunkConfig, Memory, Note};
+    use serde_json::json;
+    use tokio::io::{BufReader, DuplexStream, Lines};
+
+    /// Client end of a server running over in-memory pipes
+    struct Client {
+        requests: DuplexStream,
+        responses: Lines<BufReader<DuplexStream>>,
+        next_id: u64,
+    }
+
+    impl Client {
+        async fn send(&mut self, message: Value) {
+            let line = format!("{}\n", message);
+            self.requests.write_all(line.as_bytes()).await.unwrap();
+        }
+
+        /// Send a request and wait for its response
+        async fn request(&mut self, method: &str, params: Value) -> Value {
+            self.next_id += 1;
+            self.send(json!({
+                "jsonrpc": "2.0",
+                "id": self.next_id,
+                "method": method,
+                "params": params
+            }))
+            .await;
+            let line = self.responses.next_line().await.unwrap().unwrap();
+            let response: Value = serde_json::from_str(&line).unwrap();
+            assert_eq!(response["id"], json!(self.next_id));
+            response
+        }
+
+        async fn result(&mut self, method: &str, params: Value) -> Value {
+            let response = self.request(method, params).await;
+            assert!(response.get("error").is_none(), "{}", response);
+            response["result"].clone()
+        }
+    }
+
+    /// Serve `store` while `script` drives a client, then shut down
+    async fn with_client<F, Fut>(store: &SqliteStore, vault_dir: &Path, script: F)
+    where
+        F: FnOnce(Client) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let (requests, server_in) = tokio::io::duplex(64 * 1024);
+        let (server_out, responses) = tokio::io::duplex(64 * 1024);
+        let client = Client {
+            requests,
+            responses: BufReader::new(responses).lines(),
+            next_id: 0,
+        };
+        // The script drops the client when done, which closes the server's input
+        let (served, ()) = tokio::join!(
+            serve(store, vault_dir, BufReader::new(server_in), server_out),
+            script(client)
+        );
+        served.unwrap();
+    }
+
+    /// A vault with a project note, a PRP, a checkpoint and a memory
+    async fn vault(dir: &Path) -> (SqliteStore, Note, Note, Note) {
+        let store = SqliteStore::in_memory().unwrap();
+        let rag = RagPipeline::with_embedder(ChunkConfig::default(), HashingEmbedder::new(32));
+        let writer = NoteWriter::new(dir, rag);
+
+        let mut overview = Note::project_note("Overview", "atlas app");
+        overview.content = "# Overview\n\nA map renderer.".to_string();
+        writer.write(&store, &mut overview).await.unwrap();
+        let mut prp = Note::prp_note("Tile cache", "atlas app", "Faster panning", "Disk cache");
+        prp.content = "# Tile cache\n\nCache rendered tiles.".to_string();
+        writer.write(&store, &mut prp).await.unwrap();
+        let mut checkpoint = Note::checkpoint_note("Before cache", "session-1234", "Renderer done");
+        checkpoint.content = "# Checkpoint: Before cache".to_string();
+        writer.write(&store, &mut checkpoint).await.unwrap();
+
+        let lesson = Memory::lesson("Tiles are 256px", "The renderer assumes 256px tiles", "atlas app", "agent");
+        store.save_memory(&lesson).unwrap();
+        (store, overview, prp, checkpoint)
+    }
+
+    #[tokio::test]
+    async fn test_protocol_resources() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, overview, prp, checkpoint) = vault(dir.path()).await;
+        let markdown = std::fs::read_to_string(dir.path().join(&overview.path)).unwrap();
+
+        with_client(&store, dir.path(), |mut client| async move {
+            let init = client.result("initialize", json!({})).await;
+            assert!(init["capabilities"]["resources"].is_object());
+            assert!(init["capabilities"]["prompts"].is_object());
+
+            // Notifications are not answered: the next line is the ping's response
+            client
+                .send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+                .await;
+            assert_eq!(client.result("ping", json!({})).await, json!({}));
+
+            let templates = client.result("resources/templates/list", json!({})).await;
+            assert_eq!(templates["resourceTemplates"].as_array().unwrap().len(), 4);
+
+            let list = client.result("resources/list", json!({})).await;
+            assert!(list.get("nextCursor").is_none());
+            let uris: Vec<&str> = list["resources"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|r| r["uri"].as_str().unwrap())
+                .collect();
+            let note_uri = "vault://note/Projects/atlas-app/overview.md";
+            let prp_uri = format!("vault://prp/{}", prp.id);
+            let checkpoint_uri = format!("vault://checkpoint/{}", checkpoint.id);
+            assert!(uris.contains(&"vault://project/atlas%20app"));
+            assert!(uris.contains(&note_uri));
+            assert!(uris.contains(&prp_uri.as_str()));
+            assert!(uris.contains(&checkpoint_uri.as_str()));
+
+            // Notes are read from their file
+            let read = client.result("resources/read", json!({"uri": note_uri})).await;
+            let contents = &read["contents"][0];
+            assert_eq!(contents["mimeType"], "text/markdown");
+            assert_eq!(contents["text"], json!(markdown));
+
+            let read = client
+                .result("resources/read", json!({"uri": "vault://project/atlas%20app"}))
+                .await;
+            let text = read["contents"][0]["text"].as_str().unwrap();
+            assert!(text.contains(&format!("[Tile cache]({}): Faster panning", prp_uri)));
+
+            let read = client.result("resources/read", json!({"uri": prp_uri})).await;
+            assert_eq!(read["contents"][0]["mimeType"], "application/json");
+            let fields: Value =
+                serde_json::from_str(read["contents"][0]["text"].as_str().unwrap()).unwrap();
+            assert_eq!(fields["prp_value"], "Faster panning");
+            assert_eq!(fields["content"], "# Tile cache\n\nCache rendered tiles.");
+
+            // A checkpoint is not a PRP, and only indexed notes can be read
+            let wrong_type = format!("vault://prp/{}", checkpoint.id);
+            for uri in [wrong_type.as_str(), "vault://note/../vault.db", "vault://task/1"] {
+                let response = client.request("resources/read", json!({"uri": uri})).await;
+                assert!(response["error"]["message"].is_string(), "{}", response);
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_protocol_prompts() {
+        let dir = tempfile::tempdir().unwrap();
+        let (store, _, _, checkpoint) = vault(dir.path()).await;
+
+        with_client(&store, dir.path(), |mut client| async move {
+            let list = client.result("prompts/list", json!({})).await;
+            let names: Vec<&str> = list["prompts"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p["name"].as_str().unwrap())
+                .collect();
+            assert_eq!(names, ["start_task", "write_prp", "save_checkpoint"]);
+
+            let prompt = client
+                .result(
+                    "prompts/get",
+                    json!({"name": "start_task", "arguments": {"task": "Add zoom", "project": "atlas app"}}),
+                )
+                .await;
+            let messages = prompt["messages"].as_array().unwrap();
+            assert_eq!(messages.len(), 3);
+            assert_eq!(messages[0]["content"]["type"], "resource");
+            assert_eq!(
+                messages[0]["content"]["resource"]["uri"],
+                "vault://project/atlas%20app"
+            );
+            assert!(messages[1]["content"]["text"]
+                .as_str()
+                .unwrap()
+                .contains("Tiles are 256px"));
+            assert!(messages[2]["content"]["text"]
+                .as_str()
+                .unwrap()
+                .contains("Add zoom"));
+
+            // The new checkpoint continues from the session's last one
+            let prompt = client
+                .result(
+                    "prompts/get",
+                    json!({"name": "save_checkpoint", "arguments": {"session_id": "session-1234"}}),
+                )
+                .await;
+            let last = prompt["messages"].as_array().unwrap().last().unwrap();
+            assert!(last["content"]["text"]
+                .as_str()
+                .unwrap()
+                .contains(&format!("`parent_checkpoint` \"{}\"", checkpoint.id)));
+
+            let response = client
+                .request("prompts/get", json!({"name": "write_prp", "arguments": {}}))
+                .await;
+            assert_eq!(response["error"]["message"], "Missing feature");
+        })
+        .await;
+    }
+}
@@ -5,9 +5,13 @@
 use anyhow::Result;
 use serde_json::{json, Value};
 
-use crate::{Note, NoteType, Memory, MemoryType, SqliteStore};
+use crate::{ChunkConfig, Note, NoteType, Memory, MemoryType, SqliteStore};
 use crate::store::Store;
-use crate::rag::EmbeddingService;
+use crate::config::load_embedder;
+use crate::rag::{AnyEmbedder, Embedder, RagError, RagPipeline, RetryPolicy, SearchFilters, SearchMode};
+use crate::context::{ContextError, ContextRequest};
+use crate::index::{EmbedStatus, NoteWriter};
+use crate::memory::ConsolidationConfig;
 
 use super::protocol::ToolDefinition;
 
@@ -202,7 +206,8 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
                     "title": { "type": "string" },
                     "content": { "type": "string" },
                     "context": { "type": "string" },
-                    "tags": { "type": "array", "items": { "type": "string" } }
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "supersedes": { "type": "string", "description": "ID of an earlier decision this one replaces; it is no longer recalled" }
                 },
                 "required": ["title", "content", "context"]
             }),
@@ -250,6 +255,17 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
                 "required": ["id"]
             }),
         },
+        ToolDefinition {
+            name: "consolidate_memories".to_string(),
+            description: "Merge near-duplicate memories (summing times applied and keeping the merged IDs) and tag contradictory decisions in the same context needs-review. Resolve a conflict by recording the decision that stands with supersedes".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "dry_run": { "type": "boolean", "default": false, "description": "Report what would change without changing anything" },
+                    "threshold": { "type": "number", "default": 0.9, "description": "Similarity (0.0-1.0) at which memories count as duplicates" }
+                }
+            }),
+        },
 
         // Stats
         ToolDefinition {
@@ -264,7 +280,7 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
         // Semantic search (RAG)
         ToolDefinition {
             name: "semantic_search".to_string(),
-            description: "Search notes using semantic similarity (requires Ollama with nomic-embed-text)".to_string(),
+            description: "Search notes using semantic similarity (requires Ollama with nomic-embed-text). Pass mode \"hybrid\" to fuse FTS5 keyword ranking with vector similarity (falling back to keywords if Ollama is unavailable) or \"keyword\" for keywords only; with a mode the result is {mode, results} instead of a bare array".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -286,13 +302,79 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
                         "minimum": 0,
                         "maximum": 1,
                         "default": 0.0,
-                        "description": "Minimum similarity score (0-1)"
+                        "description": "Minimum similarity score (0-1, semantic mode only)"
+                    },
+                    "mode": {
+                        "type": "string",
+                        "enum": ["hybrid", "semantic", "keyword"],
+                        "description": "hybrid: keyword + vector fused by rank, semantic: vector only, keyword: FTS5 BM25 only. Without it the search is semantic and returns a bare array of results"
                     }
                 },
                 "required": ["query"]
             }),
         },
 
+        // Context assembly
+        ToolDefinition {
+            name: "assemble_context".to_string(),
+            description: "Assemble prompt-ready context for a query or task within a token budget. Gathers matching note chunks, the task's notes, linked notes and memories, drops duplicates, orders them by relevance with diversity (MMR) and returns one Markdown block citing each note path and heading".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "What you are working on" },
+                    "task_id": { "type": "string", "description": "vulcan-todo task ID; its linked notes lead the context" },
+                    "token_budget": { "type": "integer", "default": 4000, "minimum": 100, "description": "Maximum estimated tokens of the returned Markdown" },
+                    "project": { "type": "string", "description": "Limit note matches to a project" },
+                    "include_memories": { "type": "boolean", "default": true },
+                    "include_linked": { "type": "boolean", "default": true, "description": "Include notes linked from the best matches" },
+                    "diversity": {
+                        "type": "number",
+                        "minimum": 0,
+                        "maximum": 1,
+                        "default": 0.3,
+                        "description": "0: rank by relevance only; higher values prefer passages unlike those already chosen"
+                    }
+                }
+            }),
+        },
+
+        // Link graph
+        ToolDefinition {
+            name: "get_backlinks".to_string(),
+            description: "Get notes that link to a note via [[wikilinks]] or Markdown links".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Note ID" },
+                    "path": { "type": "string", "description": "Note path (alternative to id)" }
+                }
+            }),
+        },
+        ToolDefinition {
+            name: "get_related_notes".to_string(),
+            description: "Get notes within a few links of a note (in either direction), nearest first".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Note ID" },
+                    "path": { "type": "string", "description": "Note path (alternative to id)" },
+                    "depth": { "type": "integer", "default": 2, "minimum": 1, "maximum": 5, "description": "Maximum number of link hops" },
+                    "limit": { "type": "integer", "default": 20 }
+                }
+            }),
+        },
+        ToolDefinition {
+            name: "find_orphans".to_string(),
+            description: "Find notes with no links to or from other notes".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "note_type": { "type": "string", "enum": ["project", "task", "learning", "memory", "meta", "prp", "checkpoint"] },
+                    "limit": { "type": "integer", "default": 50 }
+                }
+            }),
+        },
+
         // Context Engineering Tools
         ToolDefinition {
             name: "get_session_context".to_string(),
@@ -391,15 +473,15 @@ pub fn get_tool_definitions() -> Vec<ToolDefinition> {
 /// Call a tool by name with given arguments
 pub async fn call_tool(store: &SqliteStore, name: &str, args: Value) -> Result<Value> {
     match name {
-        "create_note" => create_note(store, args),
+        "create_note" => create_note(store, args).await,
         "get_note" => get_note(store, args),
         "list_notes" => list_notes(store, args),
         "search_notes" => search_notes(store, args),
         "delete_note" => delete_note(store, args),
         "get_task_context" => get_task_context(store, args),
-        "create_task_context" => create_task_context(store, args),
-        "link_note_to_task" => link_note_to_task(store, args),
-        "unlink_note_from_task" => unlink_note_from_task(store, args),
+        "create_task_context" => create_task_context(store, args).await,
+        "link_note_to_task" => link_note_to_task(store, args).await,
+        "unlink_note_from_task" => unlink_note_from_task(store, args).await,
         "get_task_notes" => get_task_notes(store, args),
         "get_notes_by_ids" => get_notes_by_ids(store, args),
         "get_project_context" => get_project_context(store, args),
@@ -409,12 +491,18 @@ pub async fn call_tool(store: &SqliteStore, name: &str, args: Value) -> Result<V
         "record_preference" => record_memory(store, args, MemoryType::Preference).await,
         "recall_memories" => recall_memories(store, args).await,
         "reinforce_memory" => reinforce_memory(store, args),
+        "consolidate_memories" => consolidate_memories(store, args).await,
         "get_stats" => get_stats(store),
         "semantic_search" => semantic_search(store, args).await,
+        "assemble_context" => assemble_context(store, args).await,
+        // Link graph tools
+        "get_backlinks" => get_backlinks(store, args),
+        "get_related_notes" => get_related_notes(store, args),
+        "find_orphans" => find_orphans(store, args),
         // Context engineering tools
         "get_session_context" => get_session_context(store, args),
-        "create_prp" => create_prp(store, args),
-        "save_checkpoint" => save_checkpoint(store, args),
+        "create_prp" => create_prp(store, args).await,
+        "save_checkpoint" => save_checkpoint(store, args).await,
         "list_checkpoints" => list_checkpoints(store, args),
         "get_checkpoint" => get_checkpoint(store, args),
         _ => Err(anyhow::anyhow!("Unknown tool: {}", name)),
@@ -423,7 +511,7 @@ pub async fn call_tool(store: &SqliteStore, name: &str, args: Value) -> Result<V
 
 // Tool implementations
 
-fn create_note(store: &SqliteStore, args: Value) -> Result<Value> {
+async fn create_note(store: &SqliteStore, args: Value) -> Result<Value> {
     let title = args.get("title").and_then(|v| v.as_str())
         .ok_or_else(|| anyhow::anyhow!("Missing title"))?;
     let note_type_str = args.get("note_type").and_then(|v| v.as_str())
@@ -469,12 +557,12 @@ fn create_note(store: &SqliteStore, args: Value) -> Result<Value> {
             .collect();
     }
 
-    store.save_note(&note)?;
+    let status = note_writer()?.write(store, &mut note).await?;
 
     Ok(json!({
         "content": [{
             "type": "text",
-            "text": format!("Created note: {} ({})", note.title, note.id)
+            "text": format!("Created note: {} ({}) at {}; {}", note.title, note.id, note.path, describe_embedding(&status))
         }]
     }))
 }
@@ -599,7 +687,7 @@ fn get_task_context(store: &SqliteStore, args: Value) -> Result<Value> {
     }))
 }
 
-fn create_task_context(store: &SqliteStore, args: Value) -> Result<Value> {
+async fn create_task_context(store: &SqliteStore, args: Value) -> Result<Value> {
     let task_id = args.get("task_id").and_then(|v| v.as_str())
         .ok_or_else(|| anyhow::anyhow!("Missing task_id"))?;
     let title = args.get("title").and_then(|v| v.as_str())
@@ -614,17 +702,17 @@ fn create_task_context(store: &SqliteStore, args: Value) -> Result<Value> {
         note.context_type = Some(ct.to_string());
     }
 
-    store.save_note(&note)?;
+    let status = note_writer()?.write(store, &mut note).await?;
 
     Ok(json!({
         "content": [{
             "type": "text",
-            "text": format!("Created task context: {} ({})", note.title, note.id)
+            "text": format!("Created task context: {} ({}); {}", note.title, note.id, describe_embedding(&status))
         }]
     }))
 }
 
-fn link_note_to_task(store: &SqliteStore, args: Value) -> Result<Value> {
+async fn link_note_to_task(store: &SqliteStore, args: Value) -> Result<Value> {
     let note_id = args.get("note_id").and_then(|v| v.as_str())
         .ok_or_else(|| anyhow::anyhow!("Missing note_id"))?;
     let task_id = args.get("task_id").and_then(|v| v.as_str())
@@ -634,7 +722,7 @@ fn link_note_to_task(store: &SqliteStore, args: Value) -> Result<Value> {
     match note {
         Some(mut n) => {
             n.task_id = Some(task_id.to_string());
-            store.save_note(&n)?;
+            note_writer()?.write(store, &mut n).await?;
             Ok(json!({
                 "content": [{
                     "type": "text",
@@ -657,7 +745,7 @@ fn link_note_to_task(store: &SqliteStore, args: Value) -> Result<Value> {
     }
 }
 
-fn unlink_note_from_task(store: &SqliteStore, args: Value) -> Result<Value> {
+async fn unlink_note_from_task(store: &SqliteStore, args: Value) -> Result<Value> {
     let note_id = args.get("note_id").and_then(|v| v.as_str())
         .ok_or_else(|| anyhow::anyhow!("Missing note_id"))?;
 
@@ -666,7 +754,7 @@ fn unlink_note_from_task(store: &SqliteStore, args: Value) -> Result<Value> {
         Some(mut n) => {
             let old_task_id = n.task_id.clone();
             n.task_id = None;
-            store.save_note(&n)?;
+            note_writer()?.write(store, &mut n).await?;
             Ok(json!({
                 "content": [{
                     "type": "text",
@@ -852,6 +940,19 @@ async fn record_memory(store: &SqliteStore, args: Value, memory_type: MemoryType
 
     let mut memory = Memory::new(memory_type, title, content, context, "mcp-client");
 
+    if let Some(id) = args.get("supersedes").and_then(|v| v.as_str()) {
+        if store.get_memory(id)?.is_none() {
+            return Ok(json!({
+                "content": [{
+                    "type": "text",
+                    "text": format!("Memory not found: {}", id)
+                }],
+                "isError": true
+            }));
+        }
+        memory.supersedes = Some(id.to_string());
+    }
+
     if let Some(tags) = args.get("tags").and_then(|v| v.as_array()) {
         memory.tags = tags.iter()
             .filter_map(|v| v.as_str().map(String::from))
@@ -927,16 +1028,16 @@ async fn recall_memories_semantic(
     min_confidence: f32,
     limit: usize,
 ) -> Result<Vec<Memory>> {
-    let embedder = EmbeddingService::new();
+    let embedder = load_embedder()?;
 
     // Generate embedding for the search context
     let embedding = embedder.embed(context).await
         .map_err(|e| anyhow::anyhow!(
-            "Semantic search requires Ollama with nomic-embed-text. Error: {}", e
+            "Semantic search requires the embedding model {}. Error: {}", embedder.model(), e
         ))?;
 
     // Search by embedding similarity (request more to account for filtering)
-    let results = store.search_memories_semantic(&embedding, min_confidence, limit * 2)?;
+    let results = store.search_memories_semantic(embedder.model(), &embedding, min_confidence, limit * 2)?;
 
     // Filter by memory type if specified, and take limit
     let filtered: Vec<Memory> = results.into_iter()
@@ -978,8 +1079,74 @@ fn reinforce_memory(store: &SqliteStore, args: Value) -> Result<Value> {
     }
 }
 
+async fn consolidate_memories(store: &SqliteStore, args: Value) -> Result<Value> {
+    let dry_run = args.get("dry_run").and_then(|v| v.as_bool()).unwrap_or(false);
+    let defaults = ConsolidationConfig::default();
+    let config = ConsolidationConfig {
+        duplicate_similarity: args.get("threshold").and_then(|v| v.as_f64())
+            .map_or(defaults.duplicate_similarity, |t| t as f32),
+        ..defaults
+    };
+
+    let embedder = load_embedder()?;
+    let report = crate::memory::consolidate_memories(store, &embedder, &config, dry_run).await?;
+
+    let merged: Vec<Value> = report.merged.iter()
+        .map(|m| json!({
+            "kept": m.kept_id,
+            "title": m.title,
+            "merged": m.merged_ids,
+            "times_applied": m.times_applied,
+        }))
+        .collect();
+    let conflicts: Vec<Value> = report.conflicts.iter()
+        .map(|c| json!({
+            "context": c.context,
+            "first": { "id": c.first.0, "title": c.first.1 },
+            "second": { "id": c.second.0, "title": c.second.1 },
+            "similarity": format!("{:.2}", c.similarity),
+        }))
+        .collect();
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": format!(
+                "{} memories examined: {} {}, {} conflicting decision pair(s){}{}",
+                report.examined,
+                report.removed(),
+                if dry_run { "would be merged away" } else { "merged away" },
+                report.conflicts.len(),
+                if dry_run || conflicts.is_empty() { "" } else { " tagged needs-review" },
+                if report.unembedded > 0 {
+                    format!(". {} memories without an embedding were left out", report.unembedded)
+                } else {
+                    String::new()
+                }
+            )
+        }],
+        "data": {
+            "dry_run": dry_run,
+            "unembedded": report.unembedded,
+            "merged": merged,
+            "conflicts": conflicts,
+        }
+    }))
+}
+
 fn get_stats(store: &SqliteStore) -> Result<Value> {
     let stats = store.get_stats()?;
+    let spaces: Vec<Value> = store
+        .list_embedding_spaces()?
+        .into_iter()
+        .map(|space| json!({
+            "model": space.model,
+            "dimensions": space.dimensions,
+            "active": space.active,
+            "chunks": space.chunks,
+            "memories": space.memories,
+        }))
+        .collect();
 
     Ok(json!({
         "content": [{
@@ -989,8 +1156,10 @@ fn get_stats(store: &SqliteStore) -> Result<Value> {
                 "notes_by_type": stats.notes_by_type,
                 "total_chunks": stats.total_chunks,
                 "total_links": stats.total_links,
+                "unresolved_links": stats.unresolved_links,
                 "total_memories": stats.total_memories,
                 "projects": stats.projects,
+                "embedding_spaces": spaces,
             }))?
         }]
     }))
@@ -1001,20 +1170,35 @@ fn get_stats(store: &SqliteStore) -> Result<Value> {
 /// Generate embedding for a memory and save it to the store
 /// Returns Ok(()) on success, Err on failure (non-fatal - caller should handle gracefully)
 async fn generate_and_save_embedding(store: &SqliteStore, memory: &Memory) -> Result<()> {
-    let embedder = EmbeddingService::new();
+    let embedder = load_embedder()?;
 
-    // Combine title, content, and context for richer embedding
-    let embedding_text = format!("{} {} {}", memory.title, memory.content, memory.context);
-
-    let embedding = embedder.embed(&embedding_text).await
+    let embedding = embedder.embed(&memory.embedding_text()).await
         .map_err(|e| anyhow::anyhow!("Embedding generation failed: {}", e))?;
 
-    store.save_memory_embedding(&memory.id, &embedding)
+    store.save_memory_embedding(&memory.id, embedder.model(), &embedding)
         .map_err(|e| anyhow::anyhow!("Failed to save embedding: {}", e))?;
 
     Ok(())
 }
 
+/// Writer for notes created through MCP: file, store and embeddings
+///
+/// Embedding isn't retried inline; if the server is down the note is queued
+/// for `vulcan-vault watch` instead of holding up the agent.
+fn note_writer() -> Result<NoteWriter<AnyEmbedder>> {
+    let rag = RagPipeline::with_embedder(ChunkConfig::default(), load_embedder()?)
+        .with_retry(RetryPolicy::none());
+    Ok(NoteWriter::new(crate::vault_dir(), rag))
+}
+
+fn describe_embedding(status: &EmbedStatus) -> String {
+    match status {
+        EmbedStatus::Embedded { chunks } => format!("{} chunks embedded", chunks),
+        EmbedStatus::Queued { reason } => format!("embedding queued ({})", reason),
+        EmbedStatus::Failed { reason } => format!("not embedded ({})", reason),
+    }
+}
+
 fn parse_note_type(s: &str) -> Result<NoteType> {
     match s.to_lowercase().as_str() {
         "project" => Ok(NoteType::Project),
@@ -1039,57 +1223,159 @@ fn slug(s: &str) -> String {
         .join("-")
 }
 
+/// Resolve the note a graph tool is about from its id or path argument
+fn note_from_args(store: &SqliteStore, args: &Value) -> Result<Note> {
+    let note = if let Some(id) = args.get("id").and_then(|v| v.as_str()) {
+        store.get_note(id)?
+    } else if let Some(path) = args.get("path").and_then(|v| v.as_str()) {
+        store.get_note_by_path(path)?
+    } else {
+        return Err(anyhow::anyhow!("Must provide id or path"));
+    };
+
+    note.ok_or_else(|| anyhow::anyhow!("Note not found"))
+}
+
+fn get_backlinks(store: &SqliteStore, args: Value) -> Result<Value> {
+    let note = note_from_args(store, &args)?;
+    let backlinks = store.get_backlinks(&note.id)?;
+
+    let results: Vec<Value> = backlinks.iter()
+        .map(|link| {
+            let title = store.get_note(&link.target_id).ok().flatten().map(|n| n.title);
+            json!({
+                "id": link.target_id,
+                "path": link.target_path,
+                "title": title,
+                "link_text": link.link_text,
+                "heading": link.heading,
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": serde_json::to_string_pretty(&json!({
+                "note": { "id": note.id, "title": note.title, "path": note.path },
+                "count": results.len(),
+                "backlinks": results,
+            }))?
+        }]
+    }))
+}
+
+fn get_related_notes(store: &SqliteStore, args: Value) -> Result<Value> {
+    let note = note_from_args(store, &args)?;
+    let depth = args.get("depth").and_then(|v| v.as_u64()).unwrap_or(2).clamp(1, 5) as usize;
+    let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+
+    let related = crate::graph::related_notes(store, &note.id, depth, limit)?;
+    let results: Vec<Value> = related.iter()
+        .map(|r| json!({
+            "id": r.note.id,
+            "title": r.note.title,
+            "path": r.note.path,
+            "type": r.note.note_type.to_string(),
+            "distance": r.distance,
+        }))
+        .collect();
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": serde_json::to_string_pretty(&json!({
+                "note": { "id": note.id, "title": note.title, "path": note.path },
+                "depth": depth,
+                "count": results.len(),
+                "related": results,
+            }))?
+        }]
+    }))
+}
+
+fn find_orphans(store: &SqliteStore, args: Value) -> Result<Value> {
+    let note_type = args.get("note_type")
+        .and_then(|v| v.as_str())
+        .map(parse_note_type)
+        .transpose()?;
+    let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(50) as usize;
+
+    let orphans = store.find_orphans(note_type, limit)?;
+    let results: Vec<Value> = orphans.iter()
+        .map(|n| json!({
+            "id": n.id,
+            "title": n.title,
+            "path": n.path,
+            "type": n.note_type.to_string(),
+        }))
+        .collect();
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": serde_json::to_string_pretty(&json!({
+                "count": results.len(),
+                "orphans": results,
+            }))?
+        }]
+    }))
+}
+
 /// Semantic search using vector embeddings
 async fn semantic_search(store: &SqliteStore, args: Value) -> Result<Value> {
     let query = args.get("query").and_then(|v| v.as_str())
         .ok_or_else(|| anyhow::anyhow!("Missing query"))?;
     let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
-    let project = args.get("project").and_then(|v| v.as_str());
     let min_similarity = args.get("min_similarity").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+    // Without an explicit mode this stays the plain vector search it always
+    // was, with the same bare-array response
+    let requested_mode = args.get("mode").and_then(|v| v.as_str());
+    let mode: SearchMode = match requested_mode {
+        Some(s) => s.parse().map_err(|e: String| anyhow::anyhow!(e))?,
+        None => SearchMode::Semantic,
+    };
 
-    // Parse note type filters
-    let note_types: Option<Vec<NoteType>> = args.get("note_types")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str())
-                .filter_map(|s| parse_note_type(s).ok())
-                .collect()
-        });
+    let filters = SearchFilters {
+        // Parse note type filters
+        note_types: args.get("note_types")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str())
+                    .filter_map(|s| parse_note_type(s).ok())
+                    .collect()
+            }),
+        project: args.get("project").and_then(|v| v.as_str()).map(String::from),
+        // Tags filter: match notes that have ANY of the requested tags
+        tags: args.get("tags")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            }),
+    };
 
-    // Tags filter: match notes that have ANY of the requested tags
-    let tags: Option<Vec<String>> = args.get("tags")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(String::from))
-                .collect()
-        });
+    // Request more results to account for filtering
+    let fetch_limit = if min_similarity > 0.0 && mode == SearchMode::Semantic { limit * 3 } else { limit };
 
-    // Generate embedding for the query
-    let embedder = EmbeddingService::new();
-    let embedding = match embedder.embed(query).await {
-        Ok(emb) => emb,
-        Err(e) => {
+    let embedder = load_embedder()?;
+    let outcome = match crate::rag::search(store, &embedder, query, mode, &filters, fetch_limit).await {
+        Ok(outcome) => outcome,
+        Err(RagError::Embedding(e)) => {
             return Ok(json!({
                 "content": [{
                     "type": "text",
-                    "text": format!("Embedding error: {}. Is Ollama running with nomic-embed-text?", e)
+                    "text": format!("Embedding error: {}. Is the embedding service running with {}? Use mode \"keyword\" to search without it.", e, embedder.model())
                 }],
                 "isError": true
             }));
         }
+        Err(e) => return Err(e.into()),
     };
 
-    // Request more results to account for filtering
-    let fetch_limit = if min_similarity > 0.0 { limit * 3 } else { limit };
-
-    // Perform vector search
-    let note_types_ref: Option<&[NoteType]> = note_types.as_deref();
-    let tags_ref: Option<&[String]> = tags.as_deref();
-    let results = store.vector_search(&embedding, note_types_ref, project, tags_ref, fetch_limit)?;
-
-    if results.is_empty() {
+    if outcome.hits.is_empty() {
         return Ok(json!({
             "content": [{
                 "type": "text",
@@ -1099,28 +1385,29 @@ async fn semantic_search(store: &SqliteStore, args: Value) -> Result<Value> {
     }
 
     // Format results with relevance scores, filtering by min_similarity
-    let formatted: Vec<Value> = results.iter()
-        .filter_map(|r| {
-            // Convert cosine distance to similarity score (0-1)
-            // Cosine distance: 0 = identical, 2 = opposite
-            let similarity = 1.0 - (r.distance / 2.0);
-
-            // Apply minimum similarity filter
-            if similarity < min_similarity {
-                return None;
+    let formatted: Vec<Value> = outcome.hits.iter()
+        .filter(|hit| {
+            outcome.mode != SearchMode::Semantic
+                || hit.similarity.is_none_or(|s| s >= min_similarity)
+        })
+        .map(|hit| {
+            let mut result = json!({
+                "note_id": hit.note_id,
+                "note_title": hit.note_title,
+                "note_path": hit.note_path,
+                "note_type": hit.note_type.to_string(),
+                "project": hit.project,
+                "tags": hit.tags,
+                "heading": hit.heading,
+                "content": hit.content,
+                "similarity": hit.similarity.map(|s| format!("{:.2}", s)),
+            });
+            if requested_mode.is_some() {
+                result["score"] = json!(format!("{:.4}", hit.score));
+                result["keyword_rank"] = json!(hit.keyword_rank);
+                result["vector_rank"] = json!(hit.vector_rank);
             }
-
-            Some(json!({
-                "note_id": r.note_id,
-                "note_title": r.note_title,
-                "note_path": r.note_path,
-                "note_type": r.note_type.to_string(),
-                "project": r.project,
-                "tags": r.tags,
-                "heading": r.heading,
-                "content": r.content,
-                "similarity": format!("{:.2}", similarity),
-            }))
+            result
         })
         .take(limit)
         .collect();
@@ -1134,14 +1421,78 @@ async fn semantic_search(store: &SqliteStore, args: Value) -> Result<Value> {
         }));
     }
 
+    let response = if requested_mode.is_some() {
+        json!({
+            "mode": outcome.mode.to_string(),
+            "results": formatted,
+        })
+    } else {
+        Value::Array(formatted)
+    };
+
     Ok(json!({
         "content": [{
             "type": "text",
-            "text": serde_json::to_string_pretty(&formatted)?
+            "text": serde_json::to_string_pretty(&response)?
         }]
     }))
 }
 
+/// Pack the context for a query or task into a token budget
+async fn assemble_context(store: &SqliteStore, args: Value) -> Result<Value> {
+    let defaults = ContextRequest::default();
+    let request = ContextRequest {
+        query: args.get("query").and_then(|v| v.as_str()).map(String::from),
+        task_id: args.get("task_id").and_then(|v| v.as_str()).map(String::from),
+        project: args.get("project").and_then(|v| v.as_str()).map(String::from),
+        token_budget: args.get("token_budget").and_then(|v| v.as_u64())
+            .map_or(defaults.token_budget, |v| v as usize),
+        include_memories: args.get("include_memories").and_then(|v| v.as_bool()).unwrap_or(true),
+        include_linked: args.get("include_linked").and_then(|v| v.as_bool()).unwrap_or(true),
+        lambda: args.get("diversity").and_then(|v| v.as_f64())
+            .map_or(defaults.lambda, |d| 1.0 - d as f32),
+    };
+
+    let embedder = load_embedder()?;
+    let context = match crate::context::assemble_context(store, &embedder, &request).await {
+        Ok(context) => context,
+        Err(e @ ContextError::NoQuery) => {
+            return Ok(json!({
+                "content": [{ "type": "text", "text": e.to_string() }],
+                "isError": true
+            }));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let sources: Vec<Value> = context.sections.iter()
+        .map(|s| json!({
+            "source": s.source.to_string(),
+            "citation": s.citation,
+            "relevance": format!("{:.2}", s.relevance),
+            "truncated": s.truncated,
+        }))
+        .collect();
+
+    Ok(json!({
+        "content": [{
+            "type": "text",
+            "text": if context.sections.is_empty() {
+                "No matching notes or memories found".to_string()
+            } else {
+                context.markdown
+            }
+        }],
+        "data": {
+            "tokens": context.tokens,
+            "token_budget": context.token_budget,
+            "candidates": context.candidates,
+            "search_mode": context.search_mode.to_string(),
+            "sections": sources,
+        }
+    }))
+}
+
 // ============================================================================
 // Context Engineering Tools
 // ============================================================================
@@ -1238,7 +1589,7 @@ fn get_session_context(store: &SqliteStore, args: Value) -> Result<Value> {
 }
 
 /// Create a PRP (Product Requirements Prompt)
-fn create_prp(store: &SqliteStore, args: Value) -> Result<Value> {
+async fn create_prp(store: &SqliteStore, args: Value) -> Result<Value> {
     use crate::PrpPhase;
 
     let title = args.get("title").and_then(|v| v.as_str())
@@ -1304,19 +1655,18 @@ fn create_prp(store: &SqliteStore, args: Value) -> Result<Value> {
         note.content = content;
     }
 
-    let note_id = note.id.clone();
-    store.save_note(&note)?;
+    let status = note_writer()?.write(store, &mut note).await?;
 
     Ok(json!({
         "content": [{
             "type": "text",
-            "text": format!("Created PRP: {} (id: {})", title, note_id)
+            "text": format!("Created PRP: {} (id: {}); {}", title, note.id, describe_embedding(&status))
         }]
     }))
 }
 
 /// Save a context checkpoint
-fn save_checkpoint(store: &SqliteStore, args: Value) -> Result<Value> {
+async fn save_checkpoint(store: &SqliteStore, args: Value) -> Result<Value> {
     let name = args.get("name").and_then(|v| v.as_str())
         .ok_or_else(|| anyhow::anyhow!("Missing name"))?;
     let session_id = args.get("session_id").and_then(|v| v.as_str())
@@ -1355,13 +1705,12 @@ fn save_checkpoint(store: &SqliteStore, args: Value) -> Result<Value> {
 
     note.content = content;
 
-    let note_id = note.id.clone();
-    store.save_note(&note)?;
+    let status = note_writer()?.write(store, &mut note).await?;
 
     Ok(json!({
         "content": [{
             "type": "text",
-            "text": format!("Checkpoint saved: {} (id: {})", name, note_id)
+            "text": format!("Checkpoint saved: {} (id: {}); {}", name, note.id, describe_embedding(&status))
         }]
     }))
 }
@@ -0,0 +1,523 @@
+//! Memory consolidation - merging duplicates and flagging contradictions
+//!
+//! Agents record the same lesson many times over, in slightly different
+//! words. Consolidation clusters memories of the same type, context and
+//! project by embedding similarity and folds each cluster of near-duplicates
+//! into its strongest member. The merged memory keeps the IDs of the ones it
+//! absorbed and adds up how often they were applied.
+//!
+//! Two decisions in the same context that are about the same thing but say
+//! different things probably contradict each other. They are tagged
+//! `needs-review` until one explicitly supersedes the other (see
+//! [`Memory::supersedes`]); superseded memories take no part in
+//! consolidation.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use crate::models::{Memory, MemoryType};
+use crate::rag::{Embedder, OllamaEmbedder};
+use crate::store::Store;
+
+/// Tag given to decisions that contradict another decision
+pub const REVIEW_TAG: &str = "needs-review";
+
+/// Result type for consolidation operations
+pub type ConsolidationResult<T> = Result<T, ConsolidationError>;
+
+/// Errors that can occur during consolidation
+#[derive(Debug, thiserror::Error)]
+pub enum ConsolidationError {
+    #[error("Store error: {0}")]
+    Store(#[from] crate::store::StoreError),
+
+    #[error("Embedding error: {0}")]
+    Embedding(#[from] crate::rag::EmbeddingError),
+}
+
+/// Configuration for memory consolidation
+#[derive(Debug, Clone)]
+pub struct ConsolidationConfig {
+    /// Cosine similarity at which two memories are duplicates (default: 0.9)
+    pub duplicate_similarity: f32,
+    /// Cosine similarity at which two decisions are about the same question
+    /// (default: 0.6)
+    pub conflict_similarity: f32,
+}
+
+impl Default for ConsolidationConfig {
+    fn default() -> Self {
+        Self {
+            duplicate_similarity: 0.9,
+            conflict_similarity: 0.6,
+        }
+    }
+}
+
+/// A cluster of duplicates folded into one memory
+#[derive(Debug, Clone)]
+pub struct MergedMemories {
+    /// The memory that was kept
+    pub kept_id: String,
+    /// Its title
+    pub title: String,
+    /// Memories merged into it (and deleted)
+    pub merged_ids: Vec<String>,
+    /// Combined times applied
+    pub times_applied: u32,
+}
+
+/// Two decisions that appear to contradict each other
+#[derive(Debug, Clone)]
+pub struct MemoryConflict {
+    /// Context both decisions were recorded in
+    pub context: String,
+    /// The first decision (id, title)
+    pub first: (String, String),
+    /// The second decision (id, title)
+    pub second: (String, String),
+    /// Cosine similarity of their embeddings
+    pub similarity: f32,
+}
+
+/// Report from a consolidation pass
+#[derive(Debug, Clone, Default)]
+pub struct ConsolidationReport {
+    /// Number of memories considered
+    pub examined: usize,
+    /// Memories that had no embedding in the embedder's space yet
+    pub embedded: usize,
+    /// Memories a preview left out because they have no embedding yet
+    pub unembedded: usize,
+    /// Clusters of duplicates merged
+    pub merged: Vec<MergedMemories>,
+    /// Decisions flagged for review
+    pub conflicts: Vec<MemoryConflict>,
+    /// Whether nothing was changed (preview)
+    pub dry_run: bool,
+}
+
+impl ConsolidationReport {
+    /// Number of memories removed by merging
+    pub fn removed(&self) -> usize {
+        self.merged.iter().map(|m| m.merged_ids.len()).sum()
+    }
+}
+
+/// A memory with its embedding and whether it needs saving
+struct Candidate {
+    memory: Memory,
+    embedding: Vec<f32>,
+    changed: bool,
+}
+
+/// Service for consolidating agent memories
+///
+/// Run it periodically (or after a busy session) to keep the memory set
+/// small and consistent:
+/// - Near-duplicate memories are merged into the most confident one
+/// - Contradictory decisions are tagged [`REVIEW_TAG`]
+pub struct MemoryConsolidation<S: Store, E: Embedder = OllamaEmbedder> {
+    store: Arc<S>,
+    embedder: E,
+    config: ConsolidationConfig,
+}
+
+impl<S: Store, E: Embedder> MemoryConsolidation<S, E> {
+    /// Create a new consolidation service
+    pub fn new(store: Arc<S>, embedder: E) -> Self {
+        Self {
+            store,
+            embedder,
+            config: ConsolidationConfig::default(),
+        }
+    }
+
+    /// Create with custom configuration
+    pub fn with_config(store: Arc<S>, embedder: E, config: ConsolidationConfig) -> Self {
+        Self {
+            store,
+            embedder,
+            config,
+        }
+    }
+
+    /// Get current configuration
+    pub fn config(&self) -> &ConsolidationConfig {
+        &self.config
+    }
+
+    /// Merge duplicates and flag contradictory decisions
+    pub async fn consolidate(&self) -> ConsolidationResult<ConsolidationReport> {
+        consolidate_memories(&*self.store, &self.embedder, &self.config, false).await
+    }
+
+    /// Report what [`Self::consolidate`] would do without changing anything
+    pub async fn preview(&self) -> ConsolidationResult<ConsolidationReport> {
+        consolidate_memories(&*self.store, &self.embedder, &self.config, true).await
+    }
+}
+
+/// Run a consolidation pass over a borrowed store
+///
+/// [`MemoryConsolidation`] for callers that don't share the store through
+/// an `Arc`, such as the MCP server. With `dry_run`, nothing is written and
+/// the embedder isn't called: memories without an embedding are left out
+/// and counted in [`ConsolidationReport::unembedded`].
+pub async fn consolidate_memories<S: Store + ?Sized, E: Embedder>(
+    store: &S,
+    embedder: &E,
+    config: &ConsolidationConfig,
+    dry_run: bool,
+) -> ConsolidationResult<ConsolidationReport> {
+    let mut report = ConsolidationReport {
+        dry_run,
+        ..ConsolidationReport::default()
+    };
+
+    let memories = store.list_memories(None)?;
+    let superseded: HashSet<String> = memories
+        .iter()
+        .filter_map(|m| m.supersedes.clone())
+        .collect();
+
+    // Session summaries are records of what happened, not knowledge
+    let mut groups: BTreeMap<(String, String, String), Vec<Candidate>> = BTreeMap::new();
+    for memory in memories {
+        if memory.memory_type == MemoryType::Session || superseded.contains(&memory.id) {
+            continue;
+        }
+        let Some(embedding) = embedding(store, embedder, &memory, dry_run, &mut report).await?
+        else {
+            continue;
+        };
+        let key = (
+            memory.memory_type.to_string(),
+            memory.context.trim().to_lowercase(),
+            memory.project.clone().unwrap_or_default(),
+        );
+        groups.entry(key).or_default().push(Candidate {
+            memory,
+            embedding,
+            changed: false,
+        });
+        report.examined += 1;
+    }
+
+    for ((memory_type, _, _), candidates) in groups {
+        let (mut kept, merged_ids) = merge_duplicates(config, candidates, &mut report);
+        if memory_type == MemoryType::Decision.to_string() {
+            flag_conflicts(config, &mut kept, &mut report);
+        }
+        if !dry_run {
+            // Survivors first, so a failed save can't lose what was merged
+            // into them
+            for candidate in kept.iter().filter(|c| c.changed) {
+                store.save_memory(&candidate.memory)?;
+            }
+            for id in &merged_ids {
+                store.delete_memory(id)?;
+            }
+        }
+    }
+
+    tracing::info!(
+        "Consolidation {}: {} memories, {} merged away, {} conflicts",
+        if dry_run { "preview" } else { "complete" },
+        report.examined,
+        report.removed(),
+        report.conflicts.len()
+    );
+
+    Ok(report)
+}
+
+/// The memory's stored embedding, or a new one
+///
+/// A preview doesn't embed anything: without a stored embedding the memory
+/// is counted as unembedded and `None` returned.
+async fn embedding<S: Store + ?Sized, E: Embedder>(
+    store: &S,
+    embedder: &E,
+    memory: &Memory,
+    dry_run: bool,
+    report: &mut ConsolidationReport,
+) -> ConsolidationResult<Option<Vec<f32>>> {
+    let model = embedder.model();
+    if let Some(embedding) = store.get_memory_embedding(&memory.id, model)? {
+        return Ok(Some(embedding));
+    }
+    if dry_run {
+        report.unembedded += 1;
+        return Ok(None);
+    }
+
+    let embedding = embedder.embed(&memory.embedding_text()).await?;
+    store.save_memory_embedding(&memory.id, model, &embedding)?;
+    report.embedded += 1;
+    Ok(Some(embedding))
+}
+
+/// Fold each cluster of near-duplicates into its strongest memory
+///
+/// Memories are visited strongest first and join the first kept memory
+/// they are similar enough to, so a cluster can't drift away from the
+/// memory it is merged into. Returns the kept memories and the IDs of the
+/// ones merged away, which the caller deletes once the kept ones are saved.
+fn merge_duplicates(
+    config: &ConsolidationConfig,
+    mut candidates: Vec<Candidate>,
+    report: &mut ConsolidationReport,
+) -> (Vec<Candidate>, Vec<String>) {
+    candidates.sort_by(|a, b| {
+        b.memory
+            .confidence
+            .total_cmp(&a.memory.confidence)
+            .then(b.memory.times_applied.cmp(&a.memory.times_applied))
+            .then(a.memory.created.cmp(&b.memory.created))
+    });
+
+    let mut kept: Vec<Candidate> = Vec::new();
+    let mut merged: Vec<Vec<String>> = Vec::new();
+    for candidate in candidates {
+        let target = kept.iter().position(|k| {
+            cosine_similarity(&k.embedding, &candidate.embedding) >= config.duplicate_similarity
+        });
+        match target {
+            Some(i) => {
+                absorb(&mut kept[i].memory, &candidate.memory);
+                kept[i].changed = true;
+                merged[i].push(candidate.memory.id.clone());
+            }
+            None => {
+                kept.push(candidate);
+                merged.push(Vec::new());
+            }
+        }
+    }
+
+    let mut removed = Vec::new();
+    for (candidate, merged_ids) in kept.iter().zip(merged) {
+        if !merged_ids.is_empty() {
+            removed.extend(merged_ids.iter().cloned());
+            report.merged.push(MergedMemories {
+                kept_id: candidate.memory.id.clone(),
+                title: candidate.memory.title.clone(),
+                merged_ids,
+                times_applied: candidate.memory.times_applied,
+            });
+        }
+    }
+    (kept, removed)
+}
+
+/// Tag decisions about the same question that aren't duplicates
+fn flag_conflicts(
+    config: &ConsolidationConfig,
+    decisions: &mut [Candidate],
+    report: &mut ConsolidationReport,
+) {
+    let mut conflicted = vec![false; decisions.len()];
+    for i in 0..decisions.len() {
+        for j in i + 1..decisions.len() {
+            let similarity = cosine_similarity(&decisions[i].embedding, &decisions[j].embedding);
+            if similarity < config.conflict_similarity {
+                continue;
+            }
+            conflicted[i] = true;
+            conflicted[j] = true;
+            report.conflicts.push(MemoryConflict {
+                context: decisions[i].memory.context.clone(),
+                first: (
+                    decisions[i].memory.id.clone(),
+                    decisions[i].memory.title.clone(),
+                ),
+                second: (
+                    decisions[j].memory.id.clone(),
+                    decisions[j].memory.title.clone(),
+                ),
+                similarity,
+            });
+        }
+    }
+
+    // Flags from earlier passes go once the conflict is resolved
+    for (candidate, conflicted) in decisions.iter_mut().zip(conflicted) {
+        let flagged = candidate.memory.tags.iter().any(|t| t == REVIEW_TAG);
+        if conflicted && !flagged {
+            candidate.memory.tags.push(REVIEW_TAG.to_string());
+            candidate.changed = true;
+        } else if !conflicted && flagged {
+            candidate.memory.tags.retain(|t| t != REVIEW_TAG);
+            candidate.changed = true;
+        }
+    }
+}
+
+/// Merge `duplicate` into `memory`, keeping its history
+fn absorb(memory: &mut Memory, duplicate: &Memory) {
+    memory.times_applied += duplicate.times_applied;
+    memory.confidence = memory.confidence.max(duplicate.confidence);
+    memory.created = memory.created.min(duplicate.created);
+    memory.last_applied = memory.last_applied.max(duplicate.last_applied);
+    for tag in &duplicate.tags {
+        if !memory.tags.contains(tag) {
+            memory.tags.push(tag.clone());
+        }
+    }
+    if memory.note_id.is_none() {
+        memory.note_id = duplicate.note_id.clone();
+    }
+    if memory.supersedes.is_none() {
+        memory.supersedes = duplicate.supersedes.clone();
+    }
+    memory.merged_from.push(duplicate.id.clone());
+    memory
+        .merged_from
+        .extend(duplicate.merged_from.iter().cloned());
+}
+
+/// Cosine similarity of two embeddings (0 if either is empty or zero)
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rag::HashingEmbedder;
+    use crate::store::SqliteStore;
+
+    fn service(store: &Arc<SqliteStore>) -> MemoryConsolidation<SqliteStore, HashingEmbedder> {
+        MemoryConsolidation::new(store.clone(), HashingEmbedder::new(256))
+    }
+
+    #[tokio::test]
+    async fn test_merge_duplicates() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let mut first = Memory::lesson(
+            "Avoid unwrap",
+            "Use the ? operator instead of unwrap in library code",
+            "error-handling",
+            "claude",
+        );
+        first.times_applied = 3;
+        first.confidence = 0.9;
+        let mut second = Memory::lesson(
+            "Avoid unwrap",
+            "Use the ? operator instead of unwrap in library code.",
+            "Error-Handling",
+            "codex",
+        )
+        .with_tags(vec!["rust".to_string()]);
+        second.times_applied = 2;
+        let other = Memory::lesson(
+            "Release builds",
+            "Strip symbols from release binaries to keep them small",
+            "error-handling",
+            "claude",
+        );
+        for memory in [&first, &second, &other] {
+            store.save_memory(memory).unwrap();
+        }
+
+        // A preview neither embeds nor compares unembedded memories
+        let report = service(&store).preview().await.unwrap();
+        assert!(report.dry_run);
+        assert_eq!(
+            (report.examined, report.unembedded, report.removed()),
+            (0, 3, 0)
+        );
+        assert!(store
+            .get_memory_embedding(&first.id, HashingEmbedder::new(256).model())
+            .unwrap()
+            .is_none());
+
+        let report = service(&store).consolidate().await.unwrap();
+        assert_eq!((report.examined, report.embedded), (3, 3));
+        assert_eq!(report.merged.len(), 1);
+        assert_eq!(report.merged[0].kept_id, first.id);
+
+        let kept = store.get_memory(&first.id).unwrap().unwrap();
+        assert_eq!(kept.times_applied, 5);
+        assert_eq!(kept.merged_from, vec![second.id.clone()]);
+        assert_eq!(kept.tags, vec!["rust".to_string()]);
+        assert!(store.get_memory(&second.id).unwrap().is_none());
+        assert!(store.get_memory(&other.id).unwrap().is_some());
+
+        // Nothing left to merge; embeddings are reused
+        let report = service(&store).consolidate().await.unwrap();
+        assert_eq!(
+            (report.examined, report.embedded, report.removed()),
+            (2, 0, 0)
+        );
+
+        // A preview of embedded memories reports the merge and changes nothing
+        let third = Memory::lesson(
+            "Avoid unwrap",
+            "Use the ? operator instead of unwrap in library code!",
+            "error-handling",
+            "claude",
+        );
+        store.save_memory(&third).unwrap();
+        let embedder = HashingEmbedder::new(256);
+        store
+            .save_memory_embedding(
+                &third.id,
+                embedder.model(),
+                &embedder.embed_sync(&third.embedding_text()),
+            )
+            .unwrap();
+        let report = service(&store).preview().await.unwrap();
+        assert_eq!((report.unembedded, report.removed()), (0, 1));
+        assert!(store.get_memory(&third.id).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_flag_conflicting_decisions() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let sqlite = Memory::decision(
+            "Database for the vault",
+            "Store the vault index in SQLite with sqlite-vec for vectors",
+            "storage",
+            "claude",
+        );
+        let postgres = Memory::decision(
+            "Database for the vault",
+            "Store the vault index in Postgres with pgvector for vectors",
+            "storage",
+            "codex",
+        );
+        store.save_memory(&sqlite).unwrap();
+        store.save_memory(&postgres).unwrap();
+
+        let report = service(&store).consolidate().await.unwrap();
+        assert_eq!(report.removed(), 0);
+        assert_eq!(report.conflicts.len(), 1);
+        for id in [&sqlite.id, &postgres.id] {
+            let memory = store.get_memory(id).unwrap().unwrap();
+            assert!(memory.tags.contains(&REVIEW_TAG.to_string()));
+        }
+
+        // Superseding one resolves the conflict and hides the old decision
+        let mut decided = store.get_memory(&postgres.id).unwrap().unwrap();
+        decided.supersedes = Some(sqlite.id.clone());
+        store.save_memory(&decided).unwrap();
+
+        let report = service(&store).consolidate().await.unwrap();
+        assert!(report.conflicts.is_empty());
+        let decided = store.get_memory(&postgres.id).unwrap().unwrap();
+        assert!(decided.tags.is_empty());
+        let recalled = store.search_memories("storage", None, 0.0, 10).unwrap();
+        assert_eq!(recalled.len(), 1);
+        assert_eq!(recalled[0].id, postgres.id);
+    }
+}
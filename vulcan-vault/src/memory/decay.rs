@@ -8,7 +8,8 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
 
 use crate::models::Memory;
 use crate::store::Store;
@@ -30,7 +31,10 @@ pub enum DecayError {
 }
 
 /// Configuration for memory decay
-#[derive(Debug, Clone)]
+///
+/// Read from the `[decay]` section of `config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct DecayConfig {
     /// Confidence decrease per day of inactivity (default: 0.01)
     pub decay_rate: f32,
@@ -38,7 +42,7 @@ pub struct DecayConfig {
     pub grace_period_days: u32,
     /// Minimum confidence threshold for cleanup (default: 0.1)
     pub min_confidence: f32,
-    /// Directory for archived memories
+    /// Directory for archived memories, relative to the vault
     pub archive_dir: PathBuf,
 }
 
@@ -103,7 +107,9 @@ impl<S: Store> MemoryDecay<S> {
     /// Apply decay to all eligible memories
     ///
     /// Processes all memories and applies time-based confidence decay.
-    /// Memories within the grace period are skipped.
+    /// Memories within the grace period are skipped. Each call charges the
+    /// whole inactive period again; scheduled runs should use
+    /// [`Self::apply_decay_since`].
     pub fn apply_decay(&self) -> DecayResult<DecayReport> {
         let mut report = DecayReport::default();
 
@@ -140,6 +146,51 @@ impl<S: Store> MemoryDecay<S> {
         Ok(report)
     }
 
+    /// Apply the decay due since an earlier run
+    ///
+    /// Unlike [`Self::apply_decay`], which charges each memory for its whole
+    /// inactive period, only the inactivity after `since` is charged, so
+    /// running this on a schedule decays memories in proportion to the time
+    /// that passed. `None` (no earlier run) charges the whole period. The
+    /// grace period is taken from the configuration.
+    pub fn apply_decay_since(&self, since: Option<DateTime<Utc>>) -> DecayResult<DecayReport> {
+        self.decay_since(since, true)
+    }
+
+    /// Report what [`Self::apply_decay_since`] would do (dry run)
+    pub fn preview_decay_since(&self, since: Option<DateTime<Utc>>) -> DecayResult<DecayReport> {
+        self.decay_since(since, false)
+    }
+
+    fn decay_since(&self, since: Option<DateTime<Utc>>, apply: bool) -> DecayResult<DecayReport> {
+        let now = Utc::now();
+        let mut report = DecayReport::default();
+
+        let memories = self.store.get_memories_for_decay()?;
+        report.processed = memories.len();
+
+        for mut memory in memories {
+            let lost = memory.decay_between(
+                self.config.decay_rate,
+                self.config.grace_period_days,
+                since,
+                now,
+            );
+            if lost > f32::EPSILON {
+                if apply {
+                    self.store
+                        .update_memory_confidence(&memory.id, memory.confidence)?;
+                }
+                report.decayed += 1;
+            }
+            if memory.confidence < self.config.min_confidence {
+                report.below_threshold += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
     /// Apply decay to a specific memory
     pub fn apply_decay_to(&self, memory_id: &str) -> DecayResult<Memory> {
         let mut memory = self
@@ -235,7 +286,7 @@ impl<S: Store> MemoryDecay<S> {
             let filename = format!(
                 "{}-{}.md",
                 memory.created.format("%Y%m%d"),
-                &memory.id[..8]
+                memory.id.chars().take(8).collect::<String>()
             );
             let file_path = archive_path.join(&filename);
 
@@ -362,6 +413,33 @@ mod tests {
         assert!(content.contains("type: lesson"));
     }
 
+    #[test]
+    fn test_decay_since_last_run() {
+        use crate::store::SqliteStore;
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let mut memory = Memory::lesson("Old", "Unused for a while", "testing", "agent");
+        memory.created = Utc::now() - chrono::Duration::days(37);
+        store.save_memory(&memory).unwrap();
+
+        let decay = MemoryDecay::new(store.clone());
+        let preview = decay.preview_decay_since(None).unwrap();
+        assert_eq!(preview.decayed, 1);
+        assert_eq!(store.get_memory(&memory.id).unwrap().unwrap().confidence, 0.8);
+
+        // 30 days past the grace period
+        decay.apply_decay_since(None).unwrap();
+        let confidence = store.get_memory(&memory.id).unwrap().unwrap().confidence;
+        assert!((confidence - 0.5).abs() < 1e-3);
+
+        // A day after that run, only that day is charged
+        decay
+            .apply_decay_since(Some(Utc::now() - chrono::Duration::days(1)))
+            .unwrap();
+        let confidence = store.get_memory(&memory.id).unwrap().unwrap().confidence;
+        assert!((confidence - 0.49).abs() < 1e-3);
+    }
+
     #[test]
     fn test_decay_report_default() {
         let report = DecayReport::default();
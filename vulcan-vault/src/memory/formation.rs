@@ -6,7 +6,7 @@
 use std::sync::Arc;
 
 use crate::models::{Memory, MemoryType};
-use crate::rag::EmbeddingService;
+use crate::rag::{Embedder, OllamaEmbedder};
 use crate::store::Store;
 
 /// Source of a lesson memory
@@ -69,23 +69,22 @@ pub enum FormationError {
 ///
 /// Provides structured methods for creating different types of memories
 /// with automatic tag extraction and embedding generation.
-pub struct MemoryFormation<S: Store> {
+pub struct MemoryFormation<S: Store, E: Embedder = OllamaEmbedder> {
     store: Arc<S>,
-    embedder: EmbeddingService,
+    embedder: E,
 }
 
 impl<S: Store> MemoryFormation<S> {
-    /// Create a new memory formation service
-    pub fn new(store: Arc<S>, embedder: EmbeddingService) -> Self {
-        Self { store, embedder }
-    }
-
     /// Create with default Ollama embedder
     pub fn with_store(store: Arc<S>) -> Self {
-        Self {
-            store,
-            embedder: EmbeddingService::new(),
-        }
+        Self::new(store, OllamaEmbedder::new())
+    }
+}
+
+impl<S: Store, E: Embedder> MemoryFormation<S, E> {
+    /// Create a new memory formation service
+    pub fn new(store: Arc<S>, embedder: E) -> Self {
+        Self { store, embedder }
     }
 
     /// Record a decision memory
@@ -314,15 +313,15 @@ impl<S: Store> MemoryFormation<S> {
     /// Generate embedding and save memory to store
     async fn save_with_embedding(&self, memory: Memory) -> FormationResult<Memory> {
         // Generate embedding from content
-        let embedding_text = format!("{} {}", memory.title, memory.content);
-        let embedding = self.embedder.embed(&embedding_text).await?;
+        let embedding = self.embedder.embed(&memory.embedding_text()).await?;
 
         // Save memory to store (basic save for now)
         self.store.save_memory(&memory)?;
 
         // Save embedding separately (requires store extension)
         // This will be implemented in Task 2 when we add semantic retrieval
-        self.store.save_memory_embedding(&memory.id, &embedding)?;
+        self.store
+            .save_memory_embedding(&memory.id, self.embedder.model(), &embedding)?;
 
         tracing::debug!(
             "Recorded {} memory: {} (confidence: {})",
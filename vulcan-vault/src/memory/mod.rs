@@ -4,11 +4,17 @@
 //! - Memory formation (recording decisions, lessons, preferences)
 //! - Memory retrieval (semantic and context-based)
 //! - Memory decay and reinforcement
+//! - Memory consolidation (merging duplicates, flagging contradictions)
 
 mod formation;
 mod retrieval;
 mod decay;
+mod consolidation;
 
 pub use formation::{FormationError, FormationResult, LessonSource, MemoryFormation, SessionEvent};
 pub use retrieval::{MemoryRetrieval, RetrievalConfig, RetrievalError, RetrievalResult, ScoredMemory};
 pub use decay::{CleanupReport, DecayConfig, DecayError, DecayReport, DecayResult, MemoryDecay};
+pub use consolidation::{
+    consolidate_memories, ConsolidationConfig, ConsolidationError, ConsolidationReport,
+    ConsolidationResult, MemoryConflict, MemoryConsolidation, MergedMemories, REVIEW_TAG,
+};
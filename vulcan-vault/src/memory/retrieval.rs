@@ -7,7 +7,7 @@
 use std::sync::Arc;
 
 use crate::models::{Memory, MemoryType};
-use crate::rag::EmbeddingService;
+use crate::rag::{Embedder, OllamaEmbedder};
 use crate::store::Store;
 
 /// Result type for retrieval operations
@@ -65,15 +65,22 @@ impl Default for RetrievalConfig {
 ///
 /// Provides embedding-based search that finds memories by meaning,
 /// with optional filtering by context, type, and confidence.
-pub struct MemoryRetrieval<S: Store> {
+pub struct MemoryRetrieval<S: Store, E: Embedder = OllamaEmbedder> {
     store: Arc<S>,
-    embedder: EmbeddingService,
+    embedder: E,
     config: RetrievalConfig,
 }
 
 impl<S: Store> MemoryRetrieval<S> {
+    /// Create with default Ollama embedder
+    pub fn with_store(store: Arc<S>) -> Self {
+        Self::new(store, OllamaEmbedder::new())
+    }
+}
+
+impl<S: Store, E: Embedder> MemoryRetrieval<S, E> {
     /// Create a new memory retrieval service
-    pub fn new(store: Arc<S>, embedder: EmbeddingService) -> Self {
+    pub fn new(store: Arc<S>, embedder: E) -> Self {
         Self {
             store,
             embedder,
@@ -82,7 +89,7 @@ impl<S: Store> MemoryRetrieval<S> {
     }
 
     /// Create with custom configuration
-    pub fn with_config(store: Arc<S>, embedder: EmbeddingService, config: RetrievalConfig) -> Self {
+    pub fn with_config(store: Arc<S>, embedder: E, config: RetrievalConfig) -> Self {
         Self {
             store,
             embedder,
@@ -90,11 +97,6 @@ impl<S: Store> MemoryRetrieval<S> {
         }
     }
 
-    /// Create with default Ollama embedder
-    pub fn with_store(store: Arc<S>) -> Self {
-        Self::new(store, EmbeddingService::new())
-    }
-
     /// Search memories by semantic similarity only
     ///
     /// Embeds the query and finds memories with similar embeddings.
@@ -112,7 +114,9 @@ impl<S: Store> MemoryRetrieval<S> {
         let embedding = self.embedder.embed(query).await?;
 
         // Search by embedding similarity
-        let results = self.store.search_memories_semantic(&embedding, min_conf, lim)?;
+        let results = self
+            .store
+            .search_memories_semantic(self.embedder.model(), &embedding, min_conf, lim)?;
 
         // Convert to ScoredMemory with calculated scores
         let scored = self.score_results(results);
@@ -142,7 +146,7 @@ impl<S: Store> MemoryRetrieval<S> {
         let fetch_limit = lim * 3;
         let results = self
             .store
-            .search_memories_semantic(&embedding, min_conf, fetch_limit)?;
+            .search_memories_semantic(self.embedder.model(), &embedding, min_conf, fetch_limit)?;
 
         // Apply filters
         let filtered: Vec<_> = results
@@ -194,7 +198,7 @@ impl<S: Store> MemoryRetrieval<S> {
         let embedding = self.embedder.embed(&query).await?;
         let results = self
             .store
-            .search_memories_semantic(&embedding, 0.0, lim + 1)?;
+            .search_memories_semantic(self.embedder.model(), &embedding, 0.0, lim + 1)?;
 
         // Filter out the source memory and score
         let filtered: Vec<_> = results
@@ -242,6 +246,37 @@ impl<S: Store> MemoryRetrieval<S> {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn test_retrieval_with_offline_embedder() {
+        use crate::memory::{LessonSource, MemoryFormation};
+        use crate::rag::HashingEmbedder;
+        use crate::store::SqliteStore;
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let formation = MemoryFormation::new(store.clone(), HashingEmbedder::default());
+        formation
+            .record_lesson(
+                "Borrow checker and closures",
+                "Clone the Arc before moving it into the closure",
+                "rust",
+                "test",
+                LessonSource::Error,
+            )
+            .await
+            .unwrap();
+        formation
+            .record_decision("Use PostgreSQL", "Chosen database for the API", "backend", "test", None)
+            .await
+            .unwrap();
+
+        let retrieval = MemoryRetrieval::new(store, HashingEmbedder::default());
+        let results = retrieval
+            .search_semantic("moving an Arc into a closure", Some(0.0), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(results[0].memory.title, "Borrow checker and closures");
+    }
+
     #[test]
     fn test_distance_to_similarity() {
         // Distance 0 (identical) -> similarity 1.0
@@ -96,6 +96,16 @@ pub struct Memory {
     /// Related note ID in vault (for full context)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub note_id: Option<String>,
+
+    /// ID of an earlier memory this one replaces
+    ///
+    /// Superseded memories are kept for history but no longer recalled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,
+
+    /// IDs of duplicate memories consolidated into this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub merged_from: Vec<String>,
 }
 
 impl Memory {
@@ -122,6 +132,8 @@ impl Memory {
             created: Utc::now(),
             last_applied: None,
             note_id: None,
+            supersedes: None,
+            merged_from: Vec::new(),
         }
     }
 
@@ -195,6 +207,12 @@ impl Memory {
         self
     }
 
+    /// Mark this memory as replacing an earlier one
+    pub fn with_supersedes(mut self, memory_id: impl Into<String>) -> Self {
+        self.supersedes = Some(memory_id.into());
+        self
+    }
+
     /// Reinforce this memory (increase confidence, update last_applied)
     pub fn reinforce(&mut self) {
         self.times_applied += 1;
@@ -223,6 +241,39 @@ impl Memory {
         }
     }
 
+    /// Apply the decay due for inactivity between `since` and `now`
+    ///
+    /// A memory is inactive from its last application or, if it was never
+    /// applied, from the end of its `grace_days` after creation. Decay that
+    /// falls before `since` is assumed to have been applied by an earlier
+    /// run, so repeated runs add up to the decay for the whole period.
+    /// Returns the confidence lost.
+    pub fn decay_between(
+        &mut self,
+        decay_rate: f32,
+        grace_days: u32,
+        since: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> f32 {
+        let inactive_from = self
+            .last_applied
+            .unwrap_or_else(|| self.created + chrono::Duration::days(grace_days as i64));
+        let from = since.map_or(inactive_from, |since| since.max(inactive_from));
+        let days = (now - from).num_seconds() as f32 / 86_400.0;
+        if days <= 0.0 {
+            return 0.0;
+        }
+
+        let before = self.confidence;
+        self.confidence = (self.confidence - decay_rate * days).max(0.0);
+        before - self.confidence
+    }
+
+    /// Text embedded for semantic memory search
+    pub fn embedding_text(&self) -> String {
+        format!("{} {} {}", self.title, self.content, self.context)
+    }
+
     /// Check if this memory is still relevant (above minimum confidence)
     pub fn is_relevant(&self, min_confidence: f32) -> bool {
         self.confidence >= min_confidence
@@ -270,6 +321,28 @@ mod tests {
         assert!(mem.last_applied.is_some());
     }
 
+    #[test]
+    fn test_memory_decay_between() {
+        let now = Utc::now();
+        let mut mem = Memory::lesson("Test", "Content", "ctx", "agent");
+        mem.created = now - chrono::Duration::days(17);
+
+        // Ten days past the grace period
+        let lost = mem.decay_between(0.01, 7, None, now);
+        assert!((lost - 0.1).abs() < 1e-4);
+
+        // A run two days ago already applied everything before it
+        let mut mem = Memory::lesson("Test", "Content", "ctx", "agent");
+        mem.created = now - chrono::Duration::days(17);
+        let lost = mem.decay_between(0.01, 7, Some(now - chrono::Duration::days(2)), now);
+        assert!((lost - 0.02).abs() < 1e-4);
+
+        // Still within the grace period
+        let mut mem = Memory::lesson("Test", "Content", "ctx", "agent");
+        assert_eq!(mem.decay_between(0.01, 7, None, now), 0.0);
+        assert_eq!(mem.confidence, 0.8);
+    }
+
     #[test]
     fn test_memory_context_match() {
         let mem = Memory::preference("Style", "Content", "code-review", "agent")
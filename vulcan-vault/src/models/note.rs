@@ -41,6 +41,23 @@ impl std::fmt::Display for NoteType {
     }
 }
 
+impl std::str::FromStr for NoteType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "project" => Ok(NoteType::Project),
+            "task" => Ok(NoteType::Task),
+            "learning" => Ok(NoteType::Learning),
+            "memory" => Ok(NoteType::Memory),
+            "meta" => Ok(NoteType::Meta),
+            "prp" => Ok(NoteType::Prp),
+            "checkpoint" => Ok(NoteType::Checkpoint),
+            _ => Err(format!("Unknown note type: {}", s)),
+        }
+    }
+}
+
 /// Status of a note
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -285,7 +302,7 @@ impl Note {
     pub fn project_note(title: impl Into<String>, project: impl Into<String>) -> Self {
         let project = project.into();
         let title = title.into();
-        let path = format!("Projects/{}/{}.md", project, slug(&title));
+        let path = format!("Projects/{}/{}.md", folder(&project), slug(&title));
         let mut note = Self::new(&title, NoteType::Project, path);
         note.project = Some(project);
         note
@@ -294,7 +311,7 @@ impl Note {
     /// Create a task context note linked to a vulcan-todo task
     pub fn task_note(title: impl Into<String>, task_id: impl Into<String>) -> Self {
         let task_id = task_id.into();
-        let path = format!("Tasks/by-id/{}.md", &task_id[..8]);
+        let path = format!("Tasks/by-id/{}.md", folder(&prefix(&task_id, 8)));
         let mut note = Self::new(title, NoteType::Task, path);
         note.task_id = Some(task_id);
         note.auto_fetch = true;
@@ -305,7 +322,7 @@ impl Note {
     pub fn learning_note(title: impl Into<String>, category: impl Into<String>) -> Self {
         let title = title.into();
         let category = category.into();
-        let path = format!("Learning/{}/{}.md", &category, slug(&title));
+        let path = format!("Learning/{}/{}.md", folder(&category), slug(&title));
         let mut note = Self::new(&title, NoteType::Learning, path);
         note.category = Some(category);
         note.confidence = Some(0.5);
@@ -316,7 +333,7 @@ impl Note {
     pub fn memory_note(title: impl Into<String>, memory_type: impl Into<String>) -> Self {
         let title = title.into();
         let memory_type = memory_type.into();
-        let path = format!("Agent-Memories/{}/{}.md", &memory_type, slug(&title));
+        let path = format!("Agent-Memories/{}/{}.md", folder(&memory_type), slug(&title));
         let mut note = Self::new(&title, NoteType::Memory, path);
         note.memory_type = Some(memory_type);
         note.confidence = Some(0.8);
@@ -338,7 +355,7 @@ impl Note {
     ) -> Self {
         let title = title.into();
         let project = project.into();
-        let path = format!("PRPs/{}/{}.md", &project, slug(&title));
+        let path = format!("PRPs/{}/{}.md", folder(&project), slug(&title));
         let mut note = Self::new(&title, NoteType::Prp, path);
         note.project = Some(project);
         note.prp_value = Some(value.into());
@@ -359,7 +376,7 @@ impl Note {
     ) -> Self {
         let name = name.into();
         let session_id = session_id.into();
-        let path = format!("Checkpoints/{}/{}.md", &session_id[..8.min(session_id.len())], slug(&name));
+        let path = format!("Checkpoints/{}/{}.md", folder(&prefix(&session_id, 8)), slug(&name));
         let mut note = Self::new(&name, NoteType::Checkpoint, path);
         note.session_id = Some(session_id);
         note.checkpoint_name = Some(name);
@@ -412,6 +429,20 @@ fn slug(s: &str) -> String {
         .join("-")
 }
 
+/// Slug of a folder name taken from user input, never empty, so the note
+/// can't climb out of (or sit directly in) its parent folder
+fn folder(s: &str) -> String {
+    match slug(s) {
+        s if s.is_empty() => "untitled".to_string(),
+        s => s,
+    }
+}
+
+/// The first `n` characters of `s`
+fn prefix(s: &str, n: usize) -> String {
+    s.chars().take(n).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,6 +469,19 @@ mod tests {
         assert_eq!(slug("VulcanOS Architecture"), "vulcanos-architecture");
     }
 
+    #[test]
+    fn test_folders_are_slugged() {
+        let note = Note::project_note("Notes", "../../etc");
+        assert_eq!(note.path, "Projects/etc/notes.md");
+        assert_eq!(note.project, Some("../../etc".to_string()));
+        assert_eq!(Note::learning_note("Notes", "..").path, "Learning/untitled/notes.md");
+        assert_eq!(
+            Note::memory_note("Notes", "/abs/path").path,
+            "Agent-Memories/abs-path/notes.md"
+        );
+        assert_eq!(Note::task_note("Notes", "ab/").path, "Tasks/by-id/ab.md");
+    }
+
     #[test]
     fn test_prp_note() {
         let note = Note::prp_note(
@@ -0,0 +1,132 @@
+//! Deterministic offline embedding provider
+//!
+//! Uses the hashing trick: every lowercased word and every character trigram
+//! of a word is hashed to a position in the vector with a ±1 sign, and the
+//! result is L2-normalised. Texts sharing words or word fragments end up
+//! close, which is a fair lexical stand-in for a real model when none is
+//! available, and identical texts always get identical vectors.
+
+use super::{Embedder, EmbeddingError, EMBEDDING_DIM};
+
+/// Model name reported for hashed vectors
+pub const HASHING_MODEL: &str = "hashing-v1";
+
+/// Weight of a character trigram relative to a whole word
+const TRIGRAM_WEIGHT: f32 = 0.5;
+
+/// Embedder that needs no model, server or network
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dimensions: usize,
+}
+
+impl HashingEmbedder {
+    /// Create an embedder producing vectors of `dimensions` values
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            dimensions: dimensions.max(1),
+        }
+    }
+
+    /// Embed synchronously; the async [`Embedder::embed`] never fails
+    pub fn embed_sync(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            let word = word.to_lowercase();
+            self.add_feature(&mut vector, word.as_bytes(), 1.0);
+
+            let padded: Vec<char> = format!(" {} ", word).chars().collect();
+            if padded.len() > 4 {
+                for trigram in padded.windows(3) {
+                    let trigram: String = trigram.iter().collect();
+                    self.add_feature(&mut vector, trigram.as_bytes(), TRIGRAM_WEIGHT);
+                }
+            }
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        } else {
+            // Cosine distance is undefined for the zero vector
+            vector[0] = 1.0;
+        }
+        vector
+    }
+
+    fn add_feature(&self, vector: &mut [f32], feature: &[u8], weight: f32) {
+        let hash = fnv1a(feature);
+        let index = (hash % self.dimensions as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[index] += sign * weight;
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self::new(EMBEDDING_DIM)
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn model(&self) -> &str {
+        HASHING_MODEL
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        Ok(self.embed_sync(text))
+    }
+}
+
+/// 64-bit FNV-1a hash
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[tokio::test]
+    async fn test_hashing_embedder() {
+        let embedder = HashingEmbedder::default();
+        let a = embedder
+            .embed("Rust ownership and borrowing")
+            .await
+            .unwrap();
+        assert_eq!(a.len(), EMBEDDING_DIM);
+        assert_eq!(
+            a,
+            embedder
+                .embed("rust OWNERSHIP and borrowing")
+                .await
+                .unwrap()
+        );
+        assert!((cosine(&a, &a) - 1.0).abs() < 1e-5);
+
+        let related = embedder
+            .embed("borrow checker and ownership rules")
+            .await
+            .unwrap();
+        let unrelated = embedder.embed("chocolate cake recipe").await.unwrap();
+        assert!(cosine(&a, &related) > cosine(&a, &unrelated));
+
+        assert_eq!(embedder.embed("").await.unwrap()[0], 1.0);
+        assert_eq!(HashingEmbedder::new(16).embed_sync("x").len(), 16);
+        assert!(embedder.health_check().await.unwrap());
+    }
+}
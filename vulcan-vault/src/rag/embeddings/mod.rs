@@ -0,0 +1,191 @@
+//! Embedding providers for the RAG pipeline
+//!
+//! All providers implement [`Embedder`]:
+//! - [`OllamaEmbedder`] - Ollama's `/api/embed` (the default)
+//! - [`OpenAiEmbedder`] - OpenAI-compatible `/v1/embeddings` servers such as
+//!   llama.cpp, LM Studio and vLLM
+//! - [`HashingEmbedder`] - deterministic feature hashing, no model or network
+//!   needed (tests and air-gapped machines)
+//!
+//! The provider is chosen in `config.toml`; see [`crate::config`].
+//! Callers wrap requests in a [`RetryPolicy`] to ride out transient
+//! failures such as a restarting or overloaded server.
+
+mod hashing;
+mod ollama;
+mod openai;
+mod retry;
+
+pub use hashing::HashingEmbedder;
+pub use ollama::{OllamaEmbedder, DEFAULT_MODEL, DEFAULT_OLLAMA_URL};
+pub use openai::{OpenAiEmbedder, DEFAULT_OPENAI_URL};
+pub use retry::RetryPolicy;
+
+use std::future::Future;
+
+use thiserror::Error;
+
+/// Expected embedding dimension for nomic-embed-text
+pub const EMBEDDING_DIM: usize = 768;
+
+/// Texts sent per request by providers that accept batches
+pub const DEFAULT_BATCH_SIZE: usize = 32;
+
+/// Previous name of [`OllamaEmbedder`]
+pub type EmbeddingService = OllamaEmbedder;
+
+/// Errors that can occur during embedding generation
+#[derive(Error, Debug)]
+pub enum EmbeddingError {
+    #[error("HTTP request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("Ollama returned an error: {0}")]
+    OllamaError(String),
+
+    #[error("Embedding server returned an error: {0}")]
+    ServerError(String),
+
+    #[error("Embedding server returned HTTP {status}: {message}")]
+    Http { status: u16, message: String },
+
+    #[error("Invalid embedding dimension: expected {expected}, got {got}")]
+    InvalidDimension { expected: usize, got: usize },
+
+    #[error("Embedding service unavailable at {url}")]
+    ServiceUnavailable { url: String },
+
+    #[error("Model '{model}' not found. Run: ollama pull {model}")]
+    ModelNotFound { model: String },
+}
+
+impl EmbeddingError {
+    /// Whether the same request may succeed if retried shortly
+    ///
+    /// True for timeouts, dropped connections, an unreachable server and
+    /// HTTP 408, 429 and 5xx responses. Bad input, a missing model or a
+    /// wrong dimension fail the same way every time.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            EmbeddingError::Request(e) => {
+                e.is_timeout() || e.is_connect() || e.is_request() || e.is_body()
+            }
+            EmbeddingError::ServiceUnavailable { .. } => true,
+            EmbeddingError::Http { status, .. } => matches!(status, 408 | 429) || *status >= 500,
+            EmbeddingError::OllamaError(_)
+            | EmbeddingError::ServerError(_)
+            | EmbeddingError::InvalidDimension { .. }
+            | EmbeddingError::ModelNotFound { .. } => false,
+        }
+    }
+}
+
+/// A source of text embeddings
+///
+/// Implementations must return vectors of exactly [`Embedder::dimensions`]
+/// values.
+pub trait Embedder: Send + Sync {
+    /// Name of the model producing the vectors
+    fn model(&self) -> &str;
+
+    /// Length of the vectors this embedder produces
+    fn dimensions(&self) -> usize;
+
+    /// Generate the embedding for a single text
+    fn embed(&self, text: &str) -> impl Future<Output = Result<Vec<f32>, EmbeddingError>> + Send;
+
+    /// Generate embeddings for multiple texts, in order
+    ///
+    /// The default embeds texts one at a time.
+    fn embed_batch(
+        &self,
+        texts: &[String],
+    ) -> impl Future<Output = Result<Vec<Vec<f32>>, EmbeddingError>> + Send {
+        async move {
+            let mut embeddings = Vec::with_capacity(texts.len());
+            for text in texts {
+                embeddings.push(self.embed(text).await?);
+            }
+            Ok(embeddings)
+        }
+    }
+
+    /// Check that the service is reachable and the model is loaded
+    fn health_check(&self) -> impl Future<Output = Result<bool, EmbeddingError>> + Send {
+        async move {
+            match self.embed("test").await {
+                Ok(_) => Ok(true),
+                Err(EmbeddingError::ServiceUnavailable { .. }) => Ok(false),
+                Err(EmbeddingError::ModelNotFound { .. }) => Ok(false),
+                Err(e) => Err(e),
+            }
+        }
+    }
+}
+
+/// Any of the built-in embedders, as selected by configuration
+pub enum AnyEmbedder {
+    Ollama(OllamaEmbedder),
+    OpenAi(OpenAiEmbedder),
+    Hashing(HashingEmbedder),
+}
+
+impl Embedder for AnyEmbedder {
+    fn model(&self) -> &str {
+        match self {
+            AnyEmbedder::Ollama(e) => e.model(),
+            AnyEmbedder::OpenAi(e) => e.model(),
+            AnyEmbedder::Hashing(e) => e.model(),
+        }
+    }
+
+    fn dimensions(&self) -> usize {
+        match self {
+            AnyEmbedder::Ollama(e) => e.dimensions(),
+            AnyEmbedder::OpenAi(e) => e.dimensions(),
+            AnyEmbedder::Hashing(e) => e.dimensions(),
+        }
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        match self {
+            AnyEmbedder::Ollama(e) => e.embed(text).await,
+            AnyEmbedder::OpenAi(e) => e.embed(text).await,
+            AnyEmbedder::Hashing(e) => e.embed(text).await,
+        }
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        match self {
+            AnyEmbedder::Ollama(e) => e.embed_batch(texts).await,
+            AnyEmbedder::OpenAi(e) => e.embed_batch(texts).await,
+            AnyEmbedder::Hashing(e) => e.embed_batch(texts).await,
+        }
+    }
+
+    async fn health_check(&self) -> Result<bool, EmbeddingError> {
+        match self {
+            AnyEmbedder::Ollama(e) => e.health_check().await,
+            AnyEmbedder::OpenAi(e) => e.health_check().await,
+            AnyEmbedder::Hashing(e) => e.health_check().await,
+        }
+    }
+}
+
+impl Default for AnyEmbedder {
+    fn default() -> Self {
+        AnyEmbedder::Ollama(OllamaEmbedder::new())
+    }
+}
+
+/// Check a returned vector against the expected dimension
+fn check_dimension(embedding: &[f32], expected: usize) -> Result<(), EmbeddingError> {
+    if embedding.len() == expected {
+        Ok(())
+    } else {
+        Err(EmbeddingError::InvalidDimension {
+            expected,
+            got: embedding.len(),
+        })
+    }
+}
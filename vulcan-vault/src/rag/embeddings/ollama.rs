@@ -0,0 +1,426 @@
+//! Ollama embedding provider
+//!
+//! Generates 768-dimensional embeddings using Ollama's nomic-embed-text model.
+//! Designed for local-first operation with no cloud dependencies.
+//!
+//! Texts are sent in batches to `/api/embed`; Ollama versions that predate
+//! it get one `/api/embeddings` request per text instead.
+
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use super::{check_dimension, Embedder, EmbeddingError, DEFAULT_BATCH_SIZE, EMBEDDING_DIM};
+
+/// Default Ollama API endpoint
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// Default embedding model
+pub const DEFAULT_MODEL: &str = "nomic-embed-text";
+
+/// Embedder backed by a local Ollama server
+pub struct OllamaEmbedder {
+    client: Client,
+    ollama_url: String,
+    model: String,
+    dimensions: usize,
+    batch_size: usize,
+    /// Set once the server turned out not to have `/api/embed`
+    legacy_api: AtomicBool,
+}
+
+/// Request body for Ollama's batch embedding API
+#[derive(Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+/// Response from Ollama's batch embedding API
+#[derive(Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// Request body for Ollama's single-text embedding API
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+}
+
+/// Response from Ollama's single-text embedding API
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+/// Error response from Ollama
+#[derive(Deserialize)]
+struct OllamaErrorResponse {
+    error: String,
+}
+
+impl OllamaEmbedder {
+    /// Create a new embedding service with default configuration
+    pub fn new() -> Self {
+        Self::with_config(DEFAULT_OLLAMA_URL, DEFAULT_MODEL)
+    }
+
+    /// Create an embedding service with custom configuration
+    ///
+    /// The model is expected to produce [`EMBEDDING_DIM`] values; use
+    /// [`OllamaEmbedder::with_dimensions`] for other models.
+    pub fn with_config(ollama_url: impl Into<String>, model: impl Into<String>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            ollama_url: ollama_url.into(),
+            model: model.into(),
+            dimensions: EMBEDDING_DIM,
+            batch_size: DEFAULT_BATCH_SIZE,
+            legacy_api: AtomicBool::new(false),
+        }
+    }
+
+    /// Set the dimension the model produces
+    pub fn with_dimensions(mut self, dimensions: usize) -> Self {
+        self.dimensions = dimensions;
+        self
+    }
+
+    /// Set how many texts are sent per request
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Get the configured model name
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Get the configured Ollama URL
+    pub fn ollama_url(&self) -> &str {
+        &self.ollama_url
+    }
+
+    /// POST a JSON body to an Ollama endpoint, mapping failures to errors
+    async fn post<T: Serialize>(
+        &self,
+        endpoint: &str,
+        body: &T,
+    ) -> Result<reqwest::Response, EmbeddingError> {
+        let url = format!("{}{}", self.ollama_url, endpoint);
+        let response = self
+            .client
+            .post(&url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    EmbeddingError::ServiceUnavailable {
+                        url: self.ollama_url.clone(),
+                    }
+                } else {
+                    EmbeddingError::Request(e)
+                }
+            })?;
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        match serde_json::from_str::<OllamaErrorResponse>(&error_text) {
+            Ok(err) if err.error.contains("not found") => Err(EmbeddingError::ModelNotFound {
+                model: self.model.clone(),
+            }),
+            Ok(err) => Err(EmbeddingError::Http {
+                status: status.as_u16(),
+                message: err.error,
+            }),
+            Err(_) => Err(EmbeddingError::Http {
+                status: status.as_u16(),
+                message: error_text,
+            }),
+        }
+    }
+
+    /// Embed one batch with `/api/embed`
+    async fn embed_request(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let request = EmbedRequest {
+            model: &self.model,
+            input: texts,
+        };
+        let result: EmbedResponse = self.post("/api/embed", &request).await?.json().await?;
+
+        if result.embeddings.len() != texts.len() {
+            return Err(EmbeddingError::OllamaError(format!(
+                "expected {} embeddings, got {}",
+                texts.len(),
+                result.embeddings.len()
+            )));
+        }
+        for embedding in &result.embeddings {
+            check_dimension(embedding, self.dimensions)?;
+        }
+        Ok(result.embeddings)
+    }
+
+    /// Embed one text with the pre-0.3 `/api/embeddings`
+    async fn legacy_request(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        let request = EmbeddingRequest {
+            model: &self.model,
+            prompt: text,
+        };
+        let result: EmbeddingResponse =
+            self.post("/api/embeddings", &request).await?.json().await?;
+
+        check_dimension(&result.embedding, self.dimensions)?;
+        Ok(result.embedding)
+    }
+}
+
+impl Embedder for OllamaEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Generate embedding for a single text
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        let mut embeddings = self.embed_batch(&[text.to_string()]).await?;
+        embeddings
+            .pop()
+            .ok_or_else(|| EmbeddingError::OllamaError("empty response".to_string()))
+    }
+
+    /// Generate embeddings for several texts, `batch_size` per request
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+
+        for batch in texts.chunks(self.batch_size) {
+            if !self.legacy_api.load(Ordering::Relaxed) {
+                match self.embed_request(batch).await {
+                    Ok(batch_embeddings) => {
+                        embeddings.extend(batch_embeddings);
+                        continue;
+                    }
+                    // Old servers answer unknown routes with a plain 404
+                    Err(EmbeddingError::Http { status, .. })
+                        if status == StatusCode::NOT_FOUND.as_u16() =>
+                    {
+                        tracing::info!("Ollama has no /api/embed; embedding one text at a time");
+                        self.legacy_api.store(true, Ordering::Relaxed);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            for text in batch {
+                embeddings.push(self.legacy_request(text).await?);
+            }
+        }
+
+        Ok(embeddings)
+    }
+}
+
+impl Default for OllamaEmbedder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_service_creation() {
+        let service = OllamaEmbedder::new();
+        assert_eq!(service.ollama_url(), DEFAULT_OLLAMA_URL);
+        assert_eq!(service.model(), DEFAULT_MODEL);
+    }
+
+    #[test]
+    fn test_custom_config() {
+        let service = OllamaEmbedder::with_config("http://custom:1234", "custom-model");
+        assert_eq!(service.ollama_url(), "http://custom:1234");
+        assert_eq!(service.model(), "custom-model");
+    }
+
+    /// Serve HTTP requests with `handler(path, body) -> (status, body)`,
+    /// returning the base URL and the paths requested so far
+    async fn stub_server(
+        handler: fn(&str, &serde_json::Value) -> (u16, String),
+    ) -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let paths = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = paths.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 4096];
+                    loop {
+                        // Read one request: headers, then Content-Length bytes
+                        let header_end = loop {
+                            if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                                break i + 4;
+                            }
+                            match socket.read(&mut chunk).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                            }
+                        };
+                        let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+                        let length = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        while buf.len() < header_end + length {
+                            match socket.read(&mut chunk).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                            }
+                        }
+                        let body: serde_json::Value =
+                            serde_json::from_slice(&buf[header_end..header_end + length]).unwrap();
+                        buf.drain(..header_end + length);
+
+                        let path = head.split_whitespace().nth(1).unwrap().to_string();
+                        let (status, reply) = handler(&path, &body);
+                        seen.lock().unwrap().push(path);
+                        let response = format!(
+                            "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                            status,
+                            reply.len(),
+                            reply
+                        );
+                        if socket.write_all(response.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        (url, paths)
+    }
+
+    #[tokio::test]
+    async fn test_batched_requests() {
+        let (url, paths) = stub_server(|path, body| {
+            assert_eq!(path, "/api/embed");
+            let count = body["input"].as_array().unwrap().len();
+            let embeddings = vec![vec![0.5f32; 4]; count];
+            (
+                200,
+                serde_json::json!({ "embeddings": embeddings }).to_string(),
+            )
+        })
+        .await;
+
+        let service = OllamaEmbedder::with_config(url, "stub")
+            .with_dimensions(4)
+            .with_batch_size(2);
+        let texts: Vec<String> = (0..5).map(|i| format!("text {}", i)).collect();
+        let embeddings = service.embed_batch(&texts).await.unwrap();
+        assert_eq!(embeddings.len(), 5);
+        assert_eq!(paths.lock().unwrap().len(), 3);
+
+        assert!(matches!(
+            OllamaEmbedder::with_config(service.ollama_url(), "stub")
+                .embed("x")
+                .await,
+            Err(EmbeddingError::InvalidDimension {
+                expected: 768,
+                got: 4
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_api_fallback() {
+        let (url, paths) = stub_server(|path, body| match path {
+            "/api/embed" => (404, "404 page not found".to_string()),
+            _ if body["prompt"] == "boom" => (503, r#"{"error":"server busy"}"#.to_string()),
+            _ => (200, r#"{"embedding":[0.1,0.2]}"#.to_string()),
+        })
+        .await;
+
+        let service = OllamaEmbedder::with_config(url, "stub").with_dimensions(2);
+        let texts = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(service.embed_batch(&texts).await.unwrap().len(), 2);
+        assert_eq!(service.embed("c").await.unwrap(), vec![0.1, 0.2]);
+        // /api/embed is only tried once
+        let paths = paths.lock().unwrap().clone();
+        assert_eq!(paths.iter().filter(|p| *p == "/api/embed").count(), 1);
+        assert_eq!(paths.len(), 4);
+
+        let error = service.embed("boom").await.unwrap_err();
+        assert!(matches!(error, EmbeddingError::Http { status: 503, .. }));
+        assert!(error.is_transient());
+    }
+
+    // Integration tests require Ollama running
+    #[tokio::test]
+    #[ignore] // Run with: cargo test -- --ignored
+    async fn test_embed_integration() {
+        let service = OllamaEmbedder::new();
+
+        let embedding = service.embed("Hello, world!").await.unwrap();
+
+        assert_eq!(embedding.len(), EMBEDDING_DIM);
+        // Embeddings should be normalized (values roughly between -1 and 1)
+        assert!(embedding.iter().all(|&v| v.abs() < 10.0));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_batch_embed_integration() {
+        let service = OllamaEmbedder::new();
+
+        let texts = vec![
+            "First document".to_string(),
+            "Second document".to_string(),
+            "Third document".to_string(),
+        ];
+
+        let embeddings = service.embed_batch(&texts).await.unwrap();
+
+        assert_eq!(embeddings.len(), 3);
+        for emb in embeddings {
+            assert_eq!(emb.len(), EMBEDDING_DIM);
+        }
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_health_check() {
+        let service = OllamaEmbedder::new();
+        let healthy = service.health_check().await.unwrap();
+        assert!(healthy, "Ollama should be running with nomic-embed-text");
+    }
+}
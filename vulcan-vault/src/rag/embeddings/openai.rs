@@ -0,0 +1,200 @@
+//! OpenAI-compatible embedding provider
+//!
+//! Talks to any server implementing `POST /v1/embeddings`, which includes
+//! llama.cpp's `llama-server --embedding`, LM Studio and vLLM. Texts are sent
+//! in batches of up to `batch_size` per request.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use super::{check_dimension, Embedder, EmbeddingError, DEFAULT_BATCH_SIZE, EMBEDDING_DIM};
+
+/// Default base URL (llama.cpp's `llama-server`)
+pub const DEFAULT_OPENAI_URL: &str = "http://localhost:8080/v1";
+
+/// Embedder backed by an OpenAI-compatible embeddings endpoint
+pub struct OpenAiEmbedder {
+    client: Client,
+    base_url: String,
+    model: String,
+    api_key: Option<String>,
+    dimensions: usize,
+    batch_size: usize,
+}
+
+/// Request body for the embeddings endpoint
+#[derive(Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+/// Response from the embeddings endpoint
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    index: usize,
+}
+
+/// Error response (`{"error": {"message": ...}}`)
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorDetail,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetail {
+    message: String,
+}
+
+impl OpenAiEmbedder {
+    /// Create an embedder for the server at `base_url` (including `/v1`)
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            model: model.into(),
+            api_key: None,
+            dimensions: EMBEDDING_DIM,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Send `Authorization: Bearer <key>` with every request
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Set the dimension the model produces
+    pub fn with_dimensions(mut self, dimensions: usize) -> Self {
+        self.dimensions = dimensions;
+        self
+    }
+
+    /// Set how many texts are sent per request
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Get the configured base URL
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Embed one batch of texts
+    async fn embed_request(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let url = format!("{}/embeddings", self.base_url);
+        let mut request = self.client.post(&url).json(&EmbeddingsRequest {
+            model: &self.model,
+            input: texts,
+        });
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            if e.is_connect() {
+                EmbeddingError::ServiceUnavailable {
+                    url: self.base_url.clone(),
+                }
+            } else {
+                EmbeddingError::Request(e)
+            }
+        })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            let message = serde_json::from_str::<ErrorResponse>(&error_text)
+                .map(|e| e.error.message)
+                .unwrap_or(error_text);
+            return Err(EmbeddingError::Http {
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        let mut result: EmbeddingsResponse = response.json().await?;
+        if result.data.len() != texts.len() {
+            return Err(EmbeddingError::ServerError(format!(
+                "expected {} embeddings, got {}",
+                texts.len(),
+                result.data.len()
+            )));
+        }
+
+        // Servers may return the items in any order
+        result.data.sort_by_key(|d| d.index);
+        result
+            .data
+            .into_iter()
+            .map(|d| {
+                check_dimension(&d.embedding, self.dimensions)?;
+                Ok(d.embedding)
+            })
+            .collect()
+    }
+}
+
+impl Embedder for OpenAiEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>, EmbeddingError> {
+        let mut embeddings = self.embed_batch(&[text.to_string()]).await?;
+        embeddings
+            .pop()
+            .ok_or_else(|| EmbeddingError::ServerError("empty response".to_string()))
+    }
+
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            embeddings.extend(self.embed_request(batch).await?);
+        }
+        Ok(embeddings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() {
+        let embedder = OpenAiEmbedder::new("http://localhost:1234/v1/", "bge-small")
+            .with_api_key("secret")
+            .with_dimensions(384);
+        assert_eq!(embedder.base_url(), "http://localhost:1234/v1");
+        assert_eq!(embedder.model(), "bge-small");
+        assert_eq!(embedder.dimensions(), 384);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server() {
+        let embedder = OpenAiEmbedder::new("http://127.0.0.1:9/v1", "bge-small");
+        assert!(matches!(
+            embedder.embed("text").await,
+            Err(EmbeddingError::ServiceUnavailable { .. })
+        ));
+        assert!(!embedder.health_check().await.unwrap());
+    }
+}
@@ -0,0 +1,137 @@
+//! Retrying transient embedding failures with exponential backoff
+
+use std::future::Future;
+use std::time::Duration;
+
+use super::EmbeddingError;
+
+/// How often and how patiently to retry transient embedding errors
+///
+/// Only errors for which [`EmbeddingError::is_transient`] holds are retried;
+/// the wait doubles after every attempt, up to `max_backoff`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Wait before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for the wait between retries
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Fail on the first error
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Wait before retry number `retry` (starting at 0)
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Run `operation`, retrying it while it fails with transient errors
+    pub async fn run<T, F, Fut>(&self, mut operation: F) -> Result<T, EmbeddingError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, EmbeddingError>>,
+    {
+        let mut retry = 0;
+        loop {
+            match operation().await {
+                Err(e) if e.is_transient() && retry < self.max_retries => {
+                    let wait = self.backoff(retry);
+                    tracing::warn!("{}; retrying in {:?}", e, wait);
+                    tokio::time::sleep(wait).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http(status: u16) -> EmbeddingError {
+        EmbeddingError::Http {
+            status,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(0), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(10), Duration::from_secs(8));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(8));
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_errors_only() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        };
+
+        let mut attempts = 0;
+        let result = policy
+            .run(|| {
+                attempts += 1;
+                let result = if attempts < 3 {
+                    Err(http(503))
+                } else {
+                    Ok(attempts)
+                };
+                async move { result }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 3);
+
+        // Bad input won't get better by asking again
+        let mut attempts = 0;
+        let result: Result<(), _> = policy
+            .run(|| {
+                attempts += 1;
+                async { Err(http(400)) }
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(EmbeddingError::Http { status: 400, .. })
+        ));
+        assert_eq!(attempts, 1);
+
+        // Gives up after max_retries
+        let mut attempts = 0;
+        let result: Result<(), _> = policy
+            .run(|| {
+                attempts += 1;
+                async { Err(http(429)) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 4);
+    }
+}
@@ -2,14 +2,26 @@
 //!
 //! This module handles:
 //! - Markdown parsing and chunking
-//! - Embedding generation (Ollama)
+//! - Embedding generation (Ollama, OpenAI-compatible servers or offline hashing)
 //! - Semantic search via sqlite-vec
+//! - Hybrid keyword + semantic search with reciprocal rank fusion
+//! - Re-embedding the vault when switching embedding models
 
 mod chunker;
 mod embeddings;
+mod reembed;
+mod search;
 
 pub use chunker::Chunker;
-pub use embeddings::{EmbeddingError, EmbeddingService, EMBEDDING_DIM};
+pub use embeddings::{
+    AnyEmbedder, Embedder, EmbeddingError, EmbeddingService, HashingEmbedder, OllamaEmbedder,
+    OpenAiEmbedder, RetryPolicy, DEFAULT_BATCH_SIZE, DEFAULT_MODEL, DEFAULT_OLLAMA_URL,
+    DEFAULT_OPENAI_URL, EMBEDDING_DIM,
+};
+pub use reembed::{reembed, ReembedOptions, ReembedReport, DEFAULT_REEMBED_BATCH};
+pub use search::{
+    reciprocal_rank_fusion, search, SearchFilters, SearchHit, SearchMode, SearchOutcome, RRF_K,
+};
 
 use crate::models::{ChunkConfig, Note};
 use crate::store::{Store, StoreError};
@@ -32,32 +44,44 @@ pub enum RagError {
 pub type RagResult<T> = Result<T, RagError>;
 
 /// RAG pipeline for processing notes into searchable chunks
-pub struct RagPipeline {
+pub struct RagPipeline<E: Embedder = OllamaEmbedder> {
     chunker: Chunker,
-    embedder: EmbeddingService,
+    embedder: E,
+    retry: RetryPolicy,
 }
 
 impl RagPipeline {
     /// Create a new RAG pipeline with default configuration
     pub fn new() -> Self {
-        Self {
-            chunker: Chunker::default(),
-            embedder: EmbeddingService::new(),
-        }
+        Self::with_embedder(ChunkConfig::default(), OllamaEmbedder::new())
     }
 
     /// Create a pipeline with custom configuration
     pub fn with_config(chunk_config: ChunkConfig, ollama_url: &str, model: &str) -> Self {
+        Self::with_embedder(chunk_config, OllamaEmbedder::with_config(ollama_url, model))
+    }
+}
+
+impl<E: Embedder> RagPipeline<E> {
+    /// Create a pipeline using any embedding provider
+    pub fn with_embedder(chunk_config: ChunkConfig, embedder: E) -> Self {
         Self {
             chunker: Chunker::new(chunk_config),
-            embedder: EmbeddingService::with_config(ollama_url, model),
+            embedder,
+            retry: RetryPolicy::default(),
         }
     }
 
+    /// Set how transient embedding errors are retried
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Process a note: chunk content, generate embeddings, save to store
     ///
     /// Returns the number of chunks created
-    pub async fn process_note<S: Store>(&self, store: &S, note: &Note) -> RagResult<usize> {
+    pub async fn process_note<S: Store + ?Sized>(&self, store: &S, note: &Note) -> RagResult<usize> {
         // Skip if note has no content
         if note.content.trim().is_empty() {
             return Ok(0);
@@ -72,7 +96,10 @@ impl RagPipeline {
 
         // 2. Generate embeddings for each chunk
         let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
-        let embeddings = self.embedder.embed_batch(&texts).await?;
+        let embeddings = self
+            .retry
+            .run(|| self.embedder.embed_batch(&texts))
+            .await?;
 
         // 3. Attach embeddings to chunks
         let chunks_with_embeddings: Vec<_> = chunks
@@ -84,13 +111,13 @@ impl RagPipeline {
         let chunk_count = chunks_with_embeddings.len();
 
         // 4. Save to store
-        store.save_chunks(&note.id, &chunks_with_embeddings)?;
+        store.save_chunks(&note.id, self.embedder.model(), &chunks_with_embeddings)?;
 
         Ok(chunk_count)
     }
 
     /// Delete all chunks for a note
-    pub fn delete_note_chunks<S: Store>(&self, store: &S, note_id: &str) -> RagResult<()> {
+    pub fn delete_note_chunks<S: Store + ?Sized>(&self, store: &S, note_id: &str) -> RagResult<()> {
         store.delete_chunks(note_id)?;
         Ok(())
     }
@@ -106,7 +133,7 @@ impl RagPipeline {
     }
 
     /// Get a reference to the embedding service
-    pub fn embedder(&self) -> &EmbeddingService {
+    pub fn embedder(&self) -> &E {
         &self.embedder
     }
 }
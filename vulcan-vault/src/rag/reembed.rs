@@ -0,0 +1,308 @@
+//! Re-embedding the vault with a different model
+//!
+//! Every model gets its own embedding space, so a new model's vectors are
+//! backfilled next to the ones searches currently use. Only chunks and
+//! memories missing from the new space are embedded, which makes an
+//! interrupted run resumable. Once the space is complete it is activated in
+//! a single update; searches see either the old model or the new one, never
+//! a mix. The store refuses to activate a space that is missing vectors, so
+//! notes indexed with the old model during the run are backfilled before
+//! the switch.
+//!
+//! Batches are embedded several at a time and transient failures are retried
+//! with the run's [`RetryPolicy`].
+
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+
+use super::{Embedder, RagResult, RetryPolicy};
+use crate::index::DEFAULT_CONCURRENCY;
+use crate::store::{Store, StoreError};
+
+/// Chunks or memories embedded per request
+pub const DEFAULT_REEMBED_BATCH: usize = 32;
+
+/// Options for [`reembed`]
+#[derive(Debug, Clone)]
+pub struct ReembedOptions {
+    /// Texts sent to the embedder at once
+    pub batch_size: usize,
+    /// Make the new space active once it is complete
+    pub activate: bool,
+    /// Batches embedded at the same time
+    pub concurrency: usize,
+    /// How transient embedding errors are retried
+    pub retry: RetryPolicy,
+}
+
+impl Default for ReembedOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_REEMBED_BATCH,
+            activate: true,
+            concurrency: DEFAULT_CONCURRENCY,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+/// What a re-embedding run did
+#[derive(Debug, Clone)]
+pub struct ReembedReport {
+    /// Model of the backfilled space
+    pub model: String,
+    /// Model that was active before the run
+    pub previous: Option<String>,
+    /// Chunks embedded in this run
+    pub chunks: usize,
+    /// Memories embedded in this run
+    pub memories: usize,
+    /// Whether the space is now active
+    pub activated: bool,
+}
+
+/// Backfill the embedding space of `embedder`'s model, then switch to it
+///
+/// `on_progress` is called with the number of chunks and memories embedded
+/// so far.
+pub async fn reembed<S: Store + ?Sized, E: Embedder>(
+    store: &S,
+    embedder: &E,
+    options: &ReembedOptions,
+    mut on_progress: impl FnMut(usize),
+) -> RagResult<ReembedReport> {
+    let model = embedder.model();
+    let previous = store.active_embedding_space()?.map(|space| space.model);
+    store.create_embedding_space(model, embedder.dimensions())?;
+
+    let mut chunks = 0;
+    let mut memories = 0;
+    loop {
+        chunks += backfill_chunks(store, embedder, options, chunks + memories, &mut on_progress)
+            .await?;
+        memories +=
+            backfill_memories(store, embedder, options, chunks + memories, &mut on_progress)
+                .await?;
+
+        if !options.activate {
+            break;
+        }
+        match store.activate_embedding_space(model) {
+            Ok(()) => break,
+            // Notes or memories saved to the old space while backfilling
+            Err(StoreError::IncompleteEmbeddingSpace { missing, .. }) => {
+                tracing::info!("{} vectors were added meanwhile, backfilling them", missing);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let activated = store
+        .active_embedding_space()?
+        .is_some_and(|space| space.model == model);
+
+    Ok(ReembedReport {
+        model: model.to_string(),
+        previous,
+        chunks,
+        memories,
+        activated,
+    })
+}
+
+/// Embed the chunks missing from the model's space; returns how many
+async fn backfill_chunks<S: Store + ?Sized, E: Embedder>(
+    store: &S,
+    embedder: &E,
+    options: &ReembedOptions,
+    done: usize,
+    on_progress: &mut impl FnMut(usize),
+) -> RagResult<usize> {
+    let model = embedder.model();
+    let batch_size = options.batch_size.max(1);
+    let concurrency = options.concurrency.max(1);
+
+    let mut chunks = 0;
+    loop {
+        let pending = store.chunks_missing_embeddings(model, batch_size * concurrency)?;
+        if pending.is_empty() {
+            return Ok(chunks);
+        }
+
+        let texts: Vec<String> = pending.iter().map(|c| c.content.clone()).collect();
+        let embeddings =
+            embed_all(embedder, &options.retry, &texts, batch_size, concurrency).await?;
+        for (chunk, embedding) in pending.iter().zip(embeddings) {
+            match store.save_chunk_embedding(&chunk.id, model, &embedding) {
+                Ok(()) => chunks += 1,
+                // Re-chunked since it was read; the new chunks come next
+                Err(StoreError::ChunkNotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        tracing::info!("Embedded {} chunks with {}", chunks, model);
+        on_progress(done + chunks);
+    }
+}
+
+/// Embed the memories missing from the model's space; returns how many
+async fn backfill_memories<S: Store + ?Sized, E: Embedder>(
+    store: &S,
+    embedder: &E,
+    options: &ReembedOptions,
+    done: usize,
+    on_progress: &mut impl FnMut(usize),
+) -> RagResult<usize> {
+    let model = embedder.model();
+    let batch_size = options.batch_size.max(1);
+    let concurrency = options.concurrency.max(1);
+
+    let mut memories = 0;
+    loop {
+        let pending = store.memories_missing_embeddings(model, batch_size * concurrency)?;
+        if pending.is_empty() {
+            return Ok(memories);
+        }
+
+        let texts: Vec<String> = pending.iter().map(|m| m.embedding_text()).collect();
+        let embeddings =
+            embed_all(embedder, &options.retry, &texts, batch_size, concurrency).await?;
+        for (memory, embedding) in pending.iter().zip(embeddings) {
+            store.backfill_memory_embedding(&memory.id, model, &embedding)?;
+            memories += 1;
+        }
+        tracing::info!("Embedded {} memories with {}", memories, model);
+        on_progress(done + memories);
+    }
+}
+
+/// Embed `texts` in batches, up to `concurrency` requests at a time
+async fn embed_all<E: Embedder>(
+    embedder: &E,
+    retry: &RetryPolicy,
+    texts: &[String],
+    batch_size: usize,
+    concurrency: usize,
+) -> RagResult<Vec<Vec<f32>>> {
+    let batches: Vec<Vec<Vec<f32>>> = stream::iter(texts.chunks(batch_size))
+        .map(|batch| retry.run(move || embedder.embed_batch(batch)))
+        .buffered(concurrency)
+        .try_collect()
+        .await?;
+    Ok(batches.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Chunk, ChunkConfig, Memory, Note};
+    use crate::rag::{HashingEmbedder, RagPipeline, DEFAULT_MODEL, EMBEDDING_DIM};
+    use crate::store::SqliteStore;
+
+    #[tokio::test]
+    async fn test_reembed_switches_spaces() {
+        let store = SqliteStore::in_memory().unwrap();
+
+        // A vault embedded with the default model
+        let mut note = Note::learning_note("Ownership", "rust");
+        note.content = "## Borrowing\nReferences borrow without taking ownership.".to_string();
+        store.save_note(&note).unwrap();
+        let chunk = Chunk::new(&note.id, &note.path, note.content.clone(), 0, 0, 10)
+            .with_embedding(vec![0.1; EMBEDDING_DIM]);
+        store
+            .save_chunks(&note.id, DEFAULT_MODEL, &[chunk])
+            .unwrap();
+        let memory = Memory::lesson("Borrow", "Prefer borrowing", "rust", "test-agent");
+        store.save_memory(&memory).unwrap();
+        store
+            .save_memory_embedding(&memory.id, DEFAULT_MODEL, &[0.1; EMBEDDING_DIM])
+            .unwrap();
+
+        // Vectors from another model are refused, not compared
+        let embedder = HashingEmbedder::new(64);
+        let query = embedder.embed_sync("borrowing");
+        assert!(matches!(
+            store.vector_search(embedder.model(), &query, None, None, None, 5),
+            Err(StoreError::EmbeddingSpaceMismatch { .. })
+        ));
+        assert!(matches!(
+            store.save_memory_embedding(&memory.id, embedder.model(), &query),
+            Err(StoreError::UnknownEmbeddingSpace(_))
+        ));
+
+        // Backfill without switching, then resume and switch
+        let options = ReembedOptions {
+            batch_size: 1,
+            activate: false,
+            ..ReembedOptions::default()
+        };
+        let mut progress = Vec::new();
+        let report = reembed(&store, &embedder, &options, |done| progress.push(done))
+            .await
+            .unwrap();
+        assert_eq!((report.chunks, report.memories), (1, 1));
+        assert_eq!(progress, vec![1, 2]);
+        assert!(!report.activated);
+        assert_eq!(
+            store.active_embedding_space().unwrap().unwrap().model,
+            DEFAULT_MODEL
+        );
+
+        // A note indexed after the backfill keeps the new space from being activated
+        let mut late = Note::learning_note("Lifetimes", "rust");
+        late.content = "## Scopes\nLifetimes bound how long references live.".to_string();
+        store.save_note(&late).unwrap();
+        let late_chunk = Chunk::new(&late.id, &late.path, late.content.clone(), 0, 0, 10)
+            .with_embedding(vec![0.1; EMBEDDING_DIM]);
+        store
+            .save_chunks(&late.id, DEFAULT_MODEL, &[late_chunk])
+            .unwrap();
+        assert!(matches!(
+            store.activate_embedding_space(embedder.model()),
+            Err(StoreError::IncompleteEmbeddingSpace { missing: 1, .. })
+        ));
+
+        let report = reembed(&store, &embedder, &ReembedOptions::default(), |_| {})
+            .await
+            .unwrap();
+        assert_eq!((report.chunks, report.memories), (1, 0));
+        assert_eq!(report.previous.as_deref(), Some(DEFAULT_MODEL));
+        assert!(report.activated);
+
+        let results = store
+            .vector_search(embedder.model(), &query, None, None, None, 5)
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(store
+            .vector_search(DEFAULT_MODEL, &[0.1; EMBEDDING_DIM], None, None, None, 5)
+            .is_err());
+
+        // Writes with the old model are refused rather than lost in its space
+        assert!(matches!(
+            store.save_memory_embedding(&memory.id, DEFAULT_MODEL, &[0.1; EMBEDDING_DIM]),
+            Err(StoreError::EmbeddingSpaceMismatch { .. })
+        ));
+        let old_chunk = Chunk::new(&note.id, &note.path, note.content.clone(), 0, 0, 10)
+            .with_embedding(vec![0.1; EMBEDDING_DIM]);
+        assert!(matches!(
+            store.save_chunks(&note.id, DEFAULT_MODEL, &[old_chunk]),
+            Err(StoreError::EmbeddingSpaceMismatch { .. })
+        ));
+
+        // Both spaces stay until the old one is dropped
+        let spaces = store.list_embedding_spaces().unwrap();
+        assert_eq!(spaces.len(), 2);
+        assert_eq!(spaces[0].model, embedder.model());
+        assert_eq!((spaces[0].dimensions, spaces[0].chunks), (64, 2));
+        assert!(matches!(
+            store.delete_embedding_space(embedder.model()),
+            Err(StoreError::ActiveEmbeddingSpace(_))
+        ));
+        store.delete_embedding_space(DEFAULT_MODEL).unwrap();
+        assert_eq!(store.list_embedding_spaces().unwrap().len(), 1);
+
+        // Re-indexing a note writes to the new space
+        let pipeline = RagPipeline::with_embedder(ChunkConfig::default(), embedder);
+        pipeline.process_note(&store, &note).await.unwrap();
+        let space = store.active_embedding_space().unwrap().unwrap();
+        assert_eq!(space.chunks, 2);
+    }
+}
@@ -0,0 +1,463 @@
+//! Keyword, semantic and hybrid note search
+//!
+//! Hybrid search runs the FTS5 keyword search and the vector search side by
+//! side and merges the two rankings with reciprocal rank fusion (RRF): a note
+//! scores `1 / (k + rank)` for each list it appears in, so notes both searches
+//! agree on rise to the top without comparing BM25 scores to cosine distances.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Embedder, RagError, RagResult};
+use crate::models::NoteType;
+use crate::store::{KeywordResult, SearchResult, Store, StoreError};
+
+/// RRF damping constant; 60 is the value from the original RRF paper
+pub const RRF_K: f32 = 60.0;
+
+/// Minimum number of candidates fetched from each search before fusion
+const MIN_CANDIDATES: usize = 30;
+
+/// How to match a query against the vault
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchMode {
+    /// Vector similarity only (requires Ollama)
+    Semantic,
+    /// FTS5 BM25 ranking only
+    Keyword,
+    /// Both, fused with reciprocal rank fusion
+    #[default]
+    Hybrid,
+}
+
+impl fmt::Display for SearchMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchMode::Semantic => write!(f, "semantic"),
+            SearchMode::Keyword => write!(f, "keyword"),
+            SearchMode::Hybrid => write!(f, "hybrid"),
+        }
+    }
+}
+
+impl FromStr for SearchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "semantic" | "vector" => Ok(SearchMode::Semantic),
+            "keyword" | "fts" => Ok(SearchMode::Keyword),
+            "hybrid" => Ok(SearchMode::Hybrid),
+            _ => Err(format!("Invalid search mode: {}", s)),
+        }
+    }
+}
+
+/// Filters applied to both keyword and vector search
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilters {
+    pub note_types: Option<Vec<NoteType>>,
+    pub project: Option<String>,
+    /// Match notes that have ANY of these tags
+    pub tags: Option<Vec<String>>,
+}
+
+/// A search result from any mode
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub note_id: String,
+    pub note_path: String,
+    pub note_title: String,
+    pub note_type: NoteType,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+    /// Chunk the content comes from, if any
+    pub chunk_id: Option<String>,
+    pub heading: Option<String>,
+    /// Matching chunk content or note excerpt
+    pub content: String,
+    /// Ranking score, higher is better: similarity (semantic), BM25
+    /// (keyword) or the fused RRF score (hybrid)
+    pub score: f32,
+    /// 1-based position in the keyword ranking
+    pub keyword_rank: Option<usize>,
+    /// 1-based position in the vector ranking
+    pub vector_rank: Option<usize>,
+    /// Cosine similarity (0-1) when the vector search found the note
+    pub similarity: Option<f32>,
+}
+
+impl SearchHit {
+    fn from_keyword(result: KeywordResult, rank: usize) -> Self {
+        Self {
+            score: result.score as f32,
+            note_id: result.note_id,
+            note_path: result.note_path,
+            note_title: result.note_title,
+            note_type: result.note_type,
+            project: result.project,
+            tags: result.tags,
+            chunk_id: result.chunk_id,
+            heading: result.heading,
+            content: result.content,
+            keyword_rank: Some(rank),
+            vector_rank: None,
+            similarity: None,
+        }
+    }
+
+    fn from_vector(result: SearchResult, rank: usize) -> Self {
+        // Cosine distance: 0 = identical, 2 = opposite
+        let similarity = 1.0 - (result.distance / 2.0);
+        Self {
+            note_id: result.note_id,
+            note_path: result.note_path,
+            note_title: result.note_title,
+            note_type: result.note_type,
+            project: result.project,
+            tags: result.tags,
+            chunk_id: Some(result.chunk_id),
+            heading: result.heading,
+            content: result.content,
+            score: similarity,
+            keyword_rank: None,
+            vector_rank: Some(rank),
+            similarity: Some(similarity),
+        }
+    }
+}
+
+/// Results of a search, with the mode that actually ran
+#[derive(Debug, Clone)]
+pub struct SearchOutcome {
+    /// Hybrid search falls back to keyword search when no query embedding
+    /// can be generated, or the configured model isn't the index's
+    pub mode: SearchMode,
+    pub hits: Vec<SearchHit>,
+}
+
+/// Merge a keyword and a vector ranking into one list of notes
+///
+/// Both inputs are expected best first. Each note appears once; the content
+/// comes from its best vector chunk when it has one, since that's the passage
+/// closest in meaning to the query.
+pub fn reciprocal_rank_fusion(
+    keyword: Vec<KeywordResult>,
+    vector: Vec<SearchResult>,
+    limit: usize,
+) -> Vec<SearchHit> {
+    let mut hits: Vec<SearchHit> = Vec::new();
+
+    let mut rank = 0;
+    for result in vector {
+        if hits.iter().any(|h| h.note_id == result.note_id) {
+            continue;
+        }
+        rank += 1;
+        let mut hit = SearchHit::from_vector(result, rank);
+        hit.score = 1.0 / (RRF_K + rank as f32);
+        hits.push(hit);
+    }
+
+    for (i, result) in keyword.into_iter().enumerate() {
+        let rank = i + 1;
+        let rrf = 1.0 / (RRF_K + rank as f32);
+        match hits.iter_mut().find(|h| h.note_id == result.note_id) {
+            Some(hit) => {
+                hit.score += rrf;
+                hit.keyword_rank = Some(rank);
+            }
+            None => {
+                let mut hit = SearchHit::from_keyword(result, rank);
+                hit.score = rrf;
+                hits.push(hit);
+            }
+        }
+    }
+
+    hits.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.note_title.cmp(&b.note_title))
+    });
+    hits.truncate(limit);
+    hits
+}
+
+/// Search the vault for `query` in the given mode
+///
+/// Semantic results are per chunk, keyword and hybrid results per note.
+/// Semantic search fails if the query can't be embedded; hybrid search logs
+/// a warning and returns keyword results instead.
+pub async fn search<S: Store + ?Sized, E: Embedder>(
+    store: &S,
+    embedder: &E,
+    query: &str,
+    mode: SearchMode,
+    filters: &SearchFilters,
+    limit: usize,
+) -> RagResult<SearchOutcome> {
+    let note_types = filters.note_types.as_deref();
+    let project = filters.project.as_deref();
+    let tags = filters.tags.as_deref();
+
+    let keyword_hits = |limit| -> RagResult<Vec<SearchHit>> {
+        Ok(store
+            .keyword_search(query, note_types, project, tags, limit)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| SearchHit::from_keyword(r, i + 1))
+            .collect())
+    };
+
+    match mode {
+        SearchMode::Keyword => Ok(SearchOutcome {
+            mode,
+            hits: keyword_hits(limit)?,
+        }),
+        SearchMode::Semantic => {
+            let embedding = embedder.embed(query).await?;
+            let hits = store
+                .vector_search(
+                    embedder.model(),
+                    &embedding,
+                    note_types,
+                    project,
+                    tags,
+                    limit,
+                )?
+                .into_iter()
+                .enumerate()
+                .map(|(i, r)| SearchHit::from_vector(r, i + 1))
+                .collect();
+            Ok(SearchOutcome { mode, hits })
+        }
+        SearchMode::Hybrid => {
+            let candidates = (limit * 3).max(MIN_CANDIDATES);
+            // Several chunks of one note may match, so fetch extra
+            let vector = match embedder.embed(query).await {
+                Ok(embedding) => store
+                    .vector_search(
+                        embedder.model(),
+                        &embedding,
+                        note_types,
+                        project,
+                        tags,
+                        candidates * 2,
+                    )
+                    .map_err(RagError::from),
+                Err(e) => Err(RagError::from(e)),
+            };
+            let vector = match vector {
+                Ok(vector) => vector,
+                Err(
+                    e @ (RagError::Embedding(_)
+                    | RagError::Store(
+                        StoreError::EmbeddingSpaceMismatch { .. }
+                        | StoreError::InvalidEmbeddingDimension { .. },
+                    )),
+                ) => {
+                    tracing::warn!("Falling back to keyword search: {}", e);
+                    return Ok(SearchOutcome {
+                        mode: SearchMode::Keyword,
+                        hits: keyword_hits(limit)?,
+                    });
+                }
+                Err(e) => return Err(e),
+            };
+            let keyword = store.keyword_search(query, note_types, project, tags, candidates)?;
+
+            Ok(SearchOutcome {
+                mode,
+                hits: reciprocal_rank_fusion(keyword, vector, limit),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChunkConfig, Note};
+    use crate::rag::{HashingEmbedder, OllamaEmbedder, RagPipeline};
+    use crate::store::SqliteStore;
+
+    fn keyword(note_id: &str) -> KeywordResult {
+        KeywordResult {
+            note_id: note_id.to_string(),
+            note_path: format!("{}.md", note_id),
+            note_title: note_id.to_string(),
+            note_type: NoteType::Meta,
+            project: None,
+            tags: Vec::new(),
+            chunk_id: None,
+            heading: None,
+            content: format!("{} excerpt", note_id),
+            score: 1.0,
+        }
+    }
+
+    fn vector(note_id: &str, chunk: usize) -> SearchResult {
+        SearchResult {
+            chunk_id: format!("{}-{}", note_id, chunk),
+            note_id: note_id.to_string(),
+            note_path: format!("{}.md", note_id),
+            note_title: note_id.to_string(),
+            note_type: NoteType::Meta,
+            project: None,
+            tags: Vec::new(),
+            content: format!("{} chunk {}", note_id, chunk),
+            heading: None,
+            distance: 0.2,
+        }
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let hits = reciprocal_rank_fusion(
+            vec![keyword("a"), keyword("b"), keyword("c")],
+            vec![
+                vector("c", 0),
+                vector("c", 1),
+                vector("d", 0),
+                vector("a", 0),
+            ],
+            10,
+        );
+
+        let ids: Vec<&str> = hits.iter().map(|h| h.note_id.as_str()).collect();
+        // c: keyword #3 + vector #1, a: keyword #1 + vector #3
+        assert_eq!(ids, vec!["a", "c", "b", "d"]);
+
+        let c = &hits[1];
+        assert_eq!(c.keyword_rank, Some(3));
+        assert_eq!(c.vector_rank, Some(1));
+        assert_eq!(c.content, "c chunk 0");
+        assert!((c.score - (1.0 / 63.0 + 1.0 / 61.0)).abs() < 1e-6);
+
+        let b = &hits[2];
+        assert_eq!(b.vector_rank, None);
+        assert_eq!(b.similarity, None);
+        assert_eq!(b.content, "b excerpt");
+        assert_eq!(hits[3].vector_rank, Some(2));
+
+        assert_eq!(
+            reciprocal_rank_fusion(vec![keyword("a")], vec![vector("b", 0)], 1).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_search_mode_parsing() {
+        assert_eq!("hybrid".parse::<SearchMode>().unwrap(), SearchMode::Hybrid);
+        assert_eq!(
+            "Keyword".parse::<SearchMode>().unwrap(),
+            SearchMode::Keyword
+        );
+        assert_eq!(
+            "semantic".parse::<SearchMode>().unwrap(),
+            SearchMode::Semantic
+        );
+        assert!("fuzzy".parse::<SearchMode>().is_err());
+        assert_eq!(SearchMode::default().to_string(), "hybrid");
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_falls_back_to_keyword() {
+        let store = SqliteStore::in_memory().unwrap();
+        let mut note = Note::new("Tokio", NoteType::Learning, "Learning/tokio.md");
+        note.content = "Async runtime".to_string();
+        store.save_note(&note).unwrap();
+
+        // Nothing listens on the discard port
+        let embedder = OllamaEmbedder::with_config("http://127.0.0.1:9", "nomic-embed-text");
+        let filters = SearchFilters::default();
+
+        let outcome = search(
+            &store,
+            &embedder,
+            "runtime",
+            SearchMode::Hybrid,
+            &filters,
+            5,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome.mode, SearchMode::Keyword);
+        assert_eq!(outcome.hits.len(), 1);
+        assert_eq!(outcome.hits[0].keyword_rank, Some(1));
+
+        assert!(search(
+            &store,
+            &embedder,
+            "runtime",
+            SearchMode::Semantic,
+            &filters,
+            5
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search() {
+        let store = SqliteStore::in_memory().unwrap();
+        let rag = RagPipeline::with_embedder(ChunkConfig::default(), HashingEmbedder::default());
+        for (title, project, content) in [
+            (
+                "Ownership",
+                "rust",
+                "The borrow checker enforces ownership rules.",
+            ),
+            (
+                "Runtime",
+                "rust",
+                "Tokio schedules async tasks on worker threads.",
+            ),
+            (
+                "Recipes",
+                "cooking",
+                "Chocolate cake needs butter and ownership of an oven.",
+            ),
+        ] {
+            let mut note = Note::project_note(title, project);
+            note.content = content.to_string();
+            store.save_note(&note).unwrap();
+            rag.process_note(&store, &note).await.unwrap();
+        }
+
+        let filters = SearchFilters::default();
+        let outcome = search(
+            &store,
+            rag.embedder(),
+            "ownership borrow checker",
+            SearchMode::Hybrid,
+            &filters,
+            5,
+        )
+        .await
+        .unwrap();
+        assert_eq!(outcome.mode, SearchMode::Hybrid);
+        let top = &outcome.hits[0];
+        assert_eq!(top.note_title, "Ownership");
+        assert_eq!((top.keyword_rank, top.vector_rank), (Some(1), Some(1)));
+        assert!(top.similarity.is_some());
+
+        let filters = SearchFilters {
+            project: Some("cooking".to_string()),
+            ..Default::default()
+        };
+        let outcome = search(
+            &store,
+            rag.embedder(),
+            "ownership",
+            SearchMode::Hybrid,
+            &filters,
+            5,
+        )
+        .await
+        .unwrap();
+        let titles: Vec<&str> = outcome.hits.iter().map(|h| h.note_title.as_str()).collect();
+        assert_eq!(titles, vec!["Recipes"]);
+    }
+}
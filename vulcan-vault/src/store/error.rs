@@ -26,6 +26,23 @@ pub enum StoreError {
     #[error("Invalid embedding dimension: expected {expected}, got {got}")]
     InvalidEmbeddingDimension { expected: usize, got: usize },
 
+    #[error(
+        "Embeddings from '{requested}' can't be compared with the index built by '{active}'. \
+         Run: vulcan-vault reembed --model {requested}"
+    )]
+    EmbeddingSpaceMismatch { active: String, requested: String },
+
+    #[error("No embedding space for model '{0}'. Run: vulcan-vault reembed --model {0}")]
+    UnknownEmbeddingSpace(String),
+
+    #[error(
+        "Embedding space '{model}' is missing {missing} vectors. Run: vulcan-vault reembed --model {model}"
+    )]
+    IncompleteEmbeddingSpace { model: String, missing: usize },
+
+    #[error("Cannot delete the active embedding space '{0}'")]
+    ActiveEmbeddingSpace(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -3,9 +3,17 @@
 //! Uses SQLite with sqlite-vec extension for vector storage.
 //! The database stores:
 //! - notes: Metadata cache for quick filtering
-//! - chunks: Vector embeddings for semantic search
+//! - embedding_spaces: One row per embedding model, naming its vector tables
+//! - chunks_vec_N / memory_vec_N: Vector embeddings of chunks and memories
+//!   in embedding space N (a vault indexed before spaces existed keeps its
+//!   original `chunks` and `memory_embeddings` tables as its first space)
 //! - chunk_meta: Chunk content and positions
 //! - links: Wikilink graph cache
+//! - unresolved_links: Links whose target note doesn't exist (yet)
+//! - notes_fts / chunks_fts: FTS5 full-text indexes for keyword search
+//! - rebuild_progress: Notes already re-embedded by an unfinished rebuild
+//! - embedding_queue: Notes waiting for the embedding server
+//! - maintenance: When `vulcan-vault maintain` last ran
 
 mod sqlite_store;
 mod error;
@@ -13,6 +21,8 @@ mod error;
 pub use sqlite_store::SqliteStore;
 pub use error::StoreError;
 
+use std::collections::HashSet;
+
 use crate::models::{Note, NoteType, Chunk, Memory};
 
 /// Result type for store operations
@@ -26,6 +36,10 @@ pub trait Store: Send + Sync {
     // === Note Operations ===
 
     /// Insert or update a note
+    ///
+    /// When the note carries content, its wikilinks and Markdown links are
+    /// extracted and resolved (by path, file name, title or alias), replacing
+    /// its stored links. Notes saved without content keep their links.
     fn save_note(&self, note: &Note) -> StoreResult<()>;
 
     /// Get note by ID
@@ -34,6 +48,9 @@ pub trait Store: Send + Sync {
     /// Get note by path
     fn get_note_by_path(&self, path: &str) -> StoreResult<Option<Note>>;
 
+    /// Get notes whose content hash matches (e.g. to recognise a moved file)
+    fn get_notes_by_hash(&self, content_hash: &str) -> StoreResult<Vec<Note>>;
+
     /// Delete a note
     fn delete_note(&self, id: &str) -> StoreResult<()>;
 
@@ -45,9 +62,37 @@ pub trait Store: Send + Sync {
         limit: usize,
     ) -> StoreResult<Vec<Note>>;
 
+    /// List a page of notes, most recently modified first
+    ///
+    /// With `note_types`, only notes of those types are listed.
+    fn list_notes_page(
+        &self,
+        note_types: Option<&[NoteType]>,
+        offset: usize,
+        limit: usize,
+    ) -> StoreResult<Vec<Note>>;
+
+    /// Get the most recently modified checkpoint of a session
+    fn latest_checkpoint(&self, session_id: &str) -> StoreResult<Option<Note>>;
+
     /// Search notes by keyword
     fn search_notes(&self, query: &str, limit: usize) -> StoreResult<Vec<Note>>;
 
+    /// Full-text search over note titles, aliases, tags, bodies and chunks,
+    /// ranked by BM25
+    ///
+    /// Each note appears at most once, best match first. Query words are
+    /// matched independently (any word matches), so FTS syntax in `query` is
+    /// treated as plain text.
+    fn keyword_search(
+        &self,
+        query: &str,
+        note_types: Option<&[NoteType]>,
+        project: Option<&str>,
+        tags: Option<&[String]>,
+        limit: usize,
+    ) -> StoreResult<Vec<KeywordResult>>;
+
     /// Get notes linked to a task
     fn get_notes_by_task(&self, task_id: &str) -> StoreResult<Vec<Note>>;
 
@@ -57,7 +102,11 @@ pub trait Store: Send + Sync {
     // === Chunk Operations ===
 
     /// Save chunks for a note (replaces existing)
-    fn save_chunks(&self, note_id: &str, chunks: &[Chunk]) -> StoreResult<()>;
+    ///
+    /// Chunk embeddings are stored in the embedding space of `model`, which
+    /// must be the active one. The note's vectors in every other space are
+    /// dropped, to be backfilled by a later re-embedding.
+    fn save_chunks(&self, note_id: &str, model: &str, chunks: &[Chunk]) -> StoreResult<()>;
 
     /// Get chunks for a note
     fn get_chunks(&self, note_id: &str) -> StoreResult<Vec<Chunk>>;
@@ -66,8 +115,12 @@ pub trait Store: Send + Sync {
     fn delete_chunks(&self, note_id: &str) -> StoreResult<()>;
 
     /// Vector similarity search
+    ///
+    /// `embedding` must come from `model`, the model of the active embedding
+    /// space; vectors from different models are never compared.
     fn vector_search(
         &self,
+        model: &str,
         embedding: &[f32],
         note_types: Option<&[NoteType]>,
         project: Option<&str>,
@@ -86,6 +139,12 @@ pub trait Store: Send + Sync {
     /// Get incoming links to a note
     fn get_backlinks(&self, note_id: &str) -> StoreResult<Vec<LinkInfo>>;
 
+    /// Get links whose target note could not be found
+    fn get_unresolved_links(&self) -> StoreResult<Vec<UnresolvedLink>>;
+
+    /// Get notes with no resolved links in or out
+    fn find_orphans(&self, note_type: Option<NoteType>, limit: usize) -> StoreResult<Vec<Note>>;
+
     // === Memory Operations ===
 
     /// Save a memory entry
@@ -95,6 +154,8 @@ pub trait Store: Send + Sync {
     fn get_memory(&self, id: &str) -> StoreResult<Option<Memory>>;
 
     /// Search memories by context
+    ///
+    /// Memories superseded by another are left out.
     fn search_memories(
         &self,
         context: &str,
@@ -106,17 +167,35 @@ pub trait Store: Send + Sync {
     /// Update memory confidence
     fn update_memory_confidence(&self, id: &str, confidence: f32) -> StoreResult<()>;
 
-    /// Save memory embedding for semantic search
-    fn save_memory_embedding(&self, memory_id: &str, embedding: &[f32]) -> StoreResult<()>;
+    /// Save memory embedding for semantic search, in the space of `model`
+    ///
+    /// Like [`Store::save_chunks`], `model` must be the active space's.
+    fn save_memory_embedding(
+        &self,
+        memory_id: &str,
+        model: &str,
+        embedding: &[f32],
+    ) -> StoreResult<()>;
 
     /// Search memories by embedding similarity
+    ///
+    /// Like [`Store::vector_search`], `model` must be the active space's.
+    /// Memories superseded by another are left out.
     fn search_memories_semantic(
         &self,
+        model: &str,
         embedding: &[f32],
         min_confidence: f32,
         limit: usize,
     ) -> StoreResult<Vec<(Memory, f32)>>; // (memory, distance)
 
+    /// List all memories, superseded ones included, oldest first
+    fn list_memories(&self, memory_type: Option<&str>) -> StoreResult<Vec<Memory>>;
+
+    /// Get a memory's embedding in the space of `model`, if it has one
+    fn get_memory_embedding(&self, memory_id: &str, model: &str)
+        -> StoreResult<Option<Vec<f32>>>;
+
     /// Get memories that need decay processing
     fn get_memories_for_decay(&self) -> StoreResult<Vec<Memory>>;
 
@@ -134,6 +213,96 @@ pub trait Store: Send + Sync {
         times_applied: u32,
     ) -> StoreResult<()>;
 
+    // === Embedding Space Operations ===
+
+    /// List embedding spaces, the active one first
+    fn list_embedding_spaces(&self) -> StoreResult<Vec<EmbeddingSpace>>;
+
+    /// Get the space searches run against (none until something is embedded)
+    fn active_embedding_space(&self) -> StoreResult<Option<EmbeddingSpace>>;
+
+    /// Create the embedding space for a model, or return the existing one
+    ///
+    /// A new space only becomes active if no space is active yet.
+    fn create_embedding_space(&self, model: &str, dimensions: usize)
+        -> StoreResult<EmbeddingSpace>;
+
+    /// Make a model's space the one searches run against
+    ///
+    /// Refused with [`StoreError::IncompleteEmbeddingSpace`] while any chunk
+    /// or memory has no vector in the space; the check and the switch are
+    /// one transaction, so nothing indexed in between is left out.
+    fn activate_embedding_space(&self, model: &str) -> StoreResult<()>;
+
+    /// Delete an inactive embedding space and its vectors
+    fn delete_embedding_space(&self, model: &str) -> StoreResult<()>;
+
+    /// Get chunks that have no embedding in a model's space yet
+    fn chunks_missing_embeddings(&self, model: &str, limit: usize) -> StoreResult<Vec<Chunk>>;
+
+    /// Store the embedding of an existing chunk in a model's space
+    fn save_chunk_embedding(
+        &self,
+        chunk_id: &str,
+        model: &str,
+        embedding: &[f32],
+    ) -> StoreResult<()>;
+
+    /// Get memories that have no embedding in a model's space yet
+    fn memories_missing_embeddings(&self, model: &str, limit: usize)
+        -> StoreResult<Vec<Memory>>;
+
+    /// Store the embedding of a memory in a model's space, active or not
+    fn backfill_memory_embedding(
+        &self,
+        memory_id: &str,
+        model: &str,
+        embedding: &[f32],
+    ) -> StoreResult<()>;
+
+    // === Rebuild Progress ===
+
+    /// Notes a rebuild with `model` has already embedded
+    fn rebuild_progress(&self, model: &str) -> StoreResult<HashSet<String>>;
+
+    /// Record that a rebuild with `model` has embedded a note
+    fn mark_rebuilt(&self, note_id: &str, model: &str) -> StoreResult<()>;
+
+    /// Forget rebuild progress, once finished or to start over
+    fn clear_rebuild_progress(&self) -> StoreResult<()>;
+
+    // === Embedding Queue ===
+
+    /// Remember that a note still needs embeddings, e.g. because the
+    /// embedding server was down when it was written
+    fn queue_embedding(&self, note_id: &str, reason: &str) -> StoreResult<()>;
+
+    /// Notes waiting for embeddings, oldest first
+    fn queued_embeddings(&self) -> StoreResult<Vec<String>>;
+
+    /// Remove a note from the embedding queue
+    fn dequeue_embedding(&self, note_id: &str) -> StoreResult<()>;
+
+    // === Maintenance ===
+
+    /// Count index rows whose note, chunk or memory no longer exists
+    fn count_orphaned_rows(&self) -> StoreResult<OrphanedRows>;
+
+    /// Delete the rows [`Store::count_orphaned_rows`] counts
+    fn remove_orphaned_rows(&self) -> StoreResult<OrphanedRows>;
+
+    /// Merge full-text index segments and refresh query planner statistics
+    fn optimize(&self) -> StoreResult<()>;
+
+    /// Rebuild the database file, returning the bytes reclaimed
+    fn vacuum(&self) -> StoreResult<u64>;
+
+    /// When maintenance last ran (none if it never has)
+    fn last_maintenance(&self) -> StoreResult<Option<chrono::DateTime<chrono::Utc>>>;
+
+    /// Record when maintenance ran
+    fn set_last_maintenance(&self, at: chrono::DateTime<chrono::Utc>) -> StoreResult<()>;
+
     // === Stats ===
 
     /// Get vault statistics
@@ -165,6 +334,51 @@ pub struct SearchResult {
     pub distance: f32,
 }
 
+/// Result of a full-text keyword search
+#[derive(Debug, Clone)]
+pub struct KeywordResult {
+    /// Matching note ID
+    pub note_id: String,
+    /// Note path
+    pub note_path: String,
+    /// Note title
+    pub note_title: String,
+    /// Note type
+    pub note_type: NoteType,
+    /// Project (if any)
+    pub project: Option<String>,
+    /// Note tags
+    pub tags: Vec<String>,
+    /// Best matching chunk, if the note's chunks matched
+    pub chunk_id: Option<String>,
+    /// Section heading of the matching chunk
+    pub heading: Option<String>,
+    /// Matching chunk content, or an excerpt of the note body
+    pub content: String,
+    /// BM25 relevance (higher is better)
+    pub score: f64,
+}
+
+/// Vectors produced by one embedding model
+///
+/// Each model gets its own vector tables, so vaults can hold embeddings from
+/// several models side by side while a new one is backfilled.
+#[derive(Debug, Clone)]
+pub struct EmbeddingSpace {
+    /// Model name as reported by the embedder
+    pub model: String,
+    /// Vector length
+    pub dimensions: usize,
+    /// Whether searches run against this space
+    pub active: bool,
+    /// When the space was created
+    pub created: chrono::DateTime<chrono::Utc>,
+    /// Number of chunks embedded in this space
+    pub chunks: u64,
+    /// Number of memories embedded in this space
+    pub memories: u64,
+}
+
 /// Information about a wikilink
 #[derive(Debug, Clone)]
 pub struct LinkInfo {
@@ -178,6 +392,39 @@ pub struct LinkInfo {
     pub heading: Option<String>,
 }
 
+/// A link whose target doesn't match any note
+#[derive(Debug, Clone)]
+pub struct UnresolvedLink {
+    /// Linking note ID
+    pub source_id: String,
+    /// Linking note path
+    pub source_path: String,
+    /// Target as written (note name or vault path)
+    pub target: String,
+    /// Section heading (if [[note#heading]])
+    pub heading: Option<String>,
+}
+
+/// Index rows left behind by deleted notes, chunks and memories
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrphanedRows {
+    /// Chunks of notes that no longer exist
+    pub chunks: u64,
+    /// Chunk vectors, in any embedding space, without a chunk
+    pub chunk_vectors: u64,
+    /// Memory vectors, in any embedding space, without a memory
+    pub memory_vectors: u64,
+    /// Full-text index entries without a note or chunk
+    pub fts_rows: u64,
+}
+
+impl OrphanedRows {
+    /// Total number of orphaned rows
+    pub fn total(&self) -> u64 {
+        self.chunks + self.chunk_vectors + self.memory_vectors + self.fts_rows
+    }
+}
+
 /// Vault statistics
 #[derive(Debug, Clone, Default)]
 pub struct VaultStats {
@@ -185,6 +432,7 @@ pub struct VaultStats {
     pub notes_by_type: std::collections::HashMap<String, u64>,
     pub total_chunks: u64,
     pub total_links: u64,
+    pub unresolved_links: u64,
     pub total_memories: u64,
     pub projects: Vec<String>,
 }
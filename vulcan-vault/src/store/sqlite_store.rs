@@ -2,19 +2,37 @@
 
 #![allow(clippy::missing_transmute_annotations)]
 
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
 use sqlite_vec::sqlite3_vec_init;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::Mutex;
 use zerocopy::AsBytes;
 
+use crate::graph::extract_links;
 use crate::models::{Chunk, Memory, MemoryType, Note, NoteStatus, NoteType};
-
-use super::{LinkInfo, SearchResult, Store, StoreError, StoreResult, VaultStats};
-
-/// Expected embedding dimensions (nomic-embed-text)
-const EMBEDDING_DIM: usize = 768;
+use crate::rag::{DEFAULT_MODEL, EMBEDDING_DIM};
+
+use super::{
+    EmbeddingSpace, KeywordResult, LinkInfo, OrphanedRows, SearchResult, Store, StoreError,
+    StoreResult, UnresolvedLink, VaultStats,
+};
+
+/// How long a connection waits for another writer to finish
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Columns of `embedding_spaces` read by [`SqliteStore::space_from_row`]
+const SPACE_COLUMNS: &str = "model, dimensions, active, created, chunk_table, memory_table";
+
+/// An embedding space row, including the names of its vector tables
+struct SpaceRow {
+    model: String,
+    dimensions: usize,
+    active: bool,
+    created: String,
+    chunk_table: String,
+    memory_table: String,
+}
 
 /// SQLite-based storage with sqlite-vec for vector operations
 pub struct SqliteStore {
@@ -33,6 +51,11 @@ impl SqliteStore {
         }
 
         let conn = Connection::open(db_path)?;
+        // The CLI, the MCP server and the watch daemon share the database:
+        // wait for another writer instead of failing with SQLITE_BUSY, and
+        // let readers run while one writes
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
         let store = Self {
             conn: Mutex::new(conn),
         };
@@ -112,6 +135,7 @@ impl SqliteStore {
             CREATE INDEX IF NOT EXISTS idx_notes_project ON notes(project);
             CREATE INDEX IF NOT EXISTS idx_notes_task ON notes(task_id);
             CREATE INDEX IF NOT EXISTS idx_notes_path ON notes(path);
+            CREATE INDEX IF NOT EXISTS idx_notes_hash ON notes(content_hash);
 
             -- Chunk metadata table
             CREATE TABLE IF NOT EXISTS chunk_meta (
@@ -141,6 +165,26 @@ impl SqliteStore {
             CREATE INDEX IF NOT EXISTS idx_links_target ON links(target_id);
             CREATE INDEX IF NOT EXISTS idx_links_source ON links(source_id);
 
+            -- Lowercased note aliases, so links resolve by alias without
+            -- scanning every note's alias list
+            CREATE TABLE IF NOT EXISTS note_aliases (
+                note_id TEXT NOT NULL,
+                alias TEXT NOT NULL,
+                PRIMARY KEY (note_id, alias),
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_note_aliases_alias ON note_aliases(alias);
+
+            -- Links whose target note doesn't exist (yet)
+            CREATE TABLE IF NOT EXISTS unresolved_links (
+                source_id TEXT NOT NULL,
+                target TEXT NOT NULL,
+                heading TEXT,
+                PRIMARY KEY (source_id, target),
+                FOREIGN KEY (source_id) REFERENCES notes(id) ON DELETE CASCADE
+            );
+
             -- Memories table for agent memory
             CREATE TABLE IF NOT EXISTS memories (
                 id TEXT PRIMARY KEY,
@@ -156,40 +200,85 @@ impl SqliteStore {
                 times_applied INTEGER DEFAULT 0,
                 created TEXT NOT NULL,
                 last_applied TEXT,
-                note_id TEXT
+                note_id TEXT,
+                supersedes TEXT,
+                merged_from TEXT DEFAULT '[]'
             );
 
             CREATE INDEX IF NOT EXISTS idx_memories_type ON memories(memory_type);
             CREATE INDEX IF NOT EXISTS idx_memories_context ON memories(context);
             CREATE INDEX IF NOT EXISTS idx_memories_confidence ON memories(confidence);
+
+            -- Notes embedded so far by an interrupted rebuild
+            CREATE TABLE IF NOT EXISTS rebuild_progress (
+                note_id TEXT PRIMARY KEY,
+                model TEXT NOT NULL,
+                completed TEXT NOT NULL
+            );
+
+            -- Notes written while the embedding server was unavailable
+            CREATE TABLE IF NOT EXISTS embedding_queue (
+                note_id TEXT PRIMARY KEY,
+                reason TEXT NOT NULL,
+                queued TEXT NOT NULL,
+                FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+            );
+
+            -- When `vulcan-vault maintain` last ran (a single row)
+            CREATE TABLE IF NOT EXISTS maintenance (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                last_run TEXT NOT NULL
+            );
             "#,
         )?;
 
-        // Create virtual table for vector search (note chunks)
-        // Note: We create this separately as it uses special syntax
-        conn.execute(
-            &format!(
-                "CREATE VIRTUAL TABLE IF NOT EXISTS chunks USING vec0(
-                    id TEXT PRIMARY KEY,
-                    note_id TEXT,
-                    embedding FLOAT[{}]
-                )",
-                EMBEDDING_DIM
-            ),
-            [],
+        // Vector tables belong to embedding spaces, one per model, and are
+        // created with the model's dimension when the space is first used
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS embedding_spaces (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                model TEXT UNIQUE NOT NULL,
+                dimensions INTEGER NOT NULL,
+                chunk_table TEXT NOT NULL,
+                memory_table TEXT NOT NULL,
+                active INTEGER NOT NULL DEFAULT 0,
+                created TEXT NOT NULL
+            );
+            "#,
         )?;
+        Self::adopt_legacy_space(&conn)?;
 
-        // Create virtual table for memory embeddings (separate from chunks)
-        conn.execute(
-            &format!(
-                "CREATE VIRTUAL TABLE IF NOT EXISTS memory_embeddings USING vec0(
-                    id TEXT PRIMARY KEY,
-                    embedding FLOAT[{}]
-                )",
-                EMBEDDING_DIM
-            ),
+        // Full-text indexes for keyword search. Note bodies aren't kept in
+        // the notes table, so notes_fts holds its own copy.
+        let fts_exists: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = 'notes_fts'",
             [],
+            |row| row.get(0),
+        )?;
+        conn.execute_batch(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+                note_id UNINDEXED,
+                title,
+                aliases,
+                tags,
+                body,
+                tokenize = 'porter unicode61'
+            );
+
+            CREATE VIRTUAL TABLE IF NOT EXISTS chunks_fts USING fts5(
+                chunk_id UNINDEXED,
+                note_id UNINDEXED,
+                heading,
+                content,
+                tokenize = 'porter unicode61'
+            );
+            "#,
         )?;
+        if fts_exists == 0 {
+            Self::backfill_fts(&conn)?;
+        }
 
         // Run migrations for new columns
         self.run_migrations(&conn)?;
@@ -232,9 +321,181 @@ impl SqliteStore {
             }
         }
 
+        // Memory relation columns
+        let mut stmt = conn.prepare("PRAGMA table_info(memories)")?;
+        let existing_columns: Vec<String> = stmt.query_map([], |row| {
+            row.get::<_, String>(1)
+        })?.filter_map(|r| r.ok()).collect();
+
+        let memory_columns = [
+            ("supersedes", "TEXT"),
+            ("merged_from", "TEXT DEFAULT '[]'"),
+        ];
+
+        for (col_name, col_type) in memory_columns.iter() {
+            if !existing_columns.contains(&col_name.to_string()) {
+                let sql = format!("ALTER TABLE memories ADD COLUMN {} {}", col_name, col_type);
+                conn.execute(&sql, [])?;
+                tracing::info!("Migration: added column {} to memories table", col_name);
+            }
+        }
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_memories_supersedes ON memories(supersedes)",
+            [],
+        )?;
+
+        // Alias index for notes saved before it existed
+        let indexed: i64 =
+            conn.query_row("SELECT COUNT(*) FROM note_aliases", [], |row| row.get(0))?;
+        if indexed == 0 {
+            conn.execute(
+                r#"
+                INSERT OR IGNORE INTO note_aliases (note_id, alias)
+                SELECT notes.id, lower(json_each.value) FROM notes, json_each(notes.aliases)
+                "#,
+                [],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Register the fixed-size vector tables of a database created before
+    /// embedding spaces existed
+    ///
+    /// Those vaults were always embedded with nomic-embed-text at 768
+    /// dimensions, so the tables become that model's space.
+    fn adopt_legacy_space(conn: &Connection) -> StoreResult<()> {
+        let legacy: i64 = conn.query_row(
+            r#"
+            SELECT COUNT(*) FROM sqlite_master
+            WHERE name = 'chunks'
+              AND NOT EXISTS (SELECT 1 FROM embedding_spaces WHERE chunk_table = 'chunks')
+            "#,
+            [],
+            |row| row.get(0),
+        )?;
+        if legacy == 0 {
+            return Ok(());
+        }
+
+        conn.execute(
+            &format!(
+                "CREATE VIRTUAL TABLE IF NOT EXISTS memory_embeddings USING vec0(
+                    id TEXT PRIMARY KEY,
+                    embedding FLOAT[{}]
+                )",
+                EMBEDDING_DIM
+            ),
+            [],
+        )?;
+        conn.execute(
+            r#"
+            INSERT OR IGNORE INTO embedding_spaces
+                (model, dimensions, chunk_table, memory_table, active, created)
+            VALUES (?1, ?2, 'chunks', 'memory_embeddings',
+                    NOT EXISTS (SELECT 1 FROM embedding_spaces WHERE active = 1), ?3)
+            "#,
+            params![DEFAULT_MODEL, EMBEDDING_DIM as i64, chrono::Utc::now().to_rfc3339()],
+        )?;
+        tracing::info!("Migration: registered existing vectors as the {} embedding space", DEFAULT_MODEL);
+
+        Ok(())
+    }
+
+    /// Fill the full-text indexes of a database created before they existed
+    ///
+    /// Bodies are rebuilt from the notes' chunks; notes never embedded get
+    /// their body indexed the next time they're saved with content.
+    fn backfill_fts(conn: &Connection) -> StoreResult<()> {
+        let notes = conn.execute(
+            r#"
+            INSERT INTO notes_fts (note_id, title, aliases, tags, body)
+            SELECT n.id, n.title, n.aliases, n.tags,
+                   COALESCE((SELECT group_concat(cm.content, char(10))
+                             FROM chunk_meta cm WHERE cm.note_id = n.id), '')
+            FROM notes n
+            "#,
+            [],
+        )?;
+        conn.execute(
+            r#"
+            INSERT INTO chunks_fts (chunk_id, note_id, heading, content)
+            SELECT id, note_id, heading, content FROM chunk_meta
+            "#,
+            [],
+        )?;
+        if notes > 0 {
+            tracing::info!("Migration: indexed {} notes for full-text search", notes);
+        }
         Ok(())
     }
 
+    /// Append the type/project/tag filters shared by the search queries
+    /// (notes are aliased as `n`)
+    fn push_search_filters(
+        sql: &mut String,
+        params_vec: &mut Vec<Box<dyn rusqlite::ToSql>>,
+        note_types: Option<&[NoteType]>,
+        project: Option<&str>,
+        tags: Option<&[String]>,
+    ) {
+        if let Some(types) = note_types {
+            let type_list: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+            let placeholders = type_list.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            sql.push_str(&format!(" AND n.note_type IN ({})", placeholders));
+            for t in type_list {
+                params_vec.push(Box::new(t));
+            }
+        }
+
+        if let Some(p) = project {
+            sql.push_str(" AND n.project = ?");
+            params_vec.push(Box::new(p.to_string()));
+        }
+
+        // Tag filtering: match notes that have ANY of the requested tags
+        if let Some(tag_list) = tags {
+            if !tag_list.is_empty() {
+                let placeholders = tag_list.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+                sql.push_str(&format!(
+                    " AND EXISTS (SELECT 1 FROM json_each(n.tags) WHERE json_each.value IN ({}))",
+                    placeholders
+                ));
+                for tag in tag_list {
+                    params_vec.push(Box::new(tag.clone()));
+                }
+            }
+        }
+    }
+
+    /// Turn free text into an FTS5 query matching any of its words
+    ///
+    /// Every word is quoted, so operators and punctuation in user input
+    /// can't produce a syntax error. Returns None if nothing searchable
+    /// is left.
+    fn fts_query(query: &str) -> Option<String> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .filter(|word| word.chars().any(char::is_alphanumeric))
+            .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+            .collect();
+        if terms.is_empty() {
+            None
+        } else {
+            Some(terms.join(" OR "))
+        }
+    }
+
+    /// Start a transaction holding the write lock from the outset
+    ///
+    /// A deferred transaction that reads before it writes can't wait for
+    /// another writer in WAL mode and fails with SQLITE_BUSY at once; taking
+    /// the lock up front makes it wait out the busy timeout instead.
+    fn write_transaction(conn: &Connection) -> StoreResult<Transaction<'_>> {
+        Ok(Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?)
+    }
+
     /// Helper to serialize tags/aliases to JSON
     fn to_json_array(items: &[String]) -> String {
         serde_json::to_string(items).unwrap_or_else(|_| "[]".to_string())
@@ -250,9 +511,38 @@ impl Store for SqliteStore {
     fn save_note(&self, note: &Note) -> StoreResult<()> {
         let conn = self.lock_conn()?;
 
-        conn.execute(
+        // All or nothing: a failed save must not leave the note at this
+        // path deleted, or its index half rebuilt
+        let tx = Self::write_transaction(&conn)?;
+
+        // Another note at this path is replaced. Updating in place (rather
+        // than INSERT OR REPLACE) keeps the note's chunks and links, which
+        // would otherwise be cascade-deleted with the old row.
+        Self::delete_chunk_vectors(
+            &tx,
+            "note_id IN (SELECT id FROM notes WHERE path = ?1 AND id != ?2)",
+            params![note.path, note.id],
+        )?;
+        tx.execute(
+            "DELETE FROM chunks_fts WHERE note_id IN (SELECT id FROM notes WHERE path = ?1 AND id != ?2)",
+            params![note.path, note.id],
+        )?;
+        tx.execute(
+            "DELETE FROM notes_fts WHERE note_id IN (SELECT id FROM notes WHERE path = ?1 AND id != ?2)",
+            params![note.path, note.id],
+        )?;
+        tx.execute(
+            "DELETE FROM note_aliases WHERE note_id IN (SELECT id FROM notes WHERE path = ?1 AND id != ?2)",
+            params![note.path, note.id],
+        )?;
+        tx.execute(
+            "DELETE FROM notes WHERE path = ?1 AND id != ?2",
+            params![note.path, note.id],
+        )?;
+
+        tx.execute(
             r#"
-            INSERT OR REPLACE INTO notes (
+            INSERT INTO notes (
                 id, path, note_type, title, created, modified, status,
                 tags, aliases, project, task_id, context_type, auto_fetch,
                 category, source, course, confidence, review_date,
@@ -265,6 +555,24 @@ impl Store for SqliteStore {
                 ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25,
                 ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34
             )
+            ON CONFLICT(id) DO UPDATE SET
+                path = excluded.path, note_type = excluded.note_type, title = excluded.title,
+                created = excluded.created, modified = excluded.modified, status = excluded.status,
+                tags = excluded.tags, aliases = excluded.aliases, project = excluded.project,
+                task_id = excluded.task_id, context_type = excluded.context_type,
+                auto_fetch = excluded.auto_fetch, category = excluded.category,
+                source = excluded.source, course = excluded.course,
+                confidence = excluded.confidence, review_date = excluded.review_date,
+                memory_type = excluded.memory_type, context = excluded.context,
+                agent = excluded.agent, session_id = excluded.session_id,
+                times_applied = excluded.times_applied, last_applied = excluded.last_applied,
+                content_hash = excluded.content_hash, prp_value = excluded.prp_value,
+                prp_scope = excluded.prp_scope, success_criteria = excluded.success_criteria,
+                implementation_phases = excluded.implementation_phases,
+                linked_tasks = excluded.linked_tasks, checkpoint_name = excluded.checkpoint_name,
+                checkpoint_context = excluded.checkpoint_context,
+                checkpoint_tasks = excluded.checkpoint_tasks,
+                parent_checkpoint = excluded.parent_checkpoint
             "#,
             params![
                 note.id,
@@ -306,6 +614,45 @@ impl Store for SqliteStore {
             ],
         )?;
 
+        // Without content only the metadata columns are reindexed, keeping
+        // the body indexed by an earlier save
+        let body = if note.content.trim().is_empty() {
+            tx.query_row(
+                "SELECT body FROM notes_fts WHERE note_id = ?1",
+                params![note.id],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .unwrap_or_default()
+        } else {
+            note.content.clone()
+        };
+        tx.execute("DELETE FROM notes_fts WHERE note_id = ?1", params![note.id])?;
+        tx.execute(
+            "INSERT INTO notes_fts (note_id, title, aliases, tags, body) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                note.id,
+                note.title,
+                Self::to_json_array(&note.aliases),
+                Self::to_json_array(&note.tags),
+                body,
+            ],
+        )?;
+
+        tx.execute("DELETE FROM note_aliases WHERE note_id = ?1", params![note.id])?;
+        for alias in &note.aliases {
+            tx.execute(
+                "INSERT OR IGNORE INTO note_aliases (note_id, alias) VALUES (?1, ?2)",
+                params![note.id, alias.to_ascii_lowercase()],
+            )?;
+        }
+
+        if !note.content.trim().is_empty() {
+            Self::update_links(&tx, note)?;
+        }
+        Self::resolve_pending_links(&tx, note)?;
+
+        tx.commit()?;
         Ok(())
     }
 
@@ -337,11 +684,42 @@ impl Store for SqliteStore {
         Ok(result)
     }
 
+    fn get_notes_by_hash(&self, content_hash: &str) -> StoreResult<Vec<Note>> {
+        let conn = self.lock_conn()?;
+
+        let mut stmt = conn.prepare("SELECT * FROM notes WHERE content_hash = ?1")?;
+        let notes = stmt
+            .query_map(params![content_hash], Self::note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
     fn delete_note(&self, id: &str) -> StoreResult<()> {
         let conn = self.lock_conn()?;
 
-        // Delete from vector table first
-        conn.execute("DELETE FROM chunks WHERE note_id = ?1", params![id])?;
+        // Delete from vector and full-text tables first
+        Self::delete_chunk_vectors(&conn, "note_id = ?1", params![id])?;
+        conn.execute("DELETE FROM chunks_fts WHERE note_id = ?1", params![id])?;
+        conn.execute("DELETE FROM notes_fts WHERE note_id = ?1", params![id])?;
+
+        // Links into the note become unresolved; its own links go away
+        conn.execute(
+            r#"
+            INSERT OR IGNORE INTO unresolved_links (source_id, target, heading)
+            SELECT source_id, link_text, heading FROM links WHERE target_id = ?1
+            "#,
+            params![id],
+        )?;
+        conn.execute(
+            "DELETE FROM links WHERE source_id = ?1 OR target_id = ?1",
+            params![id],
+        )?;
+        conn.execute(
+            "DELETE FROM unresolved_links WHERE source_id = ?1",
+            params![id],
+        )?;
+        conn.execute("DELETE FROM note_aliases WHERE note_id = ?1", params![id])?;
 
         // Then delete from notes (cascades to chunk_meta and links)
         conn.execute("DELETE FROM notes WHERE id = ?1", params![id])?;
@@ -384,83 +762,261 @@ impl Store for SqliteStore {
         Ok(notes)
     }
 
-    fn search_notes(&self, query: &str, limit: usize) -> StoreResult<Vec<Note>> {
+    fn list_notes_page(
+        &self,
+        note_types: Option<&[NoteType]>,
+        offset: usize,
+        limit: usize,
+    ) -> StoreResult<Vec<Note>> {
         let conn = self.lock_conn()?;
 
-        let pattern = format!("%{}%", query.to_lowercase());
+        let mut sql = String::from("SELECT n.* FROM notes n WHERE 1=1");
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        Self::push_search_filters(&mut sql, &mut params_vec, note_types, None, None);
 
-        let mut stmt = conn.prepare(
-            r#"
-            SELECT * FROM notes
-            WHERE LOWER(title) LIKE ?1 OR LOWER(path) LIKE ?1
-            ORDER BY modified DESC
-            LIMIT ?2
-            "#,
-        )?;
+        // The id breaks ties, so pages don't overlap
+        sql.push_str(" ORDER BY n.modified DESC, n.id LIMIT ? OFFSET ?");
+        params_vec.push(Box::new(limit.min(i64::MAX as usize) as i64));
+        params_vec.push(Box::new(offset.min(i64::MAX as usize) as i64));
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
 
+        let mut stmt = conn.prepare(&sql)?;
         let notes = stmt
-            .query_map(params![pattern, limit as i64], |row| {
-                Self::note_from_row(row)
-            })?
+            .query_map(params_refs.as_slice(), Self::note_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(notes)
     }
 
-    fn get_notes_by_task(&self, task_id: &str) -> StoreResult<Vec<Note>> {
+    fn latest_checkpoint(&self, session_id: &str) -> StoreResult<Option<Note>> {
         let conn = self.lock_conn()?;
 
-        let mut stmt = conn.prepare("SELECT * FROM notes WHERE task_id = ?1")?;
-        let notes = stmt
-            .query_map(params![task_id], Self::note_from_row)?
-            .collect::<Result<Vec<_>, _>>()?;
+        let note = conn
+            .query_row(
+                "SELECT * FROM notes WHERE note_type = ?1 AND session_id = ?2 ORDER BY modified DESC LIMIT 1",
+                params![NoteType::Checkpoint.to_string(), session_id],
+                Self::note_from_row,
+            )
+            .optional()?;
 
-        Ok(notes)
+        Ok(note)
     }
 
-    fn get_notes_by_project(&self, project: &str) -> StoreResult<Vec<Note>> {
+    fn search_notes(&self, query: &str, limit: usize) -> StoreResult<Vec<Note>> {
         let conn = self.lock_conn()?;
 
+        let pattern = format!("%{}%", query.to_lowercase());
+
         let mut stmt = conn.prepare(
-            "SELECT * FROM notes WHERE project = ?1 ORDER BY note_type, title",
+            r#"
+            SELECT * FROM notes
+            WHERE LOWER(title) LIKE ?1 OR LOWER(path) LIKE ?1
+            ORDER BY modified DESC
+            LIMIT ?2
+            "#,
         )?;
+
         let notes = stmt
-            .query_map(params![project], Self::note_from_row)?
+            .query_map(params![pattern, limit as i64], |row| {
+                Self::note_from_row(row)
+            })?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(notes)
     }
 
-    fn save_chunks(&self, note_id: &str, chunks: &[Chunk]) -> StoreResult<()> {
+    fn keyword_search(
+        &self,
+        query: &str,
+        note_types: Option<&[NoteType]>,
+        project: Option<&str>,
+        tags: Option<&[String]>,
+        limit: usize,
+    ) -> StoreResult<Vec<KeywordResult>> {
+        let fts = match Self::fts_query(query) {
+            Some(fts) => fts,
+            None => return Ok(Vec::new()),
+        };
+
         let conn = self.lock_conn()?;
 
-        // Delete existing chunks for this note
-        conn.execute("DELETE FROM chunks WHERE note_id = ?1", params![note_id])?;
-        conn.execute(
-            "DELETE FROM chunk_meta WHERE note_id = ?1",
-            params![note_id],
-        )?;
+        // Title, alias and tag matches count for more than body text.
+        // bm25() is lower for better matches, so it's negated into a score.
+        let mut sql = String::from(
+            r#"
+            SELECT
+                notes_fts.note_id,
+                n.path,
+                n.title,
+                n.note_type,
+                n.project,
+                n.tags,
+                snippet(notes_fts, 4, '', '', '…', 32),
+                -bm25(notes_fts, 0.0, 10.0, 5.0, 5.0, 1.0) as score
+            FROM notes_fts
+            JOIN notes n ON notes_fts.note_id = n.id
+            WHERE notes_fts MATCH ?
+            "#,
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(fts.clone())];
+        Self::push_search_filters(&mut sql, &mut params_vec, note_types, project, tags);
+        sql.push_str(" ORDER BY score DESC LIMIT ?");
+        params_vec.push(Box::new(limit as i64));
 
-        // Insert new chunks
-        for chunk in chunks {
-            // Validate embedding dimension
-            if let Some(ref emb) = chunk.embedding {
-                if emb.len() != EMBEDDING_DIM {
-                    return Err(StoreError::InvalidEmbeddingDimension {
-                        expected: EMBEDDING_DIM,
-                        got: emb.len(),
-                    });
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let mut results = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                let note_type_str: String = row.get(3)?;
+                let tags_json: String = row.get(5)?;
+                Ok(KeywordResult {
+                    note_id: row.get(0)?,
+                    note_path: row.get(1)?,
+                    note_title: row.get(2)?,
+                    note_type: note_type_str.parse().unwrap_or(NoteType::Meta),
+                    project: row.get(4)?,
+                    tags: Self::from_json_array(&tags_json),
+                    chunk_id: None,
+                    heading: None,
+                    content: row.get(6)?,
+                    score: row.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Chunk matches point at the relevant section, and find notes whose
+        // body was only ever indexed through their chunks
+        let mut sql = String::from(
+            r#"
+            SELECT
+                chunks_fts.note_id,
+                n.path,
+                n.title,
+                n.note_type,
+                n.project,
+                n.tags,
+                chunks_fts.chunk_id,
+                cm.heading,
+                cm.content,
+                -bm25(chunks_fts, 0.0, 0.0, 2.0, 1.0) as score
+            FROM chunks_fts
+            JOIN chunk_meta cm ON chunks_fts.chunk_id = cm.id
+            JOIN notes n ON chunks_fts.note_id = n.id
+            WHERE chunks_fts MATCH ?
+            "#,
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(fts)];
+        Self::push_search_filters(&mut sql, &mut params_vec, note_types, project, tags);
+        sql.push_str(" ORDER BY score DESC LIMIT ?");
+        params_vec.push(Box::new((limit * 4) as i64));
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let chunk_hits = stmt
+            .query_map(params_refs.as_slice(), |row| {
+                let note_type_str: String = row.get(3)?;
+                let tags_json: String = row.get(5)?;
+                Ok(KeywordResult {
+                    note_id: row.get(0)?,
+                    note_path: row.get(1)?,
+                    note_title: row.get(2)?,
+                    note_type: note_type_str.parse().unwrap_or(NoteType::Meta),
+                    project: row.get(4)?,
+                    tags: Self::from_json_array(&tags_json),
+                    chunk_id: row.get(6)?,
+                    heading: row.get(7)?,
+                    content: row.get(8)?,
+                    score: row.get(9)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // One result per note: the best chunk (hits arrive best first)
+        // supplies the content, the best of either match the score
+        for hit in chunk_hits {
+            match results.iter_mut().find(|r| r.note_id == hit.note_id) {
+                Some(result) if result.chunk_id.is_none() => {
+                    result.score = result.score.max(hit.score);
+                    result.chunk_id = hit.chunk_id;
+                    result.heading = hit.heading;
+                    result.content = hit.content;
                 }
+                Some(_) => {}
+                None => results.push(hit),
+            }
+        }
 
-                // Insert into vector table
-                conn.execute(
-                    "INSERT INTO chunks (id, note_id, embedding) VALUES (?1, ?2, ?3)",
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    fn get_notes_by_task(&self, task_id: &str) -> StoreResult<Vec<Note>> {
+        let conn = self.lock_conn()?;
+
+        let mut stmt = conn.prepare("SELECT * FROM notes WHERE task_id = ?1")?;
+        let notes = stmt
+            .query_map(params![task_id], Self::note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
+    fn get_notes_by_project(&self, project: &str) -> StoreResult<Vec<Note>> {
+        let conn = self.lock_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT * FROM notes WHERE project = ?1 ORDER BY note_type, title",
+        )?;
+        let notes = stmt
+            .query_map(params![project], Self::note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
+    fn save_chunks(&self, note_id: &str, model: &str, chunks: &[Chunk]) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        // All or nothing: a failure part way must not leave the note with
+        // no chunks, or only some of them
+        let tx = Self::write_transaction(&conn)?;
+
+        // Resolve the space first so a refused model leaves the note intact
+        let space = match chunks.iter().find_map(|c| c.embedding.as_ref()) {
+            Some(emb) => Some(Self::space_for_write(&tx, model, emb.len())?),
+            None => None,
+        };
+
+        // Delete existing chunks for this note
+        Self::delete_chunk_vectors(&tx, "note_id = ?1", params![note_id])?;
+        tx.execute("DELETE FROM chunks_fts WHERE note_id = ?1", params![note_id])?;
+        tx.execute(
+            "DELETE FROM chunk_meta WHERE note_id = ?1",
+            params![note_id],
+        )?;
+
+        // Insert new chunks
+        for chunk in chunks {
+            if let (Some(emb), Some(space)) = (&chunk.embedding, &space) {
+                Self::check_dimension(space, emb.len())?;
+
+                // Insert into the space's vector table
+                tx.execute(
+                    &format!(
+                        "INSERT INTO {} (id, note_id, embedding) VALUES (?1, ?2, ?3)",
+                        space.chunk_table
+                    ),
                     params![chunk.id, note_id, emb.as_bytes()],
                 )?;
             }
 
             // Insert metadata
-            conn.execute(
+            tx.execute(
                 r#"
                 INSERT INTO chunk_meta (
                     id, note_id, content, heading, chunk_index, char_start, char_end
@@ -476,8 +1032,13 @@ impl Store for SqliteStore {
                     chunk.char_end,
                 ],
             )?;
+            tx.execute(
+                "INSERT INTO chunks_fts (chunk_id, note_id, heading, content) VALUES (?1, ?2, ?3, ?4)",
+                params![chunk.id, note_id, chunk.heading, chunk.content],
+            )?;
         }
 
+        tx.commit()?;
         Ok(())
     }
 
@@ -497,19 +1058,7 @@ impl Store for SqliteStore {
         )?;
 
         let chunks = stmt
-            .query_map(params![note_id], |row| {
-                Ok(Chunk {
-                    id: row.get(0)?,
-                    note_id: row.get(1)?,
-                    content: row.get(2)?,
-                    heading: row.get(3)?,
-                    chunk_index: row.get(4)?,
-                    char_start: row.get(5)?,
-                    char_end: row.get(6)?,
-                    note_path: row.get(7)?,
-                    embedding: None, // Don't load embedding by default
-                })
-            })?
+            .query_map(params![note_id], Self::chunk_from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(chunks)
@@ -518,7 +1067,8 @@ impl Store for SqliteStore {
     fn delete_chunks(&self, note_id: &str) -> StoreResult<()> {
         let conn = self.lock_conn()?;
 
-        conn.execute("DELETE FROM chunks WHERE note_id = ?1", params![note_id])?;
+        Self::delete_chunk_vectors(&conn, "note_id = ?1", params![note_id])?;
+        conn.execute("DELETE FROM chunks_fts WHERE note_id = ?1", params![note_id])?;
         conn.execute(
             "DELETE FROM chunk_meta WHERE note_id = ?1",
             params![note_id],
@@ -529,23 +1079,21 @@ impl Store for SqliteStore {
 
     fn vector_search(
         &self,
+        model: &str,
         embedding: &[f32],
         note_types: Option<&[NoteType]>,
         project: Option<&str>,
         tags: Option<&[String]>,
         limit: usize,
     ) -> StoreResult<Vec<SearchResult>> {
-        if embedding.len() != EMBEDDING_DIM {
-            return Err(StoreError::InvalidEmbeddingDimension {
-                expected: EMBEDDING_DIM,
-                got: embedding.len(),
-            });
-        }
-
         let conn = self.lock_conn()?;
 
+        let Some(space) = Self::space_for_query(&conn, model, embedding.len())? else {
+            return Ok(Vec::new());
+        };
+
         // Build query with filters
-        let mut sql = String::from(
+        let mut sql = format!(
             r#"
             SELECT
                 c.id as chunk_id,
@@ -558,43 +1106,18 @@ impl Store for SqliteStore {
                 cm.content,
                 cm.heading,
                 vec_distance_cosine(c.embedding, ?1) as distance
-            FROM chunks c
+            FROM {} c
             JOIN chunk_meta cm ON c.id = cm.id
             JOIN notes n ON c.note_id = n.id
             WHERE 1=1
             "#,
+            space.chunk_table
         );
 
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
         params_vec.push(Box::new(embedding.as_bytes().to_vec()));
 
-        if let Some(types) = note_types {
-            let type_list: Vec<String> = types.iter().map(|t| t.to_string()).collect();
-            let placeholders = type_list.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-            sql.push_str(&format!(" AND n.note_type IN ({})", placeholders));
-            for t in type_list {
-                params_vec.push(Box::new(t));
-            }
-        }
-
-        if let Some(p) = project {
-            sql.push_str(" AND n.project = ?");
-            params_vec.push(Box::new(p.to_string()));
-        }
-
-        // Tag filtering: match notes that have ANY of the requested tags
-        if let Some(tag_list) = tags {
-            if !tag_list.is_empty() {
-                let placeholders = tag_list.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-                sql.push_str(&format!(
-                    " AND EXISTS (SELECT 1 FROM json_each(n.tags) WHERE json_each.value IN ({}))",
-                    placeholders
-                ));
-                for tag in tag_list {
-                    params_vec.push(Box::new(tag.clone()));
-                }
-            }
-        }
+        Self::push_search_filters(&mut sql, &mut params_vec, note_types, project, tags);
 
         sql.push_str(" ORDER BY distance ASC LIMIT ?");
         params_vec.push(Box::new(limit as i64));
@@ -606,13 +1129,7 @@ impl Store for SqliteStore {
         let results = stmt
             .query_map(params_refs.as_slice(), |row| {
                 let note_type_str: String = row.get(4)?;
-                let note_type = match note_type_str.as_str() {
-                    "project" => NoteType::Project,
-                    "task" => NoteType::Task,
-                    "learning" => NoteType::Learning,
-                    "memory" => NoteType::Memory,
-                    _ => NoteType::Meta,
-                };
+                let note_type = note_type_str.parse().unwrap_or(NoteType::Meta);
 
                 // Parse tags from JSON array
                 let tags_json: String = row.get(6)?;
@@ -668,8 +1185,14 @@ impl Store for SqliteStore {
     fn get_outlinks(&self, note_id: &str) -> StoreResult<Vec<LinkInfo>> {
         let conn = self.lock_conn()?;
 
+        // Prefer the target's current path in case it moved since linking
         let mut stmt = conn.prepare(
-            "SELECT target_id, target_path, link_text, heading FROM links WHERE source_id = ?1",
+            r#"
+            SELECT l.target_id, COALESCE(n.path, l.target_path), l.link_text, l.heading
+            FROM links l
+            LEFT JOIN notes n ON l.target_id = n.id
+            WHERE l.source_id = ?1
+            "#,
         )?;
 
         let links = stmt
@@ -712,6 +1235,63 @@ impl Store for SqliteStore {
         Ok(links)
     }
 
+    fn get_unresolved_links(&self) -> StoreResult<Vec<UnresolvedLink>> {
+        let conn = self.lock_conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT u.source_id, n.path, u.target, u.heading
+            FROM unresolved_links u
+            JOIN notes n ON u.source_id = n.id
+            ORDER BY n.path, u.target
+            "#,
+        )?;
+
+        let links = stmt
+            .query_map([], |row| {
+                Ok(UnresolvedLink {
+                    source_id: row.get(0)?,
+                    source_path: row.get(1)?,
+                    target: row.get(2)?,
+                    heading: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(links)
+    }
+
+    fn find_orphans(&self, note_type: Option<NoteType>, limit: usize) -> StoreResult<Vec<Note>> {
+        let conn = self.lock_conn()?;
+
+        let mut sql = String::from(
+            r#"
+            SELECT * FROM notes
+            WHERE id NOT IN (SELECT source_id FROM links)
+              AND id NOT IN (SELECT target_id FROM links)
+            "#,
+        );
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(ref nt) = note_type {
+            sql.push_str(" AND note_type = ?");
+            params_vec.push(Box::new(nt.to_string()));
+        }
+
+        sql.push_str(" ORDER BY modified DESC LIMIT ?");
+        params_vec.push(Box::new(limit as i64));
+
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|p| p.as_ref()).collect();
+
+        let mut stmt = conn.prepare(&sql)?;
+        let notes = stmt
+            .query_map(params_refs.as_slice(), Self::note_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(notes)
+    }
+
     fn save_memory(&self, memory: &Memory) -> StoreResult<()> {
         let conn = self.lock_conn()?;
 
@@ -720,8 +1300,8 @@ impl Store for SqliteStore {
             INSERT OR REPLACE INTO memories (
                 id, memory_type, title, content, context, tags, agent,
                 session_id, project, confidence, times_applied, created,
-                last_applied, note_id
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                last_applied, note_id, supersedes, merged_from
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
             "#,
             params![
                 memory.id,
@@ -738,6 +1318,8 @@ impl Store for SqliteStore {
                 memory.created.to_rfc3339(),
                 memory.last_applied.map(|d| d.to_rfc3339()),
                 memory.note_id,
+                memory.supersedes,
+                Self::to_json_array(&memory.merged_from),
             ],
         )?;
 
@@ -772,6 +1354,7 @@ impl Store for SqliteStore {
             SELECT * FROM memories
             WHERE (LOWER(context) LIKE ?1 OR LOWER(tags) LIKE ?1)
             AND confidence >= ?2
+            AND id NOT IN (SELECT supersedes FROM memories WHERE supersedes IS NOT NULL)
             "#,
         );
 
@@ -809,139 +1392,488 @@ impl Store for SqliteStore {
         Ok(())
     }
 
-    fn save_memory_embedding(&self, memory_id: &str, embedding: &[f32]) -> StoreResult<()> {
-        if embedding.len() != EMBEDDING_DIM {
-            return Err(StoreError::InvalidEmbeddingDimension {
-                expected: EMBEDDING_DIM,
-                got: embedding.len(),
-            });
-        }
-
+    fn save_memory_embedding(
+        &self,
+        memory_id: &str,
+        model: &str,
+        embedding: &[f32],
+    ) -> StoreResult<()> {
         let conn = self.lock_conn()?;
 
-        // Delete existing embedding if any (for upsert behavior)
-        conn.execute(
-            "DELETE FROM memory_embeddings WHERE id = ?1",
-            params![memory_id],
-        )?;
-
-        // Insert new embedding
-        conn.execute(
-            "INSERT INTO memory_embeddings (id, embedding) VALUES (?1, ?2)",
-            params![memory_id, embedding.as_bytes()],
-        )?;
-
+        let tx = Self::write_transaction(&conn)?;
+        let space = Self::space_for_write(&tx, model, embedding.len())?;
+        Self::write_memory_vector(&tx, &space, memory_id, embedding)?;
+        tx.commit()?;
         Ok(())
     }
 
     fn search_memories_semantic(
         &self,
+        model: &str,
         embedding: &[f32],
         min_confidence: f32,
         limit: usize,
     ) -> StoreResult<Vec<(Memory, f32)>> {
-        if embedding.len() != EMBEDDING_DIM {
-            return Err(StoreError::InvalidEmbeddingDimension {
-                expected: EMBEDDING_DIM,
-                got: embedding.len(),
-            });
-        }
-
         let conn = self.lock_conn()?;
 
-        let mut stmt = conn.prepare(
+        let Some(space) = Self::space_for_query(&conn, model, embedding.len())? else {
+            return Ok(Vec::new());
+        };
+
+        let mut stmt = conn.prepare(&format!(
             r#"
             SELECT
                 m.*,
                 vec_distance_cosine(me.embedding, ?1) as distance
-            FROM memory_embeddings me
+            FROM {} me
             JOIN memories m ON me.id = m.id
             WHERE m.confidence >= ?2
+              AND m.id NOT IN (SELECT supersedes FROM memories WHERE supersedes IS NOT NULL)
             ORDER BY distance ASC
             LIMIT ?3
             "#,
+            space.memory_table
+        ))?;
+
+        let results = stmt
+            .query_map(
+                params![embedding.as_bytes(), min_confidence, limit as i64],
+                |row| {
+                    let memory = Self::memory_from_row(row)?;
+                    let distance: f32 = row.get("distance")?;
+                    Ok((memory, distance))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(results)
+    }
+
+    fn list_memories(&self, memory_type: Option<&str>) -> StoreResult<Vec<Memory>> {
+        let conn = self.lock_conn()?;
+
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT * FROM memories
+            WHERE ?1 IS NULL OR memory_type = ?1
+            ORDER BY created ASC
+            "#,
+        )?;
+
+        let memories = stmt
+            .query_map(params![memory_type], Self::memory_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(memories)
+    }
+
+    fn get_memory_embedding(&self, memory_id: &str, model: &str) -> StoreResult<Option<Vec<f32>>> {
+        let conn = self.lock_conn()?;
+
+        let Some(space) = Self::find_space(&conn, model)? else {
+            return Ok(None);
+        };
+
+        let bytes: Option<Vec<u8>> = conn
+            .query_row(
+                &format!("SELECT embedding FROM {} WHERE id = ?1", space.memory_table),
+                params![memory_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(bytes.map(|bytes| {
+            bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()
+        }))
+    }
+
+    fn get_memories_for_decay(&self) -> StoreResult<Vec<Memory>> {
+        let conn = self.lock_conn()?;
+
+        // Get all memories that could potentially decay
+        // (have been around for more than 7 days or have been applied before)
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT * FROM memories
+            WHERE confidence > 0
+            ORDER BY confidence ASC
+            "#,
+        )?;
+
+        let memories = stmt
+            .query_map([], Self::memory_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(memories)
+    }
+
+    fn get_memories_below_confidence(&self, threshold: f32) -> StoreResult<Vec<Memory>> {
+        let conn = self.lock_conn()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT * FROM memories WHERE confidence < ?1 ORDER BY confidence ASC",
+        )?;
+
+        let memories = stmt
+            .query_map(params![threshold], Self::memory_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(memories)
+    }
+
+    fn delete_memory(&self, id: &str) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        // Delete embeddings first
+        for space in Self::all_spaces(&conn)? {
+            conn.execute(
+                &format!("DELETE FROM {} WHERE id = ?1", space.memory_table),
+                params![id],
+            )?;
+        }
+
+        // Delete memory
+        conn.execute("DELETE FROM memories WHERE id = ?1", params![id])?;
+
+        Ok(())
+    }
+
+    fn update_memory_reinforcement(
+        &self,
+        id: &str,
+        confidence: f32,
+        times_applied: u32,
+    ) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        conn.execute(
+            r#"
+            UPDATE memories
+            SET confidence = ?1, times_applied = ?2, last_applied = ?3
+            WHERE id = ?4
+            "#,
+            params![
+                confidence,
+                times_applied as i32,
+                chrono::Utc::now().to_rfc3339(),
+                id
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn list_embedding_spaces(&self) -> StoreResult<Vec<EmbeddingSpace>> {
+        let conn = self.lock_conn()?;
+
+        Self::all_spaces(&conn)?
+            .into_iter()
+            .map(|space| Self::space_info(&conn, space))
+            .collect()
+    }
+
+    fn active_embedding_space(&self) -> StoreResult<Option<EmbeddingSpace>> {
+        let conn = self.lock_conn()?;
+
+        Self::active_space(&conn)?
+            .map(|space| Self::space_info(&conn, space))
+            .transpose()
+    }
+
+    fn create_embedding_space(
+        &self,
+        model: &str,
+        dimensions: usize,
+    ) -> StoreResult<EmbeddingSpace> {
+        let conn = self.lock_conn()?;
+
+        let tx = Self::write_transaction(&conn)?;
+        let space = match Self::find_space(&tx, model)? {
+            Some(space) => {
+                Self::check_dimension(&space, dimensions)?;
+                space
+            }
+            None => Self::new_space(&tx, model, dimensions)?,
+        };
+        tx.commit()?;
+        Self::space_info(&conn, space)
+    }
+
+    fn activate_embedding_space(&self, model: &str) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        let tx = Self::write_transaction(&conn)?;
+        let space = Self::find_space(&tx, model)?
+            .ok_or_else(|| StoreError::UnknownEmbeddingSpace(model.to_string()))?;
+
+        let missing: i64 = tx.query_row(
+            &format!(
+                r#"
+                SELECT (SELECT COUNT(*) FROM chunk_meta WHERE id NOT IN (SELECT id FROM {}))
+                     + (SELECT COUNT(*) FROM memories WHERE id NOT IN (SELECT id FROM {}))
+                "#,
+                space.chunk_table, space.memory_table
+            ),
+            [],
+            |row| row.get(0),
+        )?;
+        if missing > 0 {
+            return Err(StoreError::IncompleteEmbeddingSpace {
+                model: model.to_string(),
+                missing: missing as usize,
+            });
+        }
+
+        // A single statement, so searches never see zero or two active spaces
+        tx.execute(
+            "UPDATE embedding_spaces SET active = (model = ?1)",
+            params![model],
+        )?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn delete_embedding_space(&self, model: &str) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        let space = Self::find_space(&conn, model)?
+            .ok_or_else(|| StoreError::UnknownEmbeddingSpace(model.to_string()))?;
+        if space.active {
+            return Err(StoreError::ActiveEmbeddingSpace(model.to_string()));
+        }
+
+        let tx = Self::write_transaction(&conn)?;
+        tx.execute_batch(&format!(
+            "DROP TABLE IF EXISTS {}; DROP TABLE IF EXISTS {};",
+            space.chunk_table, space.memory_table
+        ))?;
+        tx.execute("DELETE FROM embedding_spaces WHERE model = ?1", params![model])?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn chunks_missing_embeddings(&self, model: &str, limit: usize) -> StoreResult<Vec<Chunk>> {
+        let conn = self.lock_conn()?;
+
+        let space = Self::find_space(&conn, model)?
+            .ok_or_else(|| StoreError::UnknownEmbeddingSpace(model.to_string()))?;
+
+        let mut stmt = conn.prepare(&format!(
+            r#"
+            SELECT cm.id, cm.note_id, cm.content, cm.heading,
+                   cm.chunk_index, cm.char_start, cm.char_end,
+                   n.path
+            FROM chunk_meta cm
+            JOIN notes n ON cm.note_id = n.id
+            WHERE cm.id NOT IN (SELECT id FROM {})
+            ORDER BY n.path, cm.chunk_index
+            LIMIT ?1
+            "#,
+            space.chunk_table
+        ))?;
+
+        let chunks = stmt
+            .query_map(params![limit as i64], Self::chunk_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(chunks)
+    }
+
+    fn save_chunk_embedding(
+        &self,
+        chunk_id: &str,
+        model: &str,
+        embedding: &[f32],
+    ) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        let space = Self::find_space(&conn, model)?
+            .ok_or_else(|| StoreError::UnknownEmbeddingSpace(model.to_string()))?;
+        Self::check_dimension(&space, embedding.len())?;
+
+        conn.execute(
+            &format!("DELETE FROM {} WHERE id = ?1", space.chunk_table),
+            params![chunk_id],
+        )?;
+        let inserted = conn.execute(
+            &format!(
+                "INSERT INTO {} (id, note_id, embedding) SELECT id, note_id, ?2 FROM chunk_meta WHERE id = ?1",
+                space.chunk_table
+            ),
+            params![chunk_id, embedding.as_bytes()],
         )?;
+        if inserted == 0 {
+            return Err(StoreError::ChunkNotFound(chunk_id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn memories_missing_embeddings(&self, model: &str, limit: usize) -> StoreResult<Vec<Memory>> {
+        let conn = self.lock_conn()?;
+
+        let space = Self::find_space(&conn, model)?
+            .ok_or_else(|| StoreError::UnknownEmbeddingSpace(model.to_string()))?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM memories WHERE id NOT IN (SELECT id FROM {}) ORDER BY created LIMIT ?1",
+            space.memory_table
+        ))?;
+
+        let memories = stmt
+            .query_map(params![limit as i64], Self::memory_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(memories)
+    }
+
+    fn backfill_memory_embedding(
+        &self,
+        memory_id: &str,
+        model: &str,
+        embedding: &[f32],
+    ) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        let space = Self::find_space(&conn, model)?
+            .ok_or_else(|| StoreError::UnknownEmbeddingSpace(model.to_string()))?;
+        Self::check_dimension(&space, embedding.len())?;
+        Self::write_memory_vector(&conn, &space, memory_id, embedding)
+    }
+
+    fn rebuild_progress(&self, model: &str) -> StoreResult<HashSet<String>> {
+        let conn = self.lock_conn()?;
+
+        let mut stmt = conn.prepare("SELECT note_id FROM rebuild_progress WHERE model = ?1")?;
+        let done = stmt
+            .query_map(params![model], |row| row.get(0))?
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        Ok(done)
+    }
+
+    fn mark_rebuilt(&self, note_id: &str, model: &str) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO rebuild_progress (note_id, model, completed) VALUES (?1, ?2, ?3)",
+            params![note_id, model, chrono::Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    fn clear_rebuild_progress(&self) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        conn.execute("DELETE FROM rebuild_progress", [])?;
+
+        Ok(())
+    }
+
+    fn queue_embedding(&self, note_id: &str, reason: &str) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        // Keep the original queue time so the oldest notes go first
+        conn.execute(
+            r#"
+            INSERT INTO embedding_queue (note_id, reason, queued) VALUES (?1, ?2, ?3)
+            ON CONFLICT(note_id) DO UPDATE SET reason = excluded.reason
+            "#,
+            params![note_id, reason, chrono::Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    fn queued_embeddings(&self) -> StoreResult<Vec<String>> {
+        let conn = self.lock_conn()?;
+
+        let mut stmt = conn.prepare("SELECT note_id FROM embedding_queue ORDER BY queued, note_id")?;
+        let ids = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ids)
+    }
+
+    fn dequeue_embedding(&self, note_id: &str) -> StoreResult<()> {
+        let conn = self.lock_conn()?;
+
+        conn.execute("DELETE FROM embedding_queue WHERE note_id = ?1", params![note_id])?;
+
+        Ok(())
+    }
+
+    fn count_orphaned_rows(&self) -> StoreResult<OrphanedRows> {
+        let conn = self.lock_conn()?;
+
+        Self::orphaned_rows(&conn, false)
+    }
 
-        let results = stmt
-            .query_map(
-                params![embedding.as_bytes(), min_confidence, limit as i64],
-                |row| {
-                    let memory = Self::memory_from_row(row)?;
-                    let distance: f32 = row.get("distance")?;
-                    Ok((memory, distance))
-                },
-            )?
-            .collect::<Result<Vec<_>, _>>()?;
+    fn remove_orphaned_rows(&self) -> StoreResult<OrphanedRows> {
+        let conn = self.lock_conn()?;
 
-        Ok(results)
+        let tx = Self::write_transaction(&conn)?;
+        let removed = Self::orphaned_rows(&tx, true)?;
+        tx.commit()?;
+
+        Ok(removed)
     }
 
-    fn get_memories_for_decay(&self) -> StoreResult<Vec<Memory>> {
+    fn optimize(&self) -> StoreResult<()> {
         let conn = self.lock_conn()?;
 
-        // Get all memories that could potentially decay
-        // (have been around for more than 7 days or have been applied before)
-        let mut stmt = conn.prepare(
+        conn.execute_batch(
             r#"
-            SELECT * FROM memories
-            WHERE confidence > 0
-            ORDER BY confidence ASC
+            INSERT INTO notes_fts(notes_fts) VALUES ('optimize');
+            INSERT INTO chunks_fts(chunks_fts) VALUES ('optimize');
+            PRAGMA optimize;
             "#,
         )?;
 
-        let memories = stmt
-            .query_map([], Self::memory_from_row)?
-            .collect::<Result<Vec<_>, _>>()?;
-
-        Ok(memories)
+        Ok(())
     }
 
-    fn get_memories_below_confidence(&self, threshold: f32) -> StoreResult<Vec<Memory>> {
+    fn vacuum(&self) -> StoreResult<u64> {
         let conn = self.lock_conn()?;
 
-        let mut stmt = conn.prepare(
-            "SELECT * FROM memories WHERE confidence < ?1 ORDER BY confidence ASC",
-        )?;
-
-        let memories = stmt
-            .query_map(params![threshold], Self::memory_from_row)?
-            .collect::<Result<Vec<_>, _>>()?;
+        let size = |conn: &Connection| -> rusqlite::Result<u64> {
+            let pages: u64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+            let page_size: u64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+            Ok(pages * page_size)
+        };
+        let before = size(&conn)?;
+        conn.execute_batch("VACUUM")?;
 
-        Ok(memories)
+        Ok(before.saturating_sub(size(&conn)?))
     }
 
-    fn delete_memory(&self, id: &str) -> StoreResult<()> {
+    fn last_maintenance(&self) -> StoreResult<Option<chrono::DateTime<chrono::Utc>>> {
         let conn = self.lock_conn()?;
 
-        // Delete embedding first
-        conn.execute("DELETE FROM memory_embeddings WHERE id = ?1", params![id])?;
-
-        // Delete memory
-        conn.execute("DELETE FROM memories WHERE id = ?1", params![id])?;
+        let last_run: Option<String> = conn
+            .query_row("SELECT last_run FROM maintenance WHERE id = 1", [], |row| row.get(0))
+            .optional()?;
 
-        Ok(())
+        Ok(last_run.and_then(|s| {
+            chrono::DateTime::parse_from_rfc3339(&s)
+                .map(|d| d.with_timezone(&chrono::Utc))
+                .ok()
+        }))
     }
 
-    fn update_memory_reinforcement(
-        &self,
-        id: &str,
-        confidence: f32,
-        times_applied: u32,
-    ) -> StoreResult<()> {
+    fn set_last_maintenance(&self, at: chrono::DateTime<chrono::Utc>) -> StoreResult<()> {
         let conn = self.lock_conn()?;
 
         conn.execute(
-            r#"
-            UPDATE memories
-            SET confidence = ?1, times_applied = ?2, last_applied = ?3
-            WHERE id = ?4
-            "#,
-            params![
-                confidence,
-                times_applied as i32,
-                chrono::Utc::now().to_rfc3339(),
-                id
-            ],
+            "INSERT OR REPLACE INTO maintenance (id, last_run) VALUES (1, ?1)",
+            params![at.to_rfc3339()],
         )?;
 
         Ok(())
@@ -959,6 +1891,9 @@ impl Store for SqliteStore {
         let total_links: u64 =
             conn.query_row("SELECT COUNT(*) FROM links", [], |row| row.get(0))?;
 
+        let unresolved_links: u64 =
+            conn.query_row("SELECT COUNT(*) FROM unresolved_links", [], |row| row.get(0))?;
+
         let total_memories: u64 =
             conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
 
@@ -981,6 +1916,7 @@ impl Store for SqliteStore {
             notes_by_type,
             total_chunks,
             total_links,
+            unresolved_links,
             total_memories,
             projects,
         })
@@ -988,18 +1924,367 @@ impl Store for SqliteStore {
 }
 
 impl SqliteStore {
+    fn space_from_row(row: &rusqlite::Row) -> rusqlite::Result<SpaceRow> {
+        Ok(SpaceRow {
+            model: row.get(0)?,
+            dimensions: row.get::<_, i64>(1)? as usize,
+            active: row.get(2)?,
+            created: row.get(3)?,
+            chunk_table: row.get(4)?,
+            memory_table: row.get(5)?,
+        })
+    }
+
+    /// All embedding spaces, the active one first
+    fn all_spaces(conn: &Connection) -> StoreResult<Vec<SpaceRow>> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM embedding_spaces ORDER BY active DESC, id",
+            SPACE_COLUMNS
+        ))?;
+        let spaces = stmt
+            .query_map([], Self::space_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(spaces)
+    }
+
+    fn find_space(conn: &Connection, model: &str) -> StoreResult<Option<SpaceRow>> {
+        Ok(conn
+            .query_row(
+                &format!("SELECT {} FROM embedding_spaces WHERE model = ?1", SPACE_COLUMNS),
+                params![model],
+                Self::space_from_row,
+            )
+            .optional()?)
+    }
+
+    fn active_space(conn: &Connection) -> StoreResult<Option<SpaceRow>> {
+        Ok(conn
+            .query_row(
+                &format!("SELECT {} FROM embedding_spaces WHERE active = 1", SPACE_COLUMNS),
+                [],
+                Self::space_from_row,
+            )
+            .optional()?)
+    }
+
+    /// Create a space with its vector tables; it becomes active if it's the
+    /// only one. Runs in the caller's write transaction.
+    fn new_space(tx: &Transaction, model: &str, dimensions: usize) -> StoreResult<SpaceRow> {
+        let active = Self::active_space(tx)?.is_none();
+        let created = chrono::Utc::now().to_rfc3339();
+        tx.execute(
+            r#"
+            INSERT INTO embedding_spaces
+                (model, dimensions, chunk_table, memory_table, active, created)
+            VALUES (?1, ?2, '', '', ?3, ?4)
+            "#,
+            params![model, dimensions as i64, active, created],
+        )?;
+
+        // Table names derive from the row id, never from the model name
+        let id = tx.last_insert_rowid();
+        let chunk_table = format!("chunks_vec_{}", id);
+        let memory_table = format!("memory_vec_{}", id);
+        tx.execute(
+            "UPDATE embedding_spaces SET chunk_table = ?1, memory_table = ?2 WHERE id = ?3",
+            params![chunk_table, memory_table, id],
+        )?;
+        tx.execute_batch(&format!(
+            r#"
+            CREATE VIRTUAL TABLE {} USING vec0(
+                id TEXT PRIMARY KEY,
+                note_id TEXT,
+                embedding FLOAT[{dimensions}]
+            );
+            CREATE VIRTUAL TABLE {} USING vec0(
+                id TEXT PRIMARY KEY,
+                embedding FLOAT[{dimensions}]
+            );
+            "#,
+            chunk_table, memory_table
+        ))?;
+
+        tracing::info!("Created embedding space for {} ({} dimensions)", model, dimensions);
+
+        Ok(SpaceRow {
+            model: model.to_string(),
+            dimensions,
+            active,
+            created,
+            chunk_table,
+            memory_table,
+        })
+    }
+
+    /// The space to store vectors from `model` in
+    ///
+    /// The first model to store anything gets a new, active space; after
+    /// that, new models need an explicit [`Store::create_embedding_space`].
+    /// Only the active space is written: vectors from a model that has been
+    /// switched away from would never be searched, so they are refused.
+    /// Re-embedding backfills inactive spaces through its own methods.
+    fn space_for_write(tx: &Transaction, model: &str, dimensions: usize) -> StoreResult<SpaceRow> {
+        let space = match Self::find_space(tx, model)? {
+            Some(space) => space,
+            None if Self::active_space(tx)?.is_none() => {
+                return Self::new_space(tx, model, dimensions)
+            }
+            None => return Err(StoreError::UnknownEmbeddingSpace(model.to_string())),
+        };
+        if !space.active {
+            if let Some(active) = Self::active_space(tx)? {
+                return Err(StoreError::EmbeddingSpaceMismatch {
+                    active: active.model,
+                    requested: space.model,
+                });
+            }
+        }
+        Self::check_dimension(&space, dimensions)?;
+        Ok(space)
+    }
+
+    /// Replace a memory's vector in a space
+    fn write_memory_vector(
+        conn: &Connection,
+        space: &SpaceRow,
+        memory_id: &str,
+        embedding: &[f32],
+    ) -> StoreResult<()> {
+        // Delete existing embedding if any (for upsert behavior)
+        conn.execute(
+            &format!("DELETE FROM {} WHERE id = ?1", space.memory_table),
+            params![memory_id],
+        )?;
+
+        // Insert new embedding
+        conn.execute(
+            &format!("INSERT INTO {} (id, embedding) VALUES (?1, ?2)", space.memory_table),
+            params![memory_id, embedding.as_bytes()],
+        )?;
+
+        Ok(())
+    }
+
+    /// The space to search with a query vector from `model`
+    ///
+    /// Only the active space is searched, and only with vectors from its own
+    /// model. `None` means nothing has been embedded yet.
+    fn space_for_query(
+        conn: &Connection,
+        model: &str,
+        dimensions: usize,
+    ) -> StoreResult<Option<SpaceRow>> {
+        match Self::active_space(conn)? {
+            Some(space) if space.model != model => Err(StoreError::EmbeddingSpaceMismatch {
+                active: space.model,
+                requested: model.to_string(),
+            }),
+            Some(space) => {
+                Self::check_dimension(&space, dimensions)?;
+                Ok(Some(space))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn check_dimension(space: &SpaceRow, got: usize) -> StoreResult<()> {
+        if got == space.dimensions {
+            Ok(())
+        } else {
+            Err(StoreError::InvalidEmbeddingDimension {
+                expected: space.dimensions,
+                got,
+            })
+        }
+    }
+
+    fn space_info(conn: &Connection, space: SpaceRow) -> StoreResult<EmbeddingSpace> {
+        let count = |table: &str| -> StoreResult<u64> {
+            Ok(conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })?)
+        };
+
+        Ok(EmbeddingSpace {
+            chunks: count(&space.chunk_table)?,
+            memories: count(&space.memory_table)?,
+            created: chrono::DateTime::parse_from_rfc3339(&space.created)
+                .map(|d| d.with_timezone(&chrono::Utc))
+                .unwrap_or_else(|_| chrono::Utc::now()),
+            model: space.model,
+            dimensions: space.dimensions,
+            active: space.active,
+        })
+    }
+
+    /// Delete chunk vectors matching `condition` from every space
+    fn delete_chunk_vectors<P: rusqlite::Params + Copy>(
+        conn: &Connection,
+        condition: &str,
+        params: P,
+    ) -> StoreResult<()> {
+        for space in Self::all_spaces(conn)? {
+            conn.execute(
+                &format!("DELETE FROM {} WHERE {}", space.chunk_table, condition),
+                params,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Count, or delete, index rows whose note, chunk or memory is gone
+    fn orphaned_rows(conn: &Connection, remove: bool) -> StoreResult<OrphanedRows> {
+        const LIVE_CHUNKS: &str =
+            "SELECT id FROM chunk_meta WHERE note_id IN (SELECT id FROM notes)";
+
+        let run = |table: &str, condition: &str| -> StoreResult<u64> {
+            Ok(if remove {
+                conn.execute(&format!("DELETE FROM {} WHERE {}", table, condition), [])? as u64
+            } else {
+                conn.query_row(
+                    &format!("SELECT COUNT(*) FROM {} WHERE {}", table, condition),
+                    [],
+                    |row| row.get(0),
+                )?
+            })
+        };
+
+        let mut rows = OrphanedRows::default();
+        // Vectors and FTS entries first, while their chunks still exist
+        for space in Self::all_spaces(conn)? {
+            rows.chunk_vectors +=
+                run(&space.chunk_table, &format!("id NOT IN ({})", LIVE_CHUNKS))?;
+            rows.memory_vectors +=
+                run(&space.memory_table, "id NOT IN (SELECT id FROM memories)")?;
+        }
+        rows.fts_rows += run("chunks_fts", &format!("chunk_id NOT IN ({})", LIVE_CHUNKS))?;
+        rows.fts_rows += run("notes_fts", "note_id NOT IN (SELECT id FROM notes)")?;
+        rows.chunks = run("chunk_meta", "note_id NOT IN (SELECT id FROM notes)")?;
+
+        Ok(rows)
+    }
+
+    /// Replace a note's links with the ones found in its content
+    fn update_links(conn: &Connection, note: &Note) -> StoreResult<()> {
+        conn.execute("DELETE FROM links WHERE source_id = ?1", params![note.id])?;
+        conn.execute(
+            "DELETE FROM unresolved_links WHERE source_id = ?1",
+            params![note.id],
+        )?;
+
+        for link in extract_links(&note.path, &note.content) {
+            match Self::resolve_link(conn, &link.target)? {
+                // Links within the note itself aren't part of the graph
+                Some((target_id, _)) if target_id == note.id => {}
+                Some((target_id, target_path)) => {
+                    conn.execute(
+                        r#"
+                        INSERT OR IGNORE INTO links (source_id, target_id, target_path, link_text, heading)
+                        VALUES (?1, ?2, ?3, ?4, ?5)
+                        "#,
+                        params![note.id, target_id, target_path, link.target, link.heading],
+                    )?;
+                }
+                None => {
+                    conn.execute(
+                        r#"
+                        INSERT OR IGNORE INTO unresolved_links (source_id, target, heading)
+                        VALUES (?1, ?2, ?3)
+                        "#,
+                        params![note.id, link.target, link.heading],
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find the note a link target refers to, as (id, path)
+    ///
+    /// Matches, in order of preference: the vault path (with or without
+    /// `.md`), the file name, the title, then an alias; case-insensitively.
+    fn resolve_link(conn: &Connection, target: &str) -> StoreResult<Option<(String, String)>> {
+        let name = target.trim().to_ascii_lowercase();
+        let base = name.strip_suffix(".md").unwrap_or(&name);
+        let path = format!("{}.md", base);
+        let file = format!("/{}.md", base);
+
+        let result = conn
+            .query_row(
+                r#"
+                SELECT id, path FROM notes
+                WHERE lower(path) = ?1
+                   OR substr(lower(path), -length(?2)) = ?2
+                   OR lower(title) = ?3
+                   OR id IN (SELECT note_id FROM note_aliases WHERE alias = ?3)
+                ORDER BY CASE
+                    WHEN lower(path) = ?1 THEN 0
+                    WHEN substr(lower(path), -length(?2)) = ?2 THEN 1
+                    WHEN lower(title) = ?3 THEN 2
+                    ELSE 3
+                END, length(path)
+                LIMIT 1
+                "#,
+                params![path, file, name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        Ok(result)
+    }
+
+    /// Resolve earlier links that were waiting for this note to exist
+    fn resolve_pending_links(conn: &Connection, note: &Note) -> StoreResult<()> {
+        let path = note.path.to_ascii_lowercase();
+        let base = path.strip_suffix(".md").unwrap_or(&path);
+        let stem = base.rsplit('/').next().unwrap_or(base);
+        let mut names = vec![base.to_string(), stem.to_string(), note.title.to_ascii_lowercase()];
+        names.extend(note.aliases.iter().map(|a| a.to_ascii_lowercase()));
+
+        let mut keys: Vec<String> = names.iter().map(|n| format!("{}.md", n)).collect();
+        keys.extend(names);
+
+        let placeholders = vec!["?"; keys.len()].join(", ");
+        let mut stmt = conn.prepare(&format!(
+            "SELECT source_id, target, heading FROM unresolved_links WHERE lower(trim(target)) IN ({})",
+            placeholders
+        ))?;
+        let pending = stmt
+            .query_map(rusqlite::params_from_iter(&keys), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (source_id, target, heading) in pending {
+            if source_id == note.id {
+                continue;
+            }
+            // Another note may still be the better match
+            if let Some((target_id, target_path)) = Self::resolve_link(conn, &target)? {
+                conn.execute(
+                    r#"
+                    INSERT OR IGNORE INTO links (source_id, target_id, target_path, link_text, heading)
+                    VALUES (?1, ?2, ?3, ?4, ?5)
+                    "#,
+                    params![source_id, target_id, target_path, target, heading],
+                )?;
+                conn.execute(
+                    "DELETE FROM unresolved_links WHERE source_id = ?1 AND target = ?2",
+                    params![source_id, target],
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Helper to construct Note from database row
     fn note_from_row(row: &rusqlite::Row) -> rusqlite::Result<Note> {
         use chrono::DateTime;
 
         let note_type_str: String = row.get("note_type")?;
-        let note_type = match note_type_str.as_str() {
-            "project" => NoteType::Project,
-            "task" => NoteType::Task,
-            "learning" => NoteType::Learning,
-            "memory" => NoteType::Memory,
-            _ => NoteType::Meta,
-        };
+        let note_type = note_type_str.parse().unwrap_or(NoteType::Meta);
 
         let status_str: String = row.get("status")?;
         let status = match status_str.as_str() {
@@ -1086,6 +2371,21 @@ impl SqliteStore {
         })
     }
 
+    /// Helper to construct Chunk (without its embedding) from database row
+    fn chunk_from_row(row: &rusqlite::Row) -> rusqlite::Result<Chunk> {
+        Ok(Chunk {
+            id: row.get(0)?,
+            note_id: row.get(1)?,
+            content: row.get(2)?,
+            heading: row.get(3)?,
+            chunk_index: row.get(4)?,
+            char_start: row.get(5)?,
+            char_end: row.get(6)?,
+            note_path: row.get(7)?,
+            embedding: None, // Don't load embedding by default
+        })
+    }
+
     /// Helper to construct Memory from database row
     fn memory_from_row(row: &rusqlite::Row) -> rusqlite::Result<Memory> {
         use chrono::DateTime;
@@ -1124,6 +2424,11 @@ impl SqliteStore {
                     .ok()
             }),
             note_id: row.get("note_id")?,
+            supersedes: row.get("supersedes")?,
+            merged_from: row
+                .get::<_, Option<String>>("merged_from")?
+                .map(|json| SqliteStore::from_json_array(&json))
+                .unwrap_or_default(),
         })
     }
 }
@@ -1132,6 +2437,9 @@ impl SqliteStore {
 mod tests {
     use super::*;
 
+    /// Embedding model the synthetic vectors stand in for
+    const MODEL: &str = "test-model";
+
     /// Create a synthetic embedding for testing (deterministic based on seed)
     fn synthetic_embedding(seed: u32) -> Vec<f32> {
         (0..EMBEDDING_DIM)
@@ -1164,6 +2472,103 @@ mod tests {
         assert!(store.get_note(&note.id).unwrap().is_none());
     }
 
+    #[test]
+    fn test_link_resolution() {
+        let store = SqliteStore::in_memory().unwrap();
+
+        let mut arch = Note::project_note("Architecture", "vulcanos");
+        arch.aliases = vec!["Design Doc".to_string()];
+        arch.content = "# Architecture".to_string();
+        store.save_note(&arch).unwrap();
+
+        let mut index = Note::new("Index", NoteType::Meta, "Meta/index.md");
+        index.content = "[[architecture#Storage]] [[Design Doc|design]] \
+                         [Arch](../Projects/vulcanos/architecture.md) [[Glossary]]"
+            .to_string();
+        store.save_note(&index).unwrap();
+
+        // File name, alias and relative path all resolve to the same note
+        let outlinks = store.get_outlinks(&index.id).unwrap();
+        assert_eq!(outlinks.len(), 3);
+        assert!(outlinks.iter().all(|l| l.target_id == arch.id));
+        assert_eq!(store.get_backlinks(&arch.id).unwrap().len(), 3);
+
+        let unresolved = store.get_unresolved_links().unwrap();
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].target, "Glossary");
+
+        // Creating the missing note resolves the pending link
+        let glossary = Note::new("Glossary", NoteType::Meta, "Meta/glossary.md");
+        store.save_note(&glossary).unwrap();
+        assert!(store.get_unresolved_links().unwrap().is_empty());
+        assert_eq!(store.get_backlinks(&glossary.id).unwrap().len(), 1);
+
+        // Saving without content keeps links
+        let mut reloaded = store.get_note(&index.id).unwrap().unwrap();
+        reloaded.tags.push("hub".to_string());
+        store.save_note(&reloaded).unwrap();
+        assert_eq!(store.get_outlinks(&index.id).unwrap().len(), 4);
+
+        // Deleting a target turns links to it back into unresolved ones
+        store.delete_note(&glossary.id).unwrap();
+        assert_eq!(store.get_outlinks(&index.id).unwrap().len(), 3);
+        assert_eq!(store.get_unresolved_links().unwrap().len(), 1);
+
+        let lonely = Note::new("Lonely", NoteType::Meta, "Meta/lonely.md");
+        store.save_note(&lonely).unwrap();
+        let orphans = store.find_orphans(None, 10).unwrap();
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(orphans[0].id, lonely.id);
+        assert_eq!(store.get_stats().unwrap().unresolved_links, 1);
+
+        // A dropped alias no longer resolves
+        arch.aliases = vec!["Blueprint".to_string()];
+        store.save_note(&arch).unwrap();
+        let mut hub = Note::new("Hub", NoteType::Meta, "Meta/hub.md");
+        hub.content = "[[Design Doc]] [[blueprint]]".to_string();
+        store.save_note(&hub).unwrap();
+        let outlinks = store.get_outlinks(&hub.id).unwrap();
+        assert_eq!(outlinks.len(), 1);
+        assert_eq!(outlinks[0].target_id, arch.id);
+    }
+
+    #[test]
+    fn test_failed_save_rolls_back() {
+        let store = SqliteStore::in_memory().unwrap();
+
+        let mut old = Note::new("Old", NoteType::Meta, "Meta/page.md");
+        old.content = "Old body".to_string();
+        store.save_note(&old).unwrap();
+
+        // Fail the save after the old note at this path has been deleted
+        store
+            .lock_conn()
+            .unwrap()
+            .execute_batch(
+                "CREATE TRIGGER fail_links BEFORE INSERT ON unresolved_links
+                 BEGIN SELECT RAISE(ABORT, 'no links'); END;",
+            )
+            .unwrap();
+
+        let mut new = Note::new("New", NoteType::Meta, "Meta/page.md");
+        new.content = "[[Missing]]".to_string();
+        assert!(store.save_note(&new).is_err());
+
+        assert!(store.get_note(&new.id).unwrap().is_none());
+        let kept = store.get_note(&old.id).unwrap().unwrap();
+        assert_eq!(kept.path, "Meta/page.md");
+        let body: String = store
+            .lock_conn()
+            .unwrap()
+            .query_row(
+                "SELECT body FROM notes_fts WHERE note_id = ?1",
+                params![old.id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(body, "Old body");
+    }
+
     #[test]
     fn test_memory_operations() {
         let store = SqliteStore::in_memory().unwrap();
@@ -1221,7 +2626,7 @@ mod tests {
         ];
 
         // Save chunks
-        store.save_chunks(&note.id, &chunks).unwrap();
+        store.save_chunks(&note.id, MODEL, &chunks).unwrap();
 
         // Load chunks
         let loaded = store.get_chunks(&note.id).unwrap();
@@ -1266,13 +2671,13 @@ mod tests {
             embedding: Some(synthetic_embedding(200)), // seed 200
         }];
 
-        store.save_chunks(&note1.id, &chunks1).unwrap();
-        store.save_chunks(&note2.id, &chunks2).unwrap();
+        store.save_chunks(&note1.id, MODEL, &chunks1).unwrap();
+        store.save_chunks(&note2.id, MODEL, &chunks2).unwrap();
 
         // Search with embedding similar to note1
         let query_embedding = synthetic_embedding(100);
         let results = store
-            .vector_search(&query_embedding, None, None, None, 10)
+            .vector_search(MODEL, &query_embedding, None, None, None, 10)
             .unwrap();
 
         assert!(!results.is_empty());
@@ -1317,13 +2722,13 @@ mod tests {
             embedding: Some(synthetic_embedding(1)), // Same embedding
         }];
 
-        store.save_chunks(&note1.id, &chunks1).unwrap();
-        store.save_chunks(&note2.id, &chunks2).unwrap();
+        store.save_chunks(&note1.id, MODEL, &chunks1).unwrap();
+        store.save_chunks(&note2.id, MODEL, &chunks2).unwrap();
 
         // Search with project filter
         let query = synthetic_embedding(1);
         let results = store
-            .vector_search(&query, None, Some("project-a"), None, 10)
+            .vector_search(MODEL, &query, None, Some("project-a"), None, 10)
             .unwrap();
 
         assert_eq!(results.len(), 1);
@@ -1359,14 +2764,14 @@ mod tests {
                 char_end: 20,
                 embedding: Some(synthetic_embedding(1)),
             };
-            store.save_chunks(&note.id, &[chunk]).unwrap();
+            store.save_chunks(&note.id, MODEL, &[chunk]).unwrap();
         }
 
         // Search with tag filter for "rust"
         let query = synthetic_embedding(1);
         let tags = vec!["rust".to_string()];
         let results = store
-            .vector_search(&query, None, None, Some(&tags), 10)
+            .vector_search(MODEL, &query, None, None, Some(&tags), 10)
             .unwrap();
 
         // Should only match note1 and note3 (both have "rust" tag)
@@ -1377,6 +2782,69 @@ mod tests {
         assert!(!titles.contains(&"Tagged Note 2"));
     }
 
+    #[test]
+    fn test_keyword_search() {
+        let store = SqliteStore::in_memory().unwrap();
+
+        let mut ownership = Note::new("Ownership", NoteType::Learning, "Learning/ownership.md");
+        ownership.content = "The borrow checker enforces ownership rules.".to_string();
+        ownership.tags = vec!["rust".to_string()];
+        let mut async_note = Note::project_note("Async runtime", "vulcanos");
+        async_note.content = "Tokio tasks are spawned on the runtime.".to_string();
+        let mut borrowing = Note::project_note("Borrowing", "vulcanos");
+        borrowing.content = "Notes about references.".to_string();
+        for note in [&ownership, &async_note, &borrowing] {
+            store.save_note(note).unwrap();
+        }
+
+        // Title matches rank above body matches; stemming matches "borrowed"
+        let results = store.keyword_search("borrowed", None, None, None, 10).unwrap();
+        let titles: Vec<_> = results.iter().map(|r| r.note_title.as_str()).collect();
+        assert_eq!(titles, vec!["Borrowing", "Ownership"]);
+        assert!(results[1].content.contains("borrow checker"));
+
+        // Filters apply, and FTS syntax in the query is plain text
+        let results = store
+            .keyword_search("borrow AND \"tokio", None, Some("vulcanos"), None, 10)
+            .unwrap();
+        let mut titles: Vec<_> = results.iter().map(|r| r.note_title.as_str()).collect();
+        titles.sort();
+        assert_eq!(titles, vec!["Async runtime", "Borrowing"]);
+        assert!(store.keyword_search("*", None, None, None, 10).unwrap().is_empty());
+
+        // Saving metadata without content keeps the indexed body
+        let mut renamed = async_note.clone();
+        renamed.title = "Runtime".to_string();
+        renamed.content.clear();
+        store.save_note(&renamed).unwrap();
+        let results = store.keyword_search("tokio", None, None, None, 10).unwrap();
+        assert_eq!(results[0].note_title, "Runtime");
+
+        // Chunk matches carry the section they came from
+        let chunk = Chunk {
+            id: "chunk-1".to_string(),
+            note_id: borrowing.id.clone(),
+            note_path: borrowing.path.clone(),
+            content: "Mutable references are exclusive.".to_string(),
+            heading: Some("Rules".to_string()),
+            chunk_index: 0,
+            char_start: 0,
+            char_end: 33,
+            embedding: None,
+        };
+        store.save_chunks(&borrowing.id, MODEL, &[chunk]).unwrap();
+        let results = store.keyword_search("exclusive", None, None, None, 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].chunk_id.as_deref(), Some("chunk-1"));
+        assert_eq!(results[0].heading.as_deref(), Some("Rules"));
+
+        store.delete_note(&borrowing.id).unwrap();
+        assert!(store.keyword_search("exclusive", None, None, None, 10).unwrap().is_empty());
+        let tags = vec!["rust".to_string()];
+        let results = store.keyword_search("borrow", None, None, Some(&tags), 10).unwrap();
+        assert_eq!(results.len(), 1);
+    }
+
     #[test]
     fn test_search_result_includes_tags() {
         let store = SqliteStore::in_memory().unwrap();
@@ -1398,12 +2866,12 @@ mod tests {
             char_end: 14,
             embedding: Some(synthetic_embedding(1)),
         };
-        store.save_chunks(&note.id, &[chunk]).unwrap();
+        store.save_chunks(&note.id, MODEL, &[chunk]).unwrap();
 
         // Search and verify tags are returned
         let query = synthetic_embedding(1);
         let results = store
-            .vector_search(&query, None, None, None, 10)
+            .vector_search(MODEL, &query, None, None, None, 10)
             .unwrap();
 
         assert_eq!(results.len(), 1);
@@ -1429,7 +2897,7 @@ mod tests {
             char_end: 15,
             embedding: Some(synthetic_embedding(1)),
         }];
-        store.save_chunks(&note.id, &chunks).unwrap();
+        store.save_chunks(&note.id, MODEL, &chunks).unwrap();
 
         // Verify chunks exist
         let loaded = store.get_chunks(&note.id).unwrap();
@@ -1442,4 +2910,136 @@ mod tests {
         let loaded = store.get_chunks(&note.id).unwrap();
         assert_eq!(loaded.len(), 0);
     }
+
+    #[test]
+    fn test_orphaned_rows_and_maintenance() {
+        let store = SqliteStore::in_memory().unwrap();
+
+        let mut note = Note::project_note("Orphan", "test");
+        note.content = "Left behind".to_string();
+        store.save_note(&note).unwrap();
+        let chunks = vec![Chunk {
+            id: uuid::Uuid::new_v4().to_string(),
+            note_id: note.id.clone(),
+            note_path: note.path.clone(),
+            content: "Left behind".to_string(),
+            heading: None,
+            chunk_index: 0,
+            char_start: 0,
+            char_end: 11,
+            embedding: Some(synthetic_embedding(1)),
+        }];
+        store.save_chunks(&note.id, MODEL, &chunks).unwrap();
+        let memory = Memory::lesson("Orphan", "Vector outlives it", "test", "agent");
+        store.save_memory(&memory).unwrap();
+        store
+            .save_memory_embedding(&memory.id, MODEL, &synthetic_embedding(2))
+            .unwrap();
+        assert_eq!(store.count_orphaned_rows().unwrap().total(), 0);
+
+        // Rows removed behind the store's back, as by an older version
+        {
+            let conn = store.lock_conn().unwrap();
+            conn.execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+            conn.execute("DELETE FROM notes WHERE id = ?1", params![note.id])
+                .unwrap();
+            conn.execute("DELETE FROM memories WHERE id = ?1", params![memory.id])
+                .unwrap();
+            conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+        }
+
+        let expected = OrphanedRows {
+            chunks: 1,
+            chunk_vectors: 1,
+            memory_vectors: 1,
+            fts_rows: 2,
+        };
+        assert_eq!(store.count_orphaned_rows().unwrap(), expected);
+        assert_eq!(store.remove_orphaned_rows().unwrap(), expected);
+        assert_eq!(store.count_orphaned_rows().unwrap().total(), 0);
+
+        store.optimize().unwrap();
+        store.vacuum().unwrap();
+
+        assert!(store.last_maintenance().unwrap().is_none());
+        let now = chrono::Utc::now();
+        store.set_last_maintenance(now).unwrap();
+        let last = store.last_maintenance().unwrap().unwrap();
+        assert_eq!(last.timestamp(), now.timestamp());
+    }
+
+    #[test]
+    fn test_shared_database_waits_for_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.db");
+        let writer = SqliteStore::new(&path).unwrap();
+        let other = SqliteStore::new(&path).unwrap();
+
+        let mode: String = other
+            .lock_conn()
+            .unwrap()
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(mode, "wal");
+
+        // A write while another connection holds the write lock waits for it
+        writer
+            .lock_conn()
+            .unwrap()
+            .execute_batch("BEGIN IMMEDIATE")
+            .unwrap();
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                std::thread::sleep(std::time::Duration::from_millis(200));
+                writer.lock_conn().unwrap().execute_batch("COMMIT").unwrap();
+            });
+            other
+                .save_note(&Note::new("Busy", NoteType::Meta, "Meta/busy.md"))
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_legacy_vectors_become_space() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vault.db");
+
+        // Recreate the fixed-size tables of a vault from before spaces
+        drop(SqliteStore::new(&path).unwrap());
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(&format!(
+            "DROP TABLE embedding_spaces;
+             CREATE VIRTUAL TABLE chunks USING vec0(
+                 id TEXT PRIMARY KEY, note_id TEXT, embedding FLOAT[{0}]
+             );
+             CREATE VIRTUAL TABLE memory_embeddings USING vec0(
+                 id TEXT PRIMARY KEY, embedding FLOAT[{0}]
+             );",
+            EMBEDDING_DIM
+        ))
+        .unwrap();
+        drop(conn);
+
+        let store = SqliteStore::new(&path).unwrap();
+        let note = Note::project_note("Legacy", "test");
+        store.save_note(&note).unwrap();
+        let chunk = Chunk::new(&note.id, &note.path, "Old vectors", 0, 0, 11)
+            .with_embedding(synthetic_embedding(1));
+        store.save_chunks(&note.id, DEFAULT_MODEL, &[chunk]).unwrap();
+
+        let spaces = store.list_embedding_spaces().unwrap();
+        assert_eq!(spaces.len(), 1);
+        assert_eq!(spaces[0].model, DEFAULT_MODEL);
+        assert!(spaces[0].active);
+        assert_eq!(spaces[0].chunks, 1);
+        let results = store
+            .vector_search(DEFAULT_MODEL, &synthetic_embedding(1), None, None, None, 5)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+
+        // Reopening doesn't register it twice
+        drop(store);
+        let store = SqliteStore::new(&path).unwrap();
+        assert_eq!(store.list_embedding_spaces().unwrap().len(), 1);
+    }
 }
@@ -13,9 +13,11 @@ use ratatui::{
     Frame,
 };
 
-use crate::models::{Memory, MemoryType, Note, NoteType};
-use crate::rag::EmbeddingService;
-use crate::store::{SearchResult, Store};
+use crate::models::{ChunkConfig, Memory, MemoryType, Note, NoteType};
+use crate::config::load_embedder;
+use crate::index::{EmbedStatus, NoteWriter};
+use crate::rag::{AnyEmbedder, Embedder, RagPipeline, RetryPolicy, SearchFilters, SearchHit, SearchMode};
+use crate::store::Store;
 
 use super::tui::InputMode;
 
@@ -41,7 +43,10 @@ pub struct App {
     store: Arc<dyn Store>,
 
     /// Embedding service for semantic search
-    embedding_service: EmbeddingService,
+    embedding_service: AnyEmbedder,
+
+    /// Writes new notes to the vault, the store and the embeddings
+    note_writer: NoteWriter<AnyEmbedder>,
 
     /// Tokio runtime for async operations
     runtime: Runtime,
@@ -57,7 +62,7 @@ pub struct App {
     pub selected_memory: usize,
 
     // Semantic search results
-    pub semantic_results: Vec<SearchResult>,
+    pub semantic_results: Vec<SearchHit>,
     pub semantic_memories: Vec<(Memory, f32)>,
     pub is_semantic_search: bool,
 
@@ -102,11 +107,22 @@ impl App {
         list_state.select(Some(0));
 
         let runtime = Runtime::new().expect("Failed to create tokio runtime");
-        let embedding_service = EmbeddingService::new();
+        let embedder = || {
+            load_embedder().unwrap_or_else(|e| {
+                tracing::warn!("{}; using the default Ollama embedder", e);
+                AnyEmbedder::default()
+            })
+        };
+        let embedding_service = embedder();
+        // Don't freeze the UI retrying; unembedded notes are queued
+        let rag = RagPipeline::with_embedder(ChunkConfig::default(), embedder())
+            .with_retry(RetryPolicy::none());
+        let note_writer = NoteWriter::new(crate::vault_dir(), rag);
 
         Self {
             store,
             embedding_service,
+            note_writer,
             runtime,
             notes: Vec::new(),
             filtered_notes: Vec::new(),
@@ -253,12 +269,55 @@ impl App {
         }
     }
 
-    /// Perform semantic search using Ollama embeddings
+    /// Search notes with hybrid keyword + semantic search, or memories by
+    /// semantic similarity using Ollama embeddings
     pub fn semantic_search(&mut self, query: &str) -> Result<()> {
         self.set_message("Searching...");
         self.is_semantic_search = true;
         self.search_query = query.to_string();
 
+        if self.view_mode != ViewMode::Memories {
+            // Falls back to keyword search if Ollama is down
+            let filters = SearchFilters {
+                note_types: self.type_filter.clone().map(|t| vec![t]),
+                project: self.project_filter.clone(),
+                tags: None,
+            };
+            let search = crate::rag::search(
+                &*self.store,
+                &self.embedding_service,
+                query,
+                SearchMode::Hybrid,
+                &filters,
+                20,
+            );
+            match self.runtime.block_on(search) {
+                Ok(outcome) => {
+                    // Keep results and notes aligned for the score column
+                    let (results, notes): (Vec<SearchHit>, Vec<Note>) = outcome
+                        .hits
+                        .into_iter()
+                        .filter_map(|hit| {
+                            let note = self.notes.iter().find(|n| n.id == hit.note_id)?.clone();
+                            Some((hit, note))
+                        })
+                        .unzip();
+                    let count = results.len();
+                    self.semantic_results = results;
+                    self.filtered_notes = notes;
+
+                    self.selected_note = 0;
+                    self.list_state.select(Some(0));
+                    self.set_message(format!("Found {} {} matches", count, outcome.mode));
+                }
+                Err(e) => {
+                    self.set_message(format!("Search error: {}", e));
+                    self.is_semantic_search = false;
+                }
+            }
+            return Ok(());
+        }
+
         // Generate embedding for the query
         let embedding = match self.runtime.block_on(self.embedding_service.embed(query)) {
             Ok(emb) => emb,
@@ -269,49 +328,26 @@ impl App {
             }
         };
 
-        match self.view_mode {
-            ViewMode::Notes | ViewMode::Detail => {
-                // Search notes via vector similarity
-                match self.store.vector_search(&embedding, None, None, 20) {
-                    Ok(results) => {
-                        let count = results.len();
-                        self.semantic_results = results;
-
-                        // Convert SearchResults to Notes for display
-                        self.filtered_notes = self
-                            .semantic_results
-                            .iter()
-                            .filter_map(|sr| self.notes.iter().find(|n| n.id == sr.note_id))
-                            .cloned()
-                            .collect();
-
-                        self.selected_note = 0;
-                        self.list_state.select(Some(0));
-                        self.set_message(format!("Found {} semantic matches", count));
-                    }
-                    Err(e) => {
-                        self.set_message(format!("Search error: {}", e));
-                    }
-                }
+        // Search memories via vector similarity
+        match self.store.search_memories_semantic(
+            self.embedding_service.model(),
+            &embedding,
+            0.0,
+            20,
+        ) {
+            Ok(results) => {
+                let count = results.len();
+                self.semantic_memories = results.clone();
+
+                // Convert to filtered_memories for display
+                self.filtered_memories = results.into_iter().map(|(m, _)| m).collect();
+
+                self.selected_memory = 0;
+                self.list_state.select(Some(0));
+                self.set_message(format!("Found {} semantic matches", count));
             }
-            ViewMode::Memories => {
-                // Search memories via vector similarity
-                match self.store.search_memories_semantic(&embedding, 0.0, 20) {
-                    Ok(results) => {
-                        let count = results.len();
-                        self.semantic_memories = results.clone();
-
-                        // Convert to filtered_memories for display
-                        self.filtered_memories = results.into_iter().map(|(m, _)| m).collect();
-
-                        self.selected_memory = 0;
-                        self.list_state.select(Some(0));
-                        self.set_message(format!("Found {} semantic matches", count));
-                    }
-                    Err(e) => {
-                        self.set_message(format!("Search error: {}", e));
-                    }
-                }
+            Err(e) => {
+                self.set_message(format!("Search error: {}", e));
             }
         }
 
@@ -497,9 +533,21 @@ impl App {
         // Add placeholder content
         note.content = format!("# {}\n\n<!-- Add your content here -->\n", title);
 
-        // Save to store
-        self.store.save_note(&note)?;
-        self.set_message(format!("Created note: {}", title));
+        // Write the file, save it and embed it
+        let status = self
+            .runtime
+            .block_on(self.note_writer.write(&*self.store, &mut note))?;
+        match status {
+            EmbedStatus::Embedded { .. } => self.set_message(format!("Created note: {}", title)),
+            EmbedStatus::Queued { .. } => self.set_message(format!(
+                "Created note: {} (embedding queued until {} is available)",
+                title,
+                self.embedding_service.model()
+            )),
+            EmbedStatus::Failed { reason } => {
+                self.set_message(format!("Created note: {} (not embedded: {})", title, reason))
+            }
+        }
 
         // Reload and select the new note
         self.load_data()?;
@@ -638,7 +686,8 @@ impl App {
                 note.note_type = NoteType::Task;
             }
 
-            self.store.save_note(&note)?;
+            self.runtime
+                .block_on(self.note_writer.write(&*self.store, &mut note))?;
             self.load_data()?;
         }
         Ok(())
@@ -705,7 +754,7 @@ impl App {
         let stats = self.store.get_stats().unwrap_or_default();
 
         let search_indicator = if self.is_semantic_search {
-            format!("🔮 Semantic: \"{}\"", truncate(&self.search_query, 20))
+            format!("🔮 Search: \"{}\"", truncate(&self.search_query, 20))
         } else if !self.search_query.is_empty() {
             format!("🔍 \"{}\"", truncate(&self.search_query, 20))
         } else if let Some(ref p) = self.project_filter {
@@ -883,13 +932,15 @@ impl App {
                             NoteType::Meta => Color::DarkGray,
                         };
 
-                        // Show similarity score in semantic mode, project otherwise
+                        // Show similarity (or keyword rank) in search mode, project otherwise
                         let suffix = if self.is_semantic_search {
-                            // Find similarity score from semantic_results
-                            let score = self.semantic_results.get(i)
-                                .map(|sr| format!(" {:.0}%", (1.0 - sr.distance) * 100.0))
-                                .unwrap_or_default();
-                            score
+                            self.semantic_results.get(i)
+                                .map(|hit| match (hit.similarity, hit.keyword_rank) {
+                                    (Some(similarity), _) => format!(" {:.0}%", similarity * 100.0),
+                                    (None, Some(rank)) => format!(" kw#{}", rank),
+                                    (None, None) => String::new(),
+                                })
+                                .unwrap_or_default()
                         } else {
                             let project = n.project.as_deref().unwrap_or("");
                             format!(" {}", truncate(project, 12))
@@ -905,7 +956,7 @@ impl App {
                         ]))
                     })
                     .collect();
-                let title = if self.is_semantic_search { "Semantic Results" } else { "Notes" };
+                let title = if self.is_semantic_search { "Search Results" } else { "Notes" };
                 (items, title)
             }
             ViewMode::Memories => {
@@ -1069,7 +1120,7 @@ impl App {
             "  1-5         Filter by type",
             "  0           Show all types",
             "  /           Keyword search",
-            "  s           Semantic search (Ollama)",
+            "  s           Hybrid search (keyword + Ollama)",
             "  c           Clear all filters",
             "",
             "  Note Actions",
@@ -1110,7 +1161,7 @@ impl App {
 
         let (title, hint): (&str, String) = match self.input_mode {
             Some(InputMode::Search) => (" Search ", "Enter keyword to search".to_string()),
-            Some(InputMode::SemanticSearch) => (" Semantic Search ", "Enter query for AI search".to_string()),
+            Some(InputMode::SemanticSearch) => (" Hybrid Search ", "Enter query for keyword + AI search".to_string()),
             Some(InputMode::CreateTitle) => (
                 " Create Note ",
                 format!("Type: {} (Tab to cycle) | Enter title:", self.create_type),
@@ -9,6 +9,9 @@ use vulcan_vault::{
     Chunk, ChunkConfig, Chunker, Note, NoteType, RagPipeline, SqliteStore, Store,
 };
 
+/// Embedding model the test vectors stand in for
+const MODEL: &str = "nomic-embed-text";
+
 /// Test chunking a simple document
 #[test]
 fn test_chunk_simple_document() {
@@ -92,7 +95,7 @@ fn test_store_chunks() {
     ];
 
     // Save chunks
-    store.save_chunks(&note.id, &chunks).unwrap();
+    store.save_chunks(&note.id, MODEL, &chunks).unwrap();
 
     // Retrieve chunks
     let retrieved = store.get_chunks(&note.id).unwrap();
@@ -145,11 +148,11 @@ fn test_vector_search() {
     .with_heading("Python")
     .with_embedding(python_embedding.clone())];
 
-    store.save_chunks(&note1.id, &chunks1).unwrap();
-    store.save_chunks(&note2.id, &chunks2).unwrap();
+    store.save_chunks(&note1.id, MODEL, &chunks1).unwrap();
+    store.save_chunks(&note2.id, MODEL, &chunks2).unwrap();
 
     // Search with Rust-like embedding should find Rust note first
-    let results = store.vector_search(&rust_embedding, None, None, None, 10).unwrap();
+    let results = store.vector_search(MODEL, &rust_embedding, None, None, None, 10).unwrap();
 
     assert!(!results.is_empty());
     assert_eq!(results[0].note_title, "Rust Guide");
@@ -190,12 +193,12 @@ fn test_vector_search_with_filters() {
     )
     .with_embedding(embedding.clone())];
 
-    store.save_chunks(&project_note.id, &chunks1).unwrap();
-    store.save_chunks(&learning_note.id, &chunks2).unwrap();
+    store.save_chunks(&project_note.id, MODEL, &chunks1).unwrap();
+    store.save_chunks(&learning_note.id, MODEL, &chunks2).unwrap();
 
     // Search with project filter
     let results = store
-        .vector_search(&embedding, None, Some("my-project"), None, 10)
+        .vector_search(MODEL, &embedding, None, Some("my-project"), None, 10)
         .unwrap();
 
     assert_eq!(results.len(), 1);
@@ -203,7 +206,7 @@ fn test_vector_search_with_filters() {
 
     // Search with note type filter
     let results = store
-        .vector_search(&embedding, Some(&[NoteType::Learning]), None, None, 10)
+        .vector_search(MODEL, &embedding, Some(&[NoteType::Learning]), None, None, 10)
         .unwrap();
 
     assert_eq!(results.len(), 1);